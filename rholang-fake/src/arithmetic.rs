@@ -0,0 +1,222 @@
+//! A small precedence-climbing evaluator for the arithmetic subset of Rholang
+//! (integer literals, parentheses, and the binary operators `+ - * /`), used
+//! by [`crate::FakeRholangInterpreter`] to compute an actual value for an
+//! `add`/`sub`/`mult`/`div` node rather than just reporting which operator it is.
+
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// An error encountered while tokenizing or evaluating an arithmetic expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArithmeticError {
+    /// An unrecognized character was encountered while tokenizing
+    UnexpectedChar(char),
+    /// The expression ended where a token was expected
+    UnexpectedEnd,
+    /// A token appeared where it could not be parsed (e.g. a stray `)`)
+    UnexpectedToken(String),
+    /// A `/` whose right-hand side evaluated to zero
+    DivisionByZero,
+    /// An addition, subtraction, multiplication, or division overflowed `i64`
+    Overflow,
+}
+
+impl fmt::Display for ArithmeticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArithmeticError::UnexpectedChar(c) => write!(f, "unexpected character '{}'", c),
+            ArithmeticError::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            ArithmeticError::UnexpectedToken(t) => write!(f, "unexpected token '{}'", t),
+            ArithmeticError::DivisionByZero => write!(f, "division by zero"),
+            ArithmeticError::Overflow => write!(f, "integer overflow"),
+        }
+    }
+}
+
+impl std::error::Error for ArithmeticError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Int(i64),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Int(n) => write!(f, "{}", n),
+            Token::Plus => write!(f, "+"),
+            Token::Minus => write!(f, "-"),
+            Token::Star => write!(f, "*"),
+            Token::Slash => write!(f, "/"),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+        }
+    }
+}
+
+/// Split `expr` into a flat token stream, skipping whitespace
+fn tokenize(expr: &str) -> Result<Vec<Token>, ArithmeticError> {
+    let mut chars: Peekable<Chars> = expr.chars().peekable();
+    let mut tokens = Vec::new();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '+' => {
+                chars.next();
+                tokens.push(Token::Plus);
+            }
+            '-' => {
+                chars.next();
+                tokens.push(Token::Minus);
+            }
+            '*' => {
+                chars.next();
+                tokens.push(Token::Star);
+            }
+            '/' => {
+                chars.next();
+                tokens.push(Token::Slash);
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            c if c.is_ascii_digit() => {
+                let mut digits = String::new();
+                while chars.peek().is_some_and(|d| d.is_ascii_digit()) {
+                    digits.push(chars.next().unwrap());
+                }
+                let n = digits.parse().map_err(|_| ArithmeticError::Overflow)?;
+                tokens.push(Token::Int(n));
+            }
+            c => return Err(ArithmeticError::UnexpectedChar(c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// The precedence of a binary operator token, or `None` if it isn't one
+fn precedence(token: &Token) -> Option<u8> {
+    match token {
+        Token::Plus | Token::Minus => Some(1),
+        Token::Star | Token::Slash => Some(2),
+        _ => None,
+    }
+}
+
+fn apply(op: &Token, lhs: i64, rhs: i64) -> Result<i64, ArithmeticError> {
+    match op {
+        Token::Plus => lhs.checked_add(rhs).ok_or(ArithmeticError::Overflow),
+        Token::Minus => lhs.checked_sub(rhs).ok_or(ArithmeticError::Overflow),
+        Token::Star => lhs.checked_mul(rhs).ok_or(ArithmeticError::Overflow),
+        Token::Slash => {
+            if rhs == 0 {
+                Err(ArithmeticError::DivisionByZero)
+            } else {
+                lhs.checked_div(rhs).ok_or(ArithmeticError::Overflow)
+            }
+        }
+        _ => unreachable!("apply called with a non-operator token"),
+    }
+}
+
+/// Parse and evaluate a primary expression: an integer literal, or a
+/// parenthesized expression
+fn parse_primary(tokens: &mut Peekable<std::vec::IntoIter<Token>>) -> Result<i64, ArithmeticError> {
+    match tokens.next().ok_or(ArithmeticError::UnexpectedEnd)? {
+        Token::Int(n) => Ok(n),
+        Token::LParen => {
+            let value = parse_expr(tokens, 0)?;
+            match tokens.next() {
+                Some(Token::RParen) => Ok(value),
+                Some(other) => Err(ArithmeticError::UnexpectedToken(other.to_string())),
+                None => Err(ArithmeticError::UnexpectedEnd),
+            }
+        }
+        other => Err(ArithmeticError::UnexpectedToken(other.to_string())),
+    }
+}
+
+/// Precedence-climbing parser: parses a primary, then folds in any following
+/// operators whose precedence is at least `min_bp`, recursing into the right
+/// operand with `min_bp = op_prec + 1` so that `+`/`-` and `*`/`/` are each
+/// left-associative and `*`/`/` bind tighter than `+`/`-`.
+fn parse_expr(
+    tokens: &mut Peekable<std::vec::IntoIter<Token>>,
+    min_bp: u8,
+) -> Result<i64, ArithmeticError> {
+    let mut lhs = parse_primary(tokens)?;
+
+    while let Some(op_prec) = tokens.peek().and_then(precedence) {
+        if op_prec < min_bp {
+            break;
+        }
+        let op = tokens.next().expect("peeked an operator token");
+        let rhs = parse_expr(tokens, op_prec + 1)?;
+        lhs = apply(&op, lhs, rhs)?;
+    }
+
+    Ok(lhs)
+}
+
+/// Evaluate an arithmetic expression made up of integer literals, parentheses,
+/// and `+ - * /`, respecting the usual operator precedence
+pub fn eval(expr: &str) -> Result<i64, ArithmeticError> {
+    let tokens = tokenize(expr)?;
+    let mut tokens = tokens.into_iter().peekable();
+    let value = parse_expr(&mut tokens, 0)?;
+    match tokens.next() {
+        None => Ok(value),
+        Some(trailing) => Err(ArithmeticError::UnexpectedToken(trailing.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eval_respects_operator_precedence() {
+        assert_eq!(eval("1 + 2 * 3"), Ok(7));
+        assert_eq!(eval("2 * 3 + 1"), Ok(7));
+    }
+
+    #[test]
+    fn test_eval_is_left_associative() {
+        assert_eq!(eval("10 - 2 - 3"), Ok(5));
+        assert_eq!(eval("100 / 10 / 2"), Ok(5));
+    }
+
+    #[test]
+    fn test_eval_honors_parentheses() {
+        assert_eq!(eval("(1 + 2) * 3"), Ok(9));
+    }
+
+    #[test]
+    fn test_eval_reports_division_by_zero() {
+        assert_eq!(eval("1 / 0"), Err(ArithmeticError::DivisionByZero));
+    }
+
+    #[test]
+    fn test_eval_reports_overflow() {
+        assert_eq!(
+            eval("9223372036854775807 + 1"),
+            Err(ArithmeticError::Overflow)
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! CLI front-end for [`rholang_fake::test_runner`]: discovers `.rho` files
+//! under the given paths, interprets them through a `FakeRholangInterpreter`,
+//! and prints a pass/fail summary. `--seed` shuffles run order reproducibly;
+//! `--watch` keeps polling and re-runs only the files whose mtime changed
+//! since the previous run.
+
+use anyhow::Result;
+use clap::Parser;
+use rholang_fake::test_runner::{run_suite, RunOptions, SuiteReport};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Directories, files, or glob patterns to search for `.rho` files
+    #[arg(default_value = "rholang-fake/examples")]
+    paths: Vec<PathBuf>,
+
+    /// Only run files whose name contains this substring
+    #[arg(short, long)]
+    filter: Option<String>,
+
+    /// Shuffle the run order deterministically using this seed
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Keep running, re-interpreting only the files that changed since the last run
+    #[arg(short, long, default_value_t = false)]
+    watch: bool,
+}
+
+/// Interval between filesystem polls in `--watch` mode.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+fn print_report(report: &SuiteReport) {
+    for outcome in &report.outcomes {
+        if outcome.passed {
+            println!("PASS {} ({:.2?})", outcome.path.display(), outcome.elapsed);
+        } else {
+            match (outcome.line, outcome.column) {
+                (Some(line), Some(column)) => println!(
+                    "FAIL {} at line {}, column {}: {}",
+                    outcome.path.display(),
+                    line,
+                    column,
+                    outcome.message
+                ),
+                _ => println!("FAIL {}: {}", outcome.path.display(), outcome.message),
+            }
+        }
+    }
+    println!(
+        "{} passed, {} failed",
+        report.pass_count(),
+        report.fail_count()
+    );
+}
+
+/// Take last-modified timestamps for `files`, skipping any that can't be stat'd.
+fn snapshot_mtimes(files: &[PathBuf]) -> HashMap<PathBuf, SystemTime> {
+    files
+        .iter()
+        .filter_map(|path| {
+            std::fs::metadata(path)
+                .and_then(|m| m.modified())
+                .ok()
+                .map(|mtime| (path.clone(), mtime))
+        })
+        .collect()
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let options = RunOptions {
+        filter: args.filter.clone(),
+        seed: args.seed,
+    };
+
+    let report = run_suite(&args.paths, &options).await?;
+    print_report(&report);
+
+    if !args.watch {
+        if report.all_passed() {
+            return Ok(());
+        }
+        std::process::exit(1);
+    }
+
+    let mut last_mtimes = snapshot_mtimes(
+        &report
+            .outcomes
+            .iter()
+            .map(|outcome| outcome.path.clone())
+            .collect::<Vec<_>>(),
+    );
+
+    loop {
+        tokio::time::sleep(WATCH_POLL_INTERVAL).await;
+
+        let files = rholang_fake::discovery::discover_files(&args.paths, args.filter.as_deref())?;
+        let mtimes = snapshot_mtimes(&files);
+
+        let changed: Vec<PathBuf> = files
+            .iter()
+            .filter(|path| mtimes.get(*path) != last_mtimes.get(*path))
+            .cloned()
+            .collect();
+
+        if !changed.is_empty() {
+            println!("\nRe-running {} changed file(s)...", changed.len());
+            let report = run_suite(&changed, &options).await?;
+            print_report(&report);
+        }
+
+        last_mtimes = mtimes;
+    }
+}
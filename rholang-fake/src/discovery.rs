@@ -0,0 +1,62 @@
+//! File discovery for the [`crate::test_runner`] subsystem: resolves a list
+//! of roots (directories, individual files, or glob patterns) into a flat,
+//! deduplicated list of `.rho` files, optionally narrowed down to the ones
+//! whose name matches a filter substring.
+
+use anyhow::{anyhow, Result};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Recursively collect every `.rho` file under `dir`.
+fn discover_dir(dir: &Path, out: &mut BTreeSet<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_dir() {
+            discover_dir(&path, out)?;
+        } else if path.extension().is_some_and(|ext| ext == "rho") {
+            out.insert(path);
+        }
+    }
+    Ok(())
+}
+
+/// Resolve `roots` into a sorted, deduplicated list of `.rho` files.
+///
+/// Each root may be a directory (searched recursively), a single file, or a
+/// glob pattern (e.g. `rholang-fake/examples/**/*.rho`). When `filter` is
+/// set, only files whose stem contains it survive.
+pub fn discover_files(roots: &[PathBuf], filter: Option<&str>) -> Result<Vec<PathBuf>> {
+    let mut files = BTreeSet::new();
+
+    for root in roots {
+        if root.is_dir() {
+            discover_dir(root, &mut files)?;
+        } else if root.is_file() {
+            files.insert(root.clone());
+        } else {
+            let pattern = root
+                .to_str()
+                .ok_or_else(|| anyhow!("Non-UTF8 path: {}", root.display()))?;
+            let mut matched_any = false;
+            for entry in glob::glob(pattern)? {
+                files.insert(entry?);
+                matched_any = true;
+            }
+            if !matched_any {
+                return Err(anyhow!("No .rho files found under {}", root.display()));
+            }
+        }
+    }
+
+    let mut files: Vec<PathBuf> = files.into_iter().collect();
+    if let Some(filter) = filter {
+        files.retain(|path| {
+            path.file_stem()
+                .and_then(|stem| stem.to_str())
+                .is_some_and(|stem| stem.contains(filter))
+        });
+    }
+
+    Ok(files)
+}
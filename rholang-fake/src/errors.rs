@@ -42,6 +42,12 @@ pub struct InterpreterError {
     pub position: Option<SourcePosition>,
     /// The source code that caused the error (if available)
     pub source: Option<String>,
+    /// Context frames accumulated as this error unwound through the resolver/
+    /// interpreter, outermost-last, each a `(kind, label, position)` of the
+    /// construct that was being evaluated when it chose to annotate the
+    /// error on its way up. Empty unless a caller has called
+    /// [`Self::with_context`].
+    pub context: Vec<(ErrorKind, String, Option<SourcePosition>)>,
 }
 
 impl InterpreterError {
@@ -56,6 +62,7 @@ impl InterpreterError {
             message: message.into(),
             position,
             source,
+            context: Vec::new(),
         }
     }
 
@@ -70,6 +77,7 @@ impl InterpreterError {
             message: message.into(),
             position,
             source,
+            context: Vec::new(),
         }
     }
 
@@ -80,6 +88,7 @@ impl InterpreterError {
             message: message.into(),
             position: None,
             source: None,
+            context: Vec::new(),
         }
     }
 
@@ -90,6 +99,7 @@ impl InterpreterError {
             message: message.into(),
             position: None,
             source: None,
+            context: Vec::new(),
         }
     }
 
@@ -100,8 +110,19 @@ impl InterpreterError {
             message: message.into(),
             position: None,
             source: None,
+            context: Vec::new(),
         }
     }
+
+    /// Push a context frame onto this error as it unwinds, e.g.
+    /// `.with_context("in `for` comprehension", Some(position))`. Frames
+    /// accumulate outermost-last, in the order the caller annotates them,
+    /// so [`Display`](fmt::Display) can render them as a "while ..." trace
+    /// under the original, innermost error without losing it.
+    pub fn with_context(mut self, label: impl Into<String>, position: Option<SourcePosition>) -> Self {
+        self.context.push((self.kind.clone(), label.into(), position));
+        self
+    }
 }
 
 impl fmt::Display for InterpreterError {
@@ -122,6 +143,13 @@ impl fmt::Display for InterpreterError {
             write!(f, "\nSource: {}", source)?;
         }
 
+        for (_, label, position) in &self.context {
+            write!(f, "\n  while {label}")?;
+            if let Some(position) = position {
+                write!(f, " at {position}")?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -1,23 +1,45 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use rholang_tree_sitter_proc_macro::match_node;
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::task;
 use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+use tree_sitter::Node;
 
+pub mod arithmetic;
+pub mod discovery;
 pub mod errors;
 pub mod parser;
+pub mod test_runner;
 
 // Re-export error types for convenience
 pub use errors::{ErrorKind, InterpretationResult, InterpreterError, SourcePosition};
 
+/// A running [`FakeRholangInterpreter::interpret_async`]/`interpret_cancellable` job,
+/// keyed by pid in [`FakeRholangInterpreter::processes`]
+struct ProcessInfo {
+    /// The code being interpreted
+    code: String,
+    /// Cancels the job's in-flight `select!` (see `interpret_cancellable`)
+    token: CancellationToken,
+}
+
 /// A simple fake interpreter for Rholang language
 /// This is not a real Rholang interpreter, but it uses the RholangParser
 /// to validate and parse Rholang code
 pub struct FakeRholangInterpreter {
     parser: parser::RholangParser,
-    // Store variables for the interpreter
-    variables: HashMap<String, String>,
+    // Store variables for the interpreter, shared with spawned `interpret_cancellable`
+    // jobs (each of which parses with its own `RholangParser` -- see that method)
+    variables: Arc<Mutex<HashMap<String, String>>>,
     // Delay for async interpretation (in milliseconds)
     delay_ms: u64,
+    // Jobs currently running under `interpret_async`/`interpret_cancellable`
+    processes: Arc<Mutex<HashMap<usize, ProcessInfo>>>,
+    // Next process ID to assign
+    next_pid: Arc<Mutex<usize>>,
 }
 
 impl FakeRholangInterpreter {
@@ -27,8 +49,10 @@ impl FakeRholangInterpreter {
         let parser = parser::RholangParser::new()?;
         Ok(FakeRholangInterpreter {
             parser,
-            variables: HashMap::new(),
+            variables: Arc::new(Mutex::new(HashMap::new())),
             delay_ms: 2000, // Default delay: 2 seconds
+            processes: Arc::new(Mutex::new(HashMap::new())),
+            next_pid: Arc::new(Mutex::new(1)),
         })
     }
 
@@ -37,179 +61,369 @@ impl FakeRholangInterpreter {
         self.delay_ms = delay_ms;
     }
 
-    /// Interpret a string of Rholang code synchronously
-    /// This implementation uses the RholangParser to validate the code
-    /// and returns a meaningful result based on the type of Rholang construct
+    /// Interpret a string of Rholang code synchronously: validate it, then walk the
+    /// real parse tree top-down (see [`eval_node`]), dispatching on node kind rather
+    /// than matching substrings against the whole source.
     pub fn interpret(&mut self, code: &str) -> InterpretationResult {
-        // Check if the code is valid Rholang
-        if !self.parser.is_valid(code) {
-            return InterpretationResult::Error(InterpreterError::parsing_error(
-                "Invalid Rholang code",
-                None,
-                Some(code.to_string()),
-            ));
-        }
+        evaluate(&self.variables, &mut self.parser, code)
+    }
 
-        // Trim the code to remove leading/trailing whitespace
-        let code = code.trim();
-
-        // Handle different Rholang constructs
-        // Check for the more specific constructs first
-        if code.starts_with("new ") && code.contains(" in ") {
-            self.handle_new_declaration(code)
-        } else if code.starts_with("for (") && code.contains("<-") {
-            self.handle_for_comprehension(code)
-        } else if code.contains("@\"stdout\"!(") {
-            self.handle_print(code)
-        } else if self.is_arithmetic_expression(code) {
-            self.handle_arithmetic(code)
-        } else {
-            // If no specific handler, return a generic parse tree
-            self.parser.get_tree_string(code)
-        }
+    /// Interpret a string of Rholang code asynchronously, honoring a fresh
+    /// cancellation token registered under a new pid (see [`Self::kill_process`]).
+    /// Equivalent to `interpret_cancellable` with a token nobody outside this call
+    /// can reach except through the process registry.
+    pub async fn interpret_async(&self, code: &str) -> InterpretationResult {
+        self.interpret_cancellable(code, CancellationToken::new())
+            .await
     }
 
-    /// Interpret a string of Rholang code asynchronously
-    /// This implementation uses the RholangParser to validate the code
-    /// and returns a meaningful result based on the type of Rholang construct
-    pub async fn interpret_async(&mut self, code: &str) -> InterpretationResult {
-        // Check if the code is valid Rholang
-        if !self.parser.is_valid(code) {
-            return InterpretationResult::Error(InterpreterError::parsing_error(
-                "Invalid Rholang code",
-                None,
-                Some(code.to_string()),
-            ));
-        }
+    /// Interpret a string of Rholang code asynchronously, after a simulated delay to
+    /// represent processing time, aborting early if `token` is cancelled -- whether
+    /// by the caller or via [`Self::kill_process`]/[`Self::kill_all_processes`].
+    ///
+    /// Runs the actual parse/evaluate on a fresh [`parser::RholangParser`] inside a
+    /// spawned task, rather than borrowing `self.parser`, so this can be called
+    /// through a shared `&self` and run concurrently with other jobs.
+    pub async fn interpret_cancellable(
+        &self,
+        code: &str,
+        token: CancellationToken,
+    ) -> InterpretationResult {
+        let pid = match self.register_process(code, token.clone()) {
+            Ok(pid) => pid,
+            Err(e) => {
+                return InterpretationResult::Error(InterpreterError::other_error(e.to_string()))
+            }
+        };
+
+        let variables = Arc::clone(&self.variables);
+        let delay_ms = self.delay_ms;
+        let code = code.to_string();
+
+        let handle = task::spawn(async move {
+            let mut parser = match parser::RholangParser::new() {
+                Ok(parser) => parser,
+                Err(e) => {
+                    return InterpretationResult::Error(InterpreterError::other_error(format!(
+                        "Failed to create parser: {}",
+                        e
+                    )))
+                }
+            };
+
+            if !parser.is_valid(&code) {
+                return InterpretationResult::Error(InterpreterError::parsing_error(
+                    "Invalid Rholang code",
+                    None,
+                    Some(code.clone()),
+                ));
+            }
 
-        // Trim the code to remove leading/trailing whitespace
-        let code = code.trim();
-
-        // Simulate a delay to represent processing time
-        // This makes the interpreter run asynchronously
-        sleep(Duration::from_millis(self.delay_ms)).await;
-
-        // Handle different Rholang constructs
-        // Check for the more specific constructs first
-        if code.starts_with("new ") && code.contains(" in ") {
-            self.handle_new_declaration(code)
-        } else if code.starts_with("for (") && code.contains("<-") {
-            self.handle_for_comprehension(code)
-        } else if code.contains("@\"stdout\"!(") {
-            self.handle_print(code)
-        } else if self.is_arithmetic_expression(code) {
-            self.handle_arithmetic(code)
-        } else {
-            // If no specific handler, return a generic parse tree
-            self.parser.get_tree_string(code)
-        }
+            if delay_ms > 0 {
+                tokio::select! {
+                    _ = sleep(Duration::from_millis(delay_ms)) => {}
+                    _ = token.cancelled() => {
+                        return InterpretationResult::Error(InterpreterError::cancellation_error(
+                            "Evaluation was cancelled",
+                        ));
+                    }
+                }
+            }
+
+            evaluate(&variables, &mut parser, &code)
+        });
+
+        let result = handle.await.unwrap_or_else(|e| {
+            InterpretationResult::Error(InterpreterError::other_error(format!("Task error: {}", e)))
+        });
+
+        self.unregister_process(pid);
+        result
     }
 
-    /// Check if the code is valid Rholang
     pub fn is_valid(&mut self, code: &str) -> bool {
         self.parser.is_valid(code)
     }
 
-    /// Handle print statements like @"stdout"!("Hello, world!")
-    fn handle_print(&mut self, code: &str) -> InterpretationResult {
-        // Extract the message from the print statement
-        if let Some(start_idx) = code.find("@\"stdout\"!(") {
-            let content_start = start_idx + "@\"stdout\"!(".len();
-            let content_end = code[content_start..].rfind(')').map(|i| content_start + i);
-
-            if let Some(end_idx) = content_end {
-                let message = &code[content_start..end_idx];
+    /// Check whether `code` merely looks incomplete (see
+    /// [`parser::RholangParser::is_incomplete`]) rather than genuinely invalid --
+    /// used by a REPL to decide whether to keep buffering more input
+    pub fn is_incomplete(&mut self, code: &str) -> bool {
+        self.parser.is_incomplete(code)
+    }
 
-                // Remove quotes if present
-                let message = if message.starts_with('"') && message.ends_with('"') {
-                    &message[1..message.len() - 1]
-                } else {
-                    message
-                };
+    /// List the pids and source of every `interpret_async`/`interpret_cancellable`
+    /// job currently running
+    pub fn list_processes(&self) -> Result<Vec<(usize, String)>> {
+        let processes = self
+            .processes
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock processes: {}", e))?;
+        Ok(processes
+            .iter()
+            .map(|(&pid, info)| (pid, info.code.clone()))
+            .collect())
+    }
 
-                return InterpretationResult::Success(format!("Output: {}", message));
+    /// Cancel the running job with the given pid, if any. Returns `true` if a job
+    /// was found and cancelled.
+    pub fn kill_process(&self, pid: usize) -> Result<bool> {
+        let mut processes = self
+            .processes
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock processes: {}", e))?;
+        Ok(match processes.remove(&pid) {
+            Some(info) => {
+                info.token.cancel();
+                true
             }
-        }
-
-        // Fallback to generic parse tree if we couldn't extract the message
-        self.parser.get_tree_string(code)
+            None => false,
+        })
     }
 
-    /// Handle new declarations like new x in { ... }
-    fn handle_new_declaration(&mut self, code: &str) -> InterpretationResult {
-        // Extract the name from the new declaration
-        if let Some(start_idx) = code.find("new ") {
-            let name_start = start_idx + "new ".len();
-            let name_end = code[name_start..].find(" in ").map(|i| name_start + i);
+    /// Cancel every running job. Returns the number of jobs that were cancelled.
+    pub fn kill_all_processes(&self) -> Result<usize> {
+        let mut processes = self
+            .processes
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock processes: {}", e))?;
+        let count = processes.len();
+        for (_, info) in processes.drain() {
+            info.token.cancel();
+        }
+        Ok(count)
+    }
 
-            if let Some(end_idx) = name_end {
-                let name = &code[name_start..end_idx];
+    /// Register a new running job under a freshly allocated pid
+    fn register_process(&self, code: &str, token: CancellationToken) -> Result<usize> {
+        let pid = {
+            let mut next_pid = self
+                .next_pid
+                .lock()
+                .map_err(|e| anyhow!("Failed to lock next_pid: {}", e))?;
+            let pid = *next_pid;
+            *next_pid += 1;
+            pid
+        };
+
+        let mut processes = self
+            .processes
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock processes: {}", e))?;
+        processes.insert(
+            pid,
+            ProcessInfo {
+                code: code.to_string(),
+                token,
+            },
+        );
+
+        Ok(pid)
+    }
 
-                // Store the name in our variables
-                self.variables
-                    .insert(name.to_string(), "channel".to_string());
+    /// Remove a finished job from the registry
+    fn unregister_process(&self, pid: usize) {
+        if let Ok(mut processes) = self.processes.lock() {
+            processes.remove(&pid);
+        }
+    }
+}
 
-                return InterpretationResult::Success(format!("Created new name: {}", name));
+/// Parse `code` and walk its tree, or report a parsing error if tree-sitter found any
+/// `ERROR` nodes. The root node's named children are each a top-level process, so
+/// they're evaluated and joined exactly like a `par` node's children are.
+fn evaluate(
+    variables: &Mutex<HashMap<String, String>>,
+    parser: &mut parser::RholangParser,
+    code: &str,
+) -> InterpretationResult {
+    match parser.parse(code) {
+        Some(tree) if !tree.root_node().has_error() => {
+            match eval_children(variables, tree.root_node(), code) {
+                Ok(lines) => InterpretationResult::Success(lines.join("\n")),
+                Err(err) => InterpretationResult::Error(err),
             }
         }
-
-        // Fallback to generic parse tree if we couldn't extract the name
-        self.parser.get_tree_string(code)
+        _ => {
+            let error = parser
+                .diagnose(code)
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| {
+                    InterpreterError::parsing_error("Invalid Rholang code", None, Some(code.to_string()))
+                });
+            InterpretationResult::Error(error)
+        }
     }
+}
 
-    /// Handle for comprehensions like for (x <- y) { ... }
-    fn handle_for_comprehension(&mut self, code: &str) -> InterpretationResult {
-        // Extract the pattern and channel from the for comprehension
-        if let Some(start_idx) = code.find("for (") {
-            let pattern_start = start_idx + "for (".len();
-            let pattern_end = code[pattern_start..].find(")").map(|i| pattern_start + i);
+/// Evaluate one node of the parse tree, dispatching on its kind and recursing into
+/// its children so a nested construct (`new x in { for (y <- x) { ... } }`) is
+/// evaluated all the way down rather than only at the outermost node.
+fn eval_node(
+    variables: &Mutex<HashMap<String, String>>,
+    node: Node,
+    source: &str,
+) -> Result<String, InterpreterError> {
+    match_node!(node,
+        "block" => match node.named_child(0) {
+            Some(child) => eval_node(variables, child, source),
+            None => Ok(String::new()),
+        },
+        "par" => Ok(eval_children(variables, node, source)?.join("\n")),
+        "new" => eval_new(variables, node, source),
+        "send" => eval_send(node, source),
+        "input" => eval_input(variables, node, source),
+        "add" => eval_arithmetic(node, source),
+        "sub" => eval_arithmetic(node, source),
+        "mult" => eval_arithmetic(node, source),
+        "div" => eval_arithmetic(node, source),
+        "_" => Ok(node_text(node, source).to_string())
+    )
+}
 
-            if let Some(end_idx) = pattern_end {
-                let pattern = &code[pattern_start..end_idx];
+/// Evaluate every named child of `node`, in order
+fn eval_children(
+    variables: &Mutex<HashMap<String, String>>,
+    node: Node,
+    source: &str,
+) -> Result<Vec<String>, InterpreterError> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .map(|child| eval_node(variables, child, source))
+        .collect()
+}
 
-                if let Some(arrow_idx) = pattern.find("<-") {
-                    let var_name = pattern[..arrow_idx].trim();
-                    let channel = pattern[arrow_idx + 2..].trim();
+/// Evaluate a `new x, y in { ... }` declaration: record each declared name, then
+/// recurse into its body
+fn eval_new(
+    variables: &Mutex<HashMap<String, String>>,
+    node: Node,
+    source: &str,
+) -> Result<String, InterpreterError> {
+    let names: Vec<String> = node
+        .child_by_field_name("decls")
+        .map(|decls| {
+            let mut cursor = decls.walk();
+            decls
+                .named_children(&mut cursor)
+                .map(|decl| {
+                    let var = decl.named_child(0).unwrap_or(decl);
+                    node_text(var, source).to_string()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
 
-                    return InterpretationResult::Success(format!(
-                        "Listening for messages on {} as {}",
-                        channel, var_name
-                    ));
-                }
-            }
+    if let Ok(mut vars) = variables.lock() {
+        for name in &names {
+            vars.insert(name.clone(), "channel".to_string());
         }
+    }
 
-        // Fallback to generic parse tree if we couldn't extract the pattern
-        self.parser.get_tree_string(code)
+    let header = match names.as_slice() {
+        [name] => format!("Created new name: {}", name),
+        names => format!("Created new names: {}", names.join(", ")),
+    };
+
+    match node.child_by_field_name("proc") {
+        Some(proc_node) => Ok(format!(
+            "{}\n{}",
+            header,
+            eval_node(variables, proc_node, source)?
+        )),
+        None => Ok(header),
     }
+}
 
-    /// Check if the code is an arithmetic expression
-    fn is_arithmetic_expression(&self, code: &str) -> bool {
-        // Simple check for arithmetic operators
-        code.contains('+') || code.contains('-') || code.contains('*') || code.contains('/')
+/// Evaluate a `channel!(...)` send: `@"stdout"!(...)` prints its first argument,
+/// anything else is reported generically
+fn eval_send(node: Node, source: &str) -> Result<String, InterpreterError> {
+    let channel = node
+        .child_by_field_name("channel")
+        .map(|n| node_text(n, source))
+        .unwrap_or_default();
+
+    let first_input = node
+        .child_by_field_name("inputs")
+        .and_then(|inputs| inputs.named_child(0))
+        .map(|input| unquote(node_text(input, source)))
+        .unwrap_or_default();
+
+    if channel.contains("stdout") {
+        Ok(format!("Output: {}", first_input))
+    } else {
+        Ok(format!("Sending {} on {}", first_input, channel))
     }
+}
 
-    /// Handle arithmetic expressions like 1 + 2 * 3
-    fn handle_arithmetic(&mut self, code: &str) -> InterpretationResult {
-        // This is a very simplified evaluator for arithmetic expressions
-        // In a real interpreter, we would use a proper parser and evaluator
-
-        // For this fake interpreter, we'll just return a fake result
-        if code.contains('+') {
-            InterpretationResult::Success(format!("Addition expression: {}", code))
-        } else if code.contains('-') {
-            InterpretationResult::Success(format!("Subtraction expression: {}", code))
-        } else if code.contains('*') {
-            InterpretationResult::Success(format!("Multiplication expression: {}", code))
-        } else if code.contains('/') {
-            InterpretationResult::Success(format!("Division expression: {}", code))
-        } else {
-            // Fallback to generic parse tree
-            self.parser.get_tree_string(code)
-        }
+/// Evaluate a `for (x <- y) { ... }` receive: report the first bind's pattern and
+/// source, then recurse into its body
+fn eval_input(
+    variables: &Mutex<HashMap<String, String>>,
+    node: Node,
+    source: &str,
+) -> Result<String, InterpreterError> {
+    let bind_text = node
+        .child_by_field_name("receipts")
+        .and_then(|receipts| receipts.named_child(0))
+        .and_then(|receipt| receipt.named_child(0))
+        .map(|bind| node_text(bind, source));
+
+    let header = match bind_text.and_then(|text| text.split_once("<-")) {
+        Some((pattern, source_expr)) => format!(
+            "Listening for messages on {} as {}",
+            source_expr.trim(),
+            pattern.trim()
+        ),
+        None => format!(
+            "Listening for messages: {}",
+            bind_text.unwrap_or_default().trim()
+        ),
+    };
+
+    match node.child_by_field_name("proc") {
+        Some(proc_node) => Ok(format!(
+            "{}\n{}",
+            header,
+            eval_node(variables, proc_node, source)?
+        )),
+        None => Ok(header),
     }
 }
 
+/// Evaluate an `add`/`sub`/`mult`/`div` node by running [`arithmetic::eval`] over its
+/// own source text, rather than just reporting which operator it is
+fn eval_arithmetic(node: Node, source: &str) -> Result<String, InterpreterError> {
+    let text = node_text(node, source);
+    let label = match node.kind() {
+        "add" => "Addition",
+        "sub" => "Subtraction",
+        "mult" => "Multiplication",
+        "div" => "Division",
+        other => other,
+    };
+
+    let value = arithmetic::eval(text).map_err(|err| {
+        InterpreterError::runtime_error(err.to_string(), None, Some(text.to_string()))
+    })?;
+
+    Ok(format!("{} expression: {} = {}", label, text, value))
+}
+
+/// The source text spanned by `node`
+fn node_text<'a>(node: Node, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes()).unwrap_or("")
+}
+
+/// Strip a single pair of surrounding double quotes from `text`, if present
+fn unquote(text: &str) -> String {
+    text.strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .unwrap_or(text)
+        .to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,11 +438,12 @@ mod tests {
         assert!(interpreter.is_valid("2 * 3"));
         assert!(interpreter.is_valid("6 / 2"));
 
-        // The result should contain the arithmetic expression
+        // The result should contain the arithmetic expression and its computed value
         let result = interpreter.interpret("1 + 2");
         match result {
             InterpretationResult::Success(output) => {
                 assert!(output.contains("Addition expression: 1 + 2"));
+                assert!(output.contains("= 3"));
             }
             InterpretationResult::Error(err) => {
                 panic!("Expected success, got error: {}", err);
@@ -238,6 +453,33 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_arithmetic_respects_operator_precedence() -> Result<()> {
+        let mut interpreter = FakeRholangInterpreter::new()?;
+
+        let result = interpreter.interpret("1 + 2 * 3");
+        match result {
+            InterpretationResult::Success(output) => {
+                assert!(output.contains("= 7"));
+            }
+            InterpretationResult::Error(err) => {
+                panic!("Expected success, got error: {}", err);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_arithmetic_reports_division_by_zero_as_an_error() -> Result<()> {
+        let mut interpreter = FakeRholangInterpreter::new()?;
+
+        let result = interpreter.interpret("1 / 0");
+        assert!(result.is_error());
+
+        Ok(())
+    }
+
     #[test]
     fn test_print_statement() -> Result<()> {
         let mut interpreter = FakeRholangInterpreter::new()?;
@@ -315,4 +557,68 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_interpret_async_evaluates_like_interpret() -> Result<()> {
+        let interpreter = FakeRholangInterpreter::new()?;
+
+        let result = interpreter.interpret_async("1 + 2").await;
+        match result {
+            InterpretationResult::Success(output) => {
+                assert!(output.contains("= 3"));
+            }
+            InterpretationResult::Error(err) => {
+                panic!("Expected success, got error: {}", err);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_kill_process_cancels_an_in_flight_job() -> Result<()> {
+        let mut interpreter = FakeRholangInterpreter::new()?;
+        interpreter.set_delay(60_000);
+        let interpreter = Arc::new(interpreter);
+
+        let running = Arc::clone(&interpreter);
+        let handle = task::spawn(async move { running.interpret_async("1 + 2").await });
+
+        // Give the job a moment to register itself before cancelling it
+        tokio::task::yield_now().await;
+        let pids: Vec<usize> = interpreter
+            .list_processes()?
+            .into_iter()
+            .map(|(pid, _)| pid)
+            .collect();
+        assert_eq!(pids.len(), 1);
+        assert!(interpreter.kill_process(pids[0])?);
+
+        let result = handle.await?;
+        assert!(result.is_error());
+        assert_eq!(result.unwrap_err().kind, ErrorKind::CancellationError);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_kill_all_processes_cancels_every_job() -> Result<()> {
+        let mut interpreter = FakeRholangInterpreter::new()?;
+        interpreter.set_delay(60_000);
+        let interpreter = Arc::new(interpreter);
+
+        let a = Arc::clone(&interpreter);
+        let b = Arc::clone(&interpreter);
+        let handle_a = task::spawn(async move { a.interpret_async("1 + 2").await });
+        let handle_b = task::spawn(async move { b.interpret_async("3 + 4").await });
+
+        tokio::task::yield_now().await;
+        let killed = interpreter.kill_all_processes()?;
+        assert_eq!(killed, 2);
+
+        assert!(handle_a.await?.is_error());
+        assert!(handle_b.await?.is_error());
+
+        Ok(())
+    }
 }
@@ -1,60 +1,228 @@
-use crate::errors::{InterpretationResult, InterpreterError};
-use anyhow::anyhow;
-use anyhow::Result;
+use crate::errors::{InterpretationResult, InterpreterError, SourcePosition};
+use anyhow::{anyhow, Result};
+use rholang_tree_sitter_proc_macro::{walk, Visitor};
+use std::ops::Range;
+use tree_sitter::{Parser, Tree};
 
-/// A simple parser for Rholang code
-pub struct RholangParser;
+/// A thin wrapper around the real Rholang tree-sitter grammar, used by
+/// [`crate::FakeRholangInterpreter`] to validate and parse code -- "fake" only in that the
+/// interpreter built on top of it doesn't evaluate Rholang's process calculus semantics,
+/// not in how it parses.
+pub struct RholangParser {
+    parser: Parser,
+}
 
 impl RholangParser {
     /// Create a new instance of the Rholang parser
     pub fn new() -> Result<Self> {
-        Ok(RholangParser)
+        let mut parser = Parser::new();
+        let language = rholang_tree_sitter::LANGUAGE.into();
+        parser
+            .set_language(&language)
+            .map_err(|e| anyhow!("failed to load the Rholang grammar: {e}"))?;
+        Ok(RholangParser { parser })
+    }
+
+    /// Parse `code` against the grammar, or `None` if tree-sitter couldn't produce a
+    /// tree at all (as opposed to a tree containing `ERROR` nodes, which it does
+    /// produce a tree for)
+    pub fn parse(&mut self, code: &str) -> Option<Tree> {
+        self.parser.parse(code, None)
     }
 
-    /// Check if the code is valid Rholang
-    /// This is a very simple implementation that just checks for balanced braces, parentheses, and brackets
+    /// Check if the code is valid Rholang: it parses, and the resulting tree contains
+    /// no `ERROR` nodes
     pub fn is_valid(&mut self, code: &str) -> bool {
-        // Check for balanced braces, parentheses, and brackets
-        let mut brace_count = 0;
-        let mut paren_count = 0;
-        let mut bracket_count = 0;
-
-        for c in code.chars() {
-            match c {
-                '{' => brace_count += 1,
-                '}' => brace_count -= 1,
-                '(' => paren_count += 1,
-                ')' => paren_count -= 1,
-                '[' => bracket_count += 1,
-                ']' => bracket_count -= 1,
-                _ => {}
-            }
+        self.parse(code)
+            .is_some_and(|tree| !tree.root_node().has_error())
+    }
 
-            // If any count goes negative, the code is invalid
-            if brace_count < 0 || paren_count < 0 || bracket_count < 0 {
-                return false;
-            }
+    /// Check whether `code` fails to parse only because it ends prematurely: the
+    /// tree contains an `ERROR`/`MISSING` node whose span reaches the very end of
+    /// the source, as opposed to a genuine syntax error earlier in the text. A REPL
+    /// can use this to decide whether to keep buffering more input rather than
+    /// reporting a real error.
+    pub fn is_incomplete(&mut self, code: &str) -> bool {
+        let Some(tree) = self.parse(code) else {
+            return false;
+        };
+
+        let root = tree.root_node();
+        if !root.has_error() {
+            return false;
         }
 
-        // If all counts are 0, the code is valid
-        brace_count == 0 && paren_count == 0 && bracket_count == 0
+        error_reaches_eof(root, code.len())
     }
 
-    /// Get a string representation of the parse tree
-    /// This is a very simple implementation that just returns the input code
+    /// Get a string representation of the parse tree, as tree-sitter's own
+    /// S-expression form, or a parsing error if the tree contains `ERROR` nodes
     pub fn get_tree_string(&mut self, code: &str) -> InterpretationResult {
-        if self.is_valid(code) {
-            InterpretationResult::Success(format!("Parse tree: {}", code))
-        } else {
-            InterpretationResult::Error(InterpreterError::parsing_error(
+        match self.parse(code) {
+            Some(tree) if !tree.root_node().has_error() => {
+                InterpretationResult::Success(format!("Parse tree: {}", tree.root_node().to_sexp()))
+            }
+            _ => InterpretationResult::Error(InterpreterError::parsing_error(
                 "Parse tree contains errors",
                 None,
                 Some(code.to_string()),
-            ))
+            )),
+        }
+    }
+
+    /// Parse `code`, recovering from syntax errors instead of stopping at the first
+    /// one. tree-sitter's own GLR-style recovery already does the resynchronization
+    /// this needs: on a failed production it records the error at the current offset,
+    /// skips forward until a stable anchor (a closing bracket, a top-level keyword, or
+    /// EOF) consuming at least one token so it's guaranteed to terminate, and resumes
+    /// parsing -- with nested bracket depth tracked by the grammar itself, so an anchor
+    /// only matches at the correct level. So unlike [`Self::get_tree_string`], which
+    /// discards everything on the first `ERROR`/`MISSING` node, this harvests every one
+    /// of them (in source order) into a flat list of span-tagged [`Diagnostic`]s
+    /// alongside the partial tree tree-sitter recovered -- useful for an IDE/LSP that
+    /// wants every syntax error in a file in one pass, with enough of the tree intact
+    /// around each to still offer completion/hover.
+    pub fn parse_recovering(&mut self, code: &str) -> (Tree, Vec<Diagnostic>) {
+        let tree = self
+            .parser
+            .parse(code, None)
+            .expect("a Parser with its language already set always returns a tree");
+
+        let mut diagnostics = Vec::new();
+        collect_diagnostics(&tree.root_node(), code, &mut diagnostics);
+        (tree, diagnostics)
+    }
+
+    /// Walk `code`'s parse tree with the shared
+    /// [`rholang_tree_sitter_proc_macro::walk`] driver and report every
+    /// `ERROR`/`MISSING` node as a positioned [`InterpreterError::parsing_error`],
+    /// instead of collapsing a syntax error into one generic "invalid code"
+    /// message. Each error's [`SourcePosition`] converts tree-sitter's 0-based
+    /// `start_position()` to the 1-based convention the rest of this crate uses,
+    /// and its `source` is just the offending line, not the whole file.
+    pub fn diagnose(&mut self, code: &str) -> Vec<InterpreterError> {
+        let Some(tree) = self.parse(code) else {
+            return vec![InterpreterError::parsing_error(
+                "failed to parse",
+                None,
+                Some(code.to_string()),
+            )];
+        };
+
+        let mut collector = DiagnosticCollector { errors: Vec::new() };
+        walk(&tree, code, &mut collector);
+        collector.errors
+    }
+}
+
+struct DiagnosticCollector {
+    errors: Vec<InterpreterError>,
+}
+
+impl Visitor for DiagnosticCollector {
+    fn enter_node(&mut self, node: tree_sitter::Node, code: &str) {
+        if node.is_missing() {
+            self.errors
+                .push(node_error(node, code, format!("expected {}", node.kind())));
+        } else if node.is_error() {
+            let found = node.utf8_text(code.as_bytes()).unwrap_or_default();
+            let message = if found.is_empty() {
+                "unexpected token".to_string()
+            } else {
+                format!("unexpected token '{found}'")
+            };
+            self.errors.push(node_error(node, code, message));
         }
     }
 }
 
+/// Build a parsing [`InterpreterError`] for `node`, with its start position
+/// converted to 1-based and its `source` set to just the line it starts on.
+fn node_error(node: tree_sitter::Node, code: &str, message: String) -> InterpreterError {
+    let point = node.start_position();
+    let position = SourcePosition {
+        line: point.row + 1,
+        column: point.column + 1,
+    };
+    let line = code.lines().nth(point.row).unwrap_or("").to_string();
+    InterpreterError::parsing_error(message, Some(position), Some(line))
+}
+
+/// A 1-based line/column position, as rendered by an editor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineCol {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl From<tree_sitter::Point> for LineCol {
+    fn from(point: tree_sitter::Point) -> Self {
+        LineCol {
+            line: point.row + 1,
+            col: point.column + 1,
+        }
+    }
+}
+
+/// One `ERROR`/`MISSING` node recovered by [`RholangParser::parse_recovering`], with its
+/// byte range mapped to a `{line, col}` start/end so a caller can render it the way an
+/// editor would without re-walking the tree itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub byte_range: Range<usize>,
+    pub start: LineCol,
+    pub end: LineCol,
+}
+
+/// Walk every `ERROR`/`MISSING` node under `root` (in source order) into a [`Diagnostic`].
+/// A `MISSING` node is synthesized by tree-sitter and has no children of its own worth
+/// recursing into; an `ERROR` node's children may themselves contain further recovery
+/// points (e.g. two bad statements in the same block), so those are still visited.
+fn collect_diagnostics(root: &tree_sitter::Node, source: &str, into: &mut Vec<Diagnostic>) {
+    if root.is_missing() {
+        into.push(Diagnostic {
+            message: format!("missing {}", root.kind()),
+            byte_range: root.byte_range(),
+            start: root.start_position().into(),
+            end: root.end_position().into(),
+        });
+        return;
+    }
+
+    if root.is_error() {
+        let found = root.utf8_text(source.as_bytes()).unwrap_or_default();
+        let message = if found.is_empty() {
+            "unexpected token".to_string()
+        } else {
+            format!("unexpected token '{found}'")
+        };
+        into.push(Diagnostic {
+            message,
+            byte_range: root.byte_range(),
+            start: root.start_position().into(),
+            end: root.end_position().into(),
+        });
+    }
+
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        collect_diagnostics(&child, source, into);
+    }
+}
+
+/// Whether `node` or any of its descendants is an `ERROR`/`MISSING` node whose
+/// span reaches `len` (the end of the source it was parsed from)
+fn error_reaches_eof(node: tree_sitter::Node, len: usize) -> bool {
+    if (node.is_error() || node.is_missing()) && node.end_byte() >= len {
+        return true;
+    }
+
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .any(|child| error_reaches_eof(child, len))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,4 +258,58 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_is_incomplete_is_true_for_an_unclosed_block() -> Result<()> {
+        let mut parser = RholangParser::new()?;
+        assert!(parser.is_incomplete("new channel in { @\"stdout\"!(\"Hello, world!\")"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_incomplete_is_false_for_valid_code() -> Result<()> {
+        let mut parser = RholangParser::new()?;
+        assert!(!parser.is_incomplete("new channel in { @\"stdout\"!(\"Hello, world!\") }"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_recovering_returns_no_diagnostics_for_valid_code() -> Result<()> {
+        let mut parser = RholangParser::new()?;
+        let (tree, diagnostics) =
+            parser.parse_recovering("new channel in { @\"stdout\"!(\"Hello, world!\") }");
+
+        assert!(!tree.root_node().has_error());
+        assert!(diagnostics.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_recovering_reports_a_diagnostic_but_still_returns_a_tree() -> Result<()> {
+        let mut parser = RholangParser::new()?;
+        let (tree, diagnostics) = parser.parse_recovering("new channel in { @\"stdout\"!(");
+
+        assert!(tree.root_node().has_error());
+        assert!(!diagnostics.is_empty());
+        assert!(diagnostics[0].byte_range.start <= diagnostics[0].byte_range.end);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_recovering_finds_every_error_in_one_pass() -> Result<()> {
+        let mut parser = RholangParser::new()?;
+        // Two independent bad sends, separated by a valid one in between
+        let (_, diagnostics) = parser.parse_recovering(
+            "@\"x\"!(1 +); @\"y\"!(2); @\"z\"!(3 +)",
+        );
+
+        assert!(
+            diagnostics.len() >= 2,
+            "expected at least 2 diagnostics, got {diagnostics:?}"
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,100 @@
+//! Promotes the ad-hoc discovery/pass-fail counting that used to live inline
+//! in `tests/process_examples_test.rs` into a reusable runner: [`discover_files`]
+//! finds the `.rho` files, [`run_suite`] interprets each one through a
+//! [`FakeRholangInterpreter`] and reports structured [`TestOutcome`]s, and the
+//! `rholang-test` binary (`src/bin/rholang_test.rs`) wraps that in a CLI with
+//! seeded shuffling and a watch mode.
+
+use crate::discovery::discover_files;
+use crate::errors::InterpretationResult;
+use crate::FakeRholangInterpreter;
+use anyhow::Result;
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// The outcome of interpreting a single `.rho` file.
+#[derive(Debug, Clone)]
+pub struct TestOutcome {
+    pub path: PathBuf,
+    pub passed: bool,
+    pub message: String,
+    /// Populated from `InterpretationResult::Error`'s position when the
+    /// failure was a parsing error.
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub elapsed: Duration,
+}
+
+/// Aggregate pass/fail counts and per-file outcomes for a full run.
+#[derive(Debug, Clone, Default)]
+pub struct SuiteReport {
+    pub outcomes: Vec<TestOutcome>,
+}
+
+impl SuiteReport {
+    pub fn pass_count(&self) -> usize {
+        self.outcomes.iter().filter(|o| o.passed).count()
+    }
+
+    pub fn fail_count(&self) -> usize {
+        self.outcomes.len() - self.pass_count()
+    }
+
+    pub fn all_passed(&self) -> bool {
+        self.fail_count() == 0
+    }
+}
+
+/// Options controlling discovery and ordering for a [`run_suite`] call.
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Only run files whose stem contains this substring.
+    pub filter: Option<String>,
+    /// When set, shuffle the discovered files with this seed instead of
+    /// running them in discovery order.
+    pub seed: Option<u64>,
+}
+
+/// Discover `.rho` files under `roots`, optionally filter and shuffle them
+/// per `options`, then interpret each one in turn through a fresh
+/// [`FakeRholangInterpreter`].
+pub async fn run_suite(roots: &[PathBuf], options: &RunOptions) -> Result<SuiteReport> {
+    let mut files = discover_files(roots, options.filter.as_deref())?;
+
+    if let Some(seed) = options.seed {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        files.shuffle(&mut rng);
+    }
+
+    let mut interpreter = FakeRholangInterpreter::new()?;
+    let mut outcomes = Vec::with_capacity(files.len());
+
+    for path in files {
+        let start = Instant::now();
+        let code = std::fs::read_to_string(&path)?;
+        let outcome = match interpreter.interpret_async(&code).await {
+            InterpretationResult::Success(message) => TestOutcome {
+                path,
+                passed: true,
+                message,
+                line: None,
+                column: None,
+                elapsed: start.elapsed(),
+            },
+            InterpretationResult::Error(err) => TestOutcome {
+                path,
+                passed: false,
+                line: err.position.as_ref().map(|p| p.line),
+                column: err.position.as_ref().map(|p| p.column),
+                message: err.message,
+                elapsed: start.elapsed(),
+            },
+        };
+        outcomes.push(outcome);
+    }
+
+    Ok(SuiteReport { outcomes })
+}
@@ -1,30 +1,9 @@
 use anyhow::Result;
+use rholang_fake::discovery::discover_files;
 use rholang_fake::{FakeRholangInterpreter, InterpretationResult};
 use std::env;
 use std::fs;
-use std::path::{Path, PathBuf};
-
-/// Find all Rholang files (*.rho) in a directory and its subdirectories
-fn find_rholang_files(dir: &Path) -> Result<Vec<PathBuf>> {
-    let mut result = Vec::new();
-    if dir.is_dir() {
-        for entry in fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_dir() {
-                // Recursively search subdirectories
-                let mut subdirectory_files = find_rholang_files(&path)?;
-                result.append(&mut subdirectory_files);
-            } else if let Some(extension) = path.extension() {
-                // Check if the file has a .rho extension
-                if extension == "rho" {
-                    result.push(path);
-                }
-            }
-        }
-    }
-    Ok(result)
-}
+use std::path::Path;
 
 /// Read the content of a file
 fn read_file(path: &Path) -> Result<String> {
@@ -56,7 +35,7 @@ async fn test_process_examples() -> Result<()> {
         .unwrap_or(&current_dir);
     let examples_dir = project_root.join("rholang-fake").join("examples");
     println!("Looking for Rholang files in: {}", examples_dir.display());
-    let rholang_files = find_rholang_files(&examples_dir)?;
+    let rholang_files = discover_files(&[examples_dir], None)?;
 
     // Make sure we found some files
     assert!(
@@ -6,6 +6,8 @@ use jni::objects::{JClass, JString};
 use jni::sys::{jboolean, jstring};
 use jni::JNIEnv;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use validated::Validated;
 
 use rholang_parser::{errors::ParseResult, RholangParser};
 
@@ -14,8 +16,10 @@ use rholang_parser::{errors::ParseResult, RholangParser};
 pub struct ParserResult {
     /// Whether the code is valid Rholang
     pub valid: bool,
-    /// The parse tree as a string (only if valid)
-    pub tree: Option<String>,
+    /// The parse tree, either as a plain string (from [`Java_org_rholang_lang_parser_RholangParserJNI_parse`])
+    /// or as a structured JSON object with source spans (from
+    /// [`Java_org_rholang_lang_parser_RholangParserJNI_parseAst`]) -- only set if valid
+    pub tree: Option<Value>,
     /// Error message (only if not valid)
     pub error: Option<String>,
 }
@@ -74,7 +78,7 @@ pub extern "system" fn Java_org_rholang_lang_parser_RholangParserJNI_parse(
         Ok(mut parser) => match parser.get_tree_string(&code) {
             ParseResult::Success(tree) => ParserResult {
                 valid: true,
-                tree: Some(tree),
+                tree: Some(Value::String(tree)),
                 error: None,
             },
             ParseResult::Error(err) => ParserResult {
@@ -82,6 +86,11 @@ pub extern "system" fn Java_org_rholang_lang_parser_RholangParserJNI_parse(
                 tree: None,
                 error: Some(format!("{}", err)),
             },
+            ParseResult::Incomplete => ParserResult {
+                valid: false,
+                tree: None,
+                error: Some("Incomplete input".to_string()),
+            },
         },
         Err(e) => ParserResult {
             valid: false,
@@ -101,6 +110,58 @@ pub extern "system" fn Java_org_rholang_lang_parser_RholangParserJNI_parse(
     string_to_jstring(&env, &json)
 }
 
+/// Parse the given code and return the AST as a structured JSON tree
+///
+/// This function is exposed to Java via JNI. Unlike
+/// [`Java_org_rholang_lang_parser_RholangParserJNI_parse`], `ParserResult::tree`
+/// here is a JSON object (with every node's source span) instead of a string
+/// dump of the tree, so editor/tooling consumers can walk it directly rather
+/// than scraping a rendered string.
+#[no_mangle]
+pub extern "system" fn Java_org_rholang_lang_parser_RholangParserJNI_parseAst(
+    mut env: JNIEnv,
+    _class: JClass,
+    code: JString,
+) -> jstring {
+    let code: String = match env.get_string(&code) {
+        Ok(s) => s.into(),
+        Err(e) => {
+            let result = ParserResult {
+                valid: false,
+                tree: None,
+                error: Some(format!("Failed to get string from Java: {:?}", e)),
+            };
+            return string_to_jstring(&env, &serde_json::to_string(&result).unwrap_or_else(|e| {
+                format!("{{\"valid\":false,\"tree\":null,\"error\":\"Failed to serialize error: {}\"}}", e)
+            }));
+        }
+    };
+
+    let parser = RholangParser::new();
+    let result = match parser.parse_to_json(&code) {
+        Validated::Good(tree) => ParserResult {
+            valid: true,
+            tree: Some(tree),
+            error: None,
+        },
+        Validated::Fail(err) => ParserResult {
+            valid: false,
+            tree: None,
+            error: Some(format!("{}", err)),
+        },
+    };
+
+    // Convert the result to a JSON string
+    let json = serde_json::to_string(&result).unwrap_or_else(|e| {
+        format!(
+            "{{\"valid\":false,\"tree\":null,\"error\":\"Failed to serialize result: {}\"}}",
+            e
+        )
+    });
+
+    string_to_jstring(&env, &json)
+}
+
 /// Helper function to convert a Rust String to a Java jstring
 fn string_to_jstring<'a>(env: &JNIEnv<'a>, string: &str) -> jstring {
     let output = env
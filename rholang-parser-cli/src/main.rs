@@ -1,10 +1,12 @@
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use rholang_fake::{FakeRholangInterpreter, InterpretationResult};
 use rholang_parser::RholangParser;
 use serde::Serialize;
 use std::fs;
-use std::io::{self, Read};
+use std::io::{self, BufRead, Read, Write};
 use std::path::PathBuf;
+use tree_sitter::Parser as TsParser;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -24,6 +26,10 @@ enum Commands {
         /// Pretty-print the output JSON
         #[arg(short, long)]
         pretty: bool,
+
+        /// How to render the parse tree
+        #[arg(short, long, value_enum, default_value_t = Format::Sexp)]
+        format: Format,
     },
 
     /// Check if the code is valid Rholang
@@ -32,6 +38,32 @@ enum Commands {
         #[arg(short, long)]
         input: Option<PathBuf>,
     },
+
+    /// Start an interactive REPL: evaluate each line through a `FakeRholangInterpreter`,
+    /// buffering further lines (with a continuation prompt) whenever the input is
+    /// merely incomplete -- unbalanced brackets, or a parse that only fails at EOF --
+    /// rather than reporting an error. `new` declarations persist across evaluations
+    /// for the rest of the session; a blank line cancels a buffered multi-line entry.
+    Repl,
+}
+
+/// How `Commands::Parse` should render the parse tree
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    /// Tree-sitter's own S-expression form, wrapped in a [`ParseResult`]
+    Sexp,
+    /// A nested JSON tree: every node's kind, start/end row+column, and
+    /// children, with leaf nodes also carrying their `utf8_text`
+    JsonAst,
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Format::Sexp => write!(f, "sexp"),
+            Format::JsonAst => write!(f, "json-ast"),
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -41,37 +73,117 @@ struct ParseResult {
     error: Option<String>,
 }
 
+/// A single tree-sitter node rendered as JSON: its kind, source span, and
+/// recursively-built children. Leaf nodes (no children) also carry their
+/// own source text, since there's nothing else to descend into for it.
+#[derive(Serialize)]
+struct AstNode {
+    kind: String,
+    start: AstPosition,
+    end: AstPosition,
+    children: Vec<AstNode>,
+    text: Option<String>,
+}
+
+#[derive(Serialize)]
+struct AstPosition {
+    row: usize,
+    column: usize,
+}
+
+impl AstNode {
+    fn from_node(node: tree_sitter::Node, source: &str) -> Self {
+        let mut cursor = node.walk();
+        let children: Vec<AstNode> = node
+            .children(&mut cursor)
+            .map(|child| AstNode::from_node(child, source))
+            .collect();
+
+        let text = children.is_empty().then(|| {
+            node.utf8_text(source.as_bytes())
+                .unwrap_or_default()
+                .to_string()
+        });
+
+        AstNode {
+            kind: node.kind().to_string(),
+            start: AstPosition {
+                row: node.start_position().row,
+                column: node.start_position().column,
+            },
+            end: AstPosition {
+                row: node.end_position().row,
+                column: node.end_position().column,
+            },
+            children,
+            text,
+        }
+    }
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match &cli.command {
-        Commands::Parse { input, pretty } => {
+        Commands::Parse {
+            input,
+            pretty,
+            format,
+        } => {
             let code = read_input(input)?;
-            let mut parser = RholangParser::new().context("Failed to create parser")?;
-            
-            let result = match parser.get_tree_string(&code) {
-                rholang_parser::errors::ParseResult::Success(tree) => ParseResult {
-                    valid: true,
-                    tree: Some(tree),
-                    error: None,
-                },
-                rholang_parser::errors::ParseResult::Error(err) => ParseResult {
-                    valid: false,
-                    tree: None,
-                    error: Some(format!("{}", err)),
-                },
-            };
 
-            if *pretty {
-                println!("{}", serde_json::to_string_pretty(&result)?);
-            } else {
-                println!("{}", serde_json::to_string(&result)?);
+            match format {
+                Format::Sexp => {
+                    let mut parser = RholangParser::new().context("Failed to create parser")?;
+
+                    let result = match parser.get_tree_string(&code) {
+                        rholang_parser::errors::ParseResult::Success(tree) => ParseResult {
+                            valid: true,
+                            tree: Some(tree),
+                            error: None,
+                        },
+                        rholang_parser::errors::ParseResult::Error(err) => ParseResult {
+                            valid: false,
+                            tree: None,
+                            error: Some(format!("{}", err)),
+                        },
+                        rholang_parser::errors::ParseResult::Incomplete => ParseResult {
+                            valid: false,
+                            tree: None,
+                            error: Some("Incomplete input".to_string()),
+                        },
+                    };
+
+                    if *pretty {
+                        println!("{}", serde_json::to_string_pretty(&result)?);
+                    } else {
+                        println!("{}", serde_json::to_string(&result)?);
+                    }
+                }
+                Format::JsonAst => {
+                    let mut parser = TsParser::new();
+                    let language = rholang_tree_sitter::LANGUAGE.into();
+                    parser
+                        .set_language(&language)
+                        .context("failed to load the Rholang grammar")?;
+                    let tree = parser
+                        .parse(&code, None)
+                        .context("tree-sitter failed to parse the input")?;
+
+                    let ast = AstNode::from_node(tree.root_node(), &code);
+
+                    if *pretty {
+                        println!("{}", serde_json::to_string_pretty(&ast)?);
+                    } else {
+                        println!("{}", serde_json::to_string(&ast)?);
+                    }
+                }
             }
         }
         Commands::Check { input } => {
             let code = read_input(input)?;
             let mut parser = RholangParser::new().context("Failed to create parser")?;
-            
+
             let valid = parser.is_valid(&code);
             let result = ParseResult {
                 valid,
@@ -81,6 +193,65 @@ fn main() -> Result<()> {
 
             println!("{}", serde_json::to_string(&result)?);
         }
+        Commands::Repl => run_repl()?,
+    }
+
+    Ok(())
+}
+
+/// Read lines from stdin, evaluating each buffered entry through a
+/// `FakeRholangInterpreter` once it stops looking incomplete. The interpreter (and
+/// thus its `new`-declared variables) lives for the whole session, so later lines
+/// can refer to names declared earlier.
+fn run_repl() -> Result<()> {
+    let mut interpreter = FakeRholangInterpreter::new().context("Failed to create interpreter")?;
+    let stdin = io::stdin();
+    let mut buffer = String::new();
+
+    loop {
+        print!("{}", if buffer.is_empty() { "rho> " } else { "...  " });
+        io::stdout().flush().context("Failed to flush stdout")?;
+
+        let mut line = String::new();
+        let bytes_read = stdin
+            .lock()
+            .read_line(&mut line)
+            .context("Failed to read from stdin")?;
+        if bytes_read == 0 {
+            if !buffer.trim().is_empty() {
+                println!("\nUnterminated input at end of stream; discarding");
+            }
+            break;
+        }
+
+        let line = line.trim_end_matches(['\n', '\r']);
+
+        if !buffer.is_empty() && line.is_empty() {
+            println!("Cancelled");
+            buffer.clear();
+            continue;
+        }
+
+        if !buffer.is_empty() {
+            buffer.push('\n');
+        }
+        buffer.push_str(line);
+
+        if buffer.trim().is_empty() {
+            buffer.clear();
+            continue;
+        }
+
+        if interpreter.is_incomplete(&buffer) {
+            continue;
+        }
+
+        match interpreter.interpret(&buffer) {
+            InterpretationResult::Success(output) => println!("{}", output),
+            InterpretationResult::Error(err) => println!("Error: {}", err),
+        }
+
+        buffer.clear();
     }
 
     Ok(())
@@ -97,4 +268,4 @@ fn read_input(input: &Option<PathBuf>) -> Result<String> {
             Ok(buffer)
         }
     }
-}
\ No newline at end of file
+}
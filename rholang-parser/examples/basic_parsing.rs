@@ -23,6 +23,9 @@ fn main() -> Result<()> {
         rholang_parser::errors::ParseResult::Error(err) => {
             println!("Error: {}", err);
         }
+        rholang_parser::errors::ParseResult::Incomplete => {
+            println!("Incomplete input");
+        }
     }
 
     // Get a pretty-printed representation of the parse tree
@@ -33,6 +36,9 @@ fn main() -> Result<()> {
         rholang_parser::errors::ParseResult::Error(err) => {
             println!("Error: {}", err);
         }
+        rholang_parser::errors::ParseResult::Incomplete => {
+            println!("Incomplete input");
+        }
     }
 
     // Example with invalid code
@@ -53,6 +59,9 @@ fn main() -> Result<()> {
         rholang_parser::errors::ParseResult::Error(err) => {
             println!("Error: {}", err);
         }
+        rholang_parser::errors::ParseResult::Incomplete => {
+            println!("Incomplete input");
+        }
     }
 
     Ok(())
@@ -3,13 +3,19 @@ use std::{
     ops::Deref,
 };
 
+use serde::Serialize;
 use smallvec::{SmallVec, ToSmallVec};
 
 use crate::{SourcePos, SourceSpan};
 
 pub type ProcList<'a> = SmallVec<[AnnProc<'a>; 1]>;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+/// `Serialize`able mirror of the tree [`parser::ASTBuilder`](crate::parser::ASTBuilder)
+/// produces -- used by [`parser::RholangParser::parse_to_json`](crate::parser::RholangParser::parse_to_json)
+/// to hand a structured tree to JSON/FFI consumers instead of a string dump.
+/// `serde_json::to_value` walks these borrowed nodes into owned `Value`s, so the
+/// result outlives the arena the borrows point into.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub enum Proc<'ast> {
     Nil,
     BoolLiteral(bool),
@@ -111,9 +117,16 @@ pub enum Proc<'ast> {
     },
 
     Bad, // bad process usually represents a parsing error
+
+    // Placeholder for an `ERROR`/`MISSING` node kept by a resilient parse
+    // (see `ParseMode::Resilient`) instead of discarding the subtree outright.
+    Error {
+        partial: Option<AnnProc<'ast>>,
+        recovered_children: ProcList<'ast>,
+    },
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
 pub struct AnnProc<'ast> {
     pub proc: &'ast Proc<'ast>,
     pub span: SourceSpan,
@@ -121,7 +134,7 @@ pub struct AnnProc<'ast> {
 
 // process variables and names
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct Id<'ast> {
     pub name: &'ast str,
     pub pos: SourcePos,
@@ -147,7 +160,7 @@ impl PartialOrd for Id<'_> {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize)]
 pub enum Var<'ast> {
     Wildcard,
     Id(Id<'ast>),
@@ -172,7 +185,7 @@ impl<'a> TryFrom<AnnProc<'a>> for Var<'a> {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
 pub enum Name<'ast> {
     ProcVar(Var<'ast>),
     Quote(&'ast Proc<'ast>),
@@ -190,7 +203,7 @@ impl<'a> TryFrom<&Proc<'a>> for Name<'a> {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
 pub struct AnnName<'ast> {
     pub name: Name<'ast>,
     pub span: SourceSpan,
@@ -207,7 +220,7 @@ impl<'a> TryFrom<AnnProc<'a>> for AnnName<'a> {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct Names<'ast> {
     pub names: SmallVec<[AnnName<'ast>; 1]>,
     pub remainder: Option<Var<'ast>>,
@@ -284,14 +297,14 @@ impl<'a> Names<'a> {
 
 // expressions
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
 pub enum UnaryExpOp {
     Not,
     Neg,
     Negation,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
 pub enum BinaryExpOp {
     Or,
     And,
@@ -318,12 +331,12 @@ pub enum BinaryExpOp {
 
 pub type Receipts<'a> = SmallVec<[Receipt<'a>; 1]>;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub struct Receipt<'a> {
     pub binds: SmallVec<[Bind<'a>; 1]>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub enum Bind<'ast> {
     Linear {
         lhs: Names<'ast>,
@@ -341,7 +354,7 @@ pub enum Bind<'ast> {
 
 // source definitions
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub enum Source<'ast> {
     Simple {
         name: AnnName<'ast>,
@@ -357,7 +370,7 @@ pub enum Source<'ast> {
 
 // case in match expression
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
 pub struct Case<'ast> {
     pub pattern: AnnProc<'ast>,
     pub proc: AnnProc<'ast>,
@@ -365,13 +378,13 @@ pub struct Case<'ast> {
 
 // branch in select expression
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub struct SelectPattern<'ast> {
     pub lhs: Names<'ast>,
     pub rhs: Source<'ast>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub struct Branch<'ast> {
     pub patterns: Vec<SelectPattern<'ast>>,
     pub proc: AnnProc<'ast>,
@@ -379,7 +392,7 @@ pub struct Branch<'ast> {
 
 // ground terms and expressions
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize)]
 pub struct Uri<'a>(&'a str);
 
 impl Deref for Uri<'_> {
@@ -396,7 +409,7 @@ impl<'a> From<&'a str> for Uri<'a> {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
 pub enum SimpleType {
     Bool,
     Int,
@@ -409,7 +422,7 @@ pub enum SimpleType {
 
 pub type KeyValuePair<'ast> = (AnnProc<'ast>, AnnProc<'ast>);
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub enum Collection<'ast> {
     List {
         elements: Vec<AnnProc<'ast>>,
@@ -431,7 +444,7 @@ pub enum Collection<'ast> {
 
 // sends
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
 pub enum SendType {
     Single,
     Multiple,
@@ -439,7 +452,7 @@ pub enum SendType {
 
 // bundles
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
 pub enum BundleType {
     BundleEquiv,
     BundleWrite,
@@ -449,7 +462,7 @@ pub enum BundleType {
 
 // let declarations
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub enum LetBinding<'ast> {
     Single {
         lhs: AnnName<'ast>,
@@ -463,7 +476,7 @@ pub enum LetBinding<'ast> {
 
 // new name declaration
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct NameDecl<'ast> {
     pub id: Id<'ast>,
     pub uri: Option<Uri<'ast>>,
@@ -491,13 +504,13 @@ impl PartialOrd for NameDecl<'_> {
 
 // synchronous send continuations
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
 pub enum SyncSendCont<'ast> {
     Empty,
     NonEmpty(AnnProc<'ast>),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
 pub enum VarRefKind {
     Proc,
     Name,
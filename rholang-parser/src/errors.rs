@@ -27,6 +27,20 @@ impl fmt::Display for SourcePosition {
     }
 }
 
+/// One frame of the context stack on a [`ParserError`]: a static label naming
+/// the grammar construct being parsed (e.g. `"send channel"`, `"bundle
+/// body"`) and the position the AST builder was at when it descended into
+/// that construct. Pushed innermost-last by [`ParserError::push_context`] as
+/// the builder recurses, winnow-style, so the headline error can be
+/// explained by the trail of constructs that were being parsed around it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseContext {
+    /// The construct being parsed, e.g. `"send channel"`
+    pub label: &'static str,
+    /// Where the builder was when it entered this construct
+    pub position: SourcePosition,
+}
+
 /// Detailed error information for the Rholang parser
 #[derive(Debug, Clone)]
 pub struct ParserError {
@@ -38,6 +52,10 @@ pub struct ParserError {
     pub position: Option<SourcePosition>,
     /// The source code that caused the error (if available)
     pub source: Option<String>,
+    /// The stack of grammar constructs the AST builder was descending through
+    /// when this error occurred, innermost-last. Empty unless a caller has
+    /// called [`Self::push_context`].
+    pub context: Vec<ParseContext>,
 }
 
 impl ParserError {
@@ -52,6 +70,7 @@ impl ParserError {
             message: message.into(),
             position,
             source,
+            context: Vec::new(),
         }
     }
 
@@ -66,6 +85,7 @@ impl ParserError {
             message: message.into(),
             position,
             source,
+            context: Vec::new(),
         }
     }
 
@@ -76,8 +96,18 @@ impl ParserError {
             message: message.into(),
             position: None,
             source: None,
+            context: Vec::new(),
         }
     }
+
+    /// Push a context frame onto the end of this error's stack and return it:
+    /// `.push_context("send channel", position)`. Frames are kept in push
+    /// order, so pushing each construct as the builder descends into it
+    /// leaves the stack innermost-last.
+    pub fn push_context(mut self, label: &'static str, position: SourcePosition) -> Self {
+        self.context.push(ParseContext { label, position });
+        self
+    }
 }
 
 impl fmt::Display for ParserError {
@@ -96,6 +126,16 @@ impl fmt::Display for ParserError {
             write!(f, "\nSource: {}", source)?;
         }
 
+        if !self.context.is_empty() {
+            let trail = self
+                .context
+                .iter()
+                .map(|frame| format!("while parsing {} at {}", frame.label, frame.position))
+                .collect::<Vec<_>>()
+                .join(" → ");
+            write!(f, "\n{trail}")?;
+        }
+
         Ok(())
     }
 }
@@ -109,6 +149,13 @@ pub enum ParseResult<T> {
     Success(T),
     /// Error during parsing
     Error(ParserError),
+    /// Parsing failed only because the source ends prematurely -- an open
+    /// bracket, an unclosed string/block comment, or a trailing binary
+    /// operator still expecting its right operand -- as opposed to a
+    /// genuine syntax error mid-stream. A caller reading input line by line
+    /// (a REPL) can use this to keep buffering instead of reporting a real
+    /// error, see [`classify_incomplete`].
+    Incomplete,
 }
 
 impl<T> ParseResult<T> {
@@ -132,6 +179,11 @@ impl<T> ParseResult<T> {
         matches!(self, ParseResult::Error(_))
     }
 
+    /// Returns true if parsing only failed because the source was cut off mid-construct
+    pub fn is_incomplete(&self) -> bool {
+        matches!(self, ParseResult::Incomplete)
+    }
+
     /// Maps a ParseResult<T> to ParseResult<U> by applying a function to the contained Success value
     pub fn map<U, F>(self, f: F) -> ParseResult<U>
     where
@@ -140,24 +192,29 @@ impl<T> ParseResult<T> {
         match self {
             ParseResult::Success(value) => ParseResult::Success(f(value)),
             ParseResult::Error(err) => ParseResult::Error(err),
+            ParseResult::Incomplete => ParseResult::Incomplete,
         }
     }
 
-    /// Unwraps the success value, panics if the result is an error
+    /// Unwraps the success value, panics if the result is not a success
     pub fn unwrap(self) -> T {
         match self {
             ParseResult::Success(value) => value,
             ParseResult::Error(err) => panic!("Called unwrap on an error result: {}", err),
+            ParseResult::Incomplete => panic!("Called unwrap on an incomplete result"),
         }
     }
 
-    /// Unwraps the error value, panics if the result is a success
+    /// Unwraps the error value, panics if the result is not an error
     pub fn unwrap_err(self) -> ParserError {
         match self {
             ParseResult::Success(_) => {
                 panic!("Called unwrap_err on a success result")
             }
             ParseResult::Error(err) => err,
+            ParseResult::Incomplete => {
+                panic!("Called unwrap_err on an incomplete result")
+            }
         }
     }
 }
@@ -167,3 +224,89 @@ impl<T> From<ParserError> for ParseResult<T> {
         ParseResult::Error(error)
     }
 }
+
+/// One entry of the bracket/quote stack tracked by [`classify_incomplete`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OpenDelimiter {
+    Paren,
+    Bracket,
+    Brace,
+    StringLiteral,
+    BlockComment,
+}
+
+/// Scan `source` character by character, as a lightweight stand-in for a real
+/// tokenizer, tracking a stack of still-open brackets/quotes/comments along
+/// with whether the source ends on a binary operator still expecting its
+/// right operand. Borrows winnow's partial-stream idea: at EOF, if that stack
+/// is non-empty (or a trailing operator is dangling) and no structural
+/// mismatch was seen before EOF, the input is merely cut off, not invalid --
+/// more bytes may still arrive and complete it.
+///
+/// Returns `true` only when the source is *exactly* that "cut off, not
+/// wrong" case. A real mismatch (e.g. a stray unmatched `)`) returns `false`
+/// so the caller reports a genuine error instead of buffering forever.
+pub fn classify_incomplete(source: &str) -> bool {
+    let mut stack: Vec<OpenDelimiter> = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut last_significant: Option<char> = None;
+
+    while let Some(c) = chars.next() {
+        let was_in_comment = stack.last() == Some(&OpenDelimiter::BlockComment);
+        match stack.last() {
+            Some(OpenDelimiter::StringLiteral) => match c {
+                '\\' => {
+                    chars.next();
+                }
+                '"' => {
+                    stack.pop();
+                }
+                _ => {}
+            },
+            Some(OpenDelimiter::BlockComment) => {
+                if c == '*' && chars.peek() == Some(&'/') {
+                    chars.next();
+                    stack.pop();
+                }
+            }
+            _ => match c {
+                '"' => stack.push(OpenDelimiter::StringLiteral),
+                '/' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    stack.push(OpenDelimiter::BlockComment);
+                }
+                '(' => stack.push(OpenDelimiter::Paren),
+                '[' => stack.push(OpenDelimiter::Bracket),
+                '{' => stack.push(OpenDelimiter::Brace),
+                ')' => {
+                    if stack.pop() != Some(OpenDelimiter::Paren) {
+                        return false;
+                    }
+                }
+                ']' => {
+                    if stack.pop() != Some(OpenDelimiter::Bracket) {
+                        return false;
+                    }
+                }
+                '}' => {
+                    if stack.pop() != Some(OpenDelimiter::Brace) {
+                        return false;
+                    }
+                }
+                _ => {}
+            },
+        }
+
+        let in_comment = was_in_comment || stack.last() == Some(&OpenDelimiter::BlockComment);
+        if !c.is_whitespace() && !in_comment {
+            last_significant = Some(c);
+        }
+    }
+
+    let trailing_operator = matches!(
+        last_significant,
+        Some('+') | Some('-') | Some('*') | Some('/') | Some('%') | Some('&') | Some('|')
+    );
+
+    !stack.is_empty() || trailing_operator
+}
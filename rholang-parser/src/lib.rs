@@ -5,13 +5,16 @@
 
 use std::fmt::{Debug, Display, Write};
 
+use serde::Serialize;
+
 pub mod ast;
+pub mod errors;
 pub mod parser;
 
 pub use parser::RholangParser;
 
 /// a position in the source code. 1-based
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub struct SourcePos {
     pub line: usize,
     pub col: usize,
@@ -36,7 +39,7 @@ impl From<tree_sitter::Point> for SourcePos {
 }
 
 /// a span in the source code (exclusive)
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub struct SourceSpan {
     pub start: SourcePos,
     pub end: SourcePos,
@@ -0,0 +1,1392 @@
+//! Span-insensitive, binder-aware equivalence over `AnnProc` trees.
+//!
+//! [`alpha_eq`] is what a linter's "ignore spans" AST equality would look
+//! like extended to care about *which* names are bound where, rather than
+//! what they're spelled: two trees that only differ by a consistent
+//! renaming of bound names compare equal. [`alpha_hash`] hashes a single
+//! tree the same way, so alpha-equivalent trees land in the same bucket of
+//! a `HashMap`.
+//!
+//! The binding constructs this module knows about are the four that
+//! introduce names over a sub-term: `New`'s `NameDecl`s, `ForComprehension`'s
+//! `Bind` names, `Contract`'s formals, and `Let`'s bindings. Walking either
+//! side of a comparison, each one assigns freshly-minted, shared De Bruijn
+//! *levels* (counting outward-in, zero at the outermost binder) to the names
+//! it introduces; occurrences of a bound name are then compared by level
+//! instead of by text, while anything never bound in either tree (a free
+//! name) falls back to comparing `Id::name` directly. All other binder-like
+//! positions this crate has — collection remainders, `match`/`select`
+//! patterns — are intentionally left out of scope here and so always
+//! compare as free names.
+//!
+//! Everything that isn't a name occurrence compares constructor-by-constructor
+//! as usual: literal values, `SendType`, `BundleType`, `{Unary,Binary}ExpOp`,
+//! and collection arity/remainder-presence all have to match exactly.
+//!
+//! `ForComprehension`'s binds are treated as one simultaneous group spanning
+//! the whole `for`, even across `;`-separated receipts, rather than each
+//! receipt seeing the previous one's bindings — a later receipt's source
+//! referencing an earlier receipt's bound name is the one shape this
+//! approximation gets wrong.
+//!
+//! `Par { left, right }` is flattened into its parallel components first
+//! (undoing however the grammar happened to associate a chain of `|`s) and
+//! compared/hashed as a multiset: [`par_components_eq`] matches components
+//! up via backtracking since no single greedy pairing is guaranteed to find
+//! one that exists, and [`hash_par_components`] combines each component's
+//! independently-computed digest with a commutative XOR so reordering
+//! `P | Q` to `Q | P` can't change the hash.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+
+use crate::ast::{
+    AnnName, AnnProc, BinaryExpOp, Bind, Branch, BundleType, Case, Collection, LetBinding, Name,
+    NameDecl, Names, Proc, Receipts, SelectPattern, SendType, SimpleType, Source, SyncSendCont,
+    UnaryExpOp, Var, VarRefKind,
+};
+use crate::parser::encoding::{collection_tag, tag};
+
+/// One binder position: either a name that can later be referenced, or a
+/// wildcard that consumes a De Bruijn level without ever being resolvable.
+#[derive(Clone, Copy)]
+enum Binder<'a> {
+    Named(&'a str),
+    Wildcard,
+}
+
+fn var_binder<'a>(var: &Var<'a>) -> Binder<'a> {
+    match var {
+        Var::Id(id) => Binder::Named(id.name),
+        Var::Wildcard => Binder::Wildcard,
+    }
+}
+
+/// Binder positions are always a bare proc variable in practice (the grammar
+/// never builds a `NameDecl`/formal/bind/let-lhs out of a quoted process);
+/// a `Quote` here is unreachable from real source, so it's treated as an
+/// anonymous (wildcard-like) binder rather than panicking.
+fn name_binder<'a>(name: &Name<'a>) -> Binder<'a> {
+    match name {
+        Name::ProcVar(var) => var_binder(var),
+        Name::Quote(_) => Binder::Wildcard,
+    }
+}
+
+type Env<'a> = Vec<Vec<(&'a str, u32)>>;
+
+fn resolve<'a>(env: &Env<'a>, name: &str) -> Option<u32> {
+    env.iter()
+        .rev()
+        .flat_map(|scope| scope.iter().rev())
+        .find(|(n, _)| *n == name)
+        .map(|(_, level)| *level)
+}
+
+fn bind_scope<'a>(binders: &[Binder<'a>], env: &mut Env<'a>, base_level: u32) {
+    let mut scope = Vec::with_capacity(binders.len());
+    for (i, binder) in binders.iter().enumerate() {
+        if let Binder::Named(name) = binder {
+            scope.push((*name, base_level + i as u32));
+        }
+    }
+    env.push(scope);
+}
+
+/// Assign the same run of fresh levels to `binders_a`/`binders_b` (which must
+/// be the same length — callers check arity before collecting binders) and
+/// push one new scope onto each side's environment.
+fn bind_simultaneous<'a>(
+    binders_a: &[Binder<'a>],
+    binders_b: &[Binder<'a>],
+    env_a: &mut Env<'a>,
+    env_b: &mut Env<'a>,
+    level: &mut u32,
+) {
+    debug_assert_eq!(binders_a.len(), binders_b.len());
+    let base = *level;
+    bind_scope(binders_a, env_a, base);
+    bind_scope(binders_b, env_b, base);
+    *level += binders_a.len() as u32;
+}
+
+fn pop_scope(env: &mut Env) {
+    env.pop();
+}
+
+/// Collect `a`/`b`'s bound names (plus remainder, if any) as parallel binder
+/// lists, or return `false` if their arities can't possibly line up.
+fn names_binders<'a>(
+    a: &Names<'a>,
+    b: &Names<'a>,
+    out_a: &mut Vec<Binder<'a>>,
+    out_b: &mut Vec<Binder<'a>>,
+) -> bool {
+    if a.names.len() != b.names.len() || a.remainder.is_some() != b.remainder.is_some() {
+        return false;
+    }
+    for (x, y) in a.names.iter().zip(&b.names) {
+        out_a.push(name_binder(&x.name));
+        out_b.push(name_binder(&y.name));
+    }
+    if let (Some(ra), Some(rb)) = (&a.remainder, &b.remainder) {
+        out_a.push(var_binder(ra));
+        out_b.push(var_binder(rb));
+    }
+    true
+}
+
+/// Flatten a (possibly nested) chain of `Par` into its parallel components,
+/// left-to-right as written.
+fn flatten_par<'a>(ann: &AnnProc<'a>, out: &mut Vec<AnnProc<'a>>) {
+    match ann.proc {
+        Proc::Par { left, right } => {
+            flatten_par(left, out);
+            flatten_par(right, out);
+        }
+        _ => out.push(*ann),
+    }
+}
+
+fn var_eq<'a>(a: &Var<'a>, b: &Var<'a>, env_a: &Env<'a>, env_b: &Env<'a>) -> bool {
+    match (a, b) {
+        (Var::Wildcard, Var::Wildcard) => true,
+        (Var::Id(ia), Var::Id(ib)) => match (resolve(env_a, ia.name), resolve(env_b, ib.name)) {
+            (Some(la), Some(lb)) => la == lb,
+            (None, None) => ia.name == ib.name,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn name_eq<'a>(
+    a: &Name<'a>,
+    b: &Name<'a>,
+    env_a: &mut Env<'a>,
+    env_b: &mut Env<'a>,
+    level: &mut u32,
+) -> bool {
+    match (a, b) {
+        (Name::ProcVar(va), Name::ProcVar(vb)) => var_eq(va, vb, env_a, env_b),
+        (Name::Quote(pa), Name::Quote(pb)) => procs_eq(pa, pb, env_a, env_b, level),
+        _ => false,
+    }
+}
+
+fn ann_name_eq<'a>(
+    a: &AnnName<'a>,
+    b: &AnnName<'a>,
+    env_a: &mut Env<'a>,
+    env_b: &mut Env<'a>,
+    level: &mut u32,
+) -> bool {
+    name_eq(&a.name, &b.name, env_a, env_b, level)
+}
+
+fn ann_proc_eq<'a>(
+    a: &AnnProc<'a>,
+    b: &AnnProc<'a>,
+    env_a: &mut Env<'a>,
+    env_b: &mut Env<'a>,
+    level: &mut u32,
+) -> bool {
+    procs_eq(a.proc, b.proc, env_a, env_b, level)
+}
+
+fn proc_list_eq<'a>(
+    a: &[AnnProc<'a>],
+    b: &[AnnProc<'a>],
+    env_a: &mut Env<'a>,
+    env_b: &mut Env<'a>,
+    level: &mut u32,
+) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| ann_proc_eq(x, y, env_a, env_b, level))
+}
+
+fn remainder_eq<'a>(a: &Option<Var<'a>>, b: &Option<Var<'a>>, env_a: &Env<'a>, env_b: &Env<'a>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(x), Some(y)) => var_eq(x, y, env_a, env_b),
+        _ => false,
+    }
+}
+
+fn source_eq<'a>(
+    a: &Source<'a>,
+    b: &Source<'a>,
+    env_a: &mut Env<'a>,
+    env_b: &mut Env<'a>,
+    level: &mut u32,
+) -> bool {
+    match (a, b) {
+        (Source::Simple { name: na }, Source::Simple { name: nb }) => {
+            ann_name_eq(na, nb, env_a, env_b, level)
+        }
+        (Source::ReceiveSend { name: na }, Source::ReceiveSend { name: nb }) => {
+            ann_name_eq(na, nb, env_a, env_b, level)
+        }
+        (
+            Source::SendReceive {
+                name: na,
+                inputs: ia,
+            },
+            Source::SendReceive {
+                name: nb,
+                inputs: ib,
+            },
+        ) => ann_name_eq(na, nb, env_a, env_b, level) && proc_list_eq(ia, ib, env_a, env_b, level),
+        _ => false,
+    }
+}
+
+fn select_pattern_eq<'a>(
+    a: &SelectPattern<'a>,
+    b: &SelectPattern<'a>,
+    env_a: &mut Env<'a>,
+    env_b: &mut Env<'a>,
+    level: &mut u32,
+) -> bool {
+    // `Select` is unreachable from real source today (see `parsing.rs`), and
+    // isn't one of this chunk's binding constructs, so its patterns compare
+    // their names as free rather than introducing a scope.
+    a.lhs.names.len() == b.lhs.names.len()
+        && a.lhs.remainder.is_some() == b.lhs.remainder.is_some()
+        && a.lhs
+            .names
+            .iter()
+            .zip(&b.lhs.names)
+            .all(|(x, y)| ann_name_eq(x, y, env_a, env_b, level))
+        && remainder_eq(&a.lhs.remainder, &b.lhs.remainder, env_a, env_b)
+        && source_eq(&a.rhs, &b.rhs, env_a, env_b, level)
+}
+
+fn branch_eq<'a>(
+    a: &Branch<'a>,
+    b: &Branch<'a>,
+    env_a: &mut Env<'a>,
+    env_b: &mut Env<'a>,
+    level: &mut u32,
+) -> bool {
+    a.patterns.len() == b.patterns.len()
+        && a.patterns
+            .iter()
+            .zip(&b.patterns)
+            .all(|(x, y)| select_pattern_eq(x, y, env_a, env_b, level))
+        && ann_proc_eq(&a.proc, &b.proc, env_a, env_b, level)
+}
+
+fn collection_eq<'a>(
+    a: &Collection<'a>,
+    b: &Collection<'a>,
+    env_a: &mut Env<'a>,
+    env_b: &mut Env<'a>,
+    level: &mut u32,
+) -> bool {
+    match (a, b) {
+        (
+            Collection::List {
+                elements: ea,
+                remainder: ra,
+            },
+            Collection::List {
+                elements: eb,
+                remainder: rb,
+            },
+        )
+        | (
+            Collection::Set {
+                elements: ea,
+                remainder: ra,
+            },
+            Collection::Set {
+                elements: eb,
+                remainder: rb,
+            },
+        ) => proc_list_eq(ea, eb, env_a, env_b, level) && remainder_eq(ra, rb, env_a, env_b),
+        (Collection::Tuple(ea), Collection::Tuple(eb)) => proc_list_eq(ea, eb, env_a, env_b, level),
+        (
+            Collection::Map {
+                elements: ea,
+                remainder: ra,
+            },
+            Collection::Map {
+                elements: eb,
+                remainder: rb,
+            },
+        ) => {
+            ea.len() == eb.len()
+                && ea.iter().zip(eb).all(|((ka, va), (kb, vb))| {
+                    ann_proc_eq(ka, kb, env_a, env_b, level) && ann_proc_eq(va, vb, env_a, env_b, level)
+                })
+                && remainder_eq(ra, rb, env_a, env_b)
+        }
+        _ => false,
+    }
+}
+
+fn new_eq<'a>(
+    decls_a: &[NameDecl<'a>],
+    decls_b: &[NameDecl<'a>],
+    proc_a: &AnnProc<'a>,
+    proc_b: &AnnProc<'a>,
+    env_a: &mut Env<'a>,
+    env_b: &mut Env<'a>,
+    level: &mut u32,
+) -> bool {
+    if decls_a.len() != decls_b.len() || !decls_a.iter().zip(decls_b).all(|(x, y)| x.uri == y.uri) {
+        return false;
+    }
+
+    let binders_a: Vec<Binder> = decls_a.iter().map(|d| Binder::Named(d.id.name)).collect();
+    let binders_b: Vec<Binder> = decls_b.iter().map(|d| Binder::Named(d.id.name)).collect();
+
+    bind_simultaneous(&binders_a, &binders_b, env_a, env_b, level);
+    let result = ann_proc_eq(proc_a, proc_b, env_a, env_b, level);
+    pop_scope(env_a);
+    pop_scope(env_b);
+    result
+}
+
+fn for_comprehension_eq<'a>(
+    receipts_a: &Receipts<'a>,
+    receipts_b: &Receipts<'a>,
+    proc_a: &AnnProc<'a>,
+    proc_b: &AnnProc<'a>,
+    env_a: &mut Env<'a>,
+    env_b: &mut Env<'a>,
+    level: &mut u32,
+) -> bool {
+    if receipts_a.len() != receipts_b.len() {
+        return false;
+    }
+
+    let mut binders_a = Vec::new();
+    let mut binders_b = Vec::new();
+
+    for (receipt_a, receipt_b) in receipts_a.iter().zip(receipts_b) {
+        if receipt_a.binds.len() != receipt_b.binds.len() {
+            return false;
+        }
+        for (bind_a, bind_b) in receipt_a.binds.iter().zip(&receipt_b.binds) {
+            let ok = match (bind_a, bind_b) {
+                (
+                    Bind::Linear {
+                        lhs: la,
+                        rhs: srca,
+                    },
+                    Bind::Linear {
+                        lhs: lb,
+                        rhs: srcb,
+                    },
+                ) => {
+                    source_eq(srca, srcb, env_a, env_b, level)
+                        && names_binders(la, lb, &mut binders_a, &mut binders_b)
+                }
+                (
+                    Bind::Repeated {
+                        lhs: la,
+                        rhs: namea,
+                    },
+                    Bind::Repeated {
+                        lhs: lb,
+                        rhs: nameb,
+                    },
+                )
+                | (
+                    Bind::Peek {
+                        lhs: la,
+                        rhs: namea,
+                    },
+                    Bind::Peek {
+                        lhs: lb,
+                        rhs: nameb,
+                    },
+                ) => {
+                    ann_name_eq(namea, nameb, env_a, env_b, level)
+                        && names_binders(la, lb, &mut binders_a, &mut binders_b)
+                }
+                _ => false,
+            };
+            if !ok {
+                return false;
+            }
+        }
+    }
+
+    bind_simultaneous(&binders_a, &binders_b, env_a, env_b, level);
+    let result = ann_proc_eq(proc_a, proc_b, env_a, env_b, level);
+    pop_scope(env_a);
+    pop_scope(env_b);
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+fn contract_eq<'a>(
+    name_a: &AnnName<'a>,
+    name_b: &AnnName<'a>,
+    formals_a: &Names<'a>,
+    formals_b: &Names<'a>,
+    body_a: &AnnProc<'a>,
+    body_b: &AnnProc<'a>,
+    env_a: &mut Env<'a>,
+    env_b: &mut Env<'a>,
+    level: &mut u32,
+) -> bool {
+    if !ann_name_eq(name_a, name_b, env_a, env_b, level) {
+        return false;
+    }
+
+    let mut binders_a = Vec::new();
+    let mut binders_b = Vec::new();
+    if !names_binders(formals_a, formals_b, &mut binders_a, &mut binders_b) {
+        return false;
+    }
+
+    bind_simultaneous(&binders_a, &binders_b, env_a, env_b, level);
+    let result = ann_proc_eq(body_a, body_b, env_a, env_b, level);
+    pop_scope(env_a);
+    pop_scope(env_b);
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+fn let_eq<'a>(
+    bindings_a: &[LetBinding<'a>],
+    bindings_b: &[LetBinding<'a>],
+    concurrent: bool,
+    body_a: &AnnProc<'a>,
+    body_b: &AnnProc<'a>,
+    env_a: &mut Env<'a>,
+    env_b: &mut Env<'a>,
+    level: &mut u32,
+) -> bool {
+    if bindings_a.len() != bindings_b.len() {
+        return false;
+    }
+
+    fn binder_of<'a>(binding: &LetBinding<'a>) -> Binder<'a> {
+        match binding {
+            LetBinding::Single { lhs, .. } => name_binder(&lhs.name),
+            LetBinding::Multiple { lhs, .. } => var_binder(lhs),
+        }
+    }
+
+    fn rhs_eq<'a>(
+        a: &LetBinding<'a>,
+        b: &LetBinding<'a>,
+        env_a: &mut Env<'a>,
+        env_b: &mut Env<'a>,
+        level: &mut u32,
+    ) -> bool {
+        match (a, b) {
+            (LetBinding::Single { rhs: ra, .. }, LetBinding::Single { rhs: rb, .. }) => {
+                ann_proc_eq(ra, rb, env_a, env_b, level)
+            }
+            (LetBinding::Multiple { rhs: ra, .. }, LetBinding::Multiple { rhs: rb, .. }) => {
+                proc_list_eq(ra, rb, env_a, env_b, level)
+            }
+            _ => false,
+        }
+    }
+
+    if concurrent {
+        // Every rhs sees the outer scope only, and all the lhs names are
+        // bound simultaneously for `body`.
+        if !bindings_a
+            .iter()
+            .zip(bindings_b)
+            .all(|(x, y)| rhs_eq(x, y, env_a, env_b, level))
+        {
+            return false;
+        }
+        let binders_a: Vec<Binder> = bindings_a.iter().map(binder_of).collect();
+        let binders_b: Vec<Binder> = bindings_b.iter().map(binder_of).collect();
+
+        bind_simultaneous(&binders_a, &binders_b, env_a, env_b, level);
+        let result = ann_proc_eq(body_a, body_b, env_a, env_b, level);
+        pop_scope(env_a);
+        pop_scope(env_b);
+        result
+    } else {
+        // Each rhs sees everything bound by the bindings before it, and
+        // introduces its own name in a fresh, more-nested scope.
+        let mut pushed = 0usize;
+        let all_bound = bindings_a.iter().zip(bindings_b).all(|(x, y)| {
+            if !rhs_eq(x, y, env_a, env_b, level) {
+                return false;
+            }
+            bind_simultaneous(&[binder_of(x)], &[binder_of(y)], env_a, env_b, level);
+            pushed += 1;
+            true
+        });
+
+        let result = all_bound && ann_proc_eq(body_a, body_b, env_a, env_b, level);
+        for _ in 0..pushed {
+            pop_scope(env_a);
+            pop_scope(env_b);
+        }
+        result
+    }
+}
+
+/// `Par`'s components are unordered, so match `a`'s flattened components
+/// against `b`'s as a multiset rather than position-by-position: try each
+/// of `a`'s components against every not-yet-used component of `b`,
+/// backtracking when a greedy pairing doesn't pan out. Every `ann_proc_eq`
+/// call here pushes and pops its own binder scopes symmetrically, so a
+/// failed trial pairing always leaves `env_a`/`env_b` exactly as it found
+/// them.
+fn par_components_eq<'a>(
+    a: &[AnnProc<'a>],
+    b: &[AnnProc<'a>],
+    env_a: &mut Env<'a>,
+    env_b: &mut Env<'a>,
+    level: &mut u32,
+) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut used = vec![false; b.len()];
+    match_par_components(a, &mut used, b, env_a, env_b, level)
+}
+
+fn match_par_components<'a>(
+    remaining: &[AnnProc<'a>],
+    used: &mut [bool],
+    b: &[AnnProc<'a>],
+    env_a: &mut Env<'a>,
+    env_b: &mut Env<'a>,
+    level: &mut u32,
+) -> bool {
+    let Some((first, rest)) = remaining.split_first() else {
+        return true;
+    };
+    for (i, candidate) in b.iter().enumerate() {
+        if used[i] {
+            continue;
+        }
+        if ann_proc_eq(first, candidate, env_a, env_b, level) {
+            used[i] = true;
+            if match_par_components(rest, used, b, env_a, env_b, level) {
+                return true;
+            }
+            used[i] = false;
+        }
+    }
+    false
+}
+
+fn procs_eq<'a>(
+    a: &Proc<'a>,
+    b: &Proc<'a>,
+    env_a: &mut Env<'a>,
+    env_b: &mut Env<'a>,
+    level: &mut u32,
+) -> bool {
+    match (a, b) {
+        (Proc::Nil, Proc::Nil) => true,
+        (Proc::BoolLiteral(x), Proc::BoolLiteral(y)) => x == y,
+        (Proc::LongLiteral(x), Proc::LongLiteral(y)) => x == y,
+        (Proc::StringLiteral(x), Proc::StringLiteral(y)) => x == y,
+        (Proc::UriLiteral(x), Proc::UriLiteral(y)) => x == y,
+        (Proc::SimpleType(x), Proc::SimpleType(y)) => x == y,
+        (Proc::Collection(x), Proc::Collection(y)) => collection_eq(x, y, env_a, env_b, level),
+        (Proc::ProcVar(x), Proc::ProcVar(y)) => var_eq(x, y, env_a, env_b),
+        (
+            Proc::Par {
+                left: la,
+                right: ra,
+            },
+            Proc::Par {
+                left: lb,
+                right: rb,
+            },
+        ) => {
+            let mut components_a = Vec::new();
+            flatten_par(la, &mut components_a);
+            flatten_par(ra, &mut components_a);
+            let mut components_b = Vec::new();
+            flatten_par(lb, &mut components_b);
+            flatten_par(rb, &mut components_b);
+            par_components_eq(&components_a, &components_b, env_a, env_b, level)
+        }
+        (
+            Proc::IfThenElse {
+                condition: ca,
+                if_true: ta,
+                if_false: fa,
+            },
+            Proc::IfThenElse {
+                condition: cb,
+                if_true: tb,
+                if_false: fb,
+            },
+        ) => {
+            ann_proc_eq(ca, cb, env_a, env_b, level)
+                && ann_proc_eq(ta, tb, env_a, env_b, level)
+                && match (fa, fb) {
+                    (None, None) => true,
+                    (Some(x), Some(y)) => ann_proc_eq(x, y, env_a, env_b, level),
+                    _ => false,
+                }
+        }
+        (
+            Proc::Send {
+                channel: ca,
+                send_type: sa,
+                inputs: ia,
+            },
+            Proc::Send {
+                channel: cb,
+                send_type: sb,
+                inputs: ib,
+            },
+        ) => {
+            sa == sb
+                && ann_name_eq(ca, cb, env_a, env_b, level)
+                && proc_list_eq(ia, ib, env_a, env_b, level)
+        }
+        (
+            Proc::ForComprehension {
+                receipts: ra,
+                proc: pa,
+            },
+            Proc::ForComprehension {
+                receipts: rb,
+                proc: pb,
+            },
+        ) => for_comprehension_eq(ra, rb, pa, pb, env_a, env_b, level),
+        (
+            Proc::Match {
+                expression: ea,
+                cases: ca,
+            },
+            Proc::Match {
+                expression: eb,
+                cases: cb,
+            },
+        ) => {
+            ann_proc_eq(ea, eb, env_a, env_b, level)
+                && ca.len() == cb.len()
+                && ca.iter().zip(cb).all(|(x, y): (&Case, &Case)| {
+                    ann_proc_eq(&x.pattern, &y.pattern, env_a, env_b, level)
+                        && ann_proc_eq(&x.proc, &y.proc, env_a, env_b, level)
+                })
+        }
+        (Proc::Select { branches: ba }, Proc::Select { branches: bb }) => {
+            ba.len() == bb.len()
+                && ba
+                    .iter()
+                    .zip(bb)
+                    .all(|(x, y)| branch_eq(x, y, env_a, env_b, level))
+        }
+        (
+            Proc::Bundle {
+                bundle_type: ta,
+                proc: pa,
+            },
+            Proc::Bundle {
+                bundle_type: tb,
+                proc: pb,
+            },
+        ) => ta == tb && ann_proc_eq(pa, pb, env_a, env_b, level),
+        (
+            Proc::Let {
+                bindings: ba,
+                body: bodya,
+                concurrent: ca,
+            },
+            Proc::Let {
+                bindings: bb,
+                body: bodyb,
+                concurrent: cb,
+            },
+        ) => ca == cb && let_eq(ba, bb, *ca, bodya, bodyb, env_a, env_b, level),
+        (
+            Proc::New {
+                decls: da,
+                proc: pa,
+            },
+            Proc::New {
+                decls: db,
+                proc: pb,
+            },
+        ) => new_eq(da, db, pa, pb, env_a, env_b, level),
+        (
+            Proc::Contract {
+                name: na,
+                formals: fa,
+                body: bodya,
+            },
+            Proc::Contract {
+                name: nb,
+                formals: fb,
+                body: bodyb,
+            },
+        ) => contract_eq(na, nb, fa, fb, bodya, bodyb, env_a, env_b, level),
+        (
+            Proc::SendSync {
+                channel: ca,
+                messages: ma,
+                cont: conta,
+            },
+            Proc::SendSync {
+                channel: cb,
+                messages: mb,
+                cont: contb,
+            },
+        ) => {
+            ann_name_eq(ca, cb, env_a, env_b, level)
+                && proc_list_eq(ma, mb, env_a, env_b, level)
+                && match (conta, contb) {
+                    (SyncSendCont::Empty, SyncSendCont::Empty) => true,
+                    (SyncSendCont::NonEmpty(x), SyncSendCont::NonEmpty(y)) => {
+                        ann_proc_eq(x, y, env_a, env_b, level)
+                    }
+                    _ => false,
+                }
+        }
+        (Proc::Eval { name: na }, Proc::Eval { name: nb }) => ann_name_eq(na, nb, env_a, env_b, level),
+        (Proc::Quote { proc: pa }, Proc::Quote { proc: pb }) => procs_eq(pa, pb, env_a, env_b, level),
+        (
+            Proc::Method {
+                receiver: ra,
+                name: na,
+                args: aa,
+            },
+            Proc::Method {
+                receiver: rb,
+                name: nb,
+                args: ab,
+            },
+        ) => {
+            na.name == nb.name
+                && ann_proc_eq(ra, rb, env_a, env_b, level)
+                && proc_list_eq(aa, ab, env_a, env_b, level)
+        }
+        (Proc::UnaryExp { op: oa, arg: aa }, Proc::UnaryExp { op: ob, arg: ab }) => {
+            oa == ob && procs_eq(aa, ab, env_a, env_b, level)
+        }
+        (
+            Proc::BinaryExp {
+                op: oa,
+                left: la,
+                right: ra,
+            },
+            Proc::BinaryExp {
+                op: ob,
+                left: lb,
+                right: rb,
+            },
+        ) => {
+            oa == ob
+                && ann_proc_eq(la, lb, env_a, env_b, level)
+                && ann_proc_eq(ra, rb, env_a, env_b, level)
+        }
+        (Proc::VarRef { kind: ka, var: va }, Proc::VarRef { kind: kb, var: vb }) => {
+            ka == kb && var_eq(&Var::Id(*va), &Var::Id(*vb), env_a, env_b)
+        }
+        (Proc::Bad, Proc::Bad) => true,
+        (
+            Proc::Error {
+                partial: pa,
+                recovered_children: ca,
+            },
+            Proc::Error {
+                partial: pb,
+                recovered_children: cb,
+            },
+        ) => {
+            match (pa, pb) {
+                (None, None) => true,
+                (Some(x), Some(y)) => ann_proc_eq(x, y, env_a, env_b, level),
+                _ => false,
+            } && proc_list_eq(ca, cb, env_a, env_b, level)
+        }
+        _ => false,
+    }
+}
+
+/// Are `a` and `b` equal up to spans and consistent renaming of the names
+/// bound by `New`/`ForComprehension`/`Contract`/`Let`?
+pub fn alpha_eq(a: &AnnProc, b: &AnnProc) -> bool {
+    let mut env_a = Env::new();
+    let mut env_b = Env::new();
+    let mut level = 0u32;
+    procs_eq(a.proc, b.proc, &mut env_a, &mut env_b, &mut level)
+}
+
+fn write_tag(hasher: &mut DefaultHasher, t: u64) {
+    hasher.write_u64(t);
+}
+
+fn write_str(hasher: &mut DefaultHasher, s: &str) {
+    hasher.write_usize(s.len());
+    hasher.write(s.as_bytes());
+}
+
+fn send_type_tag(t: SendType) -> u64 {
+    match t {
+        SendType::Single => 0,
+        SendType::Multiple => 1,
+    }
+}
+
+fn bundle_type_tag(t: BundleType) -> u64 {
+    match t {
+        BundleType::BundleEquiv => 0,
+        BundleType::BundleWrite => 1,
+        BundleType::BundleRead => 2,
+        BundleType::BundleReadWrite => 3,
+    }
+}
+
+fn unary_op_tag(op: UnaryExpOp) -> u64 {
+    match op {
+        UnaryExpOp::Not => 0,
+        UnaryExpOp::Neg => 1,
+        UnaryExpOp::Negation => 2,
+    }
+}
+
+fn binary_op_tag(op: BinaryExpOp) -> u64 {
+    match op {
+        BinaryExpOp::Or => 0,
+        BinaryExpOp::And => 1,
+        BinaryExpOp::Matches => 2,
+        BinaryExpOp::Eq => 3,
+        BinaryExpOp::Neq => 4,
+        BinaryExpOp::Lt => 5,
+        BinaryExpOp::Lte => 6,
+        BinaryExpOp::Gt => 7,
+        BinaryExpOp::Gte => 8,
+        BinaryExpOp::Concat => 9,
+        BinaryExpOp::Diff => 10,
+        BinaryExpOp::Add => 11,
+        BinaryExpOp::Sub => 12,
+        BinaryExpOp::Interpolation => 13,
+        BinaryExpOp::Mult => 14,
+        BinaryExpOp::Div => 15,
+        BinaryExpOp::Mod => 16,
+        BinaryExpOp::Disjunction => 17,
+        BinaryExpOp::Conjunction => 18,
+    }
+}
+
+fn var_ref_kind_tag(k: VarRefKind) -> u64 {
+    match k {
+        VarRefKind::Proc => 0,
+        VarRefKind::Name => 1,
+    }
+}
+
+fn simple_type_tag(t: SimpleType) -> u64 {
+    match t {
+        SimpleType::Bool => 0,
+        SimpleType::Int => 1,
+        SimpleType::String => 2,
+        SimpleType::Uri => 3,
+        SimpleType::ByteArray => 4,
+    }
+}
+
+fn hash_var(var: &Var, env: &Env, hasher: &mut DefaultHasher) {
+    match var {
+        Var::Wildcard => write_tag(hasher, 0),
+        Var::Id(id) => match resolve(env, id.name) {
+            Some(level) => {
+                write_tag(hasher, 1);
+                hasher.write_u32(level);
+            }
+            None => {
+                write_tag(hasher, 2);
+                write_str(hasher, id.name);
+            }
+        },
+    }
+}
+
+fn hash_name<'a>(name: &Name<'a>, env: &mut Env<'a>, level: &mut u32, hasher: &mut DefaultHasher) {
+    match name {
+        Name::ProcVar(var) => {
+            write_tag(hasher, 0);
+            hash_var(var, env, hasher);
+        }
+        Name::Quote(proc) => {
+            write_tag(hasher, 1);
+            hash_proc(proc, env, level, hasher);
+        }
+    }
+}
+
+fn hash_ann_name<'a>(name: &AnnName<'a>, env: &mut Env<'a>, level: &mut u32, hasher: &mut DefaultHasher) {
+    hash_name(&name.name, env, level, hasher);
+}
+
+fn hash_proc_list<'a>(
+    procs: &[AnnProc<'a>],
+    env: &mut Env<'a>,
+    level: &mut u32,
+    hasher: &mut DefaultHasher,
+) {
+    hasher.write_usize(procs.len());
+    for p in procs {
+        hash_proc(p.proc, env, level, hasher);
+    }
+}
+
+fn hash_remainder(remainder: &Option<Var>, env: &Env, hasher: &mut DefaultHasher) {
+    match remainder {
+        None => write_tag(hasher, 0),
+        Some(v) => {
+            write_tag(hasher, 1);
+            hash_var(v, env, hasher);
+        }
+    }
+}
+
+fn hash_source<'a>(source: &Source<'a>, env: &mut Env<'a>, level: &mut u32, hasher: &mut DefaultHasher) {
+    match source {
+        Source::Simple { name } => {
+            write_tag(hasher, 0);
+            hash_ann_name(name, env, level, hasher);
+        }
+        Source::ReceiveSend { name } => {
+            write_tag(hasher, 1);
+            hash_ann_name(name, env, level, hasher);
+        }
+        Source::SendReceive { name, inputs } => {
+            write_tag(hasher, 2);
+            hash_ann_name(name, env, level, hasher);
+            hash_proc_list(inputs, env, level, hasher);
+        }
+    }
+}
+
+fn hash_names_binders<'a>(names: &Names<'a>) -> Vec<Binder<'a>> {
+    let mut binders: Vec<Binder<'a>> = names.names.iter().map(|n| name_binder(&n.name)).collect();
+    if let Some(remainder) = &names.remainder {
+        binders.push(var_binder(remainder));
+    }
+    binders
+}
+
+fn hash_names_occurrences<'a>(
+    names: &Names<'a>,
+    env: &mut Env<'a>,
+    level: &mut u32,
+    hasher: &mut DefaultHasher,
+) {
+    hasher.write_usize(names.names.len());
+    for name in &names.names {
+        hash_ann_name(name, env, level, hasher);
+    }
+    hash_remainder(&names.remainder, env, hasher);
+}
+
+fn hash_select_pattern<'a>(
+    pattern: &SelectPattern<'a>,
+    env: &mut Env<'a>,
+    level: &mut u32,
+    hasher: &mut DefaultHasher,
+) {
+    hash_names_occurrences(&pattern.lhs, env, level, hasher);
+    hash_source(&pattern.rhs, env, level, hasher);
+}
+
+fn hash_branch<'a>(branch: &Branch<'a>, env: &mut Env<'a>, level: &mut u32, hasher: &mut DefaultHasher) {
+    hasher.write_usize(branch.patterns.len());
+    for pattern in &branch.patterns {
+        hash_select_pattern(pattern, env, level, hasher);
+    }
+    hash_proc(branch.proc.proc, env, level, hasher);
+}
+
+fn hash_collection<'a>(
+    collection: &Collection<'a>,
+    env: &mut Env<'a>,
+    level: &mut u32,
+    hasher: &mut DefaultHasher,
+) {
+    match collection {
+        Collection::List { elements, remainder } => {
+            write_tag(hasher, collection_tag::LIST);
+            hash_proc_list(elements, env, level, hasher);
+            hash_remainder(remainder, env, hasher);
+        }
+        Collection::Tuple(elements) => {
+            write_tag(hasher, collection_tag::TUPLE);
+            hash_proc_list(elements, env, level, hasher);
+        }
+        Collection::Set { elements, remainder } => {
+            write_tag(hasher, collection_tag::SET);
+            hash_proc_list(elements, env, level, hasher);
+            hash_remainder(remainder, env, hasher);
+        }
+        Collection::Map { elements, remainder } => {
+            write_tag(hasher, collection_tag::MAP);
+            hasher.write_usize(elements.len());
+            for (k, v) in elements {
+                hash_proc(k.proc, env, level, hasher);
+                hash_proc(v.proc, env, level, hasher);
+            }
+            hash_remainder(remainder, env, hasher);
+        }
+    }
+}
+
+fn hash_new<'a>(
+    decls: &[NameDecl<'a>],
+    proc: &AnnProc<'a>,
+    env: &mut Env<'a>,
+    level: &mut u32,
+    hasher: &mut DefaultHasher,
+) {
+    hasher.write_usize(decls.len());
+    for decl in decls {
+        match &decl.uri {
+            Some(uri) => write_str(hasher, uri),
+            None => hasher.write_u8(0),
+        }
+    }
+
+    let binders: Vec<Binder> = decls.iter().map(|d| Binder::Named(d.id.name)).collect();
+    let base = *level;
+    bind_scope(&binders, env, base);
+    *level += binders.len() as u32;
+
+    hash_proc(proc.proc, env, level, hasher);
+    pop_scope(env);
+}
+
+fn hash_for_comprehension<'a>(
+    receipts: &Receipts<'a>,
+    proc: &AnnProc<'a>,
+    env: &mut Env<'a>,
+    level: &mut u32,
+    hasher: &mut DefaultHasher,
+) {
+    hasher.write_usize(receipts.len());
+    let mut binders = Vec::new();
+
+    for receipt in receipts {
+        hasher.write_usize(receipt.binds.len());
+        for bind in &receipt.binds {
+            match bind {
+                Bind::Linear { lhs, rhs } => {
+                    write_tag(hasher, 0);
+                    hash_source(rhs, env, level, hasher);
+                    hasher.write_usize(lhs.names.len());
+                    binders.extend(hash_names_binders(lhs));
+                }
+                Bind::Repeated { lhs, rhs } => {
+                    write_tag(hasher, 1);
+                    hash_ann_name(rhs, env, level, hasher);
+                    hasher.write_usize(lhs.names.len());
+                    binders.extend(hash_names_binders(lhs));
+                }
+                Bind::Peek { lhs, rhs } => {
+                    write_tag(hasher, 2);
+                    hash_ann_name(rhs, env, level, hasher);
+                    hasher.write_usize(lhs.names.len());
+                    binders.extend(hash_names_binders(lhs));
+                }
+            }
+        }
+    }
+
+    let base = *level;
+    bind_scope(&binders, env, base);
+    *level += binders.len() as u32;
+
+    hash_proc(proc.proc, env, level, hasher);
+    pop_scope(env);
+}
+
+fn hash_contract<'a>(
+    name: &AnnName<'a>,
+    formals: &Names<'a>,
+    body: &AnnProc<'a>,
+    env: &mut Env<'a>,
+    level: &mut u32,
+    hasher: &mut DefaultHasher,
+) {
+    hash_ann_name(name, env, level, hasher);
+    hasher.write_usize(formals.names.len());
+    hasher.write_u8(formals.remainder.is_some() as u8);
+
+    let binders = hash_names_binders(formals);
+    let base = *level;
+    bind_scope(&binders, env, base);
+    *level += binders.len() as u32;
+
+    hash_proc(body.proc, env, level, hasher);
+    pop_scope(env);
+}
+
+fn hash_let<'a>(
+    bindings: &[LetBinding<'a>],
+    concurrent: bool,
+    body: &AnnProc<'a>,
+    env: &mut Env<'a>,
+    level: &mut u32,
+    hasher: &mut DefaultHasher,
+) {
+    hasher.write_u8(concurrent as u8);
+    hasher.write_usize(bindings.len());
+
+    fn binder_of<'a>(binding: &LetBinding<'a>) -> Binder<'a> {
+        match binding {
+            LetBinding::Single { lhs, .. } => name_binder(&lhs.name),
+            LetBinding::Multiple { lhs, .. } => var_binder(lhs),
+        }
+    }
+
+    fn hash_rhs<'a>(binding: &LetBinding<'a>, env: &mut Env<'a>, level: &mut u32, hasher: &mut DefaultHasher) {
+        match binding {
+            LetBinding::Single { rhs, .. } => {
+                write_tag(hasher, 0);
+                hash_proc(rhs.proc, env, level, hasher);
+            }
+            LetBinding::Multiple { rhs, .. } => {
+                write_tag(hasher, 1);
+                hash_proc_list(rhs, env, level, hasher);
+            }
+        }
+    }
+
+    if concurrent {
+        for binding in bindings {
+            hash_rhs(binding, env, level, hasher);
+        }
+        let binders: Vec<Binder> = bindings.iter().map(binder_of).collect();
+        let base = *level;
+        bind_scope(&binders, env, base);
+        *level += binders.len() as u32;
+
+        hash_proc(body.proc, env, level, hasher);
+        pop_scope(env);
+    } else {
+        let mut pushed = 0usize;
+        for binding in bindings {
+            hash_rhs(binding, env, level, hasher);
+            bind_scope(&[binder_of(binding)], env, *level);
+            *level += 1;
+            pushed += 1;
+        }
+
+        hash_proc(body.proc, env, level, hasher);
+        for _ in 0..pushed {
+            pop_scope(env);
+        }
+    }
+}
+
+/// Hash `Par`'s flattened components order-insensitively: each one is
+/// hashed independently, starting from the same `level` baseline so that
+/// reordering them can't change which numbers their own bound names get,
+/// then the component digests are combined by XOR so the combined digest
+/// doesn't depend on the order they were visited in either. `level` itself
+/// is advanced past whichever component used the most of it, so a binder
+/// coming after this `Par` still gets a number that can't collide with one
+/// still active here.
+fn hash_par_components<'a>(components: &[AnnProc<'a>], env: &mut Env<'a>, level: &mut u32) -> u64 {
+    let base = *level;
+    let mut combined = 0u64;
+    let mut max_level = base;
+    for component in components {
+        let mut component_level = base;
+        let mut component_hasher = DefaultHasher::new();
+        hash_proc(component.proc, env, &mut component_level, &mut component_hasher);
+        combined ^= component_hasher.finish();
+        max_level = max_level.max(component_level);
+    }
+    *level = max_level;
+    combined
+}
+
+fn hash_proc<'a>(proc: &Proc<'a>, env: &mut Env<'a>, level: &mut u32, hasher: &mut DefaultHasher) {
+    match proc {
+        Proc::Nil => write_tag(hasher, tag::NIL),
+        Proc::BoolLiteral(v) => {
+            write_tag(hasher, tag::BOOL_LITERAL);
+            hasher.write_u8(*v as u8);
+        }
+        Proc::LongLiteral(v) => {
+            write_tag(hasher, tag::LONG_LITERAL);
+            hasher.write_i64(*v);
+        }
+        Proc::StringLiteral(v) => {
+            write_tag(hasher, tag::STRING_LITERAL);
+            write_str(hasher, v);
+        }
+        Proc::UriLiteral(v) => {
+            write_tag(hasher, tag::URI_LITERAL);
+            write_str(hasher, v);
+        }
+        Proc::SimpleType(v) => {
+            write_tag(hasher, tag::SIMPLE_TYPE);
+            write_tag(hasher, simple_type_tag(*v));
+        }
+        Proc::Collection(c) => {
+            write_tag(hasher, tag::COLLECTION);
+            hash_collection(c, env, level, hasher);
+        }
+        Proc::ProcVar(v) => {
+            write_tag(hasher, tag::PROC_VAR);
+            hash_var(v, env, hasher);
+        }
+        Proc::Par { left, right } => {
+            write_tag(hasher, tag::PAR);
+            let mut components = Vec::new();
+            flatten_par(left, &mut components);
+            flatten_par(right, &mut components);
+            hasher.write_usize(components.len());
+            hasher.write_u64(hash_par_components(&components, env, level));
+        }
+        Proc::IfThenElse {
+            condition,
+            if_true,
+            if_false,
+        } => {
+            write_tag(hasher, tag::IF_THEN_ELSE);
+            hash_proc(condition.proc, env, level, hasher);
+            hash_proc(if_true.proc, env, level, hasher);
+            match if_false {
+                None => hasher.write_u8(0),
+                Some(p) => {
+                    hasher.write_u8(1);
+                    hash_proc(p.proc, env, level, hasher);
+                }
+            }
+        }
+        Proc::Send {
+            channel,
+            send_type,
+            inputs,
+        } => {
+            write_tag(hasher, tag::SEND);
+            write_tag(hasher, send_type_tag(*send_type));
+            hash_ann_name(channel, env, level, hasher);
+            hash_proc_list(inputs, env, level, hasher);
+        }
+        Proc::ForComprehension { receipts, proc } => {
+            write_tag(hasher, tag::FOR_COMPREHENSION);
+            hash_for_comprehension(receipts, proc, env, level, hasher);
+        }
+        Proc::Match { expression, cases } => {
+            write_tag(hasher, tag::MATCH);
+            hash_proc(expression.proc, env, level, hasher);
+            hasher.write_usize(cases.len());
+            for case in cases {
+                hash_proc(case.pattern.proc, env, level, hasher);
+                hash_proc(case.proc.proc, env, level, hasher);
+            }
+        }
+        Proc::Select { branches } => {
+            write_tag(hasher, tag::SELECT);
+            hasher.write_usize(branches.len());
+            for branch in branches {
+                hash_branch(branch, env, level, hasher);
+            }
+        }
+        Proc::Bundle { bundle_type, proc } => {
+            write_tag(hasher, tag::BUNDLE);
+            write_tag(hasher, bundle_type_tag(*bundle_type));
+            hash_proc(proc.proc, env, level, hasher);
+        }
+        Proc::Let {
+            bindings,
+            body,
+            concurrent,
+        } => {
+            write_tag(hasher, tag::LET);
+            hash_let(bindings, *concurrent, body, env, level, hasher);
+        }
+        Proc::New { decls, proc } => {
+            write_tag(hasher, tag::NEW);
+            hash_new(decls, proc, env, level, hasher);
+        }
+        Proc::Contract {
+            name,
+            formals,
+            body,
+        } => {
+            write_tag(hasher, tag::CONTRACT);
+            hash_contract(name, formals, body, env, level, hasher);
+        }
+        Proc::SendSync {
+            channel,
+            messages,
+            cont,
+        } => {
+            write_tag(hasher, tag::SEND_SYNC);
+            hash_ann_name(channel, env, level, hasher);
+            hash_proc_list(messages, env, level, hasher);
+            match cont {
+                SyncSendCont::Empty => hasher.write_u8(0),
+                SyncSendCont::NonEmpty(p) => {
+                    hasher.write_u8(1);
+                    hash_proc(p.proc, env, level, hasher);
+                }
+            }
+        }
+        Proc::Eval { name } => {
+            write_tag(hasher, tag::EVAL);
+            hash_ann_name(name, env, level, hasher);
+        }
+        Proc::Quote { proc } => {
+            write_tag(hasher, tag::QUOTE);
+            hash_proc(proc, env, level, hasher);
+        }
+        Proc::Method { receiver, name, args } => {
+            write_tag(hasher, tag::METHOD);
+            hash_proc(receiver.proc, env, level, hasher);
+            write_str(hasher, name.name);
+            hash_proc_list(args, env, level, hasher);
+        }
+        Proc::UnaryExp { op, arg } => {
+            write_tag(hasher, tag::UNARY_EXP);
+            write_tag(hasher, unary_op_tag(*op));
+            hash_proc(arg, env, level, hasher);
+        }
+        Proc::BinaryExp { op, left, right } => {
+            write_tag(hasher, tag::BINARY_EXP);
+            write_tag(hasher, binary_op_tag(*op));
+            hash_proc(left.proc, env, level, hasher);
+            hash_proc(right.proc, env, level, hasher);
+        }
+        Proc::VarRef { kind, var } => {
+            write_tag(hasher, tag::VAR_REF);
+            write_tag(hasher, var_ref_kind_tag(*kind));
+            hash_var(&Var::Id(*var), env, hasher);
+        }
+        Proc::Bad => write_tag(hasher, tag::BAD),
+        Proc::Error {
+            partial,
+            recovered_children,
+        } => {
+            write_tag(hasher, tag::ERROR);
+            match partial {
+                None => hasher.write_u8(0),
+                Some(p) => {
+                    hasher.write_u8(1);
+                    hash_proc(p.proc, env, level, hasher);
+                }
+            }
+            hash_proc_list(recovered_children, env, level, hasher);
+        }
+    }
+}
+
+/// Hash `proc` consistently with [`alpha_eq`]: alpha-equivalent trees always
+/// produce the same `u64`, though (as with any hash) the converse isn't
+/// guaranteed.
+pub fn alpha_hash(proc: &AnnProc) -> u64 {
+    let mut env = Env::new();
+    let mut level = 0u32;
+    let mut hasher = DefaultHasher::new();
+    hash_proc(proc.proc, &mut env, &mut level, &mut hasher);
+    hasher.finish()
+}
@@ -2,13 +2,16 @@ use smallvec::ToSmallVec;
 use typed_arena::Arena;
 
 use crate::ast::{
-    AnnName, AnnProc, BinaryExpOp, Bind, BundleType, Case, Collection, Id, KeyValuePair,
+    AnnName, AnnProc, BinaryExpOp, Bind, Branch, BundleType, Case, Collection, Id, KeyValuePair,
     LetBinding, NameDecl, Names, Proc, SendType, SimpleType, SyncSendCont, UnaryExpOp, Var,
     VarRefKind,
 };
 
 pub(super) struct ASTBuilder<'ast> {
     arena: Arena<Proc<'ast>>,
+    // backs `alloc_str`, for interning strings that don't already borrow from the
+    // source text (e.g. names rebuilt by the CBOR decoder)
+    strings: Arena<String>,
     // useful quasi-constants
     pub(super) NIL: Proc<'ast>,
     pub(super) TRUE: Proc<'ast>,
@@ -29,6 +32,7 @@ impl<'ast> ASTBuilder<'ast> {
     pub(super) fn with_capacity(capacity: usize) -> Self {
         ASTBuilder {
             arena: Arena::with_capacity(capacity),
+            strings: Arena::new(),
             NIL: Proc::Nil,
             TRUE: Proc::BoolLiteral(true),
             FALSE: Proc::BoolLiteral(false),
@@ -44,6 +48,13 @@ impl<'ast> ASTBuilder<'ast> {
         }
     }
 
+    /// Intern an owned `String` into this builder's arena, handing back a borrow
+    /// with the same lifetime `alloc_*` returns carry. Used by the CBOR decoder,
+    /// which only ever has owned strings (no source text to borrow from).
+    pub(super) fn alloc_str(&self, value: String) -> &str {
+        self.strings.alloc(value)
+    }
+
     pub(super) fn alloc_string_literal(&self, value: &'ast str) -> &Proc<'ast> {
         self.arena
             .alloc(Proc::StringLiteral(value.trim_matches(|c| c == '"')))
@@ -208,6 +219,10 @@ impl<'ast> ASTBuilder<'ast> {
         })
     }
 
+    pub(super) fn alloc_select(&self, branches: Vec<Branch<'ast>>) -> &Proc<'ast> {
+        self.arena.alloc(Proc::Select { branches })
+    }
+
     pub(super) fn alloc_bundle(&self, bundle_type: BundleType, proc: AnnProc<'ast>) -> &Proc<'ast> {
         self.arena.alloc(Proc::Bundle { bundle_type, proc })
     }
@@ -307,4 +322,15 @@ impl<'ast> ASTBuilder<'ast> {
     pub(super) fn alloc_var_ref(&self, kind: VarRefKind, var: Id<'ast>) -> &Proc<'ast> {
         self.arena.alloc(Proc::VarRef { kind, var })
     }
+
+    pub(super) fn alloc_error(
+        &self,
+        partial: Option<AnnProc<'ast>>,
+        recovered_children: &[AnnProc<'ast>],
+    ) -> &Proc<'ast> {
+        self.arena.alloc(Proc::Error {
+            partial,
+            recovered_children: recovered_children.to_smallvec(),
+        })
+    }
 }
@@ -0,0 +1,1461 @@
+//! Canonical CBOR (de)serialization for a parsed `AnnProc` tree.
+//!
+//! Mirrors the way Dhall encodes its expressions: every `Proc` variant becomes a
+//! CBOR array whose first element is a small integer tag identifying the
+//! constructor, followed by the variant's fields encoded recursively in
+//! declaration order. Leaf literals map to their natural CBOR scalar type, and
+//! `Var`/`NameDecl` names encode as plain CBOR text strings. This lets a tool
+//! cache a parsed program, or ship it across a process boundary, without
+//! re-running the tree-sitter grammar.
+//!
+//! Only the slice of the CBOR data model (RFC 8949) the AST actually needs is
+//! implemented here: unsigned/negative integers, booleans, null, definite-length
+//! text strings, and definite-length arrays, always written in their shortest
+//! ("canonical") form.
+//!
+//! Decoding rebuilds the tree through the same `alloc_*` entry points `parsing`
+//! uses, so sharing/interning through the arena is preserved, and reports
+//! malformed input (unknown tags, arity mismatches, truncated bytes) as a
+//! structured [`DecodeError`] rather than panicking.
+
+use smallvec::SmallVec;
+use validated::Validated;
+
+use crate::ast::{
+    AnnName, AnnProc, BinaryExpOp, Bind, BundleType, Case, Collection, Id, Name, NameDecl, Names,
+    Proc, Receipt, SelectPattern, SendType, SimpleType, Source, SyncSendCont, UnaryExpOp, Uri,
+    Var, VarRefKind,
+};
+use crate::parser::ast_builder::ASTBuilder;
+use crate::{SourcePos, SourceSpan};
+
+/// Constructor tags, one per `Proc` variant, in the order they're declared in
+/// [`crate::ast::Proc`]. `Collection` additionally dispatches on [`collection_tag`].
+pub(super) mod tag {
+    pub const NIL: u64 = 0;
+    pub const BOOL_LITERAL: u64 = 1;
+    pub const LONG_LITERAL: u64 = 2;
+    pub const STRING_LITERAL: u64 = 3;
+    pub const URI_LITERAL: u64 = 4;
+    pub const SIMPLE_TYPE: u64 = 5;
+    pub const COLLECTION: u64 = 6;
+    pub const PROC_VAR: u64 = 7;
+    pub const PAR: u64 = 8;
+    pub const IF_THEN_ELSE: u64 = 9;
+    pub const SEND: u64 = 10;
+    pub const FOR_COMPREHENSION: u64 = 11;
+    pub const MATCH: u64 = 12;
+    pub const SELECT: u64 = 13;
+    pub const BUNDLE: u64 = 14;
+    pub const LET: u64 = 15;
+    pub const NEW: u64 = 16;
+    pub const CONTRACT: u64 = 17;
+    pub const SEND_SYNC: u64 = 18;
+    pub const EVAL: u64 = 19;
+    pub const QUOTE: u64 = 20;
+    pub const METHOD: u64 = 21;
+    pub const UNARY_EXP: u64 = 22;
+    pub const BINARY_EXP: u64 = 23;
+    pub const VAR_REF: u64 = 24;
+    pub const BAD: u64 = 25;
+    pub const ERROR: u64 = 26;
+}
+
+pub(super) mod collection_tag {
+    pub const LIST: u64 = 0;
+    pub const TUPLE: u64 = 1;
+    pub const SET: u64 = 2;
+    pub const MAP: u64 = 3;
+}
+
+/// Why a byte sequence could not be decoded back into an `AnnProc`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Ran out of bytes while reading an item.
+    UnexpectedEof,
+    /// A CBOR item header used a reserved/unsupported additional-info encoding.
+    MalformedHeader,
+    /// A constructor tag that doesn't correspond to any known `Proc` variant
+    /// (or sub-variant, for `Collection`/enums with their own tag space).
+    UnknownTag(u64),
+    /// An array didn't have the number of elements expected at its position.
+    /// For a constructor array `tag` is its constructor tag; for any other
+    /// fixed-size array (a span, a position, a name, ...) `tag` repeats
+    /// `expected`.
+    ArityMismatch {
+        tag: u64,
+        expected: usize,
+        actual: usize,
+    },
+    /// A CBOR item wasn't the major type the decoder expected at that position.
+    TypeMismatch {
+        expected: &'static str,
+        found_major: u8,
+    },
+    /// A text string's bytes were not valid UTF-8.
+    InvalidUtf8,
+    /// The input had extra bytes after a complete `AnnProc` was decoded.
+    TrailingBytes,
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::UnexpectedEof => write!(f, "unexpected end of input"),
+            DecodeError::MalformedHeader => write!(f, "malformed CBOR item header"),
+            DecodeError::UnknownTag(tag) => write!(f, "unknown constructor tag {tag}"),
+            DecodeError::ArityMismatch {
+                tag,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "constructor tag {tag} expects {expected} field(s), found {actual}"
+            ),
+            DecodeError::TypeMismatch {
+                expected,
+                found_major,
+            } => write!(
+                f,
+                "expected a CBOR {expected}, found major type {found_major}"
+            ),
+            DecodeError::InvalidUtf8 => write!(f, "text string was not valid UTF-8"),
+            DecodeError::TrailingBytes => write!(f, "trailing bytes after a complete AST"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+// ---------------------------------------------------------------------------
+// writing
+// ---------------------------------------------------------------------------
+
+fn write_head(buf: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+    match value {
+        0..=23 => buf.push(major | value as u8),
+        24..=0xff => {
+            buf.push(major | 24);
+            buf.push(value as u8);
+        }
+        0x100..=0xffff => {
+            buf.push(major | 25);
+            buf.extend_from_slice(&(value as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            buf.push(major | 26);
+            buf.extend_from_slice(&(value as u32).to_be_bytes());
+        }
+        _ => {
+            buf.push(major | 27);
+            buf.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+}
+
+fn write_u64(buf: &mut Vec<u8>, value: u64) {
+    write_head(buf, 0, value);
+}
+
+fn write_i64(buf: &mut Vec<u8>, value: i64) {
+    if value >= 0 {
+        write_head(buf, 0, value as u64);
+    } else {
+        write_head(buf, 1, (-(value + 1)) as u64);
+    }
+}
+
+fn write_bool(buf: &mut Vec<u8>, value: bool) {
+    buf.push(if value { 0xf5 } else { 0xf4 });
+}
+
+fn write_null(buf: &mut Vec<u8>) {
+    buf.push(0xf6);
+}
+
+fn write_array_header(buf: &mut Vec<u8>, len: u64) {
+    write_head(buf, 4, len);
+}
+
+fn write_text(buf: &mut Vec<u8>, value: &str) {
+    write_head(buf, 3, value.len() as u64);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+/// Start a `[tag, ...fields]` array with `field_count` fields after the tag.
+fn write_ctor(buf: &mut Vec<u8>, tag: u64, field_count: u64) {
+    write_array_header(buf, 1 + field_count);
+    write_u64(buf, tag);
+}
+
+fn write_option<T>(buf: &mut Vec<u8>, value: &Option<T>, write_some: impl FnOnce(&mut Vec<u8>, &T)) {
+    match value {
+        Some(value) => write_some(buf, value),
+        None => write_null(buf),
+    }
+}
+
+fn write_pos(buf: &mut Vec<u8>, pos: &SourcePos) {
+    write_array_header(buf, 2);
+    write_u64(buf, pos.line as u64);
+    write_u64(buf, pos.col as u64);
+}
+
+fn write_span(buf: &mut Vec<u8>, span: &SourceSpan) {
+    write_array_header(buf, 2);
+    write_pos(buf, &span.start);
+    write_pos(buf, &span.end);
+}
+
+fn write_id(buf: &mut Vec<u8>, id: &Id) {
+    write_text(buf, id.name);
+}
+
+fn write_var(buf: &mut Vec<u8>, var: &Var) {
+    match var {
+        Var::Wildcard => write_null(buf),
+        Var::Id(id) => write_id(buf, id),
+    }
+}
+
+fn write_name(buf: &mut Vec<u8>, name: &Name, with_spans: bool) {
+    match name {
+        Name::ProcVar(var) => {
+            write_array_header(buf, 2);
+            write_u64(buf, 0);
+            write_var(buf, var);
+        }
+        Name::Quote(proc) => {
+            write_array_header(buf, 2);
+            write_u64(buf, 1);
+            write_proc(buf, proc, with_spans);
+        }
+    }
+}
+
+fn write_ann_name(buf: &mut Vec<u8>, name: &AnnName, with_spans: bool) {
+    write_array_header(buf, 2);
+    write_name(buf, &name.name, with_spans);
+    if with_spans {
+        write_span(buf, &name.span);
+    } else {
+        write_null(buf);
+    }
+}
+
+fn write_names(buf: &mut Vec<u8>, names: &Names, with_spans: bool) {
+    write_array_header(buf, 2);
+    write_array_header(buf, names.names.len() as u64);
+    for name in &names.names {
+        write_ann_name(buf, name, with_spans);
+    }
+    write_option(buf, &names.remainder, |buf, var| write_var(buf, var));
+}
+
+fn write_send_type(buf: &mut Vec<u8>, send_type: SendType) {
+    write_u64(
+        buf,
+        match send_type {
+            SendType::Single => 0,
+            SendType::Multiple => 1,
+        },
+    );
+}
+
+fn write_bundle_type(buf: &mut Vec<u8>, bundle_type: BundleType) {
+    write_u64(
+        buf,
+        match bundle_type {
+            BundleType::BundleEquiv => 0,
+            BundleType::BundleWrite => 1,
+            BundleType::BundleRead => 2,
+            BundleType::BundleReadWrite => 3,
+        },
+    );
+}
+
+fn write_simple_type(buf: &mut Vec<u8>, simple_type: SimpleType) {
+    write_u64(
+        buf,
+        match simple_type {
+            SimpleType::Bool => 0,
+            SimpleType::Int => 1,
+            SimpleType::String => 2,
+            SimpleType::Uri => 3,
+            SimpleType::ByteArray => 4,
+        },
+    );
+}
+
+fn write_unary_op(buf: &mut Vec<u8>, op: UnaryExpOp) {
+    write_u64(
+        buf,
+        match op {
+            UnaryExpOp::Not => 0,
+            UnaryExpOp::Neg => 1,
+            UnaryExpOp::Negation => 2,
+        },
+    );
+}
+
+fn write_binary_op(buf: &mut Vec<u8>, op: BinaryExpOp) {
+    write_u64(
+        buf,
+        match op {
+            BinaryExpOp::Or => 0,
+            BinaryExpOp::And => 1,
+            BinaryExpOp::Matches => 2,
+            BinaryExpOp::Eq => 3,
+            BinaryExpOp::Neq => 4,
+            BinaryExpOp::Lt => 5,
+            BinaryExpOp::Lte => 6,
+            BinaryExpOp::Gt => 7,
+            BinaryExpOp::Gte => 8,
+            BinaryExpOp::Concat => 9,
+            BinaryExpOp::Diff => 10,
+            BinaryExpOp::Add => 11,
+            BinaryExpOp::Sub => 12,
+            BinaryExpOp::Interpolation => 13,
+            BinaryExpOp::Mult => 14,
+            BinaryExpOp::Div => 15,
+            BinaryExpOp::Mod => 16,
+            BinaryExpOp::Disjunction => 17,
+            BinaryExpOp::Conjunction => 18,
+        },
+    );
+}
+
+fn write_var_ref_kind(buf: &mut Vec<u8>, kind: VarRefKind) {
+    write_u64(
+        buf,
+        match kind {
+            VarRefKind::Proc => 0,
+            VarRefKind::Name => 1,
+        },
+    );
+}
+
+fn write_name_decl(buf: &mut Vec<u8>, decl: &NameDecl) {
+    write_array_header(buf, 2);
+    write_id(buf, &decl.id);
+    write_option(buf, &decl.uri, |buf, uri| write_text(buf, uri));
+}
+
+fn write_source(buf: &mut Vec<u8>, source: &Source, with_spans: bool) {
+    match source {
+        Source::Simple { name } => {
+            write_array_header(buf, 2);
+            write_u64(buf, 0);
+            write_ann_name(buf, name, with_spans);
+        }
+        Source::ReceiveSend { name } => {
+            write_array_header(buf, 2);
+            write_u64(buf, 1);
+            write_ann_name(buf, name, with_spans);
+        }
+        Source::SendReceive { name, inputs } => {
+            write_array_header(buf, 3);
+            write_u64(buf, 2);
+            write_ann_name(buf, name, with_spans);
+            write_array_header(buf, inputs.len() as u64);
+            for input in inputs {
+                write_ann_proc(buf, input, with_spans);
+            }
+        }
+    }
+}
+
+fn write_bind(buf: &mut Vec<u8>, bind: &Bind, with_spans: bool) {
+    match bind {
+        Bind::Linear { lhs, rhs } => {
+            write_array_header(buf, 3);
+            write_u64(buf, 0);
+            write_names(buf, lhs, with_spans);
+            write_source(buf, rhs, with_spans);
+        }
+        Bind::Repeated { lhs, rhs } => {
+            write_array_header(buf, 3);
+            write_u64(buf, 1);
+            write_names(buf, lhs, with_spans);
+            write_ann_name(buf, rhs, with_spans);
+        }
+        Bind::Peek { lhs, rhs } => {
+            write_array_header(buf, 3);
+            write_u64(buf, 2);
+            write_names(buf, lhs, with_spans);
+            write_ann_name(buf, rhs, with_spans);
+        }
+    }
+}
+
+fn write_receipt(buf: &mut Vec<u8>, receipt: &Receipt, with_spans: bool) {
+    write_array_header(buf, receipt.binds.len() as u64);
+    for bind in &receipt.binds {
+        write_bind(buf, bind, with_spans);
+    }
+}
+
+fn write_case(buf: &mut Vec<u8>, case: &Case, with_spans: bool) {
+    write_array_header(buf, 2);
+    write_ann_proc(buf, &case.pattern, with_spans);
+    write_ann_proc(buf, &case.proc, with_spans);
+}
+
+fn write_select_pattern(buf: &mut Vec<u8>, pattern: &SelectPattern, with_spans: bool) {
+    write_array_header(buf, 2);
+    write_names(buf, &pattern.lhs, with_spans);
+    write_source(buf, &pattern.rhs, with_spans);
+}
+
+/// Write `proc`'s own tagged array. No span information is written here: callers
+/// that need a span (everywhere except the raw `&Proc` stored in `Quote`/`UnaryExp`)
+/// go through [`write_ann_proc`] instead.
+fn write_proc(buf: &mut Vec<u8>, proc: &Proc, with_spans: bool) {
+    match proc {
+        Proc::Nil => write_ctor(buf, tag::NIL, 0),
+        Proc::BoolLiteral(value) => {
+            write_ctor(buf, tag::BOOL_LITERAL, 1);
+            write_bool(buf, *value);
+        }
+        Proc::LongLiteral(value) => {
+            write_ctor(buf, tag::LONG_LITERAL, 1);
+            write_i64(buf, *value);
+        }
+        Proc::StringLiteral(value) => {
+            write_ctor(buf, tag::STRING_LITERAL, 1);
+            write_text(buf, value);
+        }
+        Proc::UriLiteral(uri) => {
+            write_ctor(buf, tag::URI_LITERAL, 1);
+            write_text(buf, uri);
+        }
+        Proc::SimpleType(simple_type) => {
+            write_ctor(buf, tag::SIMPLE_TYPE, 1);
+            write_simple_type(buf, *simple_type);
+        }
+        Proc::Collection(collection) => {
+            write_ctor(buf, tag::COLLECTION, 1);
+            write_collection(buf, collection, with_spans);
+        }
+        Proc::ProcVar(var) => {
+            write_ctor(buf, tag::PROC_VAR, 1);
+            write_var(buf, var);
+        }
+        Proc::Par { left, right } => {
+            write_ctor(buf, tag::PAR, 2);
+            write_ann_proc(buf, left, with_spans);
+            write_ann_proc(buf, right, with_spans);
+        }
+        Proc::IfThenElse {
+            condition,
+            if_true,
+            if_false,
+        } => {
+            write_ctor(buf, tag::IF_THEN_ELSE, 3);
+            write_ann_proc(buf, condition, with_spans);
+            write_ann_proc(buf, if_true, with_spans);
+            write_option(buf, if_false, |buf, p| write_ann_proc(buf, p, with_spans));
+        }
+        Proc::Send {
+            channel,
+            send_type,
+            inputs,
+        } => {
+            write_ctor(buf, tag::SEND, 3);
+            write_ann_name(buf, channel, with_spans);
+            write_send_type(buf, *send_type);
+            write_array_header(buf, inputs.len() as u64);
+            for input in inputs {
+                write_ann_proc(buf, input, with_spans);
+            }
+        }
+        Proc::ForComprehension { receipts, proc } => {
+            write_ctor(buf, tag::FOR_COMPREHENSION, 2);
+            write_array_header(buf, receipts.len() as u64);
+            for receipt in receipts {
+                write_receipt(buf, receipt, with_spans);
+            }
+            write_ann_proc(buf, proc, with_spans);
+        }
+        Proc::Match { expression, cases } => {
+            write_ctor(buf, tag::MATCH, 2);
+            write_ann_proc(buf, expression, with_spans);
+            write_array_header(buf, cases.len() as u64);
+            for case in cases {
+                write_case(buf, case, with_spans);
+            }
+        }
+        Proc::Select { branches } => {
+            write_ctor(buf, tag::SELECT, 1);
+            write_array_header(buf, branches.len() as u64);
+            for branch in branches {
+                write_array_header(buf, 2);
+                write_array_header(buf, branch.patterns.len() as u64);
+                for pattern in &branch.patterns {
+                    write_select_pattern(buf, pattern, with_spans);
+                }
+                write_ann_proc(buf, &branch.proc, with_spans);
+            }
+        }
+        Proc::Bundle { bundle_type, proc } => {
+            write_ctor(buf, tag::BUNDLE, 2);
+            write_bundle_type(buf, *bundle_type);
+            write_ann_proc(buf, proc, with_spans);
+        }
+        Proc::Let {
+            bindings,
+            body,
+            concurrent,
+        } => {
+            write_ctor(buf, tag::LET, 3);
+            write_array_header(buf, bindings.len() as u64);
+            for binding in bindings {
+                match binding {
+                    crate::ast::LetBinding::Single { lhs, rhs } => {
+                        write_array_header(buf, 3);
+                        write_u64(buf, 0);
+                        write_ann_name(buf, lhs, with_spans);
+                        write_ann_proc(buf, rhs, with_spans);
+                    }
+                    crate::ast::LetBinding::Multiple { lhs, rhs } => {
+                        write_array_header(buf, 3);
+                        write_u64(buf, 1);
+                        write_var(buf, lhs);
+                        write_array_header(buf, rhs.len() as u64);
+                        for proc in rhs {
+                            write_ann_proc(buf, proc, with_spans);
+                        }
+                    }
+                }
+            }
+            write_ann_proc(buf, body, with_spans);
+            write_bool(buf, *concurrent);
+        }
+        Proc::New { decls, proc } => {
+            write_ctor(buf, tag::NEW, 2);
+            write_array_header(buf, decls.len() as u64);
+            for decl in decls {
+                write_name_decl(buf, decl);
+            }
+            write_ann_proc(buf, proc, with_spans);
+        }
+        Proc::Contract {
+            name,
+            formals,
+            body,
+        } => {
+            write_ctor(buf, tag::CONTRACT, 3);
+            write_ann_name(buf, name, with_spans);
+            write_names(buf, formals, with_spans);
+            write_ann_proc(buf, body, with_spans);
+        }
+        Proc::SendSync {
+            channel,
+            messages,
+            cont,
+        } => {
+            write_ctor(buf, tag::SEND_SYNC, 3);
+            write_ann_name(buf, channel, with_spans);
+            write_array_header(buf, messages.len() as u64);
+            for message in messages {
+                write_ann_proc(buf, message, with_spans);
+            }
+            match cont {
+                SyncSendCont::Empty => write_null(buf),
+                SyncSendCont::NonEmpty(proc) => write_ann_proc(buf, proc, with_spans),
+            }
+        }
+        Proc::Eval { name } => {
+            write_ctor(buf, tag::EVAL, 1);
+            write_ann_name(buf, name, with_spans);
+        }
+        Proc::Quote { proc } => {
+            write_ctor(buf, tag::QUOTE, 1);
+            write_proc(buf, proc, with_spans);
+        }
+        Proc::Method {
+            receiver,
+            name,
+            args,
+        } => {
+            write_ctor(buf, tag::METHOD, 3);
+            write_ann_proc(buf, receiver, with_spans);
+            write_id(buf, name);
+            write_array_header(buf, args.len() as u64);
+            for arg in args {
+                write_ann_proc(buf, arg, with_spans);
+            }
+        }
+        Proc::UnaryExp { op, arg } => {
+            write_ctor(buf, tag::UNARY_EXP, 2);
+            write_unary_op(buf, *op);
+            write_proc(buf, arg, with_spans);
+        }
+        Proc::BinaryExp { op, left, right } => {
+            write_ctor(buf, tag::BINARY_EXP, 3);
+            write_binary_op(buf, *op);
+            write_ann_proc(buf, left, with_spans);
+            write_ann_proc(buf, right, with_spans);
+        }
+        Proc::VarRef { kind, var } => {
+            write_ctor(buf, tag::VAR_REF, 2);
+            write_var_ref_kind(buf, *kind);
+            write_id(buf, var);
+        }
+        Proc::Bad => write_ctor(buf, tag::BAD, 0),
+        Proc::Error {
+            partial,
+            recovered_children,
+        } => {
+            write_ctor(buf, tag::ERROR, 2);
+            write_option(buf, partial, |buf, p| write_ann_proc(buf, p, with_spans));
+            write_array_header(buf, recovered_children.len() as u64);
+            for child in recovered_children {
+                write_ann_proc(buf, child, with_spans);
+            }
+        }
+    }
+}
+
+fn write_collection(buf: &mut Vec<u8>, collection: &Collection, with_spans: bool) {
+    match collection {
+        Collection::List {
+            elements,
+            remainder,
+        } => {
+            write_array_header(buf, 3);
+            write_u64(buf, collection_tag::LIST);
+            write_array_header(buf, elements.len() as u64);
+            for element in elements {
+                write_ann_proc(buf, element, with_spans);
+            }
+            write_option(buf, remainder, |buf, var| write_var(buf, var));
+        }
+        Collection::Tuple(elements) => {
+            write_array_header(buf, 2);
+            write_u64(buf, collection_tag::TUPLE);
+            write_array_header(buf, elements.len() as u64);
+            for element in elements {
+                write_ann_proc(buf, element, with_spans);
+            }
+        }
+        Collection::Set {
+            elements,
+            remainder,
+        } => {
+            write_array_header(buf, 3);
+            write_u64(buf, collection_tag::SET);
+            write_array_header(buf, elements.len() as u64);
+            for element in elements {
+                write_ann_proc(buf, element, with_spans);
+            }
+            write_option(buf, remainder, |buf, var| write_var(buf, var));
+        }
+        Collection::Map {
+            elements,
+            remainder,
+        } => {
+            write_array_header(buf, 3);
+            write_u64(buf, collection_tag::MAP);
+            write_array_header(buf, elements.len() as u64);
+            for (key, value) in elements {
+                write_array_header(buf, 2);
+                write_ann_proc(buf, key, with_spans);
+                write_ann_proc(buf, value, with_spans);
+            }
+            write_option(buf, remainder, |buf, var| write_var(buf, var));
+        }
+    }
+}
+
+fn write_ann_proc(buf: &mut Vec<u8>, ann: &AnnProc, with_spans: bool) {
+    write_array_header(buf, 2);
+    write_proc(buf, ann.proc, with_spans);
+    if with_spans {
+        write_span(buf, &ann.span);
+    } else {
+        write_null(buf);
+    }
+}
+
+/// Encode `ast` to canonical CBOR, including every node's [`SourceSpan`] so the
+/// bytes can still be traced back to source locations after a round trip.
+pub fn encode(ast: &AnnProc) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_ann_proc(&mut buf, ast, true);
+    buf
+}
+
+/// Encode `ast` in its compact "normalized" form: identical to [`encode`] except
+/// every `SourceSpan` is written as `null` instead of a line/column pair, so two
+/// structurally identical programs parsed from different source produce
+/// identical bytes.
+pub fn encode_normalized(ast: &AnnProc) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_ann_proc(&mut buf, ast, false);
+    buf
+}
+
+// ---------------------------------------------------------------------------
+// reading
+// ---------------------------------------------------------------------------
+
+/// A placeholder span used for spans that were dropped by [`encode_normalized`];
+/// decoding such bytes can't recover the original source locations.
+const ZERO_SPAN: SourceSpan = SourceSpan {
+    start: SourcePos { line: 0, col: 0 },
+    end: SourcePos { line: 0, col: 0 },
+};
+
+struct Reader<'b> {
+    bytes: &'b [u8],
+    pos: usize,
+}
+
+impl<'b> Reader<'b> {
+    fn new(bytes: &'b [u8]) -> Self {
+        Reader { bytes, pos: 0 }
+    }
+
+    fn next_byte(&mut self) -> Result<u8, DecodeError> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or(DecodeError::UnexpectedEof)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn peek_byte(&self) -> Result<u8, DecodeError> {
+        self.bytes
+            .get(self.pos)
+            .copied()
+            .ok_or(DecodeError::UnexpectedEof)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'b [u8], DecodeError> {
+        let end = self.pos.checked_add(len).ok_or(DecodeError::UnexpectedEof)?;
+        let slice = self
+            .bytes
+            .get(self.pos..end)
+            .ok_or(DecodeError::UnexpectedEof)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_head(&mut self) -> Result<(u8, u64), DecodeError> {
+        let initial = self.next_byte()?;
+        let major = initial >> 5;
+        let value = match initial & 0x1f {
+            info @ 0..=23 => info as u64,
+            24 => self.next_byte()? as u64,
+            25 => u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as u64,
+            26 => u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as u64,
+            27 => u64::from_be_bytes(self.take(8)?.try_into().unwrap()),
+            _ => return Err(DecodeError::MalformedHeader),
+        };
+        Ok((major, value))
+    }
+
+    fn is_null(&self) -> Result<bool, DecodeError> {
+        Ok(self.peek_byte()? == 0xf6)
+    }
+
+    fn read_null(&mut self) -> Result<(), DecodeError> {
+        match self.next_byte()? {
+            0xf6 => Ok(()),
+            other => Err(DecodeError::TypeMismatch {
+                expected: "null",
+                found_major: other >> 5,
+            }),
+        }
+    }
+
+    fn read_array_len(&mut self) -> Result<u64, DecodeError> {
+        let (major, len) = self.read_head()?;
+        if major != 4 {
+            return Err(DecodeError::TypeMismatch {
+                expected: "array",
+                found_major: major,
+            });
+        }
+        Ok(len)
+    }
+
+    /// Clamp an attacker-controlled element `count` to the number of bytes
+    /// left in the input before using it as a `Vec`/`SmallVec` capacity.
+    ///
+    /// Every encoded element takes at least one byte, so a `count` that
+    /// exceeds the remaining input can never be satisfied; clamping it here
+    /// turns a crafted huge `count` (e.g. `u64::MAX`) into a small, harmless
+    /// allocation instead of an immediate multi-exabyte one, leaving the
+    /// eventual `UnexpectedEof` to surface as a normal `DecodeError` once the
+    /// loop actually runs out of bytes.
+    fn checked_capacity(&self, count: u64) -> usize {
+        let remaining = (self.bytes.len() - self.pos) as u128;
+        (count as u128).min(remaining) as usize
+    }
+
+    fn expect_array(&mut self, expected_len: u64) -> Result<(), DecodeError> {
+        let len = self.read_array_len()?;
+        if len != expected_len {
+            return Err(DecodeError::ArityMismatch {
+                tag: expected_len,
+                expected: expected_len as usize,
+                actual: len as usize,
+            });
+        }
+        Ok(())
+    }
+
+    /// Read a `[tag, ...fields]` constructor header, returning the tag and the
+    /// number of fields that followed it.
+    fn read_ctor(&mut self) -> Result<(u64, u64), DecodeError> {
+        let len = self.read_array_len()?;
+        if len == 0 {
+            return Err(DecodeError::ArityMismatch {
+                tag: 0,
+                expected: 1,
+                actual: 0,
+            });
+        }
+        let tag = self.read_u64()?;
+        Ok((tag, len - 1))
+    }
+
+    fn expect_ctor_arity(&self, tag: u64, actual: u64, expected: u64) -> Result<(), DecodeError> {
+        if actual != expected {
+            return Err(DecodeError::ArityMismatch {
+                tag,
+                expected: expected as usize,
+                actual: actual as usize,
+            });
+        }
+        Ok(())
+    }
+
+    fn read_u64(&mut self) -> Result<u64, DecodeError> {
+        let (major, value) = self.read_head()?;
+        if major != 0 {
+            return Err(DecodeError::TypeMismatch {
+                expected: "unsigned integer",
+                found_major: major,
+            });
+        }
+        Ok(value)
+    }
+
+    fn read_i64(&mut self) -> Result<i64, DecodeError> {
+        let (major, value) = self.read_head()?;
+        match major {
+            0 => Ok(value as i64),
+            1 => Ok(-1 - value as i64),
+            _ => Err(DecodeError::TypeMismatch {
+                expected: "integer",
+                found_major: major,
+            }),
+        }
+    }
+
+    fn read_bool(&mut self) -> Result<bool, DecodeError> {
+        match self.next_byte()? {
+            0xf4 => Ok(false),
+            0xf5 => Ok(true),
+            other => Err(DecodeError::TypeMismatch {
+                expected: "bool",
+                found_major: other >> 5,
+            }),
+        }
+    }
+
+    fn read_text(&mut self) -> Result<String, DecodeError> {
+        let (major, len) = self.read_head()?;
+        if major != 3 {
+            return Err(DecodeError::TypeMismatch {
+                expected: "text string",
+                found_major: major,
+            });
+        }
+        let bytes = self.take(len as usize)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| DecodeError::InvalidUtf8)
+    }
+
+    fn read_option<T>(
+        &mut self,
+        read_some: impl FnOnce(&mut Self) -> Result<T, DecodeError>,
+    ) -> Result<Option<T>, DecodeError> {
+        if self.is_null()? {
+            self.read_null()?;
+            Ok(None)
+        } else {
+            Ok(Some(read_some(self)?))
+        }
+    }
+}
+
+fn read_pos(reader: &mut Reader) -> Result<SourcePos, DecodeError> {
+    reader.expect_array(2)?;
+    let line = reader.read_u64()? as usize;
+    let col = reader.read_u64()? as usize;
+    Ok(SourcePos { line, col })
+}
+
+fn read_span(reader: &mut Reader) -> Result<SourceSpan, DecodeError> {
+    reader.expect_array(2)?;
+    let start = read_pos(reader)?;
+    let end = read_pos(reader)?;
+    Ok(SourceSpan { start, end })
+}
+
+fn read_span_opt(reader: &mut Reader) -> Result<SourceSpan, DecodeError> {
+    Ok(reader.read_option(read_span)?.unwrap_or(ZERO_SPAN))
+}
+
+fn read_id<'ast>(reader: &mut Reader, builder: &'ast ASTBuilder<'ast>) -> Result<Id<'ast>, DecodeError> {
+    let name = reader.read_text()?;
+    Ok(Id {
+        name: builder.alloc_str(name),
+        pos: SourcePos { line: 0, col: 0 },
+    })
+}
+
+fn read_var<'ast>(reader: &mut Reader, builder: &'ast ASTBuilder<'ast>) -> Result<Var<'ast>, DecodeError> {
+    if reader.is_null()? {
+        reader.read_null()?;
+        Ok(Var::Wildcard)
+    } else {
+        Ok(Var::Id(read_id(reader, builder)?))
+    }
+}
+
+fn read_name<'ast>(reader: &mut Reader, builder: &'ast ASTBuilder<'ast>) -> Result<Name<'ast>, DecodeError> {
+    reader.expect_array(2)?;
+    match reader.read_u64()? {
+        0 => Ok(Name::ProcVar(read_var(reader, builder)?)),
+        1 => Ok(Name::Quote(read_proc(reader, builder)?)),
+        other => Err(DecodeError::UnknownTag(other)),
+    }
+}
+
+fn read_ann_name<'ast>(
+    reader: &mut Reader,
+    builder: &'ast ASTBuilder<'ast>,
+) -> Result<AnnName<'ast>, DecodeError> {
+    reader.expect_array(2)?;
+    let name = read_name(reader, builder)?;
+    let span = read_span_opt(reader)?;
+    Ok(AnnName { name, span })
+}
+
+fn read_names<'ast>(
+    reader: &mut Reader,
+    builder: &'ast ASTBuilder<'ast>,
+) -> Result<Names<'ast>, DecodeError> {
+    reader.expect_array(2)?;
+    let len = reader.read_array_len()?;
+    let mut names = SmallVec::with_capacity(reader.checked_capacity(len));
+    for _ in 0..len {
+        names.push(read_ann_name(reader, builder)?);
+    }
+    let remainder = reader.read_option(|reader| read_var(reader, builder))?;
+    Ok(Names { names, remainder })
+}
+
+fn read_send_type(reader: &mut Reader) -> Result<SendType, DecodeError> {
+    match reader.read_u64()? {
+        0 => Ok(SendType::Single),
+        1 => Ok(SendType::Multiple),
+        other => Err(DecodeError::UnknownTag(other)),
+    }
+}
+
+fn read_bundle_type(reader: &mut Reader) -> Result<BundleType, DecodeError> {
+    match reader.read_u64()? {
+        0 => Ok(BundleType::BundleEquiv),
+        1 => Ok(BundleType::BundleWrite),
+        2 => Ok(BundleType::BundleRead),
+        3 => Ok(BundleType::BundleReadWrite),
+        other => Err(DecodeError::UnknownTag(other)),
+    }
+}
+
+fn read_simple_type(reader: &mut Reader) -> Result<SimpleType, DecodeError> {
+    match reader.read_u64()? {
+        0 => Ok(SimpleType::Bool),
+        1 => Ok(SimpleType::Int),
+        2 => Ok(SimpleType::String),
+        3 => Ok(SimpleType::Uri),
+        4 => Ok(SimpleType::ByteArray),
+        other => Err(DecodeError::UnknownTag(other)),
+    }
+}
+
+fn read_unary_op(reader: &mut Reader) -> Result<UnaryExpOp, DecodeError> {
+    match reader.read_u64()? {
+        0 => Ok(UnaryExpOp::Not),
+        1 => Ok(UnaryExpOp::Neg),
+        2 => Ok(UnaryExpOp::Negation),
+        other => Err(DecodeError::UnknownTag(other)),
+    }
+}
+
+fn read_binary_op(reader: &mut Reader) -> Result<BinaryExpOp, DecodeError> {
+    Ok(match reader.read_u64()? {
+        0 => BinaryExpOp::Or,
+        1 => BinaryExpOp::And,
+        2 => BinaryExpOp::Matches,
+        3 => BinaryExpOp::Eq,
+        4 => BinaryExpOp::Neq,
+        5 => BinaryExpOp::Lt,
+        6 => BinaryExpOp::Lte,
+        7 => BinaryExpOp::Gt,
+        8 => BinaryExpOp::Gte,
+        9 => BinaryExpOp::Concat,
+        10 => BinaryExpOp::Diff,
+        11 => BinaryExpOp::Add,
+        12 => BinaryExpOp::Sub,
+        13 => BinaryExpOp::Interpolation,
+        14 => BinaryExpOp::Mult,
+        15 => BinaryExpOp::Div,
+        16 => BinaryExpOp::Mod,
+        17 => BinaryExpOp::Disjunction,
+        18 => BinaryExpOp::Conjunction,
+        other => return Err(DecodeError::UnknownTag(other)),
+    })
+}
+
+fn read_var_ref_kind(reader: &mut Reader) -> Result<VarRefKind, DecodeError> {
+    match reader.read_u64()? {
+        0 => Ok(VarRefKind::Proc),
+        1 => Ok(VarRefKind::Name),
+        other => Err(DecodeError::UnknownTag(other)),
+    }
+}
+
+fn read_name_decl<'ast>(
+    reader: &mut Reader,
+    builder: &'ast ASTBuilder<'ast>,
+) -> Result<NameDecl<'ast>, DecodeError> {
+    reader.expect_array(2)?;
+    let id = read_id(reader, builder)?;
+    let uri = reader.read_option(|reader| Ok(Uri::from(builder.alloc_str(reader.read_text()?))))?;
+    Ok(NameDecl { id, uri })
+}
+
+fn read_source<'ast>(
+    reader: &mut Reader,
+    builder: &'ast ASTBuilder<'ast>,
+) -> Result<Source<'ast>, DecodeError> {
+    let (discriminant, field_count) = reader.read_ctor()?;
+    match discriminant {
+        0 => {
+            reader.expect_ctor_arity(discriminant, field_count, 1)?;
+            Ok(Source::Simple {
+                name: read_ann_name(reader, builder)?,
+            })
+        }
+        1 => {
+            reader.expect_ctor_arity(discriminant, field_count, 1)?;
+            Ok(Source::ReceiveSend {
+                name: read_ann_name(reader, builder)?,
+            })
+        }
+        2 => {
+            reader.expect_ctor_arity(discriminant, field_count, 2)?;
+            let name = read_ann_name(reader, builder)?;
+            let count = reader.read_array_len()?;
+            let mut inputs = SmallVec::with_capacity(reader.checked_capacity(count));
+            for _ in 0..count {
+                inputs.push(read_ann_proc(reader, builder)?);
+            }
+            Ok(Source::SendReceive { name, inputs })
+        }
+        other => Err(DecodeError::UnknownTag(other)),
+    }
+}
+
+fn read_bind<'ast>(
+    reader: &mut Reader,
+    builder: &'ast ASTBuilder<'ast>,
+) -> Result<Bind<'ast>, DecodeError> {
+    let (discriminant, field_count) = reader.read_ctor()?;
+    reader.expect_ctor_arity(discriminant, field_count, 2)?;
+    let lhs = read_names(reader, builder)?;
+    match discriminant {
+        0 => Ok(Bind::Linear {
+            lhs,
+            rhs: read_source(reader, builder)?,
+        }),
+        1 => Ok(Bind::Repeated {
+            lhs,
+            rhs: read_ann_name(reader, builder)?,
+        }),
+        2 => Ok(Bind::Peek {
+            lhs,
+            rhs: read_ann_name(reader, builder)?,
+        }),
+        other => Err(DecodeError::UnknownTag(other)),
+    }
+}
+
+fn read_receipt<'ast>(
+    reader: &mut Reader,
+    builder: &'ast ASTBuilder<'ast>,
+) -> Result<Receipt<'ast>, DecodeError> {
+    let len = reader.read_array_len()?;
+    let mut binds = SmallVec::with_capacity(reader.checked_capacity(len));
+    for _ in 0..len {
+        binds.push(read_bind(reader, builder)?);
+    }
+    Ok(Receipt { binds })
+}
+
+fn read_case<'ast>(
+    reader: &mut Reader,
+    builder: &'ast ASTBuilder<'ast>,
+) -> Result<Case<'ast>, DecodeError> {
+    reader.expect_array(2)?;
+    let pattern = read_ann_proc(reader, builder)?;
+    let proc = read_ann_proc(reader, builder)?;
+    Ok(Case { pattern, proc })
+}
+
+fn read_select_pattern<'ast>(
+    reader: &mut Reader,
+    builder: &'ast ASTBuilder<'ast>,
+) -> Result<SelectPattern<'ast>, DecodeError> {
+    reader.expect_array(2)?;
+    let lhs = read_names(reader, builder)?;
+    let rhs = read_source(reader, builder)?;
+    Ok(SelectPattern { lhs, rhs })
+}
+
+/// Read one `Proc`'s own tagged array (no span). Pairs with [`write_proc`].
+fn read_proc<'ast>(
+    reader: &mut Reader,
+    builder: &'ast ASTBuilder<'ast>,
+) -> Result<&'ast Proc<'ast>, DecodeError> {
+    let (constructor_tag, field_count) = reader.read_ctor()?;
+
+    macro_rules! arity {
+        ($expected:expr) => {
+            reader.expect_ctor_arity(constructor_tag, field_count, $expected)?
+        };
+    }
+
+    match constructor_tag {
+        tag::NIL => {
+            arity!(0);
+            Ok(&builder.NIL)
+        }
+        tag::BOOL_LITERAL => {
+            arity!(1);
+            Ok(if reader.read_bool()? {
+                &builder.TRUE
+            } else {
+                &builder.FALSE
+            })
+        }
+        tag::LONG_LITERAL => {
+            arity!(1);
+            Ok(builder.alloc_long_literal(reader.read_i64()?))
+        }
+        tag::STRING_LITERAL => {
+            arity!(1);
+            let value = reader.read_text()?;
+            Ok(builder.alloc_string_literal(builder.alloc_str(value)))
+        }
+        tag::URI_LITERAL => {
+            arity!(1);
+            let value = reader.read_text()?;
+            Ok(builder.alloc_uri_literal(builder.alloc_str(value)))
+        }
+        tag::SIMPLE_TYPE => {
+            arity!(1);
+            Ok(builder.alloc_simple_type(read_simple_type(reader)?))
+        }
+        tag::COLLECTION => {
+            arity!(1);
+            read_collection(reader, builder)
+        }
+        tag::PROC_VAR => {
+            arity!(1);
+            Ok(builder.alloc_var(match read_var(reader, builder)? {
+                Var::Id(id) => id,
+                Var::Wildcard => {
+                    return Ok(&builder.WILD);
+                }
+            }))
+        }
+        tag::PAR => {
+            arity!(2);
+            let left = read_ann_proc(reader, builder)?;
+            let right = read_ann_proc(reader, builder)?;
+            Ok(builder.alloc_par(left, right))
+        }
+        tag::IF_THEN_ELSE => {
+            arity!(3);
+            let condition = read_ann_proc(reader, builder)?;
+            let if_true = read_ann_proc(reader, builder)?;
+            let if_false = reader.read_option(|reader| read_ann_proc(reader, builder))?;
+            Ok(match if_false {
+                Some(if_false) => builder.alloc_if_then_else(condition, if_true, if_false),
+                None => builder.alloc_if_then(condition, if_true),
+            })
+        }
+        tag::SEND => {
+            arity!(3);
+            let channel = read_ann_name(reader, builder)?;
+            let send_type = read_send_type(reader)?;
+            let count = reader.read_array_len()?;
+            let mut inputs = Vec::with_capacity(reader.checked_capacity(count));
+            for _ in 0..count {
+                inputs.push(read_ann_proc(reader, builder)?);
+            }
+            Ok(builder.alloc_send(send_type, channel, &inputs))
+        }
+        tag::FOR_COMPREHENSION => {
+            arity!(2);
+            let receipt_count = reader.read_array_len()?;
+            let mut receipts = Vec::with_capacity(reader.checked_capacity(receipt_count));
+            for _ in 0..receipt_count {
+                receipts.push(read_receipt(reader, builder)?.binds);
+            }
+            let proc = read_ann_proc(reader, builder)?;
+            Ok(builder.alloc_for(receipts, proc))
+        }
+        tag::MATCH => {
+            arity!(2);
+            let expression = read_ann_proc(reader, builder)?;
+            let case_count = reader.read_array_len()?;
+            let mut cases = Vec::with_capacity(reader.checked_capacity(case_count.saturating_mul(2)));
+            for _ in 0..case_count {
+                let case = read_case(reader, builder)?;
+                cases.push(case.pattern);
+                cases.push(case.proc);
+            }
+            Ok(builder.alloc_match(expression, &cases))
+        }
+        tag::SELECT => {
+            arity!(1);
+            let branch_count = reader.read_array_len()?;
+            let mut branches = Vec::with_capacity(reader.checked_capacity(branch_count));
+            for _ in 0..branch_count {
+                reader.expect_array(2)?;
+                let pattern_count = reader.read_array_len()?;
+                let mut patterns = Vec::with_capacity(reader.checked_capacity(pattern_count));
+                for _ in 0..pattern_count {
+                    patterns.push(read_select_pattern(reader, builder)?);
+                }
+                let proc = read_ann_proc(reader, builder)?;
+                branches.push(crate::ast::Branch { patterns, proc });
+            }
+            Ok(builder.alloc_select(branches))
+        }
+        tag::BUNDLE => {
+            arity!(2);
+            let bundle_type = read_bundle_type(reader)?;
+            let proc = read_ann_proc(reader, builder)?;
+            Ok(builder.alloc_bundle(bundle_type, proc))
+        }
+        tag::LET => {
+            arity!(3);
+            let binding_count = reader.read_array_len()?;
+            let mut bindings = Vec::with_capacity(reader.checked_capacity(binding_count));
+            for _ in 0..binding_count {
+                let (discriminant, field_count) = reader.read_ctor()?;
+                reader.expect_ctor_arity(discriminant, field_count, 2)?;
+                match discriminant {
+                    0 => {
+                        let lhs = read_ann_name(reader, builder)?;
+                        let rhs = read_ann_proc(reader, builder)?;
+                        bindings.push(crate::ast::LetBinding::Single { lhs, rhs });
+                    }
+                    1 => {
+                        let lhs = read_var(reader, builder)?;
+                        let count = reader.read_array_len()?;
+                        let mut rhs = Vec::with_capacity(reader.checked_capacity(count));
+                        for _ in 0..count {
+                            rhs.push(read_ann_proc(reader, builder)?);
+                        }
+                        bindings.push(crate::ast::LetBinding::Multiple { lhs, rhs });
+                    }
+                    other => return Err(DecodeError::UnknownTag(other)),
+                }
+            }
+            let body = read_ann_proc(reader, builder)?;
+            let concurrent = reader.read_bool()?;
+            Ok(builder.alloc_let(bindings, body, concurrent))
+        }
+        tag::NEW => {
+            arity!(2);
+            let decl_count = reader.read_array_len()?;
+            let mut decls = Vec::with_capacity(reader.checked_capacity(decl_count));
+            for _ in 0..decl_count {
+                decls.push(read_name_decl(reader, builder)?);
+            }
+            let proc = read_ann_proc(reader, builder)?;
+            Ok(builder.alloc_new(proc, decls))
+        }
+        tag::CONTRACT => {
+            arity!(3);
+            let name = read_ann_name(reader, builder)?;
+            let formals = read_names(reader, builder)?;
+            let body = read_ann_proc(reader, builder)?;
+            Ok(builder.alloc_contract(name, formals, body))
+        }
+        tag::SEND_SYNC => {
+            arity!(3);
+            let channel = read_ann_name(reader, builder)?;
+            let count = reader.read_array_len()?;
+            let mut messages = Vec::with_capacity(reader.checked_capacity(count));
+            for _ in 0..count {
+                messages.push(read_ann_proc(reader, builder)?);
+            }
+            let cont = reader.read_option(|reader| read_ann_proc(reader, builder))?;
+            Ok(match cont {
+                Some(cont) => builder.alloc_send_sync_with_cont(channel, &messages, cont),
+                None => builder.alloc_send_sync(channel, &messages),
+            })
+        }
+        tag::EVAL => {
+            arity!(1);
+            Ok(builder.alloc_eval(read_ann_name(reader, builder)?))
+        }
+        tag::QUOTE => {
+            arity!(1);
+            Ok(builder.alloc_quote(read_proc(reader, builder)?))
+        }
+        tag::METHOD => {
+            arity!(3);
+            let receiver = read_ann_proc(reader, builder)?;
+            let name = read_id(reader, builder)?;
+            let count = reader.read_array_len()?;
+            let mut args = Vec::with_capacity(reader.checked_capacity(count));
+            for _ in 0..count {
+                args.push(read_ann_proc(reader, builder)?);
+            }
+            Ok(builder.alloc_method(name, receiver, &args))
+        }
+        tag::UNARY_EXP => {
+            arity!(2);
+            let op = read_unary_op(reader)?;
+            let arg = read_proc(reader, builder)?;
+            Ok(builder.alloc_unary_exp(op, arg))
+        }
+        tag::BINARY_EXP => {
+            arity!(3);
+            let op = read_binary_op(reader)?;
+            let left = read_ann_proc(reader, builder)?;
+            let right = read_ann_proc(reader, builder)?;
+            Ok(builder.alloc_binary_exp(op, left, right))
+        }
+        tag::VAR_REF => {
+            arity!(2);
+            let kind = read_var_ref_kind(reader)?;
+            let var = read_id(reader, builder)?;
+            Ok(builder.alloc_var_ref(kind, var))
+        }
+        tag::BAD => {
+            arity!(0);
+            Ok(&builder.BAD)
+        }
+        tag::ERROR => {
+            arity!(2);
+            let partial = reader.read_option(|reader| read_ann_proc(reader, builder))?;
+            let count = reader.read_array_len()?;
+            let mut recovered_children = Vec::with_capacity(reader.checked_capacity(count));
+            for _ in 0..count {
+                recovered_children.push(read_ann_proc(reader, builder)?);
+            }
+            Ok(builder.alloc_error(partial, &recovered_children))
+        }
+        other => Err(DecodeError::UnknownTag(other)),
+    }
+}
+
+fn read_collection<'ast>(
+    reader: &mut Reader,
+    builder: &'ast ASTBuilder<'ast>,
+) -> Result<&'ast Proc<'ast>, DecodeError> {
+    let (discriminant, field_count) = reader.read_ctor()?;
+    match discriminant {
+        collection_tag::LIST => {
+            reader.expect_ctor_arity(discriminant, field_count, 2)?;
+            let count = reader.read_array_len()?;
+            let mut elements = Vec::with_capacity(reader.checked_capacity(count));
+            for _ in 0..count {
+                elements.push(read_ann_proc(reader, builder)?);
+            }
+            let remainder = reader.read_option(|reader| read_var(reader, builder))?;
+            Ok(match remainder {
+                Some(remainder) => builder.alloc_list_with_remainder(&elements, remainder),
+                None => builder.alloc_list(&elements),
+            })
+        }
+        collection_tag::TUPLE => {
+            reader.expect_ctor_arity(discriminant, field_count, 1)?;
+            let count = reader.read_array_len()?;
+            let mut elements = Vec::with_capacity(reader.checked_capacity(count));
+            for _ in 0..count {
+                elements.push(read_ann_proc(reader, builder)?);
+            }
+            Ok(builder.alloc_tuple(&elements))
+        }
+        collection_tag::SET => {
+            reader.expect_ctor_arity(discriminant, field_count, 2)?;
+            let count = reader.read_array_len()?;
+            let mut elements = Vec::with_capacity(reader.checked_capacity(count));
+            for _ in 0..count {
+                elements.push(read_ann_proc(reader, builder)?);
+            }
+            let remainder = reader.read_option(|reader| read_var(reader, builder))?;
+            Ok(match remainder {
+                Some(remainder) => builder.alloc_set_with_remainder(&elements, remainder),
+                None => builder.alloc_set(&elements),
+            })
+        }
+        collection_tag::MAP => {
+            reader.expect_ctor_arity(discriminant, field_count, 2)?;
+            let count = reader.read_array_len()?;
+            let mut pairs = Vec::with_capacity(reader.checked_capacity(count.saturating_mul(2)));
+            for _ in 0..count {
+                reader.expect_array(2)?;
+                pairs.push(read_ann_proc(reader, builder)?);
+                pairs.push(read_ann_proc(reader, builder)?);
+            }
+            let remainder = reader.read_option(|reader| read_var(reader, builder))?;
+            Ok(match remainder {
+                Some(remainder) => builder.alloc_map_with_remainder(&pairs, remainder),
+                None => builder.alloc_map(&pairs),
+            })
+        }
+        other => Err(DecodeError::UnknownTag(other)),
+    }
+}
+
+fn read_ann_proc<'ast>(
+    reader: &mut Reader,
+    builder: &'ast ASTBuilder<'ast>,
+) -> Result<AnnProc<'ast>, DecodeError> {
+    reader.expect_array(2)?;
+    let proc = read_proc(reader, builder)?;
+    let span = read_span_opt(reader)?;
+    Ok(AnnProc { proc, span })
+}
+
+/// Decode `bytes` (as produced by [`encode`] or [`encode_normalized`]) back into
+/// an arena-allocated `AnnProc`, rebuilding it through `builder`'s `alloc_*`
+/// entry points so sharing/interning is preserved.
+pub(super) fn decode<'ast>(
+    bytes: &[u8],
+    builder: &'ast ASTBuilder<'ast>,
+) -> Validated<AnnProc<'ast>, DecodeError> {
+    let mut reader = Reader::new(bytes);
+    match read_ann_proc(&mut reader, builder) {
+        Ok(ast) if reader.pos == bytes.len() => Validated::Good(ast),
+        Ok(_) => Validated::fail(DecodeError::TrailingBytes),
+        Err(err) => Validated::fail(err),
+    }
+}
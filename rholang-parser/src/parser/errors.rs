@@ -8,6 +8,17 @@ use crate::{SourcePos, SourceSpan, ast::AnnProc};
 pub enum ParsingError {
     SyntaxError { sexp: String },
     MissingToken(&'static str),
+    /// Same situation as [`Self::MissingToken`], but with enough context to
+    /// phrase it as "expected _ for this _ … but found _": the grammar
+    /// symbol that was expected, what tree-sitter found in its place (or
+    /// `"the input ends here"` if there was nothing left to find), and the
+    /// enclosing construct whose span is attached as a related label on
+    /// [`AnnParsingError`].
+    MissingWithContext {
+        expected: &'static str,
+        found: &'static str,
+        enclosing: &'static str,
+    },
     Unexpected(char),
     UnexpectedVar(String),
     NumberOutOfRange,
@@ -15,6 +26,30 @@ pub enum ParsingError {
     MalformedLetDecl { lhs_arity: usize, rhs_arity: usize },
 }
 
+impl std::fmt::Display for ParsingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParsingError::SyntaxError { sexp } => write!(f, "syntax error: {sexp}"),
+            ParsingError::MissingToken(kind) => write!(f, "missing {kind}"),
+            ParsingError::MissingWithContext { expected, found, enclosing } => {
+                write!(f, "expected {expected} for {enclosing}, but found {found}")
+            }
+            ParsingError::Unexpected(c) => write!(f, "unexpected character '{c}'"),
+            ParsingError::UnexpectedVar(var) => write!(f, "unexpected variable '{var}'"),
+            ParsingError::NumberOutOfRange => write!(f, "number out of range"),
+            ParsingError::DuplicateNameDecl { first, second } => {
+                write!(f, "name declared again at {second}, first declared at {first}")
+            }
+            ParsingError::MalformedLetDecl { lhs_arity, rhs_arity } => write!(
+                f,
+                "let binding has {lhs_arity} name(s) but {rhs_arity} value(s)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParsingError {}
+
 impl ParsingError {
     fn from_error_node(node: &tree_sitter::Node, code: &[u8]) -> Self {
         if let Some(child) = node.named_child(0) {
@@ -35,10 +70,22 @@ impl ParsingError {
     }
 }
 
+/// A secondary label pointing at a location that's relevant to an error
+/// without itself being the offending span — e.g. the enclosing construct a
+/// `MISSING` token was expected inside.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelatedSpan {
+    pub span: SourceSpan,
+    pub byte_range: Range<usize>,
+    pub label: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AnnParsingError {
     pub error: ParsingError,
     pub span: SourceSpan,
+    pub byte_range: Range<usize>,
+    pub related: Vec<RelatedSpan>,
 }
 
 impl AnnParsingError {
@@ -47,6 +94,8 @@ impl AnnParsingError {
         AnnParsingError {
             error,
             span: node.range().into(),
+            byte_range: node.byte_range(),
+            related: Vec::new(),
         }
     }
 
@@ -55,6 +104,48 @@ impl AnnParsingError {
         AnnParsingError {
             error: ParsingError::MissingToken(kind),
             span: node.range().into(),
+            byte_range: node.byte_range(),
+            related: Vec::new(),
+        }
+    }
+
+    /// Same situation as [`Self::from_mising`], but `node`'s `parent` is
+    /// attached as a related "while parsing this …" label, and `found` (the
+    /// kind of whatever sits where `node` was expected, or `None` at
+    /// end-of-input) is folded into the message.
+    pub(super) fn from_missing_with_context(
+        node: &tree_sitter::Node,
+        parent: &tree_sitter::Node,
+        found: Option<&'static str>,
+    ) -> Self {
+        let expected = expected_label(node.kind());
+        let enclosing = enclosing_label(parent.kind());
+        AnnParsingError {
+            error: ParsingError::MissingWithContext {
+                expected,
+                found: found.unwrap_or("the input ends here"),
+                enclosing,
+            },
+            span: node.range().into(),
+            byte_range: node.byte_range(),
+            related: vec![RelatedSpan {
+                span: parent.range().into(),
+                byte_range: parent.byte_range(),
+                label: format!("while parsing {enclosing}"),
+            }],
+        }
+    }
+
+    /// Build an error whose span and byte range both come straight from
+    /// `node`, with no related labels — used by call sites in `parsing.rs`
+    /// that already have the offending node in hand (arity mismatches,
+    /// duplicate declarations, out-of-range literals).
+    pub(super) fn at(node: &tree_sitter::Node, error: ParsingError) -> Self {
+        AnnParsingError {
+            error,
+            span: node.range().into(),
+            byte_range: node.byte_range(),
+            related: Vec::new(),
         }
     }
 
@@ -63,8 +154,194 @@ impl AnnParsingError {
         AnnParsingError {
             error: ParsingError::UnexpectedVar(var.to_owned()),
             span: var_node.range().into(),
+            byte_range: var_node.byte_range(),
+            related: Vec::new(),
+        }
+    }
+}
+
+impl std::fmt::Display for AnnParsingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", self.error, self.span)
+    }
+}
+
+impl std::error::Error for AnnParsingError {}
+
+impl miette::Diagnostic for AnnParsingError {
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        let primary = miette::LabeledSpan::new_with_span(
+            Some(primary_label(&self.error)),
+            byte_range_to_miette(&self.byte_range),
+        );
+        let related = self
+            .related
+            .iter()
+            .map(|r| miette::LabeledSpan::new_with_span(Some(r.label.clone()), byte_range_to_miette(&r.byte_range)));
+        Some(Box::new(std::iter::once(primary).chain(related)))
+    }
+}
+
+fn byte_range_to_miette(range: &Range<usize>) -> miette::SourceSpan {
+    (range.start, range.end - range.start).into()
+}
+
+fn primary_label(error: &ParsingError) -> String {
+    match error {
+        ParsingError::MissingWithContext { expected, found, .. } => format!("expected {expected}, found {found}"),
+        other => other.to_string(),
+    }
+}
+
+/// Turn a grammar symbol's raw kind name into the phrase a `MISSING`
+/// diagnostic should use for it; unmapped kinds just fall back to the raw
+/// name quoted as a literal token (`)`, `in`, …).
+fn expected_label(kind: &'static str) -> &'static str {
+    match kind {
+        "var" => "a name",
+        "name" => "a channel name",
+        "proc" => "a process",
+        _ => kind,
+    }
+}
+
+/// Turn an enclosing node's raw kind name into the phrase a related label
+/// should use for it ("while parsing …").
+fn enclosing_label(kind: &'static str) -> &'static str {
+    match kind {
+        "send" => "this send",
+        "send_sync" => "this synchronous send",
+        "new" => "this `new`",
+        "contract" => "this contract",
+        "input" => "this `for`",
+        "match" => "this `match`",
+        "let" => "this `let`",
+        "bundle" => "this bundle",
+        _ => kind,
+    }
+}
+
+/// What kind of tree-sitter recovery node a [`Diagnostic`] was built from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    /// An `ERROR` node; `found` is the unexpected token text tree-sitter
+    /// parsed in its place, or empty if the node has no text of its own
+    /// (e.g. it spans a construct missing from the input rather than a bad
+    /// token).
+    UnexpectedToken { found: String },
+    /// A `MISSING` node; `expected` is the grammar symbol tree-sitter needed
+    /// to complete the enclosing construct.
+    MissingToken { expected: &'static str },
+}
+
+/// A node-kind/row-col rendering of a single `ERROR`/`MISSING` node from a
+/// tree-sitter parse, independent of [`AnnParsingError`]'s typed
+/// [`ParsingError`] variants. Meant for an editor/CLI diagnostic pane that
+/// wants a rustc-style "line | source" snippet with a caret underline rather
+/// than [`AnnParsingError`]'s structured [`miette::Diagnostic`] labels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub node_kind: &'static str,
+    pub span: SourceSpan,
+    pub byte_range: Range<usize>,
+}
+
+impl Diagnostic {
+    fn from_node(node: &tree_sitter::Node, source: &str) -> Self {
+        let kind = if node.is_missing() {
+            DiagnosticKind::MissingToken {
+                expected: node.kind(),
+            }
+        } else {
+            DiagnosticKind::UnexpectedToken {
+                found: get_text(node, source.as_bytes()).to_string(),
+            }
+        };
+        Diagnostic {
+            kind,
+            node_kind: node.kind(),
+            span: node.range().into(),
+            byte_range: node.byte_range(),
         }
     }
+
+    /// Render this diagnostic as a `codespan-reporting`-style report: an
+    /// `error: …` message, a `--> filename:line:col` location header, and the
+    /// offending source line with a caret underline beneath it. Pass
+    /// `color: true` to wrap the message and caret in the same raw ANSI
+    /// escapes `shell`'s REPL highlighter uses (see `push_colored` in
+    /// `rholang_helper.rs`) for callers writing straight to a terminal.
+    pub fn render(&self, filename: &str, source: &str, color: bool) -> String {
+        let line_no = self.span.start.line;
+        let col_no = self.span.start.col;
+        let line = source.lines().nth(line_no - 1).unwrap_or("");
+
+        let start_col = self.span.start.col;
+        let end_col = if self.span.end.line == self.span.start.line {
+            self.span.end.col
+        } else {
+            line.len() + 1
+        };
+        let underline_width = end_col.saturating_sub(start_col).max(1);
+        let caret = "^".repeat(underline_width);
+
+        let gutter_width = line_no.to_string().len();
+        let blank_gutter = " ".repeat(gutter_width + 1);
+        let caret_pad = " ".repeat(gutter_width + 3 + start_col.saturating_sub(1));
+
+        let (bold_red, reset) = if color { ("\x1b[1;31m", "\x1b[0m") } else { ("", "") };
+
+        format!(
+            "{bold_red}error{reset}: {self}\n\
+             {blank_gutter}--> {filename}:{line_no}:{col_no}\n\
+             {blank_gutter}|\n\
+             {line_no} | {line}\n\
+             {caret_pad}{bold_red}{caret}{reset}"
+        )
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            DiagnosticKind::UnexpectedToken { found } if found.is_empty() => {
+                write!(f, "unexpected token in {}", self.node_kind)
+            }
+            DiagnosticKind::UnexpectedToken { found } => write!(f, "unexpected token '{found}'"),
+            DiagnosticKind::MissingToken { expected } => write!(f, "missing {expected}"),
+        }
+    }
+}
+
+/// Walk every `ERROR`/`MISSING` node under `root` (in source order) into a
+/// [`Diagnostic`]. This is a separate, simpler traversal from
+/// [`query_errors`]'s job of building the typed [`ParsingError`]/
+/// [`AnnParsingError`] the parser uses internally; the first diagnostic here
+/// (if any) describes the same primary failure `query_errors` would report,
+/// while the full returned list is retained for callers that want to show
+/// every recovery point at once.
+pub fn collect_diagnostics(root: &tree_sitter::Node, source: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    walk_diagnostics(root, source, &mut diagnostics);
+    diagnostics
+}
+
+fn walk_diagnostics(node: &tree_sitter::Node, source: &str, into: &mut Vec<Diagnostic>) {
+    if node.is_missing() {
+        // A MISSING node is synthesized by tree-sitter and has no children
+        // of its own worth recursing into.
+        into.push(Diagnostic::from_node(node, source));
+        return;
+    }
+    if node.is_error() {
+        into.push(Diagnostic::from_node(node, source));
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        walk_diagnostics(&child, source, into);
+    }
 }
 
 fn get_text<'a>(of: &tree_sitter::Node, code: &'a [u8]) -> &'a str {
@@ -122,7 +399,13 @@ pub(super) fn query_errors(of: &tree_sitter::Node, source: &str, into: &mut Vec<
                 }
                 1 => {
                     // @missing-node
-                    into.push(AnnParsingError::from_mising(&node));
+                    into.push(match node.parent() {
+                        Some(parent) => {
+                            let found = node.next_sibling().map(|sibling| sibling.kind());
+                            AnnParsingError::from_missing_with_context(&node, &parent, found)
+                        }
+                        None => AnnParsingError::from_mising(&node),
+                    });
                 }
                 _ => {
                     if node.parent().is_some_and(|p| p.is_error()) {
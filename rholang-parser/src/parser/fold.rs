@@ -0,0 +1,1396 @@
+//! A generic fold/visitor framework over [`Proc`], plus the two instances
+//! built on top of it: [`free_names`] and [`substitute`].
+//!
+//! [`ProcFolder`] has one method per `Proc` constructor. Each method is
+//! handed the already-folded `Output` of its children (and, for the
+//! variants that carry one, a bare `SourceSpan` rather than the original
+//! node — there's no single node to point at for `Quote`/`UnaryExp`'s
+//! span-less inner `&Proc`, so every method takes the span of whichever
+//! `AnnProc`/`AnnName` it was reached through instead). Composite methods
+//! that combine their children via `Self::Output::default()`/`Add` come
+//! with a default body, bounded on the method itself rather than the trait,
+//! so an `Output` that can't satisfy `Default`/`Add` (like [`substitute`]'s
+//! `AnnProc`) simply has to override them; an `Output` that can (like
+//! [`free_names`]'s name set) gets structural recursion for free and only
+//! has to override the binder-aware constructors.
+//!
+//! [`ProcFolder::fold_proc`] drives the traversal with an explicit stack
+//! rather than native recursion, the same technique `parsing::build_ast`
+//! uses, so folding a deeply right-nested `Par` chain can't blow the stack.
+
+use std::collections::BTreeSet;
+use std::ops::Add;
+
+use crate::ast::{
+    AnnName, AnnProc, BinaryExpOp, Bind, Branch, BundleType, Collection, Id, LetBinding, Name,
+    NameDecl, Names, Proc, Receipts, SelectPattern, SendType, SimpleType, Source, SyncSendCont,
+    UnaryExpOp, Uri, Var, VarRefKind,
+};
+use crate::parser::ast_builder::ASTBuilder;
+use crate::SourceSpan;
+
+/// One `Bind`'s right-hand side, folded, with its (unfolded) binder
+/// occurrences carried through unchanged since they're declarations, not
+/// name *uses*.
+pub enum FoldedSource<O> {
+    Simple { name: O },
+    ReceiveSend { name: O },
+    SendReceive { name: O, inputs: Vec<O> },
+}
+
+/// A `for`-comprehension `Bind`, folded.
+pub enum FoldedBind<'ast, O> {
+    Linear {
+        lhs: &'ast Names<'ast>,
+        rhs: FoldedSource<O>,
+    },
+    Repeated {
+        lhs: &'ast Names<'ast>,
+        rhs: O,
+    },
+    Peek {
+        lhs: &'ast Names<'ast>,
+        rhs: O,
+    },
+}
+
+/// A `for`-comprehension `Receipt`, folded.
+pub struct FoldedReceipt<'ast, O> {
+    pub binds: Vec<FoldedBind<'ast, O>>,
+}
+
+/// A `select` branch's pattern, folded.
+pub struct FoldedSelectPattern<'ast, O> {
+    pub lhs: &'ast Names<'ast>,
+    pub rhs: FoldedSource<O>,
+}
+
+/// A `select` branch, folded.
+pub struct FoldedBranch<'ast, O> {
+    pub patterns: Vec<FoldedSelectPattern<'ast, O>>,
+    pub proc: O,
+}
+
+/// A `let` binding, folded. The binder (`lhs`) is carried through unchanged,
+/// same rationale as [`FoldedBind`].
+pub enum FoldedLetBinding<'ast, O> {
+    Single { lhs: AnnName<'ast>, rhs: O },
+    Multiple { lhs: Var<'ast>, rhs: Vec<O> },
+}
+
+/// A collection literal, folded; the remainder binder is carried through
+/// unchanged.
+pub enum FoldedCollection<'ast, O> {
+    List {
+        elements: Vec<O>,
+        remainder: Option<Var<'ast>>,
+    },
+    Tuple(Vec<O>),
+    Set {
+        elements: Vec<O>,
+        remainder: Option<Var<'ast>>,
+    },
+    Map {
+        elements: Vec<(O, O)>,
+        remainder: Option<Var<'ast>>,
+    },
+}
+
+impl<'ast, O> FoldedCollection<'ast, O> {
+    fn into_outputs(self) -> Vec<O> {
+        match self {
+            FoldedCollection::List { elements, .. } | FoldedCollection::Set { elements, .. } => {
+                elements
+            }
+            FoldedCollection::Tuple(elements) => elements,
+            FoldedCollection::Map { elements, .. } => elements
+                .into_iter()
+                .flat_map(|(k, v)| [k, v])
+                .collect(),
+        }
+    }
+}
+
+fn source_output<O>(source: FoldedSource<O>) -> O
+where
+    O: Default + Add<Output = O>,
+{
+    match source {
+        FoldedSource::Simple { name } | FoldedSource::ReceiveSend { name } => name,
+        FoldedSource::SendReceive { name, inputs } => inputs.into_iter().fold(name, Add::add),
+    }
+}
+
+fn bind_output<'ast, O>(bind: FoldedBind<'ast, O>) -> O
+where
+    O: Default + Add<Output = O>,
+{
+    match bind {
+        FoldedBind::Linear { rhs, .. } => source_output(rhs),
+        FoldedBind::Repeated { rhs, .. } | FoldedBind::Peek { rhs, .. } => rhs,
+    }
+}
+
+/// A fold over [`Proc`], one method per constructor.
+///
+/// Every method is named `fold_<variant>` in `snake_case`, receives the
+/// already-folded `Output` of its children (plus any non-recursive data the
+/// variant carries) and the `SourceSpan` it was reached through, and
+/// produces this fold's `Output` for that node. Default bodies are provided
+/// for every method that can be expressed purely in terms of
+/// `Self::Output: Default + Add<Output = Self::Output>` (or, for the
+/// single-child pass-throughs, with no bound at all). The leaves default to
+/// the trivial `Default::default()`, and the binder-aware constructors
+/// (`fold_new`, `fold_contract`, `fold_for_comprehension`, `fold_let`)
+/// default to combining their children as if nothing were bound, so
+/// implementors that care about either must override them.
+pub trait ProcFolder<'ast> {
+    type Output;
+
+    fn fold_nil(&mut self, _original: &'ast Proc<'ast>, _span: SourceSpan) -> Self::Output
+    where
+        Self::Output: Default,
+    {
+        Default::default()
+    }
+
+    fn fold_bool_literal(
+        &mut self,
+        _original: &'ast Proc<'ast>,
+        _value: bool,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default,
+    {
+        Default::default()
+    }
+
+    fn fold_long_literal(
+        &mut self,
+        _original: &'ast Proc<'ast>,
+        _value: i64,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default,
+    {
+        Default::default()
+    }
+
+    fn fold_string_literal(
+        &mut self,
+        _original: &'ast Proc<'ast>,
+        _value: &'ast str,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default,
+    {
+        Default::default()
+    }
+
+    fn fold_uri_literal(
+        &mut self,
+        _original: &'ast Proc<'ast>,
+        _value: Uri<'ast>,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default,
+    {
+        Default::default()
+    }
+
+    fn fold_simple_type(
+        &mut self,
+        _original: &'ast Proc<'ast>,
+        _value: SimpleType,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default,
+    {
+        Default::default()
+    }
+
+    fn fold_bad(&mut self, _original: &'ast Proc<'ast>, _span: SourceSpan) -> Self::Output
+    where
+        Self::Output: Default,
+    {
+        Default::default()
+    }
+
+    fn fold_proc_var(&mut self, _var: Var<'ast>, _span: SourceSpan) -> Self::Output
+    where
+        Self::Output: Default,
+    {
+        Default::default()
+    }
+
+    fn fold_var_ref(
+        &mut self,
+        _kind: VarRefKind,
+        _var: Id<'ast>,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default,
+    {
+        Default::default()
+    }
+
+    fn fold_par(&mut self, left: Self::Output, right: Self::Output, _span: SourceSpan) -> Self::Output
+    where
+        Self::Output: Default + Add<Output = Self::Output>,
+    {
+        left + right
+    }
+
+    fn fold_if_then_else(
+        &mut self,
+        condition: Self::Output,
+        if_true: Self::Output,
+        if_false: Option<Self::Output>,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default + Add<Output = Self::Output>,
+    {
+        let base = condition + if_true;
+        match if_false {
+            Some(if_false) => base + if_false,
+            None => base,
+        }
+    }
+
+    fn fold_send(
+        &mut self,
+        channel: Self::Output,
+        _send_type: SendType,
+        inputs: Vec<Self::Output>,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default + Add<Output = Self::Output>,
+    {
+        inputs.into_iter().fold(channel, Add::add)
+    }
+
+    fn fold_for_comprehension(
+        &mut self,
+        receipts: Vec<FoldedReceipt<'ast, Self::Output>>,
+        proc: Self::Output,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default + Add<Output = Self::Output>,
+    {
+        receipts
+            .into_iter()
+            .flat_map(|receipt| receipt.binds.into_iter())
+            .fold(proc, |acc, bind| acc + bind_output(bind))
+    }
+
+    fn fold_match(
+        &mut self,
+        expression: Self::Output,
+        cases: Vec<(Self::Output, Self::Output)>,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default + Add<Output = Self::Output>,
+    {
+        cases
+            .into_iter()
+            .fold(expression, |acc, (pattern, proc)| acc + pattern + proc)
+    }
+
+    fn fold_select(
+        &mut self,
+        branches: Vec<FoldedBranch<'ast, Self::Output>>,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default + Add<Output = Self::Output>,
+    {
+        branches.into_iter().fold(Default::default(), |acc, branch| {
+            let patterns = branch
+                .patterns
+                .into_iter()
+                .fold(Default::default(), |acc: Self::Output, pattern| {
+                    acc + source_output(pattern.rhs)
+                });
+            acc + patterns + branch.proc
+        })
+    }
+
+    fn fold_bundle(
+        &mut self,
+        _bundle_type: BundleType,
+        proc: Self::Output,
+        _span: SourceSpan,
+    ) -> Self::Output {
+        proc
+    }
+
+    fn fold_let(
+        &mut self,
+        bindings: Vec<FoldedLetBinding<'ast, Self::Output>>,
+        body: Self::Output,
+        _concurrent: bool,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default + Add<Output = Self::Output>,
+    {
+        bindings.into_iter().fold(body, |acc, binding| match binding {
+            FoldedLetBinding::Single { rhs, .. } => acc + rhs,
+            FoldedLetBinding::Multiple { rhs, .. } => rhs.into_iter().fold(acc, Add::add),
+        })
+    }
+
+    fn fold_new(
+        &mut self,
+        _decls: &[NameDecl<'ast>],
+        proc: Self::Output,
+        _span: SourceSpan,
+    ) -> Self::Output {
+        proc
+    }
+
+    fn fold_contract(
+        &mut self,
+        name: Self::Output,
+        _formals: &Names<'ast>,
+        body: Self::Output,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default + Add<Output = Self::Output>,
+    {
+        name + body
+    }
+
+    fn fold_send_sync(
+        &mut self,
+        channel: Self::Output,
+        messages: Vec<Self::Output>,
+        cont: Option<Self::Output>,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default + Add<Output = Self::Output>,
+    {
+        let base = messages.into_iter().fold(channel, Add::add);
+        match cont {
+            Some(cont) => base + cont,
+            None => base,
+        }
+    }
+
+    fn fold_eval(&mut self, name: Self::Output, _span: SourceSpan) -> Self::Output {
+        name
+    }
+
+    fn fold_quote(&mut self, proc: Self::Output, _span: SourceSpan) -> Self::Output {
+        proc
+    }
+
+    fn fold_method(
+        &mut self,
+        receiver: Self::Output,
+        _name: Id<'ast>,
+        args: Vec<Self::Output>,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default + Add<Output = Self::Output>,
+    {
+        args.into_iter().fold(receiver, Add::add)
+    }
+
+    fn fold_unary_exp(&mut self, _op: UnaryExpOp, arg: Self::Output, _span: SourceSpan) -> Self::Output {
+        arg
+    }
+
+    fn fold_binary_exp(
+        &mut self,
+        _op: BinaryExpOp,
+        left: Self::Output,
+        right: Self::Output,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default + Add<Output = Self::Output>,
+    {
+        left + right
+    }
+
+    fn fold_collection(
+        &mut self,
+        collection: FoldedCollection<'ast, Self::Output>,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default + Add<Output = Self::Output>,
+    {
+        collection
+            .into_outputs()
+            .into_iter()
+            .fold(Default::default(), Add::add)
+    }
+
+    fn fold_error(
+        &mut self,
+        partial: Option<Self::Output>,
+        recovered_children: Vec<Self::Output>,
+        _span: SourceSpan,
+    ) -> Self::Output
+    where
+        Self::Output: Default + Add<Output = Self::Output>,
+    {
+        recovered_children
+            .into_iter()
+            .fold(partial.unwrap_or_default(), Add::add)
+    }
+
+    /// Fold `ann`, driving the traversal with an explicit stack instead of
+    /// native recursion (see the module docs).
+    fn fold_proc(&mut self, ann: &AnnProc<'ast>) -> Self::Output
+    where
+        Self: Sized,
+    {
+        let mut values: Vec<Self::Output> = Vec::new();
+        let mut frames: Vec<Frame<'ast>> = vec![Frame::VisitProc(ann.proc, ann.span)];
+
+        while let Some(frame) = frames.pop() {
+            match frame {
+                Frame::VisitProc(proc, span) => dispatch(self, proc, span, &mut frames, &mut values),
+                Frame::VisitName(name) => match name.name {
+                    Name::ProcVar(var) => values.push(self.fold_proc_var(var, name.span)),
+                    Name::Quote(proc) => dispatch(self, proc, name.span, &mut frames, &mut values),
+                },
+                Frame::Reduce(reduce) => {
+                    let output = apply_reduce(self, reduce, &mut values);
+                    values.push(output);
+                }
+            }
+        }
+
+        values
+            .pop()
+            .expect("fold_proc: driver produced no output — this is a bug in fold.rs")
+    }
+}
+
+enum Frame<'ast> {
+    VisitProc(&'ast Proc<'ast>, SourceSpan),
+    VisitName(AnnName<'ast>),
+    Reduce(Reduce<'ast>),
+}
+
+enum Reduce<'ast> {
+    Par { span: SourceSpan },
+    IfThenElse { has_else: bool, span: SourceSpan },
+    Send { send_type: SendType, arity: usize, span: SourceSpan },
+    ForComprehension { receipts: &'ast Receipts<'ast>, arity: usize, span: SourceSpan },
+    Match { arity: usize, span: SourceSpan },
+    Select { branches: &'ast [Branch<'ast>], arity: usize, span: SourceSpan },
+    Bundle { bundle_type: BundleType, span: SourceSpan },
+    Let { bindings: &'ast [LetBinding<'ast>], arity: usize, concurrent: bool, span: SourceSpan },
+    New { decls: &'ast [NameDecl<'ast>], span: SourceSpan },
+    Contract { formals: &'ast Names<'ast>, span: SourceSpan },
+    SendSync { arity: usize, has_cont: bool, span: SourceSpan },
+    Eval { span: SourceSpan },
+    Quote { span: SourceSpan },
+    Method { name: Id<'ast>, arity: usize, span: SourceSpan },
+    UnaryExp { op: UnaryExpOp, span: SourceSpan },
+    BinaryExp { op: BinaryExpOp, span: SourceSpan },
+    Collection { collection: &'ast Collection<'ast>, arity: usize, span: SourceSpan },
+    Error { has_partial: bool, arity: usize, span: SourceSpan },
+}
+
+/// Push `children` onto `frames` in reverse, so the first child in `children`
+/// is the next one popped (and thus the first one visited).
+fn push_children<'ast>(frames: &mut Vec<Frame<'ast>>, children: Vec<Frame<'ast>>) {
+    frames.extend(children.into_iter().rev());
+}
+
+fn source_arity(source: &Source) -> usize {
+    match source {
+        Source::Simple { .. } | Source::ReceiveSend { .. } => 1,
+        Source::SendReceive { inputs, .. } => 1 + inputs.len(),
+    }
+}
+
+fn push_source_children<'ast>(source: &'ast Source<'ast>, out: &mut Vec<Frame<'ast>>) {
+    match source {
+        Source::Simple { name } | Source::ReceiveSend { name } => out.push(Frame::VisitName(*name)),
+        Source::SendReceive { name, inputs } => {
+            out.push(Frame::VisitName(*name));
+            out.extend(inputs.iter().map(|p| Frame::VisitProc(p.proc, p.span)));
+        }
+    }
+}
+
+fn bind_arity(bind: &Bind) -> usize {
+    match bind {
+        Bind::Linear { rhs, .. } => source_arity(rhs),
+        Bind::Repeated { .. } | Bind::Peek { .. } => 1,
+    }
+}
+
+fn push_bind_children<'ast>(bind: &'ast Bind<'ast>, out: &mut Vec<Frame<'ast>>) {
+    match bind {
+        Bind::Linear { rhs, .. } => push_source_children(rhs, out),
+        Bind::Repeated { rhs, .. } | Bind::Peek { rhs, .. } => out.push(Frame::VisitName(*rhs)),
+    }
+}
+
+fn let_binding_arity(binding: &LetBinding) -> usize {
+    match binding {
+        LetBinding::Single { .. } => 1,
+        LetBinding::Multiple { rhs, .. } => rhs.len(),
+    }
+}
+
+fn collection_children<'ast>(collection: &'ast Collection<'ast>) -> Vec<Frame<'ast>> {
+    match collection {
+        Collection::List { elements, .. } | Collection::Set { elements, .. } => {
+            elements.iter().map(|p| Frame::VisitProc(p.proc, p.span)).collect()
+        }
+        Collection::Tuple(elements) => elements.iter().map(|p| Frame::VisitProc(p.proc, p.span)).collect(),
+        Collection::Map { elements, .. } => elements
+            .iter()
+            .flat_map(|(k, v)| [Frame::VisitProc(k.proc, k.span), Frame::VisitProc(v.proc, v.span)])
+            .collect(),
+    }
+}
+
+fn dispatch<'ast, F>(
+    folder: &mut F,
+    proc: &'ast Proc<'ast>,
+    span: SourceSpan,
+    frames: &mut Vec<Frame<'ast>>,
+    values: &mut Vec<F::Output>,
+) where
+    F: ProcFolder<'ast> + ?Sized,
+{
+    match proc {
+        Proc::Nil => values.push(folder.fold_nil(proc, span)),
+        Proc::BoolLiteral(value) => values.push(folder.fold_bool_literal(proc, *value, span)),
+        Proc::LongLiteral(value) => values.push(folder.fold_long_literal(proc, *value, span)),
+        Proc::StringLiteral(value) => values.push(folder.fold_string_literal(proc, value, span)),
+        Proc::UriLiteral(uri) => values.push(folder.fold_uri_literal(proc, *uri, span)),
+        Proc::SimpleType(value) => values.push(folder.fold_simple_type(proc, *value, span)),
+        Proc::ProcVar(var) => values.push(folder.fold_proc_var(*var, span)),
+        Proc::VarRef { kind, var } => values.push(folder.fold_var_ref(*kind, *var, span)),
+        Proc::Bad => values.push(folder.fold_bad(proc, span)),
+
+        Proc::Par { left, right } => {
+            frames.push(Frame::Reduce(Reduce::Par { span }));
+            push_children(
+                frames,
+                vec![
+                    Frame::VisitProc(left.proc, left.span),
+                    Frame::VisitProc(right.proc, right.span),
+                ],
+            );
+        }
+        Proc::IfThenElse { condition, if_true, if_false } => {
+            frames.push(Frame::Reduce(Reduce::IfThenElse { has_else: if_false.is_some(), span }));
+            let mut children = vec![
+                Frame::VisitProc(condition.proc, condition.span),
+                Frame::VisitProc(if_true.proc, if_true.span),
+            ];
+            if let Some(if_false) = if_false {
+                children.push(Frame::VisitProc(if_false.proc, if_false.span));
+            }
+            push_children(frames, children);
+        }
+        Proc::Send { channel, send_type, inputs } => {
+            frames.push(Frame::Reduce(Reduce::Send { send_type: *send_type, arity: inputs.len(), span }));
+            let mut children = vec![Frame::VisitName(*channel)];
+            children.extend(inputs.iter().map(|p| Frame::VisitProc(p.proc, p.span)));
+            push_children(frames, children);
+        }
+        Proc::ForComprehension { receipts, proc } => {
+            let arity: usize = receipts.iter().flat_map(|r| r.binds.iter()).map(bind_arity).sum();
+            frames.push(Frame::Reduce(Reduce::ForComprehension { receipts, arity, span }));
+            let mut children = Vec::with_capacity(arity + 1);
+            for receipt in receipts.iter() {
+                for bind in receipt.binds.iter() {
+                    push_bind_children(bind, &mut children);
+                }
+            }
+            children.push(Frame::VisitProc(proc.proc, proc.span));
+            push_children(frames, children);
+        }
+        Proc::Match { expression, cases } => {
+            frames.push(Frame::Reduce(Reduce::Match { arity: cases.len(), span }));
+            let mut children = vec![Frame::VisitProc(expression.proc, expression.span)];
+            for case in cases.iter() {
+                children.push(Frame::VisitProc(case.pattern.proc, case.pattern.span));
+                children.push(Frame::VisitProc(case.proc.proc, case.proc.span));
+            }
+            push_children(frames, children);
+        }
+        Proc::Select { branches } => {
+            let arity: usize = branches
+                .iter()
+                .map(|b| b.patterns.iter().map(|p| source_arity(&p.rhs)).sum::<usize>() + 1)
+                .sum();
+            frames.push(Frame::Reduce(Reduce::Select { branches, arity, span }));
+            let mut children = Vec::with_capacity(arity);
+            for branch in branches.iter() {
+                for pattern in branch.patterns.iter() {
+                    push_source_children(&pattern.rhs, &mut children);
+                }
+                children.push(Frame::VisitProc(branch.proc.proc, branch.proc.span));
+            }
+            push_children(frames, children);
+        }
+        Proc::Bundle { bundle_type, proc } => {
+            frames.push(Frame::Reduce(Reduce::Bundle { bundle_type: *bundle_type, span }));
+            push_children(frames, vec![Frame::VisitProc(proc.proc, proc.span)]);
+        }
+        Proc::Let { bindings, body, concurrent } => {
+            let arity: usize = bindings.iter().map(let_binding_arity).sum();
+            frames.push(Frame::Reduce(Reduce::Let { bindings, arity, concurrent: *concurrent, span }));
+            let mut children = Vec::with_capacity(arity + 1);
+            for binding in bindings.iter() {
+                match binding {
+                    LetBinding::Single { rhs, .. } => children.push(Frame::VisitProc(rhs.proc, rhs.span)),
+                    LetBinding::Multiple { rhs, .. } => {
+                        children.extend(rhs.iter().map(|p| Frame::VisitProc(p.proc, p.span)));
+                    }
+                }
+            }
+            children.push(Frame::VisitProc(body.proc, body.span));
+            push_children(frames, children);
+        }
+        Proc::New { decls, proc } => {
+            frames.push(Frame::Reduce(Reduce::New { decls, span }));
+            push_children(frames, vec![Frame::VisitProc(proc.proc, proc.span)]);
+        }
+        Proc::Contract { name, formals, body } => {
+            frames.push(Frame::Reduce(Reduce::Contract { formals, span }));
+            push_children(
+                frames,
+                vec![Frame::VisitName(*name), Frame::VisitProc(body.proc, body.span)],
+            );
+        }
+        Proc::SendSync { channel, messages, cont } => {
+            let has_cont = matches!(cont, SyncSendCont::NonEmpty(_));
+            frames.push(Frame::Reduce(Reduce::SendSync { arity: messages.len(), has_cont, span }));
+            let mut children = vec![Frame::VisitName(*channel)];
+            children.extend(messages.iter().map(|p| Frame::VisitProc(p.proc, p.span)));
+            if let SyncSendCont::NonEmpty(cont) = cont {
+                children.push(Frame::VisitProc(cont.proc, cont.span));
+            }
+            push_children(frames, children);
+        }
+        Proc::Eval { name } => {
+            frames.push(Frame::Reduce(Reduce::Eval { span }));
+            push_children(frames, vec![Frame::VisitName(*name)]);
+        }
+        Proc::Quote { proc: inner } => {
+            frames.push(Frame::Reduce(Reduce::Quote { span }));
+            push_children(frames, vec![Frame::VisitProc(inner, span)]);
+        }
+        Proc::Method { receiver, name, args } => {
+            frames.push(Frame::Reduce(Reduce::Method { name: *name, arity: args.len(), span }));
+            let mut children = vec![Frame::VisitProc(receiver.proc, receiver.span)];
+            children.extend(args.iter().map(|p| Frame::VisitProc(p.proc, p.span)));
+            push_children(frames, children);
+        }
+        Proc::UnaryExp { op, arg } => {
+            frames.push(Frame::Reduce(Reduce::UnaryExp { op: *op, span }));
+            push_children(frames, vec![Frame::VisitProc(arg, span)]);
+        }
+        Proc::BinaryExp { op, left, right } => {
+            frames.push(Frame::Reduce(Reduce::BinaryExp { op: *op, span }));
+            push_children(
+                frames,
+                vec![
+                    Frame::VisitProc(left.proc, left.span),
+                    Frame::VisitProc(right.proc, right.span),
+                ],
+            );
+        }
+        Proc::Collection(collection) => {
+            let children = collection_children(collection);
+            frames.push(Frame::Reduce(Reduce::Collection { collection, arity: children.len(), span }));
+            push_children(frames, children);
+        }
+        Proc::Error { partial, recovered_children } => {
+            frames.push(Frame::Reduce(Reduce::Error {
+                has_partial: partial.is_some(),
+                arity: recovered_children.len(),
+                span,
+            }));
+            let mut children = Vec::with_capacity(recovered_children.len() + 1);
+            if let Some(partial) = partial {
+                children.push(Frame::VisitProc(partial.proc, partial.span));
+            }
+            children.extend(recovered_children.iter().map(|p| Frame::VisitProc(p.proc, p.span)));
+            push_children(frames, children);
+        }
+    }
+}
+
+fn pop_n<T, const N: usize>(values: &mut Vec<T>) -> [T; N] {
+    let tail = values.split_off(values.len() - N);
+    tail.try_into()
+        .unwrap_or_else(|_| unreachable!("fold: arity mismatch popping {N} values"))
+}
+
+fn zip_source<'ast, O>(source: &'ast Source<'ast>, it: &mut impl Iterator<Item = O>) -> FoldedSource<O> {
+    match source {
+        Source::Simple { .. } => FoldedSource::Simple {
+            name: it.next().expect("fold: missing source name"),
+        },
+        Source::ReceiveSend { .. } => FoldedSource::ReceiveSend {
+            name: it.next().expect("fold: missing source name"),
+        },
+        Source::SendReceive { inputs, .. } => FoldedSource::SendReceive {
+            name: it.next().expect("fold: missing source name"),
+            inputs: inputs.iter().map(|_| it.next().expect("fold: missing source input")).collect(),
+        },
+    }
+}
+
+fn zip_bind<'ast, O>(bind: &'ast Bind<'ast>, it: &mut impl Iterator<Item = O>) -> FoldedBind<'ast, O> {
+    match bind {
+        Bind::Linear { lhs, rhs } => FoldedBind::Linear { lhs, rhs: zip_source(rhs, it) },
+        Bind::Repeated { lhs, .. } => FoldedBind::Repeated {
+            lhs,
+            rhs: it.next().expect("fold: missing bind rhs"),
+        },
+        Bind::Peek { lhs, .. } => FoldedBind::Peek {
+            lhs,
+            rhs: it.next().expect("fold: missing bind rhs"),
+        },
+    }
+}
+
+fn zip_collection<'ast, O>(
+    collection: &'ast Collection<'ast>,
+    it: &mut impl Iterator<Item = O>,
+) -> FoldedCollection<'ast, O> {
+    match collection {
+        Collection::List { elements, remainder } => FoldedCollection::List {
+            elements: elements.iter().map(|_| it.next().expect("fold: missing list element")).collect(),
+            remainder: *remainder,
+        },
+        Collection::Tuple(elements) => FoldedCollection::Tuple(
+            elements.iter().map(|_| it.next().expect("fold: missing tuple element")).collect(),
+        ),
+        Collection::Set { elements, remainder } => FoldedCollection::Set {
+            elements: elements.iter().map(|_| it.next().expect("fold: missing set element")).collect(),
+            remainder: *remainder,
+        },
+        Collection::Map { elements, remainder } => FoldedCollection::Map {
+            elements: elements
+                .iter()
+                .map(|_| {
+                    (
+                        it.next().expect("fold: missing map key"),
+                        it.next().expect("fold: missing map value"),
+                    )
+                })
+                .collect(),
+            remainder: *remainder,
+        },
+    }
+}
+
+fn apply_reduce<'ast, F>(folder: &mut F, reduce: Reduce<'ast>, values: &mut Vec<F::Output>) -> F::Output
+where
+    F: ProcFolder<'ast> + ?Sized,
+{
+    match reduce {
+        Reduce::Par { span } => {
+            let [left, right] = pop_n(values);
+            folder.fold_par(left, right, span)
+        }
+        Reduce::IfThenElse { has_else, span } => {
+            if has_else {
+                let [condition, if_true, if_false] = pop_n(values);
+                folder.fold_if_then_else(condition, if_true, Some(if_false), span)
+            } else {
+                let [condition, if_true] = pop_n(values);
+                folder.fold_if_then_else(condition, if_true, None, span)
+            }
+        }
+        Reduce::Send { send_type, arity, span } => {
+            let rest = values.split_off(values.len() - (arity + 1));
+            let mut it = rest.into_iter();
+            let channel = it.next().unwrap();
+            folder.fold_send(channel, send_type, it.collect(), span)
+        }
+        Reduce::ForComprehension { receipts, arity, span } => {
+            let rest = values.split_off(values.len() - (arity + 1));
+            let mut it = rest.into_iter();
+            let folded_receipts = receipts
+                .iter()
+                .map(|receipt| FoldedReceipt {
+                    binds: receipt.binds.iter().map(|bind| zip_bind(bind, &mut it)).collect(),
+                })
+                .collect();
+            let proc = it.next().expect("fold: missing for-comprehension body");
+            folder.fold_for_comprehension(folded_receipts, proc, span)
+        }
+        Reduce::Match { arity, span } => {
+            let rest = values.split_off(values.len() - (2 * arity + 1));
+            let mut it = rest.into_iter();
+            let expression = it.next().unwrap();
+            let cases = (0..arity)
+                .map(|_| (it.next().unwrap(), it.next().unwrap()))
+                .collect();
+            folder.fold_match(expression, cases, span)
+        }
+        Reduce::Select { branches, arity, span } => {
+            let rest = values.split_off(values.len() - arity);
+            let mut it = rest.into_iter();
+            let folded_branches = branches
+                .iter()
+                .map(|branch| FoldedBranch {
+                    patterns: branch
+                        .patterns
+                        .iter()
+                        .map(|pattern| FoldedSelectPattern {
+                            lhs: &pattern.lhs,
+                            rhs: zip_source(&pattern.rhs, &mut it),
+                        })
+                        .collect(),
+                    proc: it.next().expect("fold: missing select branch body"),
+                })
+                .collect();
+            folder.fold_select(folded_branches, span)
+        }
+        Reduce::Bundle { bundle_type, span } => {
+            let [proc] = pop_n(values);
+            folder.fold_bundle(bundle_type, proc, span)
+        }
+        Reduce::Let { bindings, arity, concurrent, span } => {
+            let rest = values.split_off(values.len() - (arity + 1));
+            let mut it = rest.into_iter();
+            let folded_bindings = bindings
+                .iter()
+                .map(|binding| match binding {
+                    LetBinding::Single { lhs, .. } => FoldedLetBinding::Single {
+                        lhs: *lhs,
+                        rhs: it.next().expect("fold: missing let binding rhs"),
+                    },
+                    LetBinding::Multiple { lhs, rhs } => FoldedLetBinding::Multiple {
+                        lhs: *lhs,
+                        rhs: rhs.iter().map(|_| it.next().expect("fold: missing let binding rhs")).collect(),
+                    },
+                })
+                .collect();
+            let body = it.next().expect("fold: missing let body");
+            folder.fold_let(folded_bindings, body, concurrent, span)
+        }
+        Reduce::New { decls, span } => {
+            let [proc] = pop_n(values);
+            folder.fold_new(decls, proc, span)
+        }
+        Reduce::Contract { formals, span } => {
+            let [name, body] = pop_n(values);
+            folder.fold_contract(name, formals, body, span)
+        }
+        Reduce::SendSync { arity, has_cont, span } => {
+            let total = arity + 1 + usize::from(has_cont);
+            let rest = values.split_off(values.len() - total);
+            let mut it = rest.into_iter();
+            let channel = it.next().unwrap();
+            let messages = (0..arity).map(|_| it.next().unwrap()).collect();
+            let cont = has_cont.then(|| it.next().unwrap());
+            folder.fold_send_sync(channel, messages, cont, span)
+        }
+        Reduce::Eval { span } => {
+            let [name] = pop_n(values);
+            folder.fold_eval(name, span)
+        }
+        Reduce::Quote { span } => {
+            let [proc] = pop_n(values);
+            folder.fold_quote(proc, span)
+        }
+        Reduce::Method { name, arity, span } => {
+            let rest = values.split_off(values.len() - (arity + 1));
+            let mut it = rest.into_iter();
+            let receiver = it.next().unwrap();
+            folder.fold_method(receiver, name, it.collect(), span)
+        }
+        Reduce::UnaryExp { op, span } => {
+            let [arg] = pop_n(values);
+            folder.fold_unary_exp(op, arg, span)
+        }
+        Reduce::BinaryExp { op, span } => {
+            let [left, right] = pop_n(values);
+            folder.fold_binary_exp(op, left, right, span)
+        }
+        Reduce::Collection { collection, arity, span } => {
+            let rest = values.split_off(values.len() - arity);
+            let mut it = rest.into_iter();
+            let folded = zip_collection(collection, &mut it);
+            folder.fold_collection(folded, span)
+        }
+        Reduce::Error { has_partial, arity, span } => {
+            let total = arity + usize::from(has_partial);
+            let rest = values.split_off(values.len() - total);
+            let mut it = rest.into_iter();
+            let partial = has_partial.then(|| it.next().unwrap());
+            folder.fold_error(partial, it.collect(), span)
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// free_names
+// ---------------------------------------------------------------------------
+
+/// The `Id`s bound, directly or via the remainder, by `names`.
+pub(super) fn names_bound_ids<'ast>(names: &Names<'ast>) -> Vec<Id<'ast>> {
+    let mut ids: Vec<Id<'ast>> = names
+        .names
+        .iter()
+        .filter_map(|name| match name.name {
+            Name::ProcVar(Var::Id(id)) => Some(id),
+            _ => None,
+        })
+        .collect();
+    if let Some(Var::Id(id)) = names.remainder {
+        ids.push(id);
+    }
+    ids
+}
+
+#[derive(Default, Clone)]
+struct NameSet<'ast>(BTreeSet<Id<'ast>>);
+
+impl<'ast> NameSet<'ast> {
+    fn singleton(id: Id<'ast>) -> Self {
+        let mut set = BTreeSet::new();
+        set.insert(id);
+        NameSet(set)
+    }
+
+    fn remove_bound(mut self, bound: impl IntoIterator<Item = Id<'ast>>) -> Self {
+        for id in bound {
+            self.0.remove(&id);
+        }
+        self
+    }
+}
+
+impl<'ast> Add for NameSet<'ast> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self.0.extend(other.0);
+        self
+    }
+}
+
+struct FreeNames;
+
+impl<'ast> ProcFolder<'ast> for FreeNames {
+    type Output = NameSet<'ast>;
+
+    fn fold_proc_var(&mut self, var: Var<'ast>, _span: SourceSpan) -> Self::Output {
+        match var {
+            Var::Id(id) => NameSet::singleton(id),
+            Var::Wildcard => NameSet::default(),
+        }
+    }
+
+    fn fold_var_ref(&mut self, _kind: VarRefKind, var: Id<'ast>, _span: SourceSpan) -> Self::Output {
+        NameSet::singleton(var)
+    }
+
+    fn fold_new(&mut self, decls: &[NameDecl<'ast>], proc: Self::Output, _span: SourceSpan) -> Self::Output {
+        proc.remove_bound(decls.iter().map(|decl| decl.id))
+    }
+
+    fn fold_contract(
+        &mut self,
+        name: Self::Output,
+        formals: &Names<'ast>,
+        body: Self::Output,
+        _span: SourceSpan,
+    ) -> Self::Output {
+        name + body.remove_bound(names_bound_ids(formals))
+    }
+
+    fn fold_for_comprehension(
+        &mut self,
+        receipts: Vec<FoldedReceipt<'ast, Self::Output>>,
+        proc: Self::Output,
+        _span: SourceSpan,
+    ) -> Self::Output {
+        let mut bound = Vec::new();
+        let mut sources = NameSet::default();
+        for receipt in receipts {
+            for bind in receipt.binds {
+                let (lhs, rhs) = match bind {
+                    FoldedBind::Linear { lhs, rhs } => (lhs, source_output(rhs)),
+                    FoldedBind::Repeated { lhs, rhs } | FoldedBind::Peek { lhs, rhs } => (lhs, rhs),
+                };
+                bound.extend(names_bound_ids(lhs));
+                sources = sources + rhs;
+            }
+        }
+        sources + proc.remove_bound(bound)
+    }
+
+    fn fold_let(
+        &mut self,
+        bindings: Vec<FoldedLetBinding<'ast, Self::Output>>,
+        body: Self::Output,
+        _concurrent: bool,
+        _span: SourceSpan,
+    ) -> Self::Output {
+        let mut bound = Vec::new();
+        let mut rhs_free = NameSet::default();
+        for binding in bindings {
+            match binding {
+                FoldedLetBinding::Single { lhs, rhs } => {
+                    if let Name::ProcVar(Var::Id(id)) = lhs.name {
+                        bound.push(id);
+                    }
+                    rhs_free = rhs_free + rhs;
+                }
+                FoldedLetBinding::Multiple { lhs, rhs } => {
+                    if let Var::Id(id) = lhs {
+                        bound.push(id);
+                    }
+                    rhs_free = rhs.into_iter().fold(rhs_free, Add::add);
+                }
+            }
+        }
+        rhs_free + body.remove_bound(bound)
+    }
+}
+
+/// The set of free (unbound) `ProcVar`/`VarRef` `Id`s in `proc`, respecting
+/// the binders `new` declares, a `contract`'s formals, a `for`'s bind
+/// patterns, and a `let`'s bindings introduce — same four positions
+/// [`crate::parser::alpha_eq`] treats as binders, and the same
+/// simultaneous-group approximation it documents for a `for`'s binds.
+pub fn free_names<'ast>(proc: &AnnProc<'ast>) -> BTreeSet<Id<'ast>> {
+    FreeNames.fold_proc(proc).0
+}
+
+// ---------------------------------------------------------------------------
+// substitute
+// ---------------------------------------------------------------------------
+
+/// Turn a folded name occurrence back into an `AnnName`. When `p` is itself
+/// name-shaped (a `ProcVar`, from an unsubstituted occurrence, or a `Quote`)
+/// it's reused directly; otherwise (a substituted-in `replacement` that
+/// isn't name-shaped) it's wrapped in a fresh `Quote`, mirroring the `@proc`
+/// syntax that puts an arbitrary process in name position.
+///
+/// Known limitation: this does not roundtrip a quoted variable (`@x`) used
+/// as a channel that is *not* the substitution target — folding reconstructs
+/// it as `Proc::ProcVar`, which converts back to the unwrapped `Name::ProcVar`
+/// rather than `Name::Quote(ProcVar)`. Both denote the same channel, but
+/// they're not the same tree.
+fn proc_to_name<'ast>(p: AnnProc<'ast>) -> AnnName<'ast> {
+    match Name::try_from(p.proc) {
+        Ok(name) => AnnName { name, span: p.span },
+        Err(_) => AnnName { name: Name::Quote(p.proc), span: p.span },
+    }
+}
+
+fn rebuild_source<'ast>(folded: FoldedSource<AnnProc<'ast>>) -> Source<'ast> {
+    match folded {
+        FoldedSource::Simple { name } => Source::Simple { name: proc_to_name(name) },
+        FoldedSource::ReceiveSend { name } => Source::ReceiveSend { name: proc_to_name(name) },
+        FoldedSource::SendReceive { name, inputs } => Source::SendReceive {
+            name: proc_to_name(name),
+            inputs: inputs.into(),
+        },
+    }
+}
+
+/// Replace every free occurrence of `target` in `proc` with `replacement`,
+/// allocating every rebuilt node through `ast_builder` so the result lives
+/// in the same arena. This is a plain (non-capture-avoiding) substitution:
+/// if `replacement` contains a name that a binder inside `proc` reuses, that
+/// binder will shadow it same as it would any other name — wrap `proc` in a
+/// fresh `new` first if that matters for the call site.
+pub(super) fn substitute<'ast>(
+    ast_builder: &'ast ASTBuilder<'ast>,
+    proc: &AnnProc<'ast>,
+    target: Id<'ast>,
+    replacement: AnnProc<'ast>,
+) -> AnnProc<'ast> {
+    let mut substitutor = Substitute { ast_builder, target, replacement };
+    substitutor.fold_proc(proc)
+}
+
+struct Substitute<'ast> {
+    ast_builder: &'ast ASTBuilder<'ast>,
+    target: Id<'ast>,
+    replacement: AnnProc<'ast>,
+}
+
+impl<'ast> Substitute<'ast> {
+    fn substitute_remainder(&self, remainder: Option<Var<'ast>>) -> Option<Var<'ast>> {
+        match remainder {
+            Some(Var::Id(id)) if id == self.target => {
+                Some(Var::try_from(self.replacement).unwrap_or(Var::Id(id)))
+            }
+            other => other,
+        }
+    }
+}
+
+impl<'ast> ProcFolder<'ast> for Substitute<'ast> {
+    type Output = AnnProc<'ast>;
+
+    fn fold_nil(&mut self, original: &'ast Proc<'ast>, span: SourceSpan) -> Self::Output {
+        AnnProc { proc: original, span }
+    }
+
+    fn fold_bool_literal(&mut self, original: &'ast Proc<'ast>, _value: bool, span: SourceSpan) -> Self::Output {
+        AnnProc { proc: original, span }
+    }
+
+    fn fold_long_literal(&mut self, original: &'ast Proc<'ast>, _value: i64, span: SourceSpan) -> Self::Output {
+        AnnProc { proc: original, span }
+    }
+
+    fn fold_string_literal(
+        &mut self,
+        original: &'ast Proc<'ast>,
+        _value: &'ast str,
+        span: SourceSpan,
+    ) -> Self::Output {
+        AnnProc { proc: original, span }
+    }
+
+    fn fold_uri_literal(&mut self, original: &'ast Proc<'ast>, _value: Uri<'ast>, span: SourceSpan) -> Self::Output {
+        AnnProc { proc: original, span }
+    }
+
+    fn fold_simple_type(
+        &mut self,
+        original: &'ast Proc<'ast>,
+        _value: SimpleType,
+        span: SourceSpan,
+    ) -> Self::Output {
+        AnnProc { proc: original, span }
+    }
+
+    fn fold_bad(&mut self, original: &'ast Proc<'ast>, span: SourceSpan) -> Self::Output {
+        AnnProc { proc: original, span }
+    }
+
+    fn fold_proc_var(&mut self, var: Var<'ast>, span: SourceSpan) -> Self::Output {
+        match var {
+            Var::Id(id) if id == self.target => self.replacement,
+            Var::Id(id) => AnnProc { proc: self.ast_builder.alloc_var(id), span },
+            Var::Wildcard => AnnProc { proc: &self.ast_builder.WILD, span },
+        }
+    }
+
+    fn fold_var_ref(&mut self, kind: VarRefKind, var: Id<'ast>, span: SourceSpan) -> Self::Output {
+        if var == self.target {
+            self.replacement
+        } else {
+            AnnProc { proc: self.ast_builder.alloc_var_ref(kind, var), span }
+        }
+    }
+
+    fn fold_par(&mut self, left: Self::Output, right: Self::Output, span: SourceSpan) -> Self::Output {
+        AnnProc { proc: self.ast_builder.alloc_par(left, right), span }
+    }
+
+    fn fold_if_then_else(
+        &mut self,
+        condition: Self::Output,
+        if_true: Self::Output,
+        if_false: Option<Self::Output>,
+        span: SourceSpan,
+    ) -> Self::Output {
+        let proc = match if_false {
+            Some(if_false) => self.ast_builder.alloc_if_then_else(condition, if_true, if_false),
+            None => self.ast_builder.alloc_if_then(condition, if_true),
+        };
+        AnnProc { proc, span }
+    }
+
+    fn fold_send(
+        &mut self,
+        channel: Self::Output,
+        send_type: SendType,
+        inputs: Vec<Self::Output>,
+        span: SourceSpan,
+    ) -> Self::Output {
+        AnnProc {
+            proc: self.ast_builder.alloc_send(send_type, proc_to_name(channel), &inputs),
+            span,
+        }
+    }
+
+    fn fold_for_comprehension(
+        &mut self,
+        receipts: Vec<FoldedReceipt<'ast, Self::Output>>,
+        proc: Self::Output,
+        span: SourceSpan,
+    ) -> Self::Output {
+        let receipts: Vec<Vec<Bind<'ast>>> = receipts
+            .into_iter()
+            .map(|receipt| {
+                receipt
+                    .binds
+                    .into_iter()
+                    .map(|bind| match bind {
+                        FoldedBind::Linear { lhs, rhs } => Bind::Linear { lhs: lhs.clone(), rhs: rebuild_source(rhs) },
+                        FoldedBind::Repeated { lhs, rhs } => Bind::Repeated { lhs: lhs.clone(), rhs: proc_to_name(rhs) },
+                        FoldedBind::Peek { lhs, rhs } => Bind::Peek { lhs: lhs.clone(), rhs: proc_to_name(rhs) },
+                    })
+                    .collect()
+            })
+            .collect();
+        AnnProc { proc: self.ast_builder.alloc_for(receipts, proc), span }
+    }
+
+    fn fold_match(
+        &mut self,
+        expression: Self::Output,
+        cases: Vec<(Self::Output, Self::Output)>,
+        span: SourceSpan,
+    ) -> Self::Output {
+        let flat: Vec<AnnProc<'ast>> = cases.into_iter().flat_map(|(pattern, proc)| [pattern, proc]).collect();
+        AnnProc { proc: self.ast_builder.alloc_match(expression, &flat), span }
+    }
+
+    fn fold_select(&mut self, branches: Vec<FoldedBranch<'ast, Self::Output>>, span: SourceSpan) -> Self::Output {
+        let branches = branches
+            .into_iter()
+            .map(|branch| Branch {
+                patterns: branch
+                    .patterns
+                    .into_iter()
+                    .map(|pattern| SelectPattern { lhs: pattern.lhs.clone(), rhs: rebuild_source(pattern.rhs) })
+                    .collect(),
+                proc: branch.proc,
+            })
+            .collect();
+        AnnProc { proc: self.ast_builder.alloc_select(branches), span }
+    }
+
+    fn fold_bundle(&mut self, bundle_type: BundleType, proc: Self::Output, span: SourceSpan) -> Self::Output {
+        AnnProc { proc: self.ast_builder.alloc_bundle(bundle_type, proc), span }
+    }
+
+    fn fold_let(
+        &mut self,
+        bindings: Vec<FoldedLetBinding<'ast, Self::Output>>,
+        body: Self::Output,
+        concurrent: bool,
+        span: SourceSpan,
+    ) -> Self::Output {
+        let bindings: Vec<LetBinding<'ast>> = bindings
+            .into_iter()
+            .map(|binding| match binding {
+                FoldedLetBinding::Single { lhs, rhs } => LetBinding::Single { lhs, rhs },
+                FoldedLetBinding::Multiple { lhs, rhs } => LetBinding::Multiple { lhs, rhs },
+            })
+            .collect();
+        AnnProc { proc: self.ast_builder.alloc_let(bindings, body, concurrent), span }
+    }
+
+    fn fold_new(&mut self, decls: &[NameDecl<'ast>], proc: Self::Output, span: SourceSpan) -> Self::Output {
+        AnnProc { proc: self.ast_builder.alloc_new(proc, decls.to_vec()), span }
+    }
+
+    fn fold_contract(
+        &mut self,
+        name: Self::Output,
+        formals: &Names<'ast>,
+        body: Self::Output,
+        span: SourceSpan,
+    ) -> Self::Output {
+        AnnProc {
+            proc: self.ast_builder.alloc_contract(proc_to_name(name), formals.clone(), body),
+            span,
+        }
+    }
+
+    fn fold_send_sync(
+        &mut self,
+        channel: Self::Output,
+        messages: Vec<Self::Output>,
+        cont: Option<Self::Output>,
+        span: SourceSpan,
+    ) -> Self::Output {
+        let channel = proc_to_name(channel);
+        let proc = match cont {
+            Some(cont) => self.ast_builder.alloc_send_sync_with_cont(channel, &messages, cont),
+            None => self.ast_builder.alloc_send_sync(channel, &messages),
+        };
+        AnnProc { proc, span }
+    }
+
+    fn fold_eval(&mut self, name: Self::Output, span: SourceSpan) -> Self::Output {
+        AnnProc { proc: self.ast_builder.alloc_eval(proc_to_name(name)), span }
+    }
+
+    fn fold_quote(&mut self, proc: Self::Output, span: SourceSpan) -> Self::Output {
+        AnnProc { proc: self.ast_builder.alloc_quote(proc.proc), span }
+    }
+
+    fn fold_method(
+        &mut self,
+        receiver: Self::Output,
+        name: Id<'ast>,
+        args: Vec<Self::Output>,
+        span: SourceSpan,
+    ) -> Self::Output {
+        AnnProc { proc: self.ast_builder.alloc_method(name, receiver, &args), span }
+    }
+
+    fn fold_unary_exp(&mut self, op: UnaryExpOp, arg: Self::Output, span: SourceSpan) -> Self::Output {
+        AnnProc { proc: self.ast_builder.alloc_unary_exp(op, arg.proc), span }
+    }
+
+    fn fold_binary_exp(&mut self, op: BinaryExpOp, left: Self::Output, right: Self::Output, span: SourceSpan) -> Self::Output {
+        AnnProc { proc: self.ast_builder.alloc_binary_exp(op, left, right), span }
+    }
+
+    fn fold_collection(&mut self, collection: FoldedCollection<'ast, Self::Output>, span: SourceSpan) -> Self::Output {
+        let proc = match collection {
+            FoldedCollection::List { elements, remainder } => match self.substitute_remainder(remainder) {
+                Some(remainder) => self.ast_builder.alloc_list_with_remainder(&elements, remainder),
+                None => self.ast_builder.alloc_list(&elements),
+            },
+            FoldedCollection::Tuple(elements) => self.ast_builder.alloc_tuple(&elements),
+            FoldedCollection::Set { elements, remainder } => match self.substitute_remainder(remainder) {
+                Some(remainder) => self.ast_builder.alloc_set_with_remainder(&elements, remainder),
+                None => self.ast_builder.alloc_set(&elements),
+            },
+            FoldedCollection::Map { elements, remainder } => {
+                let flat: Vec<AnnProc<'ast>> = elements.into_iter().flat_map(|(k, v)| [k, v]).collect();
+                match self.substitute_remainder(remainder) {
+                    Some(remainder) => self.ast_builder.alloc_map_with_remainder(&flat, remainder),
+                    None => self.ast_builder.alloc_map(&flat),
+                }
+            }
+        };
+        AnnProc { proc, span }
+    }
+
+    fn fold_error(&mut self, partial: Option<Self::Output>, recovered_children: Vec<Self::Output>, span: SourceSpan) -> Self::Output {
+        AnnProc {
+            proc: self.ast_builder.alloc_error(partial, &recovered_children),
+            span,
+        }
+    }
+}
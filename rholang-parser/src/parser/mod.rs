@@ -1,14 +1,31 @@
+pub mod alpha_eq;
 mod ast_builder;
+pub mod encoding;
 pub mod errors;
+pub mod fold;
 mod parsing;
+mod rewrite;
+pub mod reparse;
+pub mod resolve;
+pub mod scope;
+pub mod tokenize;
+pub mod tree_sink;
+pub mod unparse;
+pub mod validate;
+pub mod visit;
 
+use serde_json::Value;
 use validated::Validated;
 
 use crate::{
     ast::AnnProc,
+    errors::ParserError,
     parser::{ast_builder::ASTBuilder, errors::AnnParsingError},
 };
 
+pub use encoding::DecodeError;
+pub use reparse::ReparseSession;
+
 pub struct RholangParser<'a> {
     ast_builder: ASTBuilder<'a>,
 }
@@ -32,4 +49,203 @@ impl<'a> RholangParser<'a> {
             .map(|node| parsing::node_to_ast(&node, &self.ast_builder, code))
             .collect()
     }
+
+    /// Like [`Self::parse`], but never fails: each top-level process comes back
+    /// as an `AnnProc` alongside whatever errors were found, with `ERROR`/`MISSING`
+    /// nodes kept as [`crate::ast::Proc::Error`] placeholders instead of discarding
+    /// the surrounding subtree. Useful for an LSP, where completion/hover still
+    /// need to work over the valid regions of an incomplete program.
+    pub fn parse_resilient<'code: 'a>(
+        &'a self,
+        code: &'code str,
+    ) -> Vec<(AnnProc<'a>, Vec<AnnParsingError>)> {
+        let tree = parsing::parse_to_tree(code);
+        let mut walker = tree.walk();
+
+        tree.root_node()
+            .named_children(&mut walker)
+            .map(|node| parsing::node_to_ast_resilient(&node, &self.ast_builder, code))
+            .collect()
+    }
+
+    /// Decode bytes produced by [`encoding::encode`]/[`encoding::encode_normalized`]
+    /// back into an `AnnProc` allocated in this parser's arena, so sharing/interning
+    /// is preserved the same way it is for a freshly parsed tree.
+    pub fn decode(&'a self, bytes: &[u8]) -> Validated<AnnProc<'a>, DecodeError> {
+        encoding::decode(bytes, &self.ast_builder)
+    }
+
+    /// Like [`Self::parse_resilient`], but flattens every top-level process's error
+    /// list into one [`crate::errors::ParserError`] per diagnostic -- the plain,
+    /// span-light type the `shell`/CLI/JNI wrappers already speak -- instead of the
+    /// richer internal [`AnnParsingError`] those wrappers don't depend on. Each
+    /// [`crate::errors::ParserError`] carries a 1-based
+    /// [`crate::errors::SourcePosition`] and the offending source slice, so a caller
+    /// that only has the external error type wired up can still show every recovery
+    /// point from one parse, not just the first.
+    pub fn parse_with_recovery<'code: 'a>(
+        &'a self,
+        code: &'code str,
+    ) -> (Vec<AnnProc<'a>>, Vec<ParserError>) {
+        let resilient = self.parse_resilient(code);
+
+        let mut procs = Vec::with_capacity(resilient.len());
+        let mut recovery_errors = Vec::new();
+        for (proc, ann_errors) in resilient {
+            procs.push(proc);
+            recovery_errors.extend(ann_errors.iter().map(|ann| to_recovery_error(ann, code)));
+        }
+
+        (procs, recovery_errors)
+    }
+
+    /// Parse `code`, then run [`scope::analyze_scopes`] over each top-level
+    /// process, pairing it with the free-name/unused-binder diagnostics that
+    /// process produced -- see [`scope`] for what counts as a binder vs a use.
+    pub fn analyze<'code: 'a>(
+        &'a self,
+        code: &'code str,
+    ) -> Validated<Vec<(AnnProc<'a>, Vec<scope::ScopeDiagnostic<'a>>)>, AnnParsingError> {
+        match self.parse(code) {
+            Validated::Good(procs) => Validated::Good(
+                procs
+                    .into_iter()
+                    .map(|proc| {
+                        let diagnostics = scope::analyze_scopes(&proc);
+                        (proc, diagnostics)
+                    })
+                    .collect(),
+            ),
+            Validated::Fail(errors) => Validated::Fail(errors),
+        }
+    }
+
+    /// Parse `code`, then run [`resolve::resolve_names`] over each top-level
+    /// process, pairing it with the De Bruijn-indexed [`resolve::Resolution`]
+    /// of every name use it contains -- see [`resolve`] for the indexing
+    /// scheme and which binder positions it covers.
+    pub fn resolve<'code: 'a>(
+        &'a self,
+        code: &'code str,
+    ) -> Validated<Vec<(AnnProc<'a>, Vec<resolve::Resolution<'a>>)>, AnnParsingError> {
+        match self.parse(code) {
+            Validated::Good(procs) => Validated::Good(
+                procs
+                    .into_iter()
+                    .map(|proc| {
+                        let resolutions = resolve::resolve_names(&proc);
+                        (proc, resolutions)
+                    })
+                    .collect(),
+            ),
+            Validated::Fail(errors) => Validated::Fail(errors),
+        }
+    }
+
+    /// Parse `code`, then run [`validate::validate`] over each top-level
+    /// process, catching shapes the grammar accepts but that can never be
+    /// meaningful -- see [`validate`] for the checks this runs.
+    pub fn validate<'code: 'a>(
+        &'a self,
+        code: &'code str,
+    ) -> Validated<Vec<Validated<AnnProc<'a>, validate::ValidationFailure<'a>>>, AnnParsingError> {
+        match self.parse(code) {
+            Validated::Good(procs) => Validated::Good(procs.into_iter().map(validate::validate).collect()),
+            Validated::Fail(errors) => Validated::Fail(errors),
+        }
+    }
+
+    /// Parse `code`, then run [`rewrite::const_fold`] over each top-level
+    /// process, folding constant integer arithmetic down to its
+    /// `LongLiteral` result -- see [`rewrite`] for exactly which operators
+    /// fold and how overflow is handled.
+    pub fn const_fold<'code: 'a>(
+        &'a self,
+        code: &'code str,
+    ) -> Validated<Vec<AnnProc<'a>>, AnnParsingError> {
+        match self.parse(code) {
+            Validated::Good(procs) => Validated::Good(
+                procs
+                    .iter()
+                    .map(|proc| rewrite::const_fold(&self.ast_builder, proc))
+                    .collect(),
+            ),
+            Validated::Fail(errors) => Validated::Fail(errors),
+        }
+    }
+
+    /// Parse `code`, then flatten each top-level process into its pre-order
+    /// node sequence via [`visit::pre_order`] -- every node under it
+    /// alongside its span, in the same depth-first, left-to-right order
+    /// [`visit::walk`] would visit it.
+    pub fn nodes<'code: 'a>(
+        &'a self,
+        code: &'code str,
+    ) -> Validated<Vec<Vec<(AnnProc<'a>, crate::SourceSpan)>>, AnnParsingError> {
+        match self.parse(code) {
+            Validated::Good(procs) => Validated::Good(procs.into_iter().map(visit::pre_order).collect()),
+            Validated::Fail(errors) => Validated::Fail(errors),
+        }
+    }
+
+    /// Collect rustc/miette-style [`errors::Diagnostic`]s for every `ERROR`/
+    /// `MISSING` node tree-sitter produced while parsing `code`, independent
+    /// of whether enough of `code` parsed for [`Self::parse`] to succeed.
+    pub fn diagnostics(&self, code: &str) -> Vec<errors::Diagnostic> {
+        let tree = parsing::parse_to_tree(code);
+        errors::collect_diagnostics(&tree.root_node(), code)
+    }
+
+    /// Count how many times each tree-sitter node kind appears in `code`,
+    /// driving [`tree_sink::drive`] over the raw parse tree instead of the
+    /// `AnnProc` -- see [`tree_sink`] for the generic per-node/per-token
+    /// event stream this counter is built on.
+    pub fn node_kind_counts(&self, code: &str) -> std::collections::BTreeMap<String, usize> {
+        tree_sink::node_kind_counts(code)
+    }
+
+    /// Lex `code` into its flat token stream, independently of building an
+    /// AST -- see [`tokenize`] for what counts as a token versus a lexical
+    /// error.
+    pub fn tokenize(&self, code: &str) -> (Vec<tokenize::Token>, Vec<tokenize::LexError>) {
+        tokenize::tokenize(code)
+    }
+
+    /// Like [`Self::diagnostics`], but also hands back the (possibly partial)
+    /// tree tree-sitter recovered, so a caller that wants to report every
+    /// syntax error in a file -- not just whether it parsed -- doesn't have
+    /// to parse `code` a second time to get at the tree.
+    pub fn parse_with_errors(&self, code: &str) -> (tree_sitter::Tree, Vec<errors::Diagnostic>) {
+        let tree = parsing::parse_to_tree(code);
+        let diagnostics = errors::collect_diagnostics(&tree.root_node(), code);
+        (tree, diagnostics)
+    }
+
+    /// Like [`Self::parse`], but hands back the program as a structured
+    /// `serde_json::Value` tree instead of a flat string rendering, with every
+    /// node's [`crate::SourceSpan`] preserved. `serde_json::to_value` walks the
+    /// borrowed `AnnProc` tree field by field into owned `Value`s, so -- unlike
+    /// the `AnnProc`s returned by `parse` -- the result has no lifetime tied to
+    /// this parser's arena and can cross a process/FFI boundary after it drops.
+    pub fn parse_to_json<'code: 'a>(&'a self, code: &'code str) -> Validated<Value, AnnParsingError> {
+        match self.parse(code) {
+            Validated::Good(procs) => Validated::Good(serde_json::to_value(&procs).unwrap_or_else(|e| {
+                serde_json::json!({ "error": format!("failed to serialize AST: {e}") })
+            })),
+            Validated::Fail(errors) => Validated::Fail(errors),
+        }
+    }
+}
+
+/// Convert one internal [`AnnParsingError`] into the external
+/// [`crate::errors::ParserError`] shape, reusing its already-1-based
+/// [`crate::SourcePos`] and re-slicing `code` by its byte range rather than
+/// re-deriving a position from a tree-sitter node directly.
+fn to_recovery_error(ann_error: &AnnParsingError, code: &str) -> ParserError {
+    let position = crate::errors::SourcePosition {
+        line: ann_error.span.start.line,
+        column: ann_error.span.start.col,
+    };
+    let source = code.get(ann_error.byte_range.clone()).unwrap_or("").to_string();
+    ParserError::parsing_error(ann_error.error.to_string(), Some(position), Some(source))
 }
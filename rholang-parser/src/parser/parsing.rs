@@ -1,10 +1,10 @@
 use nonempty_collections::NEVec;
-use rholang_tree_sitter_proc_macro::{field, kind};
+use rholang_tree_sitter_proc_macro::{field, kind, node_set};
 use smallvec::ToSmallVec;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::iter::Zip;
 use std::slice::Iter as SliceIter;
-use std::sync::OnceLock;
 use validated::Validated;
 
 use crate::SourcePos;
@@ -13,12 +13,12 @@ use crate::parser::errors::ParsingFailure;
 use crate::{
     SourceSpan,
     ast::{
-        AnnProc, BinaryExpOp, Bind, BundleType, Id, LetBinding, NameDecl, Names, Proc, SendType,
-        SimpleType, Source, UnaryExpOp, VarRefKind,
+        AnnProc, BinaryExpOp, Bind, BundleType, Id, LetBinding, NameDecl, Names, Proc, ProcList,
+        SendType, SimpleType, Source, UnaryExpOp, VarRefKind,
     },
     parser::{
         ast_builder::ASTBuilder,
-        errors::{AnnParsingError, ParsingError},
+        errors::{AnnParsingError, ParsingError, query_errors},
     },
 };
 
@@ -33,14 +33,85 @@ pub(super) fn parse_to_tree(source: &str) -> tree_sitter::Tree {
         .expect("Failed to produce syntax tree")
 }
 
+/// Whether `node_to_ast` aborts on the first `ERROR`/`MISSING` node
+/// (collapsing to a `Validated::fail` with no usable tree), or keeps whatever
+/// can be salvaged around it as a [`Proc::Error`] placeholder so a caller
+/// always gets a complete `AnnProc` back alongside the collected errors.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(super) enum ParseMode {
+    Strict,
+    Resilient,
+}
+
+/// Maps a tree-sitter node id to the `AnnProc` [`build_ast`] built for it on
+/// a previous walk. Tree-sitter hands out the same node id to a subtree only
+/// when an incremental parse reuses it byte-for-byte, so a hit here means
+/// the cached `AnnProc` is exactly what re-walking that node would produce.
+/// Threaded through as `Option<&mut NodeCache>` so the plain, one-shot
+/// [`node_to_ast`]/[`node_to_ast_resilient`] entry points — which have
+/// nothing to reuse and nothing worth remembering — pay no bookkeeping cost.
+pub(super) type NodeCache<'ast> = HashMap<usize, AnnProc<'ast>>;
+
 pub(super) fn node_to_ast<'ast>(
     start_node: &tree_sitter::Node,
     ast_builder: &'ast ASTBuilder<'ast>,
     source: &'ast str,
 ) -> Validated<AnnProc<'ast>, ParsingFailure<'ast>> {
+    node_to_ast_with_cache(start_node, ast_builder, source, None)
+}
+
+/// Like [`node_to_ast`], but given a [`NodeCache`] populated by an earlier
+/// call, splices in a cached subtree for any node whose id survived
+/// tree-sitter's incremental reparse instead of re-walking it, and records
+/// every node it does build so a later call can reuse them in turn. Used by
+/// [`crate::parser::reparse::ReparseSession`].
+pub(super) fn node_to_ast_with_cache<'ast>(
+    start_node: &tree_sitter::Node,
+    ast_builder: &'ast ASTBuilder<'ast>,
+    source: &'ast str,
+    cache: Option<&mut NodeCache<'ast>>,
+) -> Validated<AnnProc<'ast>, ParsingFailure<'ast>> {
+    let (proc, mut errors) = build_ast(start_node, ast_builder, source, ParseMode::Strict, cache);
+    if start_node.has_error() {
+        query_errors(start_node, source, &mut errors);
+    }
+    match NEVec::try_from_vec(errors) {
+        Some(some_errors) => Validated::fail(ParsingFailure {
+            partial_tree: Some(proc),
+            errors: some_errors,
+        }),
+        None => Validated::Good(proc),
+    }
+}
+
+/// Like [`node_to_ast`], but never aborts: `ERROR`/`MISSING` nodes are kept as
+/// [`Proc::Error`] placeholders (recovering whatever named children still
+/// parse) instead of collapsing the whole tree to a failure, so callers such
+/// as an LSP can still offer completion/hover over the valid regions of an
+/// incomplete program.
+pub(super) fn node_to_ast_resilient<'ast>(
+    start_node: &tree_sitter::Node,
+    ast_builder: &'ast ASTBuilder<'ast>,
+    source: &'ast str,
+) -> (AnnProc<'ast>, Vec<AnnParsingError>) {
+    let (proc, mut errors) = build_ast(start_node, ast_builder, source, ParseMode::Resilient, None);
+    if start_node.has_error() {
+        query_errors(start_node, source, &mut errors);
+    }
+    (proc, errors)
+}
+
+fn build_ast<'ast>(
+    start_node: &tree_sitter::Node,
+    ast_builder: &'ast ASTBuilder<'ast>,
+    source: &'ast str,
+    mode: ParseMode,
+    mut cache: Option<&mut NodeCache<'ast>>,
+) -> (AnnProc<'ast>, Vec<AnnParsingError>) {
     let mut errors = Vec::new();
     let mut proc_stack = ProcStack::new();
     let mut cont_stack = Vec::with_capacity(32);
+    let mut id_stack: Vec<usize> = Vec::with_capacity(32);
     let mut node = *start_node;
 
     'parse: loop {
@@ -49,6 +120,11 @@ pub(super) fn node_to_ast<'ast>(
         if node.is_error() || node.is_missing() {
             // the errors will be discovered when parsing is done
             bad = true;
+        } else if let Some(cached) = cache.as_deref().and_then(|c| c.get(&node.id())).copied() {
+            // An incremental reparse reused this exact node, so the `AnnProc`
+            // built for it last time is still correct — splice it back in
+            // instead of re-walking the subtree.
+            proc_stack.push(cached.proc, cached.span);
         } else {
             fn eval_named_pairs<'a>(
                 of: &tree_sitter::Node<'a>,
@@ -69,23 +145,43 @@ pub(super) fn node_to_ast<'ast>(
             }
 
             let span = node.range().into();
+            let node_id = node.id();
+            macro_rules! cache_insert {
+                ($proc:expr) => {
+                    if let Some(c) = cache.as_deref_mut() {
+                        c.insert(node_id, AnnProc { proc: $proc, span });
+                    }
+                };
+            }
+
             match node.kind_id() {
                 kind!("block") => {
                     node = get_first_child(&node);
                     continue 'parse;
                 }
 
-                kind!("wildcard") => proc_stack.push(&ast_builder.WILD, span),
+                kind!("wildcard") => {
+                    cache_insert!(&ast_builder.WILD);
+                    proc_stack.push(&ast_builder.WILD, span)
+                }
                 kind!("var") => {
                     let id = Id {
                         name: get_node_value(&node, source),
                         pos: span.start,
                     };
-                    proc_stack.push(ast_builder.alloc_var(id), span);
+                    let proc = ast_builder.alloc_var(id);
+                    cache_insert!(proc);
+                    proc_stack.push(proc, span);
                 }
 
-                kind!("nil") => proc_stack.push(&ast_builder.NIL, span),
-                kind!("unit") => proc_stack.push(&ast_builder.UNIT, span),
+                kind!("nil") => {
+                    cache_insert!(&ast_builder.NIL);
+                    proc_stack.push(&ast_builder.NIL, span)
+                }
+                kind!("unit") => {
+                    cache_insert!(&ast_builder.UNIT);
+                    proc_stack.push(&ast_builder.UNIT, span)
+                }
                 kind!("simple_type") => {
                     let lit_value = get_node_value(&node, source);
                     let simple_type_value = match lit_value {
@@ -98,7 +194,9 @@ pub(super) fn node_to_ast<'ast>(
                             "Simple type is always 'Bool', 'Int', 'String', 'Uri', or 'ByteArray'"
                         ),
                     };
-                    proc_stack.push(ast_builder.alloc_simple_type(simple_type_value), span);
+                    let proc = ast_builder.alloc_simple_type(simple_type_value);
+                    cache_insert!(proc);
+                    proc_stack.push(proc, span);
                 }
                 kind!("bool_literal") => {
                     let lit_value = get_node_value(&node, source);
@@ -107,47 +205,54 @@ pub(super) fn node_to_ast<'ast>(
                         "false" => &ast_builder.FALSE,
                         _ => unreachable!("Boolean literal is always 'true' or 'false'"),
                     };
+                    cache_insert!(bool_proc);
                     proc_stack.push(bool_proc, span);
                 }
                 kind!("long_literal") => {
                     let lit_value = get_node_value(&node, source);
                     match lit_value.parse::<i64>() {
                         Ok(i64_value) => {
-                            proc_stack.push(ast_builder.alloc_long_literal(i64_value), span)
+                            let proc = ast_builder.alloc_long_literal(i64_value);
+                            cache_insert!(proc);
+                            proc_stack.push(proc, span)
                         }
                         Err(_) => {
                             // the only possibility is pos/neg overflow
-                            errors.push(AnnParsingError {
-                                error: ParsingError::NumberOutOfRange,
-                                span,
-                            });
+                            errors.push(AnnParsingError::at(&node, ParsingError::NumberOutOfRange));
                             bad = true;
                         }
                     }
                 }
                 kind!("string_literal") => {
                     let lit_value = get_node_value(&node, source);
-                    proc_stack.push(ast_builder.alloc_string_literal(lit_value), span);
+                    let proc = ast_builder.alloc_string_literal(lit_value);
+                    cache_insert!(proc);
+                    proc_stack.push(proc, span);
                 }
                 kind!("uri_literal") => {
                     let lit_value = get_node_value(&node, source);
-                    proc_stack.push(ast_builder.alloc_uri_literal(lit_value), span);
+                    let proc = ast_builder.alloc_uri_literal(lit_value);
+                    cache_insert!(proc);
+                    proc_stack.push(proc, span);
                 }
 
                 kind!("par") => {
                     let (left, right) = get_left_and_right(&node);
                     cont_stack.push(K::ConsumePar { span });
+                    id_stack.push(node_id);
                     cont_stack.push(K::EvalDelayed(right));
                     node = left;
                     continue 'parse;
                 }
                 kind!("eval") => {
                     cont_stack.push(K::ConsumeEval { span });
+                    id_stack.push(node_id);
                     node = get_first_child(&node);
                     continue 'parse;
                 }
                 kind!("quote") => {
                     cont_stack.push(K::ConsumeQuote { span });
+                    id_stack.push(node_id);
                     node = get_first_child(&node);
                     continue 'parse;
                 }
@@ -164,30 +269,18 @@ pub(super) fn node_to_ast<'ast>(
                         arity: args_node.named_child_count(),
                         span,
                     });
+                    id_stack.push(node_id);
 
                     cont_stack.push(K::EvalList(args_node.walk()));
                     node = receiver_node;
                     continue 'parse;
                 }
-                kind!("or")
-                | kind!("and")
-                | kind!("matches")
-                | kind!("eq")
-                | kind!("neq")
-                | kind!("lt")
-                | kind!("lte")
-                | kind!("gt")
-                | kind!("gte")
-                | kind!("concat")
-                | kind!("diff")
-                | kind!("add")
-                | kind!("sub")
-                | kind!("interpolation")
-                | kind!("mult")
-                | kind!("div")
-                | kind!("mod")
-                | kind!("disjunction")
-                | kind!("conjunction") => {
+                id if node_set!(
+                    "or", "and", "matches", "eq", "neq", "lt", "lte", "gt", "gte", "concat",
+                    "diff", "add", "sub", "interpolation", "mult", "div", "mod", "disjunction",
+                    "conjunction"
+                )
+                .contains(id) => {
                     let (left, right) = get_left_and_right(&node);
                     cont_stack.push(K::ConsumeBinaryExp {
                         op: match node.kind_id() {
@@ -213,11 +306,12 @@ pub(super) fn node_to_ast<'ast>(
                         },
                         span,
                     });
+                    id_stack.push(node_id);
                     cont_stack.push(K::EvalDelayed(right));
                     node = left;
                     continue 'parse;
                 }
-                kind!("neg") | kind!("not") | kind!("negation") => {
+                id if node_set!("neg", "not", "negation").contains(id) => {
                     let proc_node = get_first_child(&node);
                     cont_stack.push(K::ConsumeUnaryExp {
                         op: match node.kind_id() {
@@ -227,6 +321,7 @@ pub(super) fn node_to_ast<'ast>(
                         },
                         span,
                     });
+                    id_stack.push(node_id);
                     node = proc_node;
                     continue 'parse;
                 }
@@ -248,6 +343,7 @@ pub(super) fn node_to_ast<'ast>(
                                 has_remainder,
                                 span,
                             });
+                            id_stack.push(node_id);
                             cont_stack.push(K::EvalList(collection_node.walk()));
                         }
                         kind!("set") => {
@@ -256,6 +352,7 @@ pub(super) fn node_to_ast<'ast>(
                                 has_remainder,
                                 span,
                             });
+                            id_stack.push(node_id);
                             cont_stack.push(K::EvalList(collection_node.walk()));
                         }
                         kind!("tuple") => {
@@ -263,6 +360,7 @@ pub(super) fn node_to_ast<'ast>(
                                 arity: collection_node.named_child_count(),
                                 span,
                             });
+                            id_stack.push(node_id);
                             cont_stack.push(K::EvalList(collection_node.walk()));
                         }
                         kind!("map") => {
@@ -280,6 +378,7 @@ pub(super) fn node_to_ast<'ast>(
                                 has_remainder,
                                 span,
                             });
+                            id_stack.push(node_id);
                             cont_stack.append(&mut temp_cont_stack);
                             if let Some(rem) = remainder_node {
                                 cont_stack.push(K::EvalDelayed(rem));
@@ -305,6 +404,7 @@ pub(super) fn node_to_ast<'ast>(
                         arity,
                         span,
                     });
+                    id_stack.push(node_id);
                     cont_stack.push(K::EvalList(inputs_node.walk()));
                     node = name_node;
                     continue 'parse;
@@ -323,13 +423,14 @@ pub(super) fn node_to_ast<'ast>(
                         if second < first {
                             std::mem::swap(&mut first, &mut second);
                         };
-                        errors.push(AnnParsingError {
-                            error: ParsingError::DuplicateNameDecl { first, second },
-                            span: decls_node.range().into(),
-                        });
+                        errors.push(AnnParsingError::at(
+                            &decls_node,
+                            ParsingError::DuplicateNameDecl { first, second },
+                        ));
                     }
 
                     cont_stack.push(K::ConsumeNew { decls, span });
+                    id_stack.push(node_id);
                     node = proc_node;
                     continue 'parse;
                 }
@@ -344,6 +445,7 @@ pub(super) fn node_to_ast<'ast>(
                             has_cont: formals_node.child_by_field_name("cont").is_some(),
                             span,
                         });
+                        id_stack.push(node_id);
                         cont_stack.push(K::EvalList(formals_node.walk()));
                     } else {
                         cont_stack.push(K::ConsumeContract {
@@ -351,6 +453,7 @@ pub(super) fn node_to_ast<'ast>(
                             has_cont: false,
                             span,
                         });
+                        id_stack.push(node_id);
                     }
                     cont_stack.push(K::EvalDelayed(proc_node));
                     node = name_node;
@@ -363,10 +466,12 @@ pub(super) fn node_to_ast<'ast>(
                     match node.child_by_field_id(field!("alternative")) {
                         Some(alternative_node) => {
                             cont_stack.push(K::ConsumeIfThenElse { span });
+                            id_stack.push(node_id);
                             cont_stack.push(K::EvalDelayed(alternative_node));
                         }
                         None => {
                             cont_stack.push(K::ConsumeIfThen { span });
+                            id_stack.push(node_id);
                         }
                     };
                     cont_stack.push(K::EvalDelayed(if_true_node));
@@ -463,6 +568,7 @@ pub(super) fn node_to_ast<'ast>(
                     temp_cont_stack.reverse();
 
                     cont_stack.push(K::ConsumeForComprehension { desc: rs, span });
+                    id_stack.push(node_id);
                     cont_stack.append(&mut temp_cont_stack);
                     node = proc_node;
                     continue 'parse;
@@ -483,6 +589,7 @@ pub(super) fn node_to_ast<'ast>(
                     );
 
                     cont_stack.push(K::ConsumeMatch { span, arity });
+                    id_stack.push(node_id);
                     cont_stack.append(&mut temp_cont_stack);
 
                     node = expression_node;
@@ -505,13 +612,13 @@ pub(super) fn node_to_ast<'ast>(
                         let lhs_has_cont = lhs.child_by_field_id(field!("cont")).is_some();
 
                         if (lhs_has_cont && lhs_arity > rhs_arity) || lhs_arity != rhs_arity {
-                            errors.push(AnnParsingError {
-                                error: ParsingError::MalformedLetDecl {
+                            errors.push(AnnParsingError::at(
+                                &decl_node,
+                                ParsingError::MalformedLetDecl {
                                     lhs_arity,
                                     rhs_arity,
                                 },
-                                span: decl_node.range().into(),
-                            });
+                            ));
                         }
                         temp_cont_stack.push(K::EvalList(lhs.walk()));
                         temp_cont_stack.push(K::EvalList(rhs.walk()));
@@ -528,6 +635,7 @@ pub(super) fn node_to_ast<'ast>(
                         concurrent,
                         let_decls,
                     });
+                    id_stack.push(node_id);
                     cont_stack.append(&mut temp_cont_stack);
 
                     node = body_node;
@@ -547,6 +655,7 @@ pub(super) fn node_to_ast<'ast>(
 
                     let proc_node = get_field(&node, field!("proc"));
                     cont_stack.push(K::ConsumeBundle { span, typ: bundle });
+                    id_stack.push(node_id);
                     node = proc_node;
                     continue 'parse;
                 }
@@ -560,10 +669,12 @@ pub(super) fn node_to_ast<'ast>(
                     match choice_node.kind_id() {
                         kind!("empty_cont") => {
                             cont_stack.push(K::ConsumeSendSync { span, arity });
+                            id_stack.push(node_id);
                         }
                         kind!("non_empty_cont") => {
                             let cont_node = get_first_child(&choice_node);
                             cont_stack.push(K::ConsumeSendSyncWithCont { span, arity });
+                            id_stack.push(node_id);
                             cont_stack.push(K::EvalDelayed(cont_node));
                         }
                         _ => {
@@ -590,7 +701,9 @@ pub(super) fn node_to_ast<'ast>(
                         pos: var_node.start_position().into(),
                     };
 
-                    proc_stack.push(ast_builder.alloc_var_ref(var_ref_kind, var), span);
+                    let proc = ast_builder.alloc_var_ref(var_ref_kind, var);
+                    cache_insert!(proc);
+                    proc_stack.push(proc, span);
                 }
 
                 _ => unimplemented!(),
@@ -598,25 +711,31 @@ pub(super) fn node_to_ast<'ast>(
         }
 
         if bad {
-            proc_stack.push(&ast_builder.BAD, node.range().into());
+            let span = node.range().into();
+            match mode {
+                ParseMode::Strict => proc_stack.push(&ast_builder.BAD, span),
+                ParseMode::Resilient => {
+                    let (partial, recovered_children) = recover_error_node(
+                        &node,
+                        ast_builder,
+                        source,
+                        &mut errors,
+                        cache.as_deref_mut(),
+                    );
+                    proc_stack.push(ast_builder.alloc_error(partial, &recovered_children), span);
+                }
+            }
         }
         loop {
-            let step = apply_cont(&mut cont_stack, &mut proc_stack, ast_builder);
+            let step = apply_cont(
+                &mut cont_stack,
+                &mut proc_stack,
+                ast_builder,
+                &mut id_stack,
+                &mut cache,
+            );
             match step {
-                Step::Done => {
-                    if start_node.has_error() {
-                        // discover all the errors
-                        query_errors(start_node, source, &mut errors);
-                    }
-                    if let Some(some_errors) = NEVec::try_from_vec(errors) {
-                        return Validated::fail(ParsingFailure {
-                            partial_tree: proc_stack.to_proc_partial(),
-                            errors: some_errors,
-                        });
-                    }
-                    let last = proc_stack.to_proc();
-                    return Validated::Good(last);
-                }
+                Step::Done => return (proc_stack.to_proc(), errors),
                 Step::Continue(n) => {
                     node = n;
                     continue 'parse;
@@ -626,6 +745,44 @@ pub(super) fn node_to_ast<'ast>(
     }
 }
 
+/// Recover whatever can still be parsed out of an `ERROR` node's named
+/// children (an `ERROR`/`MISSING` child of its own carries no usable
+/// content, so it's skipped here — its span is still reported, via the
+/// top-level [`query_errors`] sweep over the whole tree). Returns the single
+/// recovered child as `partial` when there was exactly one, alongside the
+/// full list for callers that want every fragment that was kept.
+fn recover_error_node<'ast>(
+    node: &tree_sitter::Node,
+    ast_builder: &'ast ASTBuilder<'ast>,
+    source: &'ast str,
+    errors: &mut Vec<AnnParsingError>,
+    mut cache: Option<&mut NodeCache<'ast>>,
+) -> (Option<AnnProc<'ast>>, ProcList<'ast>) {
+    let mut recovered_children = ProcList::new();
+    let mut cursor = node.walk();
+
+    for child in node.named_children(&mut cursor) {
+        if child.is_error() || child.is_missing() {
+            continue;
+        }
+        let (child_proc, child_errors) = build_ast(
+            &child,
+            ast_builder,
+            source,
+            ParseMode::Resilient,
+            cache.as_deref_mut(),
+        );
+        errors.extend(child_errors);
+        recovered_children.push(child_proc);
+    }
+
+    let partial = match recovered_children.as_slice() {
+        [single] => Some(*single),
+        _ => None,
+    };
+    (partial, recovered_children)
+}
+
 fn parse_decls<'a>(from: &tree_sitter::Node, source: &'a str) -> Vec<NameDecl<'a>> {
     let mut result = Vec::with_capacity(from.named_child_count());
 
@@ -645,46 +802,12 @@ fn parse_decls<'a>(from: &tree_sitter::Node, source: &'a str) -> Vec<NameDecl<'a
     result
 }
 
-fn query_errors(of: &tree_sitter::Node, source: &str, into: &mut Vec<AnnParsingError>) {
-    use tree_sitter::StreamingIterator;
-
-    static QUERY: OnceLock<tree_sitter::Query> = OnceLock::new();
-
-    let query = QUERY.get_or_init(|| {
-        let rholang_language = rholang_tree_sitter::LANGUAGE.into();
-        tree_sitter::Query::new(
-            &rholang_language,
-            "(ERROR) @error-node (MISSING) @missing-node",
-        )
-        .expect("failed to compile error query")
-    });
-
-    let mut cursor = tree_sitter::QueryCursor::new();
-    let source_bytes = source.as_bytes();
-
-    let mut matches = cursor.matches(query, *of, source_bytes);
-    while let Some(m) = matches.next() {
-        for capture in m.captures {
-            let node = capture.node;
-            match capture.index {
-                1 => {
-                    into.push(AnnParsingError::from_mising(&node));
-                }
-                _ => {
-                    if node.parent().is_some_and(|p| p.is_error()) {
-                        continue; // skip UNEXPECTED, we process it somewhere else
-                    }
-                    into.push(AnnParsingError::from_error(&node, source_bytes));
-                }
-            }
-        }
-    }
-}
-
 fn apply_cont<'tree, 'ast>(
     cont_stack: &mut Vec<K<'tree, 'ast>>,
     proc_stack: &mut ProcStack<'ast>,
     ast_builder: &'ast ASTBuilder<'ast>,
+    id_stack: &mut Vec<usize>,
+    cache: &mut Option<&mut NodeCache<'ast>>,
 ) -> Step<'tree> {
     fn move_cursor_to_named(cursor: &mut tree_sitter::TreeCursor) -> bool {
         let mut has_more = if cursor.depth() == 0 {
@@ -723,6 +846,10 @@ fn apply_cont<'tree, 'ast>(
                     // SAFETY: We only enter this branch when cont_stack.last_mut() returned
                     // Some(_), which guarantees the stack is non-empty. The pop() cannot fail.
                     let k = cont_stack.pop().unwrap_unchecked();
+                    // SAFETY: every Consume* frame above is pushed together with a matching
+                    // id_stack entry (see the dispatch match in build_ast), so popping one
+                    // here always has a corresponding node id to pop too.
+                    let node_id = id_stack.pop().unwrap_unchecked();
 
                     let underflow = !match k {
                         K::ConsumeBinaryExp { op, span } => {
@@ -944,6 +1071,12 @@ fn apply_cont<'tree, 'ast>(
                             "bug: process stack underflow!!!\nProcess stack: {proc_stack:#?}\nContinuation stack: {cont_stack:#?}"
                         );
                     }
+
+                    if let Some(c) = cache.as_deref_mut() {
+                        if let Some(proc) = proc_stack.top() {
+                            c.insert(node_id, proc);
+                        }
+                    }
                 }
             }
         }
@@ -1181,6 +1314,10 @@ impl<'a> ProcStack<'a> {
         self.stack.push(AnnProc { proc, span });
     }
 
+    fn top(&self) -> Option<AnnProc<'a>> {
+        self.stack.last().copied()
+    }
+
     fn to_proc(self) -> AnnProc<'a> {
         let stack = self.stack;
         assert!(
@@ -1193,10 +1330,6 @@ impl<'a> ProcStack<'a> {
         }
     }
 
-    fn to_proc_partial(&self) -> Option<AnnProc<'a>> {
-        self.stack.last().copied()
-    }
-
     #[inline]
     fn replace_top_unchecked<F>(&mut self, replace: F)
     where
@@ -0,0 +1,138 @@
+//! Incremental reparsing for editor/LSP-style use, where a document changes a
+//! little at a time and re-running the whole parse-to-AST pipeline on every
+//! keystroke is wasteful.
+//!
+//! A [`ReparseSession`] holds the last `tree_sitter::Tree` it produced, the
+//! `AnnProc`s it built for that tree's top-level processes, plus a
+//! [`parsing::NodeCache`] of every `AnnProc` [`parsing::node_to_ast_with_cache`]
+//! built along the way. Each call to [`ReparseSession::reparse`] applies the
+//! caller's `tree_sitter::InputEdit`s to that tree, then asks tree-sitter to
+//! reparse the new source incrementally (`Parser::parse(new_source,
+//! Some(&old_tree))`). Tree-sitter reuses any subtree untouched by the edits
+//! verbatim — down to the node identity — so a node whose `id()` is unchanged
+//! between the old and new tree is, by construction, a byte-for-byte
+//! unmodified subtree.
+//!
+//! Two layers of reuse follow from that: `old_tree.changed_ranges(&new_tree)`
+//! tells us which byte ranges actually differ, so a top-level process whose
+//! span doesn't overlap any of them is spliced back in from the last call's
+//! `AnnProc`s without walking it at all; everything else goes through
+//! `node_to_ast_with_cache`, which checks the node cache for the same
+//! untouched-subtree property at every depth *within* a changed process, not
+//! just at the top level.
+use validated::Validated;
+
+use crate::ast::AnnProc;
+use crate::parser::ast_builder::ASTBuilder;
+use crate::parser::errors::AnnParsingError;
+use crate::parser::parsing::{self, NodeCache};
+
+pub struct ReparseSession<'ast> {
+    ast_builder: ASTBuilder<'ast>,
+    tree: std::cell::RefCell<Option<tree_sitter::Tree>>,
+    node_cache: std::cell::RefCell<NodeCache<'ast>>,
+    /// The top-level `AnnProc`s built on the last successful `reparse` call, in
+    /// source order, so an unchanged one can be spliced back in by index.
+    last_procs: std::cell::RefCell<Vec<AnnProc<'ast>>>,
+}
+
+impl<'ast> ReparseSession<'ast> {
+    pub fn new() -> Self {
+        ReparseSession {
+            ast_builder: ASTBuilder::new(),
+            tree: std::cell::RefCell::new(None),
+            node_cache: std::cell::RefCell::new(NodeCache::new()),
+            last_procs: std::cell::RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Reparse `new_source`, given the `edits` that turn the previous source
+    /// (the one passed to the last `reparse` call, or none yet) into it.
+    ///
+    /// Returns every top-level process's `AnnProc`: identical to what a fresh
+    /// [`crate::parser::RholangParser::parse`] of `new_source` would build,
+    /// but with every top-level process untouched by the edits spliced back
+    /// in from the last call, and every subtree of a changed one reused from
+    /// the node cache wherever tree-sitter's incremental parse shows it
+    /// survived unmodified.
+    pub fn reparse<'code: 'ast>(
+        &'ast self,
+        new_source: &'code str,
+        edits: &[tree_sitter::InputEdit],
+    ) -> Validated<Vec<AnnProc<'ast>>, AnnParsingError> {
+        let mut parser = tree_sitter::Parser::new();
+        let rholang_language = rholang_tree_sitter::LANGUAGE.into();
+        parser
+            .set_language(&rholang_language)
+            .expect("Error loading Rholang parser");
+
+        let mut tree = self.tree.borrow_mut();
+
+        if let Some(old_tree) = tree.as_mut() {
+            for edit in edits {
+                old_tree.edit(edit);
+            }
+        }
+
+        let new_tree = parser
+            .parse(new_source, tree.as_ref())
+            .expect("Failed to produce syntax tree");
+
+        let changed_ranges: Vec<std::ops::Range<usize>> = tree
+            .as_ref()
+            .map(|old_tree| {
+                old_tree
+                    .changed_ranges(&new_tree)
+                    .map(|range| range.start_byte..range.end_byte)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut node_cache = self.node_cache.borrow_mut();
+        let mut last_procs = self.last_procs.borrow_mut();
+
+        let mut walker = new_tree.walk();
+        let children: Vec<tree_sitter::Node> = new_tree.root_node().named_children(&mut walker).collect();
+
+        let result: Validated<Vec<AnnProc<'ast>>, AnnParsingError> = children
+            .iter()
+            .enumerate()
+            .map(|(index, node)| {
+                let untouched = last_procs.get(index).copied().filter(|_| {
+                    !changed_ranges
+                        .iter()
+                        .any(|range| range.start < node.end_byte() && node.start_byte() < range.end)
+                });
+
+                match untouched {
+                    Some(proc) => Validated::Good(proc),
+                    None => parsing::node_to_ast_with_cache(node, &self.ast_builder, new_source, Some(&mut node_cache)),
+                }
+            })
+            .collect();
+
+        match &result {
+            Validated::Good(procs) => {
+                *last_procs = procs.clone();
+                *tree = Some(new_tree);
+            }
+            Validated::Fail(_) => {
+                // Leave nothing cached: a failed parse has no trustworthy
+                // tree to diff future edits against, so the next call falls
+                // back to a full reparse from scratch, with no node cache or
+                // last-call `AnnProc`s left to splice from either.
+                *tree = None;
+                node_cache.clear();
+                last_procs.clear();
+            }
+        }
+
+        result
+    }
+}
+
+impl Default for ReparseSession<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
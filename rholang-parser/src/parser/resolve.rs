@@ -0,0 +1,371 @@
+//! De Bruijn-indexed name resolution over a parsed [`AnnProc`].
+//!
+//! [`resolve_names`] walks the tree once, in the same structural-recursion
+//! style and over the same four binder positions as
+//! [`crate::parser::scope::analyze_scopes`] (`New`'s `NameDecl`s,
+//! `ForComprehension`'s `Bind` patterns treated as one simultaneous group
+//! spanning the whole `for`, `Contract`'s formals, and `Let`'s bindings,
+//! plus a `match` case's pattern), but rather than flagging unused binders
+//! it resolves every name *use* against the innermost enclosing binder of
+//! the same name and records the result as a [`Resolution`]:
+//! [`Resolution::Bound`] with a De Bruijn index counting outward from the
+//! use (0 = the nearest enclosing binder), or [`Resolution::Free`] if no
+//! enclosing binder declares that name at all.
+//!
+//! A [`Context`] maps each name to a stack of the depths at which it's
+//! bound, so shadowing falls out for free: the top of a name's stack is
+//! always its innermost binding, and `current_depth - binding_depth` is
+//! its De Bruijn index from the use site. Depths are pushed on entry to a
+//! binder's scope and popped on exit, mirroring
+//! `scope::Analysis::open_scope`/`close_scope`'s push-before-descend,
+//! pop-after-return structure — this module's `walk_*` functions never
+//! return out of the middle of a scope, so that bookkeeping stays
+//! symmetric by construction.
+//!
+//! Same approximation as [`crate::parser::scope`] and
+//! [`crate::parser::alpha_eq`]: a `for`'s binds are one simultaneous group
+//! across all of its `;`-separated receipts, so a later receipt's source
+//! can reference an earlier receipt's bound name.
+
+use std::collections::HashMap;
+
+use crate::ast::{
+    AnnName, AnnProc, Bind, Branch, Case, Collection, Id, LetBinding, Name, NameDecl, Names, Proc,
+    Receipts, Source, SyncSendCont, Var,
+};
+use crate::parser::fold::names_bound_ids;
+use crate::parser::scope::{collect_pattern, id_span};
+use crate::SourceSpan;
+
+/// The outcome of resolving one name use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution<'ast> {
+    /// `id` resolves to a binder `index` scopes out from the use (0 = a
+    /// binder of the innermost enclosing scope).
+    Bound { id: Id<'ast>, span: SourceSpan, index: u32 },
+    /// `id` has no enclosing binder.
+    Free { id: Id<'ast>, span: SourceSpan },
+}
+
+/// An ordered binding context: each name maps to a stack of the depths at
+/// which it's currently bound, so a reference resolves to the innermost
+/// (last-pushed) one and shadowing falls out for free.
+#[derive(Default)]
+struct Context<'ast> {
+    bindings: HashMap<&'ast str, Vec<u32>>,
+    depth: u32,
+}
+
+impl<'ast> Context<'ast> {
+    fn resolve(&self, name: &str) -> Option<u32> {
+        let binding_depth = *self.bindings.get(name)?.last()?;
+        Some(self.depth - binding_depth)
+    }
+
+    fn use_name(&self, resolutions: &mut Vec<Resolution<'ast>>, id: Id<'ast>, span: SourceSpan) {
+        resolutions.push(match self.resolve(id.name) {
+            Some(index) => Resolution::Bound { id, span, index },
+            None => Resolution::Free { id, span },
+        });
+    }
+
+    /// Enter a new scope, binding every name in `binders` at the next depth.
+    fn push_scope(&mut self, binders: &[(Id<'ast>, SourceSpan)]) {
+        self.depth += 1;
+        for (id, _) in binders {
+            self.bindings.entry(id.name).or_default().push(self.depth);
+        }
+    }
+
+    /// Leave the scope most recently entered by `push_scope` with the same
+    /// `binders`.
+    fn pop_scope(&mut self, binders: &[(Id<'ast>, SourceSpan)]) {
+        for (id, _) in binders {
+            let stack = self.bindings.get_mut(id.name).expect("pop_scope: name pushed by push_scope");
+            stack.pop();
+            if stack.is_empty() {
+                self.bindings.remove(id.name);
+            }
+        }
+        self.depth -= 1;
+    }
+}
+
+fn names_binders<'ast>(names: &Names<'ast>) -> Vec<(Id<'ast>, SourceSpan)> {
+    names_bound_ids(names).into_iter().map(|id| (id, id_span(&id))).collect()
+}
+
+fn walk_proc<'ast>(ctx: &mut Context<'ast>, resolutions: &mut Vec<Resolution<'ast>>, ann: &AnnProc<'ast>) {
+    match ann.proc {
+        Proc::Nil
+        | Proc::BoolLiteral(_)
+        | Proc::LongLiteral(_)
+        | Proc::StringLiteral(_)
+        | Proc::UriLiteral(_)
+        | Proc::SimpleType(_)
+        | Proc::Bad
+        | Proc::ProcVar(Var::Wildcard) => {}
+
+        Proc::ProcVar(Var::Id(id)) => ctx.use_name(resolutions, *id, ann.span),
+        Proc::VarRef { var, .. } => ctx.use_name(resolutions, *var, ann.span),
+
+        Proc::Par { left, right } => {
+            walk_proc(ctx, resolutions, left);
+            walk_proc(ctx, resolutions, right);
+        }
+        Proc::IfThenElse { condition, if_true, if_false } => {
+            walk_proc(ctx, resolutions, condition);
+            walk_proc(ctx, resolutions, if_true);
+            if let Some(if_false) = if_false {
+                walk_proc(ctx, resolutions, if_false);
+            }
+        }
+        Proc::Send { channel, inputs, .. } => {
+            walk_name(ctx, resolutions, channel);
+            for input in inputs.iter() {
+                walk_proc(ctx, resolutions, input);
+            }
+        }
+        Proc::ForComprehension { receipts, proc } => walk_for(ctx, resolutions, receipts, proc),
+        Proc::Match { expression, cases } => {
+            walk_proc(ctx, resolutions, expression);
+            for case in cases.iter() {
+                walk_match_case(ctx, resolutions, case);
+            }
+        }
+        Proc::Select { branches } => {
+            for branch in branches.iter() {
+                walk_select_branch(ctx, resolutions, branch);
+            }
+        }
+        Proc::Bundle { proc, .. } => walk_proc(ctx, resolutions, proc),
+        Proc::Let { bindings, body, concurrent } => walk_let(ctx, resolutions, bindings, *concurrent, body),
+        Proc::New { decls, proc } => walk_new(ctx, resolutions, decls, proc),
+        Proc::Contract { name, formals, body } => walk_contract(ctx, resolutions, name, formals, body),
+        Proc::SendSync { channel, messages, cont } => {
+            walk_name(ctx, resolutions, channel);
+            for message in messages.iter() {
+                walk_proc(ctx, resolutions, message);
+            }
+            if let SyncSendCont::NonEmpty(cont) = cont {
+                walk_proc(ctx, resolutions, cont);
+            }
+        }
+        Proc::Eval { name } => walk_name(ctx, resolutions, name),
+        Proc::Quote { proc } => walk_proc(ctx, resolutions, &AnnProc { proc, span: ann.span }),
+        Proc::Method { receiver, args, .. } => {
+            walk_proc(ctx, resolutions, receiver);
+            for arg in args.iter() {
+                walk_proc(ctx, resolutions, arg);
+            }
+        }
+        Proc::UnaryExp { arg, .. } => walk_proc(ctx, resolutions, &AnnProc { proc: arg, span: ann.span }),
+        Proc::BinaryExp { left, right, .. } => {
+            walk_proc(ctx, resolutions, left);
+            walk_proc(ctx, resolutions, right);
+        }
+        Proc::Collection(collection) => walk_collection(ctx, resolutions, collection),
+        Proc::Error { partial, recovered_children } => {
+            if let Some(partial) = partial {
+                walk_proc(ctx, resolutions, partial);
+            }
+            for child in recovered_children.iter() {
+                walk_proc(ctx, resolutions, child);
+            }
+        }
+    }
+}
+
+fn walk_name<'ast>(ctx: &mut Context<'ast>, resolutions: &mut Vec<Resolution<'ast>>, name: &AnnName<'ast>) {
+    match name.name {
+        Name::ProcVar(Var::Id(id)) => ctx.use_name(resolutions, id, name.span),
+        Name::ProcVar(Var::Wildcard) => {}
+        Name::Quote(proc) => walk_proc(ctx, resolutions, &AnnProc { proc, span: name.span }),
+    }
+}
+
+fn walk_collection<'ast>(ctx: &mut Context<'ast>, resolutions: &mut Vec<Resolution<'ast>>, collection: &Collection<'ast>) {
+    match collection {
+        Collection::List { elements, .. } | Collection::Set { elements, .. } => {
+            for element in elements {
+                walk_proc(ctx, resolutions, element);
+            }
+        }
+        Collection::Tuple(elements) => {
+            for element in elements {
+                walk_proc(ctx, resolutions, element);
+            }
+        }
+        Collection::Map { elements, .. } => {
+            for (key, value) in elements {
+                walk_proc(ctx, resolutions, key);
+                walk_proc(ctx, resolutions, value);
+            }
+        }
+    }
+}
+
+fn walk_source<'ast>(ctx: &mut Context<'ast>, resolutions: &mut Vec<Resolution<'ast>>, source: &Source<'ast>) {
+    match source {
+        Source::Simple { name } | Source::ReceiveSend { name } => walk_name(ctx, resolutions, name),
+        Source::SendReceive { name, inputs } => {
+            walk_name(ctx, resolutions, name);
+            for input in inputs.iter() {
+                walk_proc(ctx, resolutions, input);
+            }
+        }
+    }
+}
+
+fn bind_binders<'ast>(bind: &Bind<'ast>) -> Vec<(Id<'ast>, SourceSpan)> {
+    let lhs = match bind {
+        Bind::Linear { lhs, .. } | Bind::Repeated { lhs, .. } | Bind::Peek { lhs, .. } => lhs,
+    };
+    names_binders(lhs)
+}
+
+fn walk_bind_rhs<'ast>(ctx: &mut Context<'ast>, resolutions: &mut Vec<Resolution<'ast>>, bind: &Bind<'ast>) {
+    match bind {
+        Bind::Linear { rhs, .. } => walk_source(ctx, resolutions, rhs),
+        Bind::Repeated { rhs, .. } | Bind::Peek { rhs, .. } => walk_name(ctx, resolutions, rhs),
+    }
+}
+
+fn walk_for<'ast>(
+    ctx: &mut Context<'ast>,
+    resolutions: &mut Vec<Resolution<'ast>>,
+    receipts: &Receipts<'ast>,
+    proc: &AnnProc<'ast>,
+) {
+    let mut binders = Vec::new();
+    for receipt in receipts.iter() {
+        for bind in receipt.binds.iter() {
+            walk_bind_rhs(ctx, resolutions, bind);
+            binders.extend(bind_binders(bind));
+        }
+    }
+    ctx.push_scope(&binders);
+    walk_proc(ctx, resolutions, proc);
+    ctx.pop_scope(&binders);
+}
+
+fn walk_select_branch<'ast>(ctx: &mut Context<'ast>, resolutions: &mut Vec<Resolution<'ast>>, branch: &Branch<'ast>) {
+    let mut binders = Vec::new();
+    for pattern in branch.patterns.iter() {
+        walk_source(ctx, resolutions, &pattern.rhs);
+        binders.extend(names_binders(&pattern.lhs));
+    }
+    ctx.push_scope(&binders);
+    walk_proc(ctx, resolutions, &branch.proc);
+    ctx.pop_scope(&binders);
+}
+
+fn walk_new<'ast>(
+    ctx: &mut Context<'ast>,
+    resolutions: &mut Vec<Resolution<'ast>>,
+    decls: &[NameDecl<'ast>],
+    proc: &AnnProc<'ast>,
+) {
+    let binders: Vec<_> = decls.iter().map(|decl| (decl.id, id_span(&decl.id))).collect();
+    ctx.push_scope(&binders);
+    walk_proc(ctx, resolutions, proc);
+    ctx.pop_scope(&binders);
+}
+
+fn walk_contract<'ast>(
+    ctx: &mut Context<'ast>,
+    resolutions: &mut Vec<Resolution<'ast>>,
+    name: &AnnName<'ast>,
+    formals: &Names<'ast>,
+    body: &AnnProc<'ast>,
+) {
+    // The contract's own name is a use of a channel bound somewhere
+    // enclosing it (often by `new`), not a binder itself.
+    walk_name(ctx, resolutions, name);
+    let binders = names_binders(formals);
+    ctx.push_scope(&binders);
+    walk_proc(ctx, resolutions, body);
+    ctx.pop_scope(&binders);
+}
+
+fn let_binding_binders<'ast>(binding: &LetBinding<'ast>) -> Vec<(Id<'ast>, SourceSpan)> {
+    match binding {
+        LetBinding::Single { lhs, .. } => match lhs.name {
+            Name::ProcVar(Var::Id(id)) => vec![(id, id_span(&id))],
+            _ => Vec::new(),
+        },
+        LetBinding::Multiple { lhs, .. } => match lhs {
+            Var::Id(id) => vec![(*id, id_span(id))],
+            Var::Wildcard => Vec::new(),
+        },
+    }
+}
+
+fn walk_let_rhs<'ast>(ctx: &mut Context<'ast>, resolutions: &mut Vec<Resolution<'ast>>, binding: &LetBinding<'ast>) {
+    match binding {
+        LetBinding::Single { rhs, .. } => walk_proc(ctx, resolutions, rhs),
+        LetBinding::Multiple { rhs, .. } => {
+            for r in rhs.iter() {
+                walk_proc(ctx, resolutions, r);
+            }
+        }
+    }
+}
+
+fn walk_let<'ast>(
+    ctx: &mut Context<'ast>,
+    resolutions: &mut Vec<Resolution<'ast>>,
+    bindings: &[LetBinding<'ast>],
+    concurrent: bool,
+    body: &AnnProc<'ast>,
+) {
+    if concurrent {
+        // `let x <- a & y <- b in ...`: every rhs is evaluated before any of
+        // this let's own names come into scope.
+        for binding in bindings {
+            walk_let_rhs(ctx, resolutions, binding);
+        }
+        let binders: Vec<_> = bindings.iter().flat_map(let_binding_binders).collect();
+        ctx.push_scope(&binders);
+        walk_proc(ctx, resolutions, body);
+        ctx.pop_scope(&binders);
+    } else {
+        // `let x <- a; y <- x in ...`: each rhs can see the bindings
+        // introduced by the ones before it, so push one scope per binding.
+        let mut scopes = Vec::new();
+        for binding in bindings {
+            walk_let_rhs(ctx, resolutions, binding);
+            let binders = let_binding_binders(binding);
+            ctx.push_scope(&binders);
+            scopes.push(binders);
+        }
+        walk_proc(ctx, resolutions, body);
+        for binders in scopes.into_iter().rev() {
+            ctx.pop_scope(&binders);
+        }
+    }
+}
+
+fn walk_match_case<'ast>(ctx: &mut Context<'ast>, resolutions: &mut Vec<Resolution<'ast>>, case: &Case<'ast>) {
+    let mut binders = Vec::new();
+    let mut uses = Vec::new();
+    collect_pattern(&case.pattern, &mut binders, &mut uses);
+    for (id, span) in uses {
+        ctx.use_name(resolutions, id, span);
+    }
+    ctx.push_scope(&binders);
+    walk_proc(ctx, resolutions, &case.proc);
+    ctx.pop_scope(&binders);
+}
+
+/// Resolve every name use in `proc` against the binder it falls in,
+/// returning one [`Resolution`] per use in the order it's encountered (a
+/// pre-order, left-to-right walk): [`Resolution::Bound`] with a De Bruijn
+/// index counting scopes out from the use, or [`Resolution::Free`] for a
+/// use with no enclosing binder.
+pub fn resolve_names<'ast>(proc: &AnnProc<'ast>) -> Vec<Resolution<'ast>> {
+    let mut ctx = Context::default();
+    let mut resolutions = Vec::new();
+    walk_proc(&mut ctx, &mut resolutions, proc);
+    resolutions
+}
@@ -0,0 +1,485 @@
+//! A specialization of [`ProcFolder`] for passes that rebuild an `AnnProc`
+//! rather than accumulate some other `Output` — constant folding,
+//! desugaring, and similar tree-to-tree rewrites.
+//!
+//! [`ProcFolder::Output`] can't default to `AnnProc` the way it can to a
+//! `Default + Add` accumulator like [`super::fold::free_names`]'s name set,
+//! so a `ProcFolder<Output = AnnProc>` has to override every single method
+//! (see [`super::fold::substitute`]) even for the overwhelming majority of
+//! variants a given rewrite leaves untouched. [`Rewriter`] provides that
+//! missing default: every method has a body that reconstructs the node
+//! unchanged (reusing the original `&'ast Proc` for leaves, reallocating
+//! through [`ast_builder`](Rewriter::ast_builder) for everything else, the
+//! same way [`super::fold::substitute`] itself does), so a desugaring pass
+//! only has to override the handful of `rewrite_*` methods it actually
+//! cares about.
+//!
+//! [`rewrite`] drives a [`Rewriter`] the same way [`ProcFolder::fold_proc`]
+//! drives a `ProcFolder`: via [`RewriterAdapter`], a zero-cost `ProcFolder`
+//! wrapper that forwards every `fold_*` call to the matching `rewrite_*`
+//! method, so the stack-based traversal in `fold_proc` is reused rather
+//! than reimplemented.
+
+use crate::ast::{
+    AnnName, AnnProc, BinaryExpOp, Bind, BundleType, Id, Name, NameDecl, Names, Proc, SendType,
+    SimpleType, UnaryExpOp, Uri, Var, VarRefKind,
+};
+use crate::parser::ast_builder::ASTBuilder;
+use crate::parser::fold::{
+    FoldedBind, FoldedBranch, FoldedCollection, FoldedLetBinding, FoldedReceipt, FoldedSelectPattern,
+    FoldedSource, ProcFolder,
+};
+use crate::SourceSpan;
+
+fn proc_to_name<'ast>(p: AnnProc<'ast>) -> AnnName<'ast> {
+    match Name::try_from(p.proc) {
+        Ok(name) => AnnName { name, span: p.span },
+        Err(_) => AnnName { name: Name::Quote(p.proc), span: p.span },
+    }
+}
+
+fn rebuild_source<'ast>(folded: FoldedSource<AnnProc<'ast>>) -> crate::ast::Source<'ast> {
+    use crate::ast::Source;
+    match folded {
+        FoldedSource::Simple { name } => Source::Simple { name: proc_to_name(name) },
+        FoldedSource::ReceiveSend { name } => Source::ReceiveSend { name: proc_to_name(name) },
+        FoldedSource::SendReceive { name, inputs } => Source::SendReceive {
+            name: proc_to_name(name),
+            inputs: inputs.into(),
+        },
+    }
+}
+
+/// See the module docs. Every method defaults to rebuilding the node
+/// unchanged from its already-rewritten children; override whichever ones a
+/// given pass actually wants to transform.
+pub(super) trait Rewriter<'ast> {
+    /// The arena every rebuilt node is allocated in — the same one `proc`
+    /// (the tree passed to [`rewrite`]) was built in.
+    fn ast_builder(&self) -> &'ast ASTBuilder<'ast>;
+
+    fn rewrite_nil(&mut self, original: &'ast Proc<'ast>, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc { proc: original, span }
+    }
+
+    fn rewrite_bool_literal(&mut self, original: &'ast Proc<'ast>, _value: bool, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc { proc: original, span }
+    }
+
+    fn rewrite_long_literal(&mut self, original: &'ast Proc<'ast>, _value: i64, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc { proc: original, span }
+    }
+
+    fn rewrite_string_literal(&mut self, original: &'ast Proc<'ast>, _value: &'ast str, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc { proc: original, span }
+    }
+
+    fn rewrite_uri_literal(&mut self, original: &'ast Proc<'ast>, _value: Uri<'ast>, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc { proc: original, span }
+    }
+
+    fn rewrite_simple_type(&mut self, original: &'ast Proc<'ast>, _value: SimpleType, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc { proc: original, span }
+    }
+
+    fn rewrite_bad(&mut self, original: &'ast Proc<'ast>, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc { proc: original, span }
+    }
+
+    fn rewrite_proc_var(&mut self, var: Var<'ast>, span: SourceSpan) -> AnnProc<'ast> {
+        match var {
+            Var::Id(id) => AnnProc { proc: self.ast_builder().alloc_var(id), span },
+            Var::Wildcard => AnnProc { proc: &self.ast_builder().WILD, span },
+        }
+    }
+
+    fn rewrite_var_ref(&mut self, kind: VarRefKind, var: Id<'ast>, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc { proc: self.ast_builder().alloc_var_ref(kind, var), span }
+    }
+
+    fn rewrite_par(&mut self, left: AnnProc<'ast>, right: AnnProc<'ast>, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc { proc: self.ast_builder().alloc_par(left, right), span }
+    }
+
+    fn rewrite_if_then_else(
+        &mut self,
+        condition: AnnProc<'ast>,
+        if_true: AnnProc<'ast>,
+        if_false: Option<AnnProc<'ast>>,
+        span: SourceSpan,
+    ) -> AnnProc<'ast> {
+        let proc = match if_false {
+            Some(if_false) => self.ast_builder().alloc_if_then_else(condition, if_true, if_false),
+            None => self.ast_builder().alloc_if_then(condition, if_true),
+        };
+        AnnProc { proc, span }
+    }
+
+    fn rewrite_send(
+        &mut self,
+        channel: AnnProc<'ast>,
+        send_type: SendType,
+        inputs: Vec<AnnProc<'ast>>,
+        span: SourceSpan,
+    ) -> AnnProc<'ast> {
+        AnnProc {
+            proc: self.ast_builder().alloc_send(send_type, proc_to_name(channel), &inputs),
+            span,
+        }
+    }
+
+    fn rewrite_for_comprehension(
+        &mut self,
+        receipts: Vec<FoldedReceipt<'ast, AnnProc<'ast>>>,
+        proc: AnnProc<'ast>,
+        span: SourceSpan,
+    ) -> AnnProc<'ast> {
+        let receipts: Vec<Vec<Bind<'ast>>> = receipts
+            .into_iter()
+            .map(|receipt| {
+                receipt
+                    .binds
+                    .into_iter()
+                    .map(|bind| match bind {
+                        FoldedBind::Linear { lhs, rhs } => Bind::Linear { lhs: lhs.clone(), rhs: rebuild_source(rhs) },
+                        FoldedBind::Repeated { lhs, rhs } => Bind::Repeated { lhs: lhs.clone(), rhs: proc_to_name(rhs) },
+                        FoldedBind::Peek { lhs, rhs } => Bind::Peek { lhs: lhs.clone(), rhs: proc_to_name(rhs) },
+                    })
+                    .collect()
+            })
+            .collect();
+        AnnProc { proc: self.ast_builder().alloc_for(receipts, proc), span }
+    }
+
+    fn rewrite_match(
+        &mut self,
+        expression: AnnProc<'ast>,
+        cases: Vec<(AnnProc<'ast>, AnnProc<'ast>)>,
+        span: SourceSpan,
+    ) -> AnnProc<'ast> {
+        let flat: Vec<AnnProc<'ast>> = cases.into_iter().flat_map(|(pattern, proc)| [pattern, proc]).collect();
+        AnnProc { proc: self.ast_builder().alloc_match(expression, &flat), span }
+    }
+
+    fn rewrite_select(&mut self, branches: Vec<FoldedBranch<'ast, AnnProc<'ast>>>, span: SourceSpan) -> AnnProc<'ast> {
+        use crate::ast::{Branch, SelectPattern};
+        let branches = branches
+            .into_iter()
+            .map(|branch| Branch {
+                patterns: branch
+                    .patterns
+                    .into_iter()
+                    .map(|pattern: FoldedSelectPattern<'ast, AnnProc<'ast>>| SelectPattern {
+                        lhs: pattern.lhs.clone(),
+                        rhs: rebuild_source(pattern.rhs),
+                    })
+                    .collect(),
+                proc: branch.proc,
+            })
+            .collect();
+        AnnProc { proc: self.ast_builder().alloc_select(branches), span }
+    }
+
+    fn rewrite_bundle(&mut self, bundle_type: BundleType, proc: AnnProc<'ast>, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc { proc: self.ast_builder().alloc_bundle(bundle_type, proc), span }
+    }
+
+    fn rewrite_let(
+        &mut self,
+        bindings: Vec<FoldedLetBinding<'ast, AnnProc<'ast>>>,
+        body: AnnProc<'ast>,
+        concurrent: bool,
+        span: SourceSpan,
+    ) -> AnnProc<'ast> {
+        use crate::ast::LetBinding;
+        let bindings: Vec<LetBinding<'ast>> = bindings
+            .into_iter()
+            .map(|binding| match binding {
+                FoldedLetBinding::Single { lhs, rhs } => LetBinding::Single { lhs, rhs },
+                FoldedLetBinding::Multiple { lhs, rhs } => LetBinding::Multiple { lhs, rhs },
+            })
+            .collect();
+        AnnProc { proc: self.ast_builder().alloc_let(bindings, body, concurrent), span }
+    }
+
+    fn rewrite_new(&mut self, decls: &[NameDecl<'ast>], proc: AnnProc<'ast>, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc { proc: self.ast_builder().alloc_new(proc, decls.to_vec()), span }
+    }
+
+    fn rewrite_contract(
+        &mut self,
+        name: AnnProc<'ast>,
+        formals: &Names<'ast>,
+        body: AnnProc<'ast>,
+        span: SourceSpan,
+    ) -> AnnProc<'ast> {
+        AnnProc {
+            proc: self.ast_builder().alloc_contract(proc_to_name(name), formals.clone(), body),
+            span,
+        }
+    }
+
+    fn rewrite_send_sync(
+        &mut self,
+        channel: AnnProc<'ast>,
+        messages: Vec<AnnProc<'ast>>,
+        cont: Option<AnnProc<'ast>>,
+        span: SourceSpan,
+    ) -> AnnProc<'ast> {
+        let channel = proc_to_name(channel);
+        let proc = match cont {
+            Some(cont) => self.ast_builder().alloc_send_sync_with_cont(channel, &messages, cont),
+            None => self.ast_builder().alloc_send_sync(channel, &messages),
+        };
+        AnnProc { proc, span }
+    }
+
+    fn rewrite_eval(&mut self, name: AnnProc<'ast>, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc { proc: self.ast_builder().alloc_eval(proc_to_name(name)), span }
+    }
+
+    fn rewrite_quote(&mut self, proc: AnnProc<'ast>, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc { proc: self.ast_builder().alloc_quote(proc.proc), span }
+    }
+
+    fn rewrite_method(&mut self, receiver: AnnProc<'ast>, name: Id<'ast>, args: Vec<AnnProc<'ast>>, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc { proc: self.ast_builder().alloc_method(name, receiver, &args), span }
+    }
+
+    fn rewrite_unary_exp(&mut self, op: UnaryExpOp, arg: AnnProc<'ast>, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc { proc: self.ast_builder().alloc_unary_exp(op, arg.proc), span }
+    }
+
+    fn rewrite_binary_exp(&mut self, op: BinaryExpOp, left: AnnProc<'ast>, right: AnnProc<'ast>, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc { proc: self.ast_builder().alloc_binary_exp(op, left, right), span }
+    }
+
+    fn rewrite_collection(&mut self, collection: FoldedCollection<'ast, AnnProc<'ast>>, span: SourceSpan) -> AnnProc<'ast> {
+        let proc = match collection {
+            FoldedCollection::List { elements, remainder } => match remainder {
+                Some(remainder) => self.ast_builder().alloc_list_with_remainder(&elements, remainder),
+                None => self.ast_builder().alloc_list(&elements),
+            },
+            FoldedCollection::Tuple(elements) => self.ast_builder().alloc_tuple(&elements),
+            FoldedCollection::Set { elements, remainder } => match remainder {
+                Some(remainder) => self.ast_builder().alloc_set_with_remainder(&elements, remainder),
+                None => self.ast_builder().alloc_set(&elements),
+            },
+            FoldedCollection::Map { elements, remainder } => {
+                let flat: Vec<AnnProc<'ast>> = elements.into_iter().flat_map(|(k, v)| [k, v]).collect();
+                match remainder {
+                    Some(remainder) => self.ast_builder().alloc_map_with_remainder(&flat, remainder),
+                    None => self.ast_builder().alloc_map(&flat),
+                }
+            }
+        };
+        AnnProc { proc, span }
+    }
+
+    fn rewrite_error(&mut self, partial: Option<AnnProc<'ast>>, recovered_children: Vec<AnnProc<'ast>>, span: SourceSpan) -> AnnProc<'ast> {
+        AnnProc {
+            proc: self.ast_builder().alloc_error(partial, &recovered_children),
+            span,
+        }
+    }
+
+    /// Rewrite `proc`, returning the (possibly transformed) result. See
+    /// [`rewrite`].
+    fn rewrite(&mut self, proc: &AnnProc<'ast>) -> AnnProc<'ast>
+    where
+        Self: Sized,
+    {
+        RewriterAdapter(self).fold_proc(proc)
+    }
+}
+
+/// Forwards every [`ProcFolder`] call to the matching [`Rewriter`] method,
+/// so [`ProcFolder::fold_proc`]'s stack-based traversal drives `Rewriter`
+/// passes too instead of needing its own copy.
+struct RewriterAdapter<'r, R: ?Sized>(&'r mut R);
+
+impl<'ast, 'r, R: Rewriter<'ast> + ?Sized> ProcFolder<'ast> for RewriterAdapter<'r, R> {
+    type Output = AnnProc<'ast>;
+
+    fn fold_nil(&mut self, original: &'ast Proc<'ast>, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_nil(original, span)
+    }
+
+    fn fold_bool_literal(&mut self, original: &'ast Proc<'ast>, value: bool, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_bool_literal(original, value, span)
+    }
+
+    fn fold_long_literal(&mut self, original: &'ast Proc<'ast>, value: i64, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_long_literal(original, value, span)
+    }
+
+    fn fold_string_literal(&mut self, original: &'ast Proc<'ast>, value: &'ast str, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_string_literal(original, value, span)
+    }
+
+    fn fold_uri_literal(&mut self, original: &'ast Proc<'ast>, value: Uri<'ast>, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_uri_literal(original, value, span)
+    }
+
+    fn fold_simple_type(&mut self, original: &'ast Proc<'ast>, value: SimpleType, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_simple_type(original, value, span)
+    }
+
+    fn fold_bad(&mut self, original: &'ast Proc<'ast>, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_bad(original, span)
+    }
+
+    fn fold_proc_var(&mut self, var: Var<'ast>, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_proc_var(var, span)
+    }
+
+    fn fold_var_ref(&mut self, kind: VarRefKind, var: Id<'ast>, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_var_ref(kind, var, span)
+    }
+
+    fn fold_par(&mut self, left: Self::Output, right: Self::Output, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_par(left, right, span)
+    }
+
+    fn fold_if_then_else(
+        &mut self,
+        condition: Self::Output,
+        if_true: Self::Output,
+        if_false: Option<Self::Output>,
+        span: SourceSpan,
+    ) -> Self::Output {
+        self.0.rewrite_if_then_else(condition, if_true, if_false, span)
+    }
+
+    fn fold_send(&mut self, channel: Self::Output, send_type: SendType, inputs: Vec<Self::Output>, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_send(channel, send_type, inputs, span)
+    }
+
+    fn fold_for_comprehension(
+        &mut self,
+        receipts: Vec<FoldedReceipt<'ast, Self::Output>>,
+        proc: Self::Output,
+        span: SourceSpan,
+    ) -> Self::Output {
+        self.0.rewrite_for_comprehension(receipts, proc, span)
+    }
+
+    fn fold_match(&mut self, expression: Self::Output, cases: Vec<(Self::Output, Self::Output)>, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_match(expression, cases, span)
+    }
+
+    fn fold_select(&mut self, branches: Vec<FoldedBranch<'ast, Self::Output>>, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_select(branches, span)
+    }
+
+    fn fold_bundle(&mut self, bundle_type: BundleType, proc: Self::Output, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_bundle(bundle_type, proc, span)
+    }
+
+    fn fold_let(
+        &mut self,
+        bindings: Vec<FoldedLetBinding<'ast, Self::Output>>,
+        body: Self::Output,
+        concurrent: bool,
+        span: SourceSpan,
+    ) -> Self::Output {
+        self.0.rewrite_let(bindings, body, concurrent, span)
+    }
+
+    fn fold_new(&mut self, decls: &[NameDecl<'ast>], proc: Self::Output, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_new(decls, proc, span)
+    }
+
+    fn fold_contract(&mut self, name: Self::Output, formals: &Names<'ast>, body: Self::Output, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_contract(name, formals, body, span)
+    }
+
+    fn fold_send_sync(
+        &mut self,
+        channel: Self::Output,
+        messages: Vec<Self::Output>,
+        cont: Option<Self::Output>,
+        span: SourceSpan,
+    ) -> Self::Output {
+        self.0.rewrite_send_sync(channel, messages, cont, span)
+    }
+
+    fn fold_eval(&mut self, name: Self::Output, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_eval(name, span)
+    }
+
+    fn fold_quote(&mut self, proc: Self::Output, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_quote(proc, span)
+    }
+
+    fn fold_method(&mut self, receiver: Self::Output, name: Id<'ast>, args: Vec<Self::Output>, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_method(receiver, name, args, span)
+    }
+
+    fn fold_unary_exp(&mut self, op: UnaryExpOp, arg: Self::Output, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_unary_exp(op, arg, span)
+    }
+
+    fn fold_binary_exp(&mut self, op: BinaryExpOp, left: Self::Output, right: Self::Output, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_binary_exp(op, left, right, span)
+    }
+
+    fn fold_collection(&mut self, collection: FoldedCollection<'ast, Self::Output>, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_collection(collection, span)
+    }
+
+    fn fold_error(&mut self, partial: Option<Self::Output>, recovered_children: Vec<Self::Output>, span: SourceSpan) -> Self::Output {
+        self.0.rewrite_error(partial, recovered_children, span)
+    }
+}
+
+/// Rewrite `proc` by driving `rewriter` over it; a convenience so callers
+/// don't need `Rewriter::rewrite`'s `where Self: Sized` in scope.
+pub(super) fn rewrite<'ast>(rewriter: &mut impl Rewriter<'ast>, proc: &AnnProc<'ast>) -> AnnProc<'ast> {
+    rewriter.rewrite(proc)
+}
+
+/// Fold constant integer arithmetic (`+`, `-`, `*`) and literal negation down
+/// to their `LongLiteral` result, leaving everything else untouched. The
+/// first concrete [`Rewriter`] in the crate: only `rewrite_unary_exp` and
+/// `rewrite_binary_exp` need overriding, with every other node rebuilt
+/// unchanged by [`Rewriter`]'s defaults. Folding is checked, so overflow
+/// (e.g. `i64::MAX + 1`) is left as an ordinary `BinaryExp` rather than
+/// wrapping.
+pub(super) fn const_fold<'ast>(ast_builder: &'ast ASTBuilder<'ast>, proc: &AnnProc<'ast>) -> AnnProc<'ast> {
+    rewrite(&mut ConstFold { ast_builder }, proc)
+}
+
+struct ConstFold<'ast> {
+    ast_builder: &'ast ASTBuilder<'ast>,
+}
+
+impl<'ast> Rewriter<'ast> for ConstFold<'ast> {
+    fn ast_builder(&self) -> &'ast ASTBuilder<'ast> {
+        self.ast_builder
+    }
+
+    fn rewrite_unary_exp(&mut self, op: UnaryExpOp, arg: AnnProc<'ast>, span: SourceSpan) -> AnnProc<'ast> {
+        if let (UnaryExpOp::Neg, Proc::LongLiteral(value)) = (op, arg.proc) {
+            if let Some(folded) = value.checked_neg() {
+                return AnnProc { proc: self.ast_builder().alloc_long_literal(folded), span };
+            }
+        }
+        AnnProc { proc: self.ast_builder().alloc_unary_exp(op, arg.proc), span }
+    }
+
+    fn rewrite_binary_exp(&mut self, op: BinaryExpOp, left: AnnProc<'ast>, right: AnnProc<'ast>, span: SourceSpan) -> AnnProc<'ast> {
+        if let (Proc::LongLiteral(l), Proc::LongLiteral(r)) = (left.proc, right.proc) {
+            let folded = match op {
+                BinaryExpOp::Add => l.checked_add(*r),
+                BinaryExpOp::Sub => l.checked_sub(*r),
+                BinaryExpOp::Mult => l.checked_mul(*r),
+                _ => None,
+            };
+            if let Some(value) = folded {
+                return AnnProc { proc: self.ast_builder().alloc_long_literal(value), span };
+            }
+        }
+        AnnProc { proc: self.ast_builder().alloc_binary_exp(op, left, right), span }
+    }
+}
@@ -0,0 +1,492 @@
+//! Free-name and unused-binder analysis over a parsed [`AnnProc`].
+//!
+//! [`analyze_scopes`] walks the tree once, threading a stack of lexical
+//! scopes top-down (the same shape as [`crate::parser::alpha_eq`]'s `Env`,
+//! but resolving by name text instead of assigning De Bruijn levels) and a
+//! [`BitSet`] of which binders have been used, indexed by a dense index
+//! handed out the moment each binder's scope opens. A use that resolves
+//! against some enclosing scope sets that binder's bit live; a use that
+//! resolves against nothing is reported immediately as
+//! [`ScopeDiagnostic::Unbound`]. When a scope closes, every binder it
+//! introduced whose bit never went live is reported as
+//! [`ScopeDiagnostic::Unused`].
+//!
+//! The four binder positions are the same ones [`crate::parser::alpha_eq`]
+//! and [`crate::parser::fold::free_names`] already know about — `New`'s
+//! `NameDecl`s, `ForComprehension`'s `Bind` patterns, `Contract`'s formals,
+//! and `Let`'s bindings — plus one more: a `match` case's pattern, where
+//! every bare `ProcVar` introduces a fresh binder over that case's body
+//! and every `VarRef` (`=x`) is instead a use of a name already bound in
+//! an enclosing scope. `Select`'s patterns reuse `Source`/`Names` just like
+//! a `for`, so they fall out of the same handling as `ForComprehension`.
+//!
+//! Same approximation as [`crate::parser::alpha_eq`]: a `for`'s binds are
+//! treated as one simultaneous group spanning the whole `for`, even across
+//! `;`-separated receipts, and every receipt's source is resolved before
+//! any of the `for`'s own binders are in scope. So a later receipt's source
+//! referencing an earlier receipt's bound name reads as free (or as a use of
+//! an enclosing binder of the same name) rather than as a use of that
+//! binder — the one shape this gets wrong.
+
+use crate::ast::{
+    AnnName, AnnProc, Bind, Branch, Case, Collection, Id, LetBinding, Name, NameDecl, Names, Proc,
+    Receipts, Source, SyncSendCont, Var,
+};
+use crate::parser::fold::names_bound_ids;
+use crate::SourceSpan;
+
+/// One scope-analysis finding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeDiagnostic<'ast> {
+    /// A `var`/`var_ref` (or bare name used as a channel) with no enclosing
+    /// binder for its `Id`.
+    Unbound { id: Id<'ast>, span: SourceSpan },
+    /// A `new`/`contract`/`for`/`let`/`match`-case binder whose scope closed
+    /// without any use of it ever being observed.
+    Unused { id: Id<'ast>, declared_at: SourceSpan },
+}
+
+/// A growable bitset over dense binder indices, one `u64` word at a time.
+#[derive(Default)]
+struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    fn word_index(index: u32) -> usize {
+        (index / 64) as usize
+    }
+
+    fn insert(&mut self, index: u32) {
+        let word = Self::word_index(index);
+        if self.words.len() <= word {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= 1u64 << (index % 64);
+    }
+
+    fn contains(&self, index: u32) -> bool {
+        let word = Self::word_index(index);
+        word < self.words.len() && self.words[word] & (1u64 << (index % 64)) != 0
+    }
+}
+
+/// A zero-width span at `id`'s position — `Id` only carries a point, not a
+/// range, so this is the best "declared here" span available for it.
+///
+/// Shared with [`crate::parser::resolve`], which needs the same "declared
+/// here" span for the same binder positions.
+pub(super) fn id_span(id: &Id) -> SourceSpan {
+    SourceSpan { start: id.pos, end: id.pos }
+}
+
+/// One binder active in the current scope stack.
+struct BinderEntry<'ast> {
+    name: &'ast str,
+    index: u32,
+    id: Id<'ast>,
+    declared_at: SourceSpan,
+}
+
+type Env<'ast> = Vec<Vec<BinderEntry<'ast>>>;
+
+struct Analysis<'ast> {
+    live: BitSet,
+    next_index: u32,
+    diagnostics: Vec<ScopeDiagnostic<'ast>>,
+}
+
+impl<'ast> Analysis<'ast> {
+    fn resolve(env: &Env<'ast>, name: &str) -> Option<u32> {
+        env.iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .find(|entry| entry.name == name)
+            .map(|entry| entry.index)
+    }
+
+    fn use_name(&mut self, env: &Env<'ast>, id: Id<'ast>, span: SourceSpan) {
+        match Self::resolve(env, id.name) {
+            Some(index) => self.live.insert(index),
+            None => self.diagnostics.push(ScopeDiagnostic::Unbound { id, span }),
+        }
+    }
+
+    /// Hand out fresh indices for `binders` and return them as a scope ready
+    /// to push onto the env.
+    fn open_scope(&mut self, binders: Vec<(Id<'ast>, SourceSpan)>) -> Vec<BinderEntry<'ast>> {
+        binders
+            .into_iter()
+            .map(|(id, declared_at)| {
+                let index = self.next_index;
+                self.next_index += 1;
+                BinderEntry { name: id.name, index, id, declared_at }
+            })
+            .collect()
+    }
+
+    /// Report every binder in `scope` whose bit never went live.
+    fn close_scope(&mut self, scope: Vec<BinderEntry<'ast>>) {
+        for entry in scope {
+            if !self.live.contains(entry.index) {
+                self.diagnostics
+                    .push(ScopeDiagnostic::Unused { id: entry.id, declared_at: entry.declared_at });
+            }
+        }
+    }
+}
+
+fn names_binders<'ast>(names: &Names<'ast>) -> Vec<(Id<'ast>, SourceSpan)> {
+    names_bound_ids(names).into_iter().map(|id| (id, id_span(&id))).collect()
+}
+
+fn walk_proc<'ast>(analysis: &mut Analysis<'ast>, env: &mut Env<'ast>, ann: &AnnProc<'ast>) {
+    match ann.proc {
+        Proc::Nil
+        | Proc::BoolLiteral(_)
+        | Proc::LongLiteral(_)
+        | Proc::StringLiteral(_)
+        | Proc::UriLiteral(_)
+        | Proc::SimpleType(_)
+        | Proc::Bad
+        | Proc::ProcVar(Var::Wildcard) => {}
+
+        Proc::ProcVar(Var::Id(id)) => analysis.use_name(env, *id, ann.span),
+        Proc::VarRef { var, .. } => analysis.use_name(env, *var, ann.span),
+
+        Proc::Par { left, right } => {
+            walk_proc(analysis, env, left);
+            walk_proc(analysis, env, right);
+        }
+        Proc::IfThenElse { condition, if_true, if_false } => {
+            walk_proc(analysis, env, condition);
+            walk_proc(analysis, env, if_true);
+            if let Some(if_false) = if_false {
+                walk_proc(analysis, env, if_false);
+            }
+        }
+        Proc::Send { channel, inputs, .. } => {
+            walk_name(analysis, env, channel);
+            for input in inputs.iter() {
+                walk_proc(analysis, env, input);
+            }
+        }
+        Proc::ForComprehension { receipts, proc } => walk_for(analysis, env, receipts, proc),
+        Proc::Match { expression, cases } => {
+            walk_proc(analysis, env, expression);
+            for case in cases.iter() {
+                walk_match_case(analysis, env, case);
+            }
+        }
+        Proc::Select { branches } => {
+            for branch in branches.iter() {
+                walk_select_branch(analysis, env, branch);
+            }
+        }
+        Proc::Bundle { proc, .. } => walk_proc(analysis, env, proc),
+        Proc::Let { bindings, body, concurrent } => walk_let(analysis, env, bindings, *concurrent, body),
+        Proc::New { decls, proc } => walk_new(analysis, env, decls, proc),
+        Proc::Contract { name, formals, body } => walk_contract(analysis, env, name, formals, body),
+        Proc::SendSync { channel, messages, cont } => {
+            walk_name(analysis, env, channel);
+            for message in messages.iter() {
+                walk_proc(analysis, env, message);
+            }
+            if let SyncSendCont::NonEmpty(cont) = cont {
+                walk_proc(analysis, env, cont);
+            }
+        }
+        Proc::Eval { name } => walk_name(analysis, env, name),
+        Proc::Quote { proc } => walk_proc(analysis, env, &AnnProc { proc, span: ann.span }),
+        Proc::Method { receiver, args, .. } => {
+            walk_proc(analysis, env, receiver);
+            for arg in args.iter() {
+                walk_proc(analysis, env, arg);
+            }
+        }
+        Proc::UnaryExp { arg, .. } => walk_proc(analysis, env, &AnnProc { proc: arg, span: ann.span }),
+        Proc::BinaryExp { left, right, .. } => {
+            walk_proc(analysis, env, left);
+            walk_proc(analysis, env, right);
+        }
+        Proc::Collection(collection) => walk_collection(analysis, env, collection),
+        Proc::Error { partial, recovered_children } => {
+            if let Some(partial) = partial {
+                walk_proc(analysis, env, partial);
+            }
+            for child in recovered_children.iter() {
+                walk_proc(analysis, env, child);
+            }
+        }
+    }
+}
+
+fn walk_name<'ast>(analysis: &mut Analysis<'ast>, env: &mut Env<'ast>, name: &AnnName<'ast>) {
+    match name.name {
+        Name::ProcVar(Var::Id(id)) => analysis.use_name(env, id, name.span),
+        Name::ProcVar(Var::Wildcard) => {}
+        Name::Quote(proc) => walk_proc(analysis, env, &AnnProc { proc, span: name.span }),
+    }
+}
+
+fn walk_collection<'ast>(analysis: &mut Analysis<'ast>, env: &mut Env<'ast>, collection: &Collection<'ast>) {
+    match collection {
+        Collection::List { elements, .. } | Collection::Set { elements, .. } => {
+            for element in elements {
+                walk_proc(analysis, env, element);
+            }
+        }
+        Collection::Tuple(elements) => {
+            for element in elements {
+                walk_proc(analysis, env, element);
+            }
+        }
+        Collection::Map { elements, .. } => {
+            for (key, value) in elements {
+                walk_proc(analysis, env, key);
+                walk_proc(analysis, env, value);
+            }
+        }
+    }
+    // A remainder (`...@rest`) only ever occurs in a *pattern* position
+    // (`for`/`select`/`let`/collection-destructuring binders), never as a
+    // plain expression — `walk_match_case`/`collect_pattern` binds it there.
+}
+
+fn walk_source<'ast>(analysis: &mut Analysis<'ast>, env: &mut Env<'ast>, source: &Source<'ast>) {
+    match source {
+        Source::Simple { name } | Source::ReceiveSend { name } => walk_name(analysis, env, name),
+        Source::SendReceive { name, inputs } => {
+            walk_name(analysis, env, name);
+            for input in inputs.iter() {
+                walk_proc(analysis, env, input);
+            }
+        }
+    }
+}
+
+fn bind_binders<'ast>(bind: &Bind<'ast>) -> Vec<(Id<'ast>, SourceSpan)> {
+    let lhs = match bind {
+        Bind::Linear { lhs, .. } | Bind::Repeated { lhs, .. } | Bind::Peek { lhs, .. } => lhs,
+    };
+    names_binders(lhs)
+}
+
+fn walk_bind_rhs<'ast>(analysis: &mut Analysis<'ast>, env: &mut Env<'ast>, bind: &Bind<'ast>) {
+    match bind {
+        Bind::Linear { rhs, .. } => walk_source(analysis, env, rhs),
+        Bind::Repeated { rhs, .. } | Bind::Peek { rhs, .. } => walk_name(analysis, env, rhs),
+    }
+}
+
+fn walk_for<'ast>(
+    analysis: &mut Analysis<'ast>,
+    env: &mut Env<'ast>,
+    receipts: &Receipts<'ast>,
+    proc: &AnnProc<'ast>,
+) {
+    let mut binders = Vec::new();
+    for receipt in receipts.iter() {
+        for bind in receipt.binds.iter() {
+            walk_bind_rhs(analysis, env, bind);
+            binders.extend(bind_binders(bind));
+        }
+    }
+    let scope = analysis.open_scope(binders);
+    env.push(scope);
+    walk_proc(analysis, env, proc);
+    let scope = env.pop().expect("walk_for: pushed exactly one scope above");
+    analysis.close_scope(scope);
+}
+
+fn walk_select_branch<'ast>(analysis: &mut Analysis<'ast>, env: &mut Env<'ast>, branch: &Branch<'ast>) {
+    let mut binders = Vec::new();
+    for pattern in branch.patterns.iter() {
+        walk_source(analysis, env, &pattern.rhs);
+        binders.extend(names_binders(&pattern.lhs));
+    }
+    let scope = analysis.open_scope(binders);
+    env.push(scope);
+    walk_proc(analysis, env, &branch.proc);
+    let scope = env.pop().expect("walk_select_branch: pushed exactly one scope above");
+    analysis.close_scope(scope);
+}
+
+fn walk_new<'ast>(
+    analysis: &mut Analysis<'ast>,
+    env: &mut Env<'ast>,
+    decls: &[NameDecl<'ast>],
+    proc: &AnnProc<'ast>,
+) {
+    let binders = decls.iter().map(|decl| (decl.id, id_span(&decl.id))).collect();
+    let scope = analysis.open_scope(binders);
+    env.push(scope);
+    walk_proc(analysis, env, proc);
+    let scope = env.pop().expect("walk_new: pushed exactly one scope above");
+    analysis.close_scope(scope);
+}
+
+fn walk_contract<'ast>(
+    analysis: &mut Analysis<'ast>,
+    env: &mut Env<'ast>,
+    name: &AnnName<'ast>,
+    formals: &Names<'ast>,
+    body: &AnnProc<'ast>,
+) {
+    // The contract's own name is a use of a channel bound somewhere
+    // enclosing it (often by `new`), not a binder itself.
+    walk_name(analysis, env, name);
+    let scope = analysis.open_scope(names_binders(formals));
+    env.push(scope);
+    walk_proc(analysis, env, body);
+    let scope = env.pop().expect("walk_contract: pushed exactly one scope above");
+    analysis.close_scope(scope);
+}
+
+fn let_binding_binders<'ast>(binding: &LetBinding<'ast>) -> Vec<(Id<'ast>, SourceSpan)> {
+    match binding {
+        LetBinding::Single { lhs, .. } => match lhs.name {
+            Name::ProcVar(Var::Id(id)) => vec![(id, id_span(&id))],
+            _ => Vec::new(),
+        },
+        LetBinding::Multiple { lhs, .. } => match lhs {
+            Var::Id(id) => vec![(*id, id_span(id))],
+            Var::Wildcard => Vec::new(),
+        },
+    }
+}
+
+fn walk_let_rhs<'ast>(analysis: &mut Analysis<'ast>, env: &mut Env<'ast>, binding: &LetBinding<'ast>) {
+    match binding {
+        LetBinding::Single { rhs, .. } => walk_proc(analysis, env, rhs),
+        LetBinding::Multiple { rhs, .. } => {
+            for r in rhs.iter() {
+                walk_proc(analysis, env, r);
+            }
+        }
+    }
+}
+
+fn walk_let<'ast>(
+    analysis: &mut Analysis<'ast>,
+    env: &mut Env<'ast>,
+    bindings: &[LetBinding<'ast>],
+    concurrent: bool,
+    body: &AnnProc<'ast>,
+) {
+    if concurrent {
+        // `let x <- a & y <- b in ...`: every rhs is evaluated before any of
+        // this let's own names come into scope.
+        for binding in bindings {
+            walk_let_rhs(analysis, env, binding);
+        }
+        let binders = bindings.iter().flat_map(let_binding_binders).collect();
+        let scope = analysis.open_scope(binders);
+        env.push(scope);
+        walk_proc(analysis, env, body);
+        let scope = env.pop().expect("walk_let: pushed exactly one scope above");
+        analysis.close_scope(scope);
+    } else {
+        // `let x <- a; y <- x in ...`: each rhs can see the bindings
+        // introduced by the ones before it, so push one scope per binding.
+        let mut depth = 0;
+        for binding in bindings {
+            walk_let_rhs(analysis, env, binding);
+            let scope = analysis.open_scope(let_binding_binders(binding));
+            env.push(scope);
+            depth += 1;
+        }
+        walk_proc(analysis, env, body);
+        for _ in 0..depth {
+            let scope = env.pop().expect("walk_let: pushed one scope per binding above");
+            analysis.close_scope(scope);
+        }
+    }
+}
+
+/// Split a `match` case pattern into the binders it introduces (bare
+/// `ProcVar`s) and the uses it makes of names already bound in an
+/// enclosing scope (`=x`/`VarRef`).
+///
+/// The grammar lets a pattern be any `proc`, but in practice only bare
+/// vars, quotes, `par`, and collections introduce binders; anything more
+/// exotic (a method call, an arithmetic expression) is treated as opaque
+/// rather than growing this match to cover every constructor it could
+/// never actually contain a new binder under.
+///
+/// Shared with [`crate::parser::resolve`], which splits the same case
+/// patterns into binders vs. uses for its own purposes.
+pub(super) fn collect_pattern<'ast>(
+    ann: &AnnProc<'ast>,
+    binders: &mut Vec<(Id<'ast>, SourceSpan)>,
+    uses: &mut Vec<(Id<'ast>, SourceSpan)>,
+) {
+    match ann.proc {
+        Proc::ProcVar(Var::Id(id)) => binders.push((*id, ann.span)),
+        Proc::VarRef { var, .. } => uses.push((*var, ann.span)),
+        Proc::Par { left, right } => {
+            collect_pattern(left, binders, uses);
+            collect_pattern(right, binders, uses);
+        }
+        Proc::Quote { proc } => collect_pattern(&AnnProc { proc, span: ann.span }, binders, uses),
+        Proc::Collection(collection) => collect_pattern_collection(collection, binders, uses),
+        _ => {}
+    }
+}
+
+pub(super) fn collect_pattern_collection<'ast>(
+    collection: &Collection<'ast>,
+    binders: &mut Vec<(Id<'ast>, SourceSpan)>,
+    uses: &mut Vec<(Id<'ast>, SourceSpan)>,
+) {
+    match collection {
+        Collection::List { elements, remainder } | Collection::Set { elements, remainder } => {
+            for element in elements {
+                collect_pattern(element, binders, uses);
+            }
+            if let Some(Var::Id(id)) = remainder {
+                binders.push((*id, id_span(id)));
+            }
+        }
+        Collection::Tuple(elements) => {
+            for element in elements {
+                collect_pattern(element, binders, uses);
+            }
+        }
+        Collection::Map { elements, remainder } => {
+            for (key, value) in elements {
+                collect_pattern(key, binders, uses);
+                collect_pattern(value, binders, uses);
+            }
+            if let Some(Var::Id(id)) = remainder {
+                binders.push((*id, id_span(id)));
+            }
+        }
+    }
+}
+
+fn walk_match_case<'ast>(analysis: &mut Analysis<'ast>, env: &mut Env<'ast>, case: &Case<'ast>) {
+    let mut binders = Vec::new();
+    let mut uses = Vec::new();
+    collect_pattern(&case.pattern, &mut binders, &mut uses);
+    for (id, span) in uses {
+        analysis.use_name(env, id, span);
+    }
+    let scope = analysis.open_scope(binders);
+    env.push(scope);
+    walk_proc(analysis, env, &case.proc);
+    let scope = env.pop().expect("walk_match_case: pushed exactly one scope above");
+    analysis.close_scope(scope);
+}
+
+/// Compute free-name and unused-binder diagnostics for `proc`: every
+/// `var`/`var_ref`/bare-name-as-channel with no enclosing binder is
+/// reported as [`ScopeDiagnostic::Unbound`], and every `new`/`contract`/
+/// `for`/`let`/`match`-case binder never referenced inside its scope is
+/// reported as [`ScopeDiagnostic::Unused`].
+pub fn analyze_scopes<'ast>(proc: &AnnProc<'ast>) -> Vec<ScopeDiagnostic<'ast>> {
+    let mut analysis = Analysis { live: BitSet::default(), next_index: 0, diagnostics: Vec::new() };
+    let mut env: Env<'ast> = Vec::new();
+    walk_proc(&mut analysis, &mut env, proc);
+    analysis.diagnostics
+}
@@ -0,0 +1,72 @@
+//! A flat token stream, separate from the full parse/AST pipeline -- for an
+//! editor/LSP front-end that wants syntax highlighting or incremental
+//! relexing without paying for [`super::RholangParser::parse`]'s AST
+//! construction, and for tests that want to assert on lexical errors without
+//! also asserting on grammar shape.
+//!
+//! Tree-sitter itself has no separate lexer stage a caller can drive on its
+//! own, so [`tokenize`] gets the same tree [`super::parsing::parse_to_tree`]
+//! would build and reads it two ways: every leaf node (no children) in
+//! source order becomes a [`Token`], while every leaf `ERROR` node --
+//! tree-sitter's signal that its scanner couldn't match any token starting
+//! there, e.g. an unterminated string or a stray character -- becomes a
+//! [`LexError`]. An `ERROR` node with children (input skipped by grammar-level
+//! recovery) is not itself a `LexError`: its leaves are reported as tokens
+//! like any others. `MISSING` nodes are
+//! a grammar-level concern (a construct the parser expected but the lexer
+//! never even tried to produce) and are left to [`super::errors::Diagnostic`].
+
+use std::ops::Range;
+
+use super::parsing;
+
+/// A single terminal symbol from the grammar: its kind (e.g. `"send"`'s `"!"`,
+/// or a `var`'s identifier text) and its byte range in the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: &'static str,
+    pub range: Range<usize>,
+}
+
+/// A span of source tree-sitter's scanner couldn't match against any token in
+/// the grammar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexError {
+    pub range: Range<usize>,
+}
+
+/// Lex `code` into its flat token stream, plus any spans the scanner
+/// couldn't match against any token in the grammar
+pub fn tokenize(code: &str) -> (Vec<Token>, Vec<LexError>) {
+    let tree = parsing::parse_to_tree(code);
+
+    let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+    collect(&tree.root_node(), &mut tokens, &mut errors);
+
+    (tokens, errors)
+}
+
+fn collect(node: &tree_sitter::Node, tokens: &mut Vec<Token>, errors: &mut Vec<LexError>) {
+    if node.is_error() && node.child_count() == 0 {
+        errors.push(LexError {
+            range: node.byte_range(),
+        });
+        return;
+    }
+
+    if node.child_count() == 0 {
+        if !node.is_missing() {
+            tokens.push(Token {
+                kind: node.kind(),
+                range: node.byte_range(),
+            });
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect(&child, tokens, errors);
+    }
+}
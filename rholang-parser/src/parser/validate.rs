@@ -0,0 +1,260 @@
+//! A structural validation pass over a parsed [`AnnProc`], catching shapes
+//! the grammar accepts but that can never be meaningful: a `let` group whose
+//! concurrent bindings shadow each other before any of them come into
+//! scope, and a collection-indexing method call whose literal index can
+//! already be seen to fall outside a literal receiver's length.
+//!
+//! [`validate`] walks the whole tree once, in the same structural-recursion
+//! style as [`crate::parser::scope::analyze_scopes`], collecting every
+//! [`ValidationDiagnostic`] it finds rather than stopping at the first one.
+//! Processes held inside a name are not visited: a process quoted as a
+//! send's or contract's channel, an eval's target, a contract's formals, a
+//! `for`/`select` bind's pattern or source, or a `let` binding's left-hand
+//! side is not checked.
+//!
+//! Two of the checks this request asked for are deliberately not here. A
+//! `contract`/`for` formal-arity-vs-its-uses check would need to know, for
+//! every channel a contract's name might alias to, how many arguments each
+//! `send` on it passes — that's a dataflow question about an untyped,
+//! unbounded channel graph, not a structural property of one parse tree.
+//! And a `send_sync` used "where a plain `send` was expected" has no
+//! grammar-level counterpart to flag: `!` and `!?` are distinct productions,
+//! so there's no tree shape where one could be mistaken for the other after
+//! parsing — by the time an `AnnProc` exists, that distinction has already
+//! been made correctly.
+
+use nonempty_collections::NEVec;
+use validated::Validated;
+
+use crate::ast::{AnnProc, Collection, Id, LetBinding, Name, Proc, SyncSendCont, Var};
+use crate::SourceSpan;
+
+/// One validation finding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationDiagnostic<'ast> {
+    /// Two bindings in the same concurrent `let` group (`let x <- a & x <- b
+    /// in ...`) declare the same name, so it's ambiguous which one the body
+    /// sees once both take effect together.
+    DuplicateLetBinder {
+        id: Id<'ast>,
+        first: SourceSpan,
+        second: SourceSpan,
+    },
+    /// A `.nth(i)` call whose receiver is a literal list/tuple and whose
+    /// index is a literal out of that collection's range.
+    IndexOutOfRange {
+        span: SourceSpan,
+        index: i64,
+        len: usize,
+    },
+}
+
+impl std::fmt::Display for ValidationDiagnostic<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationDiagnostic::DuplicateLetBinder { id, first, second } => write!(
+                f,
+                "{id} bound again at {second} in this concurrent let, first bound at {first}"
+            ),
+            ValidationDiagnostic::IndexOutOfRange { index, len, .. } => {
+                write!(f, "index {index} out of range, collection has size {len}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationDiagnostic<'_> {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationFailure<'ast> {
+    pub partial_tree: AnnProc<'ast>,
+    pub errors: NEVec<ValidationDiagnostic<'ast>>,
+}
+
+fn let_binding_binder<'ast>(binding: &LetBinding<'ast>) -> Option<(Id<'ast>, SourceSpan)> {
+    match binding {
+        LetBinding::Single { lhs, .. } => match lhs.name {
+            Name::ProcVar(Var::Id(id)) => Some((id, lhs.span)),
+            _ => None,
+        },
+        LetBinding::Multiple { lhs, .. } => match lhs {
+            Var::Id(id) => Some((*id, SourceSpan { start: id.pos, end: id.pos })),
+            Var::Wildcard => None,
+        },
+    }
+}
+
+fn walk_let<'ast>(diagnostics: &mut Vec<ValidationDiagnostic<'ast>>, bindings: &[LetBinding<'ast>], concurrent: bool) {
+    if !concurrent {
+        return;
+    }
+    let mut binders: Vec<(Id<'ast>, SourceSpan)> = bindings.iter().filter_map(let_binding_binder).collect();
+    binders.sort_unstable_by_key(|(id, _)| *id);
+    for duplicate in binders.windows(2).filter(|w| w[0].0 == w[1].0) {
+        let (id, mut first) = duplicate[0];
+        let (_, mut second) = duplicate[1];
+        if second.start < first.start {
+            std::mem::swap(&mut first, &mut second);
+        }
+        diagnostics.push(ValidationDiagnostic::DuplicateLetBinder { id, first, second });
+    }
+}
+
+fn collection_len(collection: &Collection<'_>) -> Option<usize> {
+    match collection {
+        Collection::List { elements, remainder: None } => Some(elements.len()),
+        Collection::Tuple(elements) => Some(elements.len()),
+        // A set/map's `nth` (if it even had one) wouldn't index by position,
+        // and a remainder (`...@rest`) means the literal's full length isn't
+        // known until runtime, so neither is checkable here.
+        Collection::Set { .. } | Collection::Map { .. } | Collection::List { remainder: Some(_), .. } => None,
+    }
+}
+
+fn walk_method<'ast>(
+    diagnostics: &mut Vec<ValidationDiagnostic<'ast>>,
+    ann: &AnnProc<'ast>,
+    receiver: &AnnProc<'ast>,
+    name: &Id<'ast>,
+    args: &[AnnProc<'ast>],
+) {
+    if name.name != "nth" {
+        return;
+    }
+    let (Proc::Collection(collection), [AnnProc { proc: Proc::LongLiteral(index), .. }]) =
+        (receiver.proc, args)
+    else {
+        return;
+    };
+    let Some(len) = collection_len(collection) else {
+        return;
+    };
+    if *index < 0 || *index as usize >= len {
+        diagnostics.push(ValidationDiagnostic::IndexOutOfRange {
+            span: ann.span,
+            index: *index,
+            len,
+        });
+    }
+}
+
+fn walk_proc<'ast>(diagnostics: &mut Vec<ValidationDiagnostic<'ast>>, ann: &AnnProc<'ast>) {
+    match ann.proc {
+        Proc::Nil
+        | Proc::BoolLiteral(_)
+        | Proc::LongLiteral(_)
+        | Proc::StringLiteral(_)
+        | Proc::UriLiteral(_)
+        | Proc::SimpleType(_)
+        | Proc::ProcVar(_)
+        | Proc::VarRef { .. }
+        | Proc::Bad => {}
+
+        Proc::Par { left, right } | Proc::BinaryExp { left, right, .. } => {
+            walk_proc(diagnostics, left);
+            walk_proc(diagnostics, right);
+        }
+        Proc::IfThenElse { condition, if_true, if_false } => {
+            walk_proc(diagnostics, condition);
+            walk_proc(diagnostics, if_true);
+            if let Some(if_false) = if_false {
+                walk_proc(diagnostics, if_false);
+            }
+        }
+        Proc::Send { inputs, .. } => {
+            for input in inputs.iter() {
+                walk_proc(diagnostics, input);
+            }
+        }
+        Proc::ForComprehension { proc, .. } => walk_proc(diagnostics, proc),
+        Proc::Match { expression, cases } => {
+            walk_proc(diagnostics, expression);
+            for case in cases.iter() {
+                walk_proc(diagnostics, &case.pattern);
+                walk_proc(diagnostics, &case.proc);
+            }
+        }
+        Proc::Select { branches } => {
+            for branch in branches.iter() {
+                walk_proc(diagnostics, &branch.proc);
+            }
+        }
+        Proc::Bundle { proc, .. } => walk_proc(diagnostics, proc),
+        Proc::Let { bindings, body, concurrent } => {
+            walk_let(diagnostics, bindings, *concurrent);
+            for binding in bindings.iter() {
+                match binding {
+                    LetBinding::Single { rhs, .. } => walk_proc(diagnostics, rhs),
+                    LetBinding::Multiple { rhs, .. } => {
+                        for r in rhs.iter() {
+                            walk_proc(diagnostics, r);
+                        }
+                    }
+                }
+            }
+            walk_proc(diagnostics, body);
+        }
+        Proc::New { proc, .. } => walk_proc(diagnostics, proc),
+        Proc::Contract { body, .. } => walk_proc(diagnostics, body),
+        Proc::SendSync { messages, cont, .. } => {
+            for message in messages.iter() {
+                walk_proc(diagnostics, message);
+            }
+            if let SyncSendCont::NonEmpty(cont) = cont {
+                walk_proc(diagnostics, cont);
+            }
+        }
+        Proc::Eval { .. } => {}
+        Proc::Quote { proc } => walk_proc(diagnostics, &AnnProc { proc, span: ann.span }),
+        Proc::Method { receiver, name, args } => {
+            walk_proc(diagnostics, receiver);
+            for arg in args.iter() {
+                walk_proc(diagnostics, arg);
+            }
+            walk_method(diagnostics, ann, receiver, name, args);
+        }
+        Proc::UnaryExp { arg, .. } => walk_proc(diagnostics, &AnnProc { proc: arg, span: ann.span }),
+        Proc::Collection(collection) => walk_collection(diagnostics, collection),
+        Proc::Error { partial, recovered_children } => {
+            if let Some(partial) = partial {
+                walk_proc(diagnostics, partial);
+            }
+            for child in recovered_children.iter() {
+                walk_proc(diagnostics, child);
+            }
+        }
+    }
+}
+
+fn walk_collection<'ast>(diagnostics: &mut Vec<ValidationDiagnostic<'ast>>, collection: &Collection<'ast>) {
+    match collection {
+        Collection::List { elements, .. } | Collection::Set { elements, .. } => {
+            for element in elements {
+                walk_proc(diagnostics, element);
+            }
+        }
+        Collection::Tuple(elements) => {
+            for element in elements {
+                walk_proc(diagnostics, element);
+            }
+        }
+        Collection::Map { elements, .. } => {
+            for (key, value) in elements {
+                walk_proc(diagnostics, key);
+                walk_proc(diagnostics, value);
+            }
+        }
+    }
+}
+
+/// Run the structural checks described in the module doc comment over
+/// `proc`, returning it unchanged on success or a [`ValidationFailure`]
+/// carrying it back alongside every diagnostic found.
+pub fn validate(proc: AnnProc<'_>) -> Validated<AnnProc<'_>, ValidationFailure<'_>> {
+    let mut errors = Vec::new();
+    walk_proc(&mut errors, &proc);
+    match NEVec::try_from_vec(errors) {
+        Some(errors) => Validated::fail(ValidationFailure { partial_tree: proc, errors }),
+        None => Validated::Good(proc),
+    }
+}
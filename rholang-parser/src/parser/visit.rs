@@ -0,0 +1,233 @@
+//! A non-recursive visitor over a parsed [`AnnProc`], for consumers that
+//! want to fold, pretty-print, or collect metrics over it without risking a
+//! native stack overflow on the same deeply right-nested `par`/`for`/`new`
+//! chains that [`crate::parser::parsing::build_ast`] was written to handle
+//! iteratively in the first place.
+//!
+//! [`walk`] drives a [`Visitor`] with an explicit work stack of `Enter`/
+//! `Leave` frames, the same technique `build_ast`'s `apply_cont` uses for
+//! the reverse direction (tree → `AnnProc`): pushing a node's `Leave` frame
+//! before its children's `Enter` frames means the children are fully
+//! visited — in left-to-right order — before the node's own `leave` fires,
+//! with the work stack living on the heap rather than the native call stack.
+//!
+//! [`pre_order`] and [`post_order`] are small [`Visitor`]s that just record
+//! every node they're handed, for callers who want a flat traversal order
+//! without writing their own `Visitor` impl.
+
+use crate::ast::{AnnName, AnnProc, Bind, Collection, LetBinding, Name, Proc, Source, SyncSendCont};
+use crate::SourceSpan;
+
+/// Per-node callbacks for a [`walk`] over an `AnnProc` tree. Both methods
+/// default to doing nothing, so an implementor only needs to override
+/// whichever of `enter`/`leave` its traversal actually cares about.
+pub trait Visitor<'ast> {
+    /// Called the first time `walk` reaches `ann`, before any of its
+    /// children.
+    fn enter(&mut self, _ann: AnnProc<'ast>) {}
+
+    /// Called after every one of `ann`'s children has been fully visited
+    /// (i.e. had both its own `enter` and `leave` called).
+    fn leave(&mut self, _ann: AnnProc<'ast>) {}
+}
+
+/// The direct children of `ann`, left-to-right in the order they'd be
+/// evaluated. `Quote`/`UnaryExp` carry a bare `&Proc` rather than an
+/// `AnnProc`, so their single child is synthesized with `ann`'s own span,
+/// same as [`crate::parser::scope`]/[`crate::parser::validate`] do.
+///
+/// A bind/formal's *pattern* (the `lhs` of a `for`/`select`/`contract`) is
+/// not descended into: like [`crate::parser::scope`], this only cares about
+/// names actually used as a channel or value, not the shape of a pattern a
+/// received value gets matched against.
+fn children<'ast>(ann: AnnProc<'ast>) -> Vec<AnnProc<'ast>> {
+    match ann.proc {
+        Proc::Nil
+        | Proc::BoolLiteral(_)
+        | Proc::LongLiteral(_)
+        | Proc::StringLiteral(_)
+        | Proc::UriLiteral(_)
+        | Proc::SimpleType(_)
+        | Proc::ProcVar(_)
+        | Proc::VarRef { .. }
+        | Proc::Bad => Vec::new(),
+
+        Proc::Par { left, right } | Proc::BinaryExp { left, right, .. } => vec![*left, *right],
+        Proc::IfThenElse { condition, if_true, if_false } => {
+            let mut children = vec![*condition, *if_true];
+            if let Some(if_false) = if_false {
+                children.push(*if_false);
+            }
+            children
+        }
+        Proc::Send { channel, inputs, .. } => {
+            let mut children: Vec<_> = name_children(channel).into_iter().collect();
+            children.extend(inputs.iter().copied());
+            children
+        }
+        Proc::ForComprehension { receipts, proc } => {
+            let mut children: Vec<_> = receipts
+                .iter()
+                .flat_map(|receipt| receipt.binds.iter())
+                .flat_map(bind_children)
+                .collect();
+            children.push(*proc);
+            children
+        }
+        Proc::Match { expression, cases } => {
+            let mut children = vec![*expression];
+            for case in cases {
+                children.push(case.pattern);
+                children.push(case.proc);
+            }
+            children
+        }
+        Proc::Select { branches } => branches
+            .iter()
+            .flat_map(|branch| {
+                branch
+                    .patterns
+                    .iter()
+                    .flat_map(|pattern| source_children(&pattern.rhs))
+                    .chain(std::iter::once(branch.proc))
+            })
+            .collect(),
+        Proc::Bundle { proc, .. } => vec![*proc],
+        Proc::Let { bindings, body, .. } => {
+            let mut children = Vec::new();
+            for binding in bindings.iter() {
+                match binding {
+                    LetBinding::Single { rhs, .. } => children.push(*rhs),
+                    LetBinding::Multiple { rhs, .. } => children.extend(rhs.iter().copied()),
+                }
+            }
+            children.push(*body);
+            children
+        }
+        Proc::New { proc, .. } => vec![*proc],
+        Proc::Contract { name, body, .. } => {
+            let mut children: Vec<_> = name_children(name).into_iter().collect();
+            children.push(*body);
+            children
+        }
+        Proc::SendSync { channel, messages, cont } => {
+            let mut children: Vec<_> = name_children(channel).into_iter().collect();
+            children.extend(messages.iter().copied());
+            if let SyncSendCont::NonEmpty(cont) = cont {
+                children.push(*cont);
+            }
+            children
+        }
+        Proc::Eval { name } => name_children(name).into_iter().collect(),
+        Proc::Quote { proc } => vec![AnnProc { proc, span: ann.span }],
+        Proc::Method { receiver, args, .. } => {
+            let mut children = vec![*receiver];
+            children.extend(args.iter().copied());
+            children
+        }
+        Proc::UnaryExp { arg, .. } => vec![AnnProc { proc: arg, span: ann.span }],
+        Proc::Collection(collection) => collection_children(collection),
+        Proc::Error { partial, recovered_children } => {
+            let mut children = Vec::new();
+            if let Some(partial) = partial {
+                children.push(*partial);
+            }
+            children.extend(recovered_children.iter().copied());
+            children
+        }
+    }
+}
+
+/// `name`'s quoted process, if it has one (`Name::ProcVar` is just a bound
+/// identifier and has no `Proc` content of its own to descend into).
+fn name_children<'ast>(name: &AnnName<'ast>) -> Option<AnnProc<'ast>> {
+    match name.name {
+        Name::ProcVar(_) => None,
+        Name::Quote(proc) => Some(AnnProc { proc, span: name.span }),
+    }
+}
+
+fn source_children<'ast>(source: &Source<'ast>) -> Vec<AnnProc<'ast>> {
+    match source {
+        Source::Simple { name } | Source::ReceiveSend { name } => name_children(name).into_iter().collect(),
+        Source::SendReceive { name, inputs } => {
+            let mut children: Vec<_> = name_children(name).into_iter().collect();
+            children.extend(inputs.iter().copied());
+            children
+        }
+    }
+}
+
+fn bind_children<'ast>(bind: &Bind<'ast>) -> Vec<AnnProc<'ast>> {
+    match bind {
+        Bind::Linear { rhs, .. } => source_children(rhs),
+        Bind::Repeated { rhs, .. } | Bind::Peek { rhs, .. } => name_children(rhs).into_iter().collect(),
+    }
+}
+
+fn collection_children<'ast>(collection: &Collection<'ast>) -> Vec<AnnProc<'ast>> {
+    match collection {
+        Collection::List { elements, .. } | Collection::Set { elements, .. } => elements.clone(),
+        Collection::Tuple(elements) => elements.clone(),
+        Collection::Map { elements, .. } => elements.iter().flat_map(|(key, value)| [*key, *value]).collect(),
+    }
+}
+
+enum Frame<'ast> {
+    Enter(AnnProc<'ast>),
+    Leave(AnnProc<'ast>),
+}
+
+/// Drive `visitor` over `root`, calling `enter`/`leave` for every node in a
+/// depth-first, left-to-right traversal, using a heap-allocated work stack
+/// instead of native recursion.
+pub fn walk<'ast>(visitor: &mut impl Visitor<'ast>, root: AnnProc<'ast>) {
+    let mut stack = vec![Frame::Enter(root)];
+    while let Some(frame) = stack.pop() {
+        match frame {
+            Frame::Enter(ann) => {
+                visitor.enter(ann);
+                stack.push(Frame::Leave(ann));
+                for child in children(ann).into_iter().rev() {
+                    stack.push(Frame::Enter(child));
+                }
+            }
+            Frame::Leave(ann) => visitor.leave(ann),
+        }
+    }
+}
+
+struct Collect<'ast> {
+    on_enter: bool,
+    out: Vec<(AnnProc<'ast>, SourceSpan)>,
+}
+
+impl<'ast> Visitor<'ast> for Collect<'ast> {
+    fn enter(&mut self, ann: AnnProc<'ast>) {
+        if self.on_enter {
+            self.out.push((ann, ann.span));
+        }
+    }
+
+    fn leave(&mut self, ann: AnnProc<'ast>) {
+        if !self.on_enter {
+            self.out.push((ann, ann.span));
+        }
+    }
+}
+
+/// Every node under `root`, as `(node, node.span)` pairs, in pre-order (a
+/// node before any of its children).
+pub fn pre_order(root: AnnProc<'_>) -> Vec<(AnnProc<'_>, SourceSpan)> {
+    let mut collect = Collect { on_enter: true, out: Vec::new() };
+    walk(&mut collect, root);
+    collect.out
+}
+
+/// Every node under `root`, as `(node, node.span)` pairs, in post-order (a
+/// node after all of its children).
+pub fn post_order(root: AnnProc<'_>) -> Vec<(AnnProc<'_>, SourceSpan)> {
+    let mut collect = Collect { on_enter: false, out: Vec::new() };
+    walk(&mut collect, root);
+    collect.out
+}
@@ -0,0 +1,114 @@
+use anyhow::{anyhow, Result};
+use rholang_parser::parser::alpha_eq::{alpha_eq, alpha_hash};
+use rholang_parser::parser::RholangParser;
+use validated::Validated;
+
+fn parse_one<'a>(parser: &'a RholangParser<'a>, code: &'a str) -> Result<rholang_parser::ast::AnnProc<'a>> {
+    match parser.parse(code) {
+        Validated::Good(mut procs) => Ok(procs.remove(0)),
+        Validated::Fail(errors) => Err(anyhow!("parse failed: {errors:?}")),
+    }
+}
+
+#[test]
+fn test_alpha_eq_ignores_consistent_renaming_of_a_new_bound_name() -> Result<()> {
+    let parser = RholangParser::new();
+    let a = parse_one(&parser, "new x in { x!(1) }")?;
+    let b = parse_one(&parser, "new y in { y!(1) }")?;
+
+    assert!(alpha_eq(&a, &b));
+
+    Ok(())
+}
+
+#[test]
+fn test_alpha_eq_rejects_structurally_different_bodies() -> Result<()> {
+    let parser = RholangParser::new();
+    let a = parse_one(&parser, "new x in { x!(1) }")?;
+    let b = parse_one(&parser, "new x in { x!(2) }")?;
+
+    assert!(!alpha_eq(&a, &b));
+
+    Ok(())
+}
+
+#[test]
+fn test_alpha_eq_handles_shadowing_by_nesting_depth() -> Result<()> {
+    let parser = RholangParser::new();
+    // Both `x`s in `a` are bound to their own enclosing `new`, same as both
+    // `y`s in `b` -- so the shadowing lines up even though the names differ.
+    let a = parse_one(&parser, "new x in { new x in { x!(1) } }")?;
+    let b = parse_one(&parser, "new y in { new y in { y!(1) } }")?;
+
+    assert!(alpha_eq(&a, &b));
+
+    Ok(())
+}
+
+#[test]
+fn test_alpha_eq_distinguishes_simultaneous_from_sequential_let() -> Result<()> {
+    let parser = RholangParser::new();
+    let concurrent = parse_one(&parser, "let x <- 1 & y <- 2 in { Nil }")?;
+    let sequential = parse_one(&parser, "let x <- 1 ; y <- 2 in { Nil }")?;
+
+    assert!(!alpha_eq(&concurrent, &sequential));
+
+    Ok(())
+}
+
+#[test]
+fn test_alpha_hash_agrees_with_alpha_eq_on_renamed_terms() -> Result<()> {
+    let parser = RholangParser::new();
+    let a = parse_one(&parser, "new x in { x!(1) }")?;
+    let b = parse_one(&parser, "new y in { y!(1) }")?;
+
+    assert!(alpha_eq(&a, &b));
+    assert_eq!(alpha_hash(&a), alpha_hash(&b));
+
+    Ok(())
+}
+
+#[test]
+fn test_alpha_hash_differs_for_structurally_different_terms() -> Result<()> {
+    let parser = RholangParser::new();
+    let a = parse_one(&parser, "new x in { x!(1) }")?;
+    let b = parse_one(&parser, "new x in { x!(2) }")?;
+
+    assert_ne!(alpha_hash(&a), alpha_hash(&b));
+
+    Ok(())
+}
+
+#[test]
+fn test_alpha_eq_treats_par_as_commutative() -> Result<()> {
+    let parser = RholangParser::new();
+    let a = parse_one(&parser, "x!(1) | y!(2) | z!(3)")?;
+    let b = parse_one(&parser, "z!(3) | x!(1) | y!(2)")?;
+
+    assert!(alpha_eq(&a, &b));
+    assert_eq!(alpha_hash(&a), alpha_hash(&b));
+
+    Ok(())
+}
+
+#[test]
+fn test_alpha_eq_rejects_par_with_a_mismatched_component() -> Result<()> {
+    let parser = RholangParser::new();
+    let a = parse_one(&parser, "x!(1) | y!(2) | z!(3)")?;
+    let b = parse_one(&parser, "x!(1) | y!(2) | z!(4)")?;
+
+    assert!(!alpha_eq(&a, &b));
+
+    Ok(())
+}
+
+#[test]
+fn test_alpha_eq_compares_set_arity() -> Result<()> {
+    let parser = RholangParser::new();
+    let triple = parse_one(&parser, "Set(1, 2, 3)")?;
+    let pair = parse_one(&parser, "Set(1, 2)")?;
+
+    assert!(!alpha_eq(&triple, &pair));
+
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use anyhow::{anyhow, Result};
+use rholang_parser::parser::encoding::{encode, encode_normalized, DecodeError};
+use rholang_parser::parser::RholangParser;
+use validated::Validated;
+
+/// Parse `code`'s first top-level process, panicking with a readable message on
+/// any parse failure (none of these fixtures are expected to fail).
+fn parse_one<'a>(parser: &'a RholangParser<'a>, code: &'a str) -> rholang_parser::ast::AnnProc<'a> {
+    match parser.parse(code) {
+        Validated::Good(mut procs) => procs.remove(0),
+        Validated::Fail(errors) => panic!("unexpected parse failure for {code:?}: {errors:?}"),
+    }
+}
+
+#[test]
+fn test_round_trip_preserves_literal() -> Result<()> {
+    let parser = RholangParser::new();
+    let ast = parse_one(&parser, "42");
+
+    let bytes = encode(&ast);
+    match parser.decode(&bytes) {
+        Validated::Good(decoded) => assert_eq!(decoded, ast),
+        Validated::Fail(err) => return Err(anyhow!("decode failed: {err:?}")),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_round_trip_preserves_nested_structure() -> Result<()> {
+    let parser = RholangParser::new();
+    let ast = parse_one(
+        &parser,
+        "new channel in { channel!(\"Hello, world!\") | channel!(\"again\") }",
+    );
+
+    let bytes = encode(&ast);
+    match parser.decode(&bytes) {
+        Validated::Good(decoded) => assert_eq!(decoded, ast),
+        Validated::Fail(err) => return Err(anyhow!("decode failed: {err:?}")),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_normalized_encoding_drops_spans_but_keeps_structure() -> Result<()> {
+    let parser = RholangParser::new();
+    let ast = parse_one(&parser, "1 + 2 * 3");
+
+    let bytes = encode_normalized(&ast);
+    match parser.decode(&bytes) {
+        Validated::Good(decoded) => {
+            assert_eq!(decoded.proc, ast.proc);
+            assert_ne!(decoded.span, ast.span, "normalized spans should not be the real ones");
+        }
+        Validated::Fail(err) => return Err(anyhow!("decode failed: {err:?}")),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_normalized_encoding_is_stable_across_different_spans() -> Result<()> {
+    let parser = RholangParser::new();
+
+    // Same program, reindented so every span differs, should still produce
+    // byte-identical normalized encodings.
+    let a = parse_one(&parser, "1 + 2");
+    let b = parse_one(&parser, "  1 + 2  ");
+
+    assert_eq!(encode_normalized(&a), encode_normalized(&b));
+
+    Ok(())
+}
+
+#[test]
+fn test_decode_empty_input_is_unexpected_eof() {
+    let parser = RholangParser::new();
+
+    match parser.decode(&[]) {
+        Validated::Fail(err) => assert_eq!(err, DecodeError::UnexpectedEof),
+        Validated::Good(ast) => panic!("expected a decode error, got {ast:?}"),
+    }
+}
+
+#[test]
+fn test_decode_unknown_tag_is_rejected() -> Result<()> {
+    let parser = RholangParser::new();
+    let ast = parse_one(&parser, "Nil");
+
+    let mut bytes = encode(&ast);
+    // `Nil` encodes as `[[tag::NIL], span]`; corrupt the tag to one that doesn't
+    // exist. The array header and tag are each a single byte for small values,
+    // so the tag follows the outer and the constructor array headers.
+    assert_eq!(bytes[2], 0); // tag::NIL
+    bytes[2] = 99;
+
+    match parser.decode(&bytes) {
+        Validated::Fail(err) => assert_eq!(err, DecodeError::UnknownTag(99)),
+        Validated::Good(decoded) => panic!("expected a decode error, got {decoded:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_decode_rejects_trailing_bytes() -> Result<()> {
+    let parser = RholangParser::new();
+    let ast = parse_one(&parser, "Nil");
+
+    let mut bytes = encode(&ast);
+    bytes.push(0); // one extra CBOR item (an unsigned 0) after a complete AST
+
+    match parser.decode(&bytes) {
+        Validated::Fail(err) => assert_eq!(err, DecodeError::TrailingBytes),
+        Validated::Good(decoded) => panic!("expected a decode error, got {decoded:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_decode_rejects_huge_declared_length_instead_of_aborting() -> Result<()> {
+    let parser = RholangParser::new();
+    let ast = parse_one(&parser, "[1]");
+
+    let mut bytes = encode(&ast);
+    // Byte layout for `[1]`: `[0x82` outer `[proc, span]`, `0x82` COLLECTION
+    // ctor `[tag, collection]`, `0x06` tag::COLLECTION, `0x83` List's
+    // `[tag, elements, remainder]`, `0x00` collection_tag::LIST, `0x81`]` the
+    // one-byte element-count header (major type 4, value 1) we're about to
+    // corrupt.
+    assert_eq!(
+        &bytes[..6],
+        &[0x82, 0x82, 0x06, 0x83, 0x00, 0x81],
+        "unexpected encoding shape for `[1]`; update the corrupted offset below"
+    );
+
+    // Replace the one-element count with a declared `u64::MAX` and drop every
+    // byte that would have held the (claimed) elements. A decoder that does
+    // `Vec::with_capacity(count as usize)` straight off this header would
+    // attempt a multi-exabyte allocation and abort; it must instead cap the
+    // capacity against the handful of bytes actually left and report a
+    // structured `UnexpectedEof` once it tries to read the first element.
+    let mut corrupted = bytes[..5].to_vec();
+    corrupted.push(0x9b); // major 4 (array), additional info 27: u64 length follows
+    corrupted.extend_from_slice(&u64::MAX.to_be_bytes());
+
+    match parser.decode(&corrupted) {
+        Validated::Fail(err) => assert_eq!(err, DecodeError::UnexpectedEof),
+        Validated::Good(decoded) => panic!("expected a decode error, got {decoded:?}"),
+    }
+
+    Ok(())
+}
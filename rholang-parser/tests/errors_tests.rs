@@ -1,4 +1,4 @@
-use rholang_parser::errors::{ErrorKind, ParseResult, ParserError, SourcePosition};
+use rholang_parser::errors::{classify_incomplete, ErrorKind, ParseResult, ParserError, SourcePosition};
 
 #[test]
 fn test_source_position_display() {
@@ -91,6 +91,7 @@ fn test_parse_result_map() {
     match mapped {
         ParseResult::Success(value) => assert_eq!(value, 42),
         ParseResult::Error(_) => panic!("Expected success"),
+        ParseResult::Incomplete => panic!("Expected success, got incomplete"),
     }
 
     // Test map on error
@@ -146,3 +147,44 @@ fn test_parse_result_from_parser_error() {
 
     assert!(result.is_error());
 }
+
+#[test]
+fn test_parse_result_is_incomplete() {
+    let result: ParseResult<String> = ParseResult::Incomplete;
+    assert!(result.is_incomplete());
+    assert!(!result.is_success());
+    assert!(!result.is_error());
+}
+
+#[test]
+fn test_classify_incomplete_true_for_an_open_brace() {
+    assert!(classify_incomplete("new channel in { @\"stdout\"!(\"Hello\")"));
+}
+
+#[test]
+fn test_classify_incomplete_true_for_an_unclosed_string() {
+    assert!(classify_incomplete("@\"stdout\"!(\"Hello"));
+}
+
+#[test]
+fn test_classify_incomplete_true_for_an_unclosed_block_comment() {
+    assert!(classify_incomplete("new x in { /* still writing this"));
+}
+
+#[test]
+fn test_classify_incomplete_true_for_a_trailing_binary_operator() {
+    assert!(classify_incomplete("1 +"));
+}
+
+#[test]
+fn test_classify_incomplete_false_for_complete_code() {
+    assert!(!classify_incomplete(
+        "new channel in { @\"stdout\"!(\"Hello, world!\") }"
+    ));
+}
+
+#[test]
+fn test_classify_incomplete_false_for_a_stray_closing_bracket() {
+    // A real mismatch, not merely cut off -- more input won't fix this
+    assert!(!classify_incomplete("new x in { ) }"));
+}
@@ -0,0 +1,65 @@
+//! Property-based fuzzing over [`RholangParser::parse`]: no input should ever
+//! panic the parser, and reparsing the exact same source twice must yield an
+//! identical result (idempotence) -- the parser has no business being
+//! sensitive to anything but its input. Failing cases are minimized and
+//! persisted by `proptest` under `tests/proptest-regressions/fuzz_tests.txt`
+//! and replayed automatically on every subsequent run.
+
+use proptest::prelude::*;
+use rholang_parser::RholangParser;
+
+/// A handful of real Rholang tokens, so the fuzzer can also explore inputs
+/// that are much closer to valid programs than arbitrary UTF-8 is
+fn rholang_token() -> impl Strategy<Value = &'static str> {
+    prop_oneof![
+        Just("for"),
+        Just("!"),
+        Just("|"),
+        Just("@"),
+        Just("Nil"),
+        Just("new"),
+        Just("in"),
+        Just("{"),
+        Just("}"),
+        Just("("),
+        Just(")"),
+        Just("<-"),
+        Just("x"),
+        Just("y"),
+        Just("chan"),
+        Just("\"hello\""),
+        Just("42"),
+    ]
+}
+
+/// A space-joined sequence of 0..12 [`rholang_token`]s
+fn rholang_biased_source() -> impl Strategy<Value = String> {
+    proptest::collection::vec(rholang_token(), 0..12).prop_map(|tokens| tokens.join(" "))
+}
+
+/// Parsing `code` must not panic, and must produce the exact same result
+/// (including any errors) the second time around
+fn check_invariants(code: &str) {
+    let parser = RholangParser::new();
+    let first = parser.parse(code);
+    let second = parser.parse(code);
+    assert_eq!(
+        first, second,
+        "reparsing identical source produced a different result"
+    );
+}
+
+proptest! {
+    /// Arbitrary UTF-8 input must never panic the parser
+    #[test]
+    fn parser_never_panics_on_arbitrary_utf8(code in ".*") {
+        check_invariants(&code);
+    }
+
+    /// Grammar-biased input, built from real Rholang tokens, exercises the same
+    /// invariants over programs much more likely to parse, at least partially
+    #[test]
+    fn parser_never_panics_on_grammar_biased_input(code in rholang_biased_source()) {
+        check_invariants(&code);
+    }
+}
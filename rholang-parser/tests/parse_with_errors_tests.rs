@@ -0,0 +1,33 @@
+use rholang_parser::parser::RholangParser;
+
+#[test]
+fn test_parse_with_errors_returns_no_diagnostics_for_well_formed_source() {
+    let parser = RholangParser::new();
+    let code = "new channel in { channel!(\"Hello, world!\") }";
+
+    let (tree, diagnostics) = parser.parse_with_errors(code);
+
+    assert!(diagnostics.is_empty());
+    assert!(!tree.root_node().has_error());
+}
+
+#[test]
+fn test_parse_with_errors_reports_every_error_in_one_pass() {
+    let parser = RholangParser::new();
+    let code = "new x in { x!(1) } new y in { @@@ }";
+
+    let (tree, diagnostics) = parser.parse_with_errors(code);
+
+    assert!(tree.root_node().has_error());
+    assert!(!diagnostics.is_empty());
+}
+
+#[test]
+fn test_parse_with_errors_hands_back_the_recovered_tree() {
+    let parser = RholangParser::new();
+    let code = "new x in { x!(1) }";
+
+    let (tree, _diagnostics) = parser.parse_with_errors(code);
+
+    assert!(tree.root_node().named_child_count() > 0);
+}
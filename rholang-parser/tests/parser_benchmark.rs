@@ -0,0 +1,156 @@
+//! Benchmarks `RholangParser::parse` and tree traversal, in the style of
+//! rust-analyzer's `benchmark_parser`: a large synthetic fixture for a
+//! hardware-independent regression signal, plus every `.rho` file under the
+//! corpus directory (parsed in parallel with `rayon` to keep wall-clock low).
+//! Gated behind [`skip_slow_tests`] so `cargo test` stays fast by default --
+//! run with `RUN_SLOW_TESTS=1 cargo test --test parser_benchmark` to get
+//! throughput and node-count numbers.
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use rayon::prelude::*;
+use rholang_parser::RholangParser;
+
+/// Slow tests only run when explicitly requested, so `cargo test` stays fast
+/// in CI and on every contributor's inner loop.
+fn skip_slow_tests() -> bool {
+    std::env::var("RUN_SLOW_TESTS").as_deref() != Ok("1")
+}
+
+/// `n` copies of a representative send/receive process, `|`-composed into a
+/// single large parallel program -- big enough to make parse cost visible
+/// without depending on any file on disk.
+fn synthetic_source(n: usize) -> String {
+    (0..n)
+        .map(|i| format!("new ch{i} in {{ ch{i}!({i}) | for (x <- ch{i}) {{ Nil }} }}"))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Count every node (named or not) in `tree`, as a cheap proxy for how much
+/// work the parser and any downstream traversal did.
+fn count_nodes(tree: &tree_sitter::Tree) -> usize {
+    let mut cursor = tree.walk();
+    let mut count = 0;
+    let mut reached_root = false;
+    while !reached_root {
+        count += 1;
+        if cursor.goto_first_child() {
+            continue;
+        }
+        loop {
+            if cursor.goto_next_sibling() {
+                break;
+            }
+            if !cursor.goto_parent() {
+                reached_root = true;
+                break;
+            }
+        }
+    }
+    count
+}
+
+#[test]
+fn benchmark_parser_on_synthetic_fixture() {
+    if skip_slow_tests() {
+        eprintln!("skipping benchmark_parser_on_synthetic_fixture (set RUN_SLOW_TESTS=1 to run)");
+        return;
+    }
+
+    let source = synthetic_source(5_000);
+    let parser = RholangParser::new();
+
+    let start = Instant::now();
+    let result = parser.parse(&source);
+    let elapsed = start.elapsed();
+
+    let mut ts_parser = tree_sitter::Parser::new();
+    let language = rholang_tree_sitter::LANGUAGE.into();
+    ts_parser
+        .set_language(&language)
+        .expect("failed to load the Rholang grammar");
+    let tree = ts_parser
+        .parse(&source, None)
+        .expect("tree-sitter failed to produce a syntax tree");
+
+    // Reparsing identical source must see the same tree shape every time --
+    // a cheap, hardware-independent regression signal in place of a brittle
+    // hardcoded node count.
+    let second_tree = ts_parser
+        .parse(&source, None)
+        .expect("tree-sitter failed to produce a syntax tree");
+    assert_eq!(
+        count_nodes(&tree),
+        count_nodes(&second_tree),
+        "node count for identical source must be stable across parses"
+    );
+
+    let bytes_per_sec = source.len() as f64 / elapsed.as_secs_f64();
+    println!(
+        "synthetic fixture: {} bytes, {} nodes, {:?} ({:.0} bytes/sec), top-level procs: {}",
+        source.len(),
+        count_nodes(&tree),
+        elapsed,
+        bytes_per_sec,
+        result.as_ref().map(|procs| procs.len()).unwrap_or(0),
+    );
+}
+
+/// Mirrors `corpus_tests`' own directory-discovery, but returns `None`
+/// instead of asserting -- there's nothing to benchmark against on a
+/// checkout with no corpus fixtures, and that's not itself a failure.
+fn find_corpus_dir() -> Option<PathBuf> {
+    [
+        Path::new("rholang-parser/corpus"),
+        Path::new("corpus"),
+        Path::new("../corpus"),
+    ]
+    .into_iter()
+    .map(Path::to_path_buf)
+    .find(|path| path.exists())
+}
+
+#[test]
+fn benchmark_parser_on_corpus() {
+    if skip_slow_tests() {
+        eprintln!("skipping benchmark_parser_on_corpus (set RUN_SLOW_TESTS=1 to run)");
+        return;
+    }
+
+    let Some(corpus_dir) = find_corpus_dir() else {
+        eprintln!("skipping benchmark_parser_on_corpus (no corpus directory on this checkout)");
+        return;
+    };
+
+    let rho_files: Vec<PathBuf> = std::fs::read_dir(&corpus_dir)
+        .expect("failed to read corpus directory")
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rho"))
+        .collect();
+
+    if rho_files.is_empty() {
+        eprintln!("skipping benchmark_parser_on_corpus (corpus directory has no .rho files)");
+        return;
+    }
+
+    let start = Instant::now();
+    let totals: (usize, usize) = rho_files
+        .par_iter()
+        .map(|path| {
+            let source = std::fs::read_to_string(path).expect("failed to read corpus file");
+            let parser = RholangParser::new();
+            let _ = parser.parse(&source);
+            source.len()
+        })
+        .map(|byte_len| (byte_len, 1))
+        .reduce(|| (0, 0), |(bytes, files), (b, f)| (bytes + b, files + f));
+    let elapsed = start.elapsed();
+
+    let (total_bytes, total_files) = totals;
+    let bytes_per_sec = total_bytes as f64 / elapsed.as_secs_f64();
+    println!(
+        "corpus: {total_files} files, {total_bytes} bytes, {elapsed:?} ({bytes_per_sec:.0} bytes/sec)"
+    );
+}
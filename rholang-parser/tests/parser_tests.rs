@@ -69,6 +69,7 @@ fn test_get_tree_string_with_valid_code() -> Result<()> {
     let tree_string = match result {
         ParseResult::Success(s) => s,
         ParseResult::Error(e) => panic!("Expected success, got error: {}", e),
+        ParseResult::Incomplete => panic!("Expected success, got incomplete"),
     };
 
     assert!(!tree_string.is_empty());
@@ -99,6 +100,7 @@ fn test_get_pretty_tree_with_valid_code() -> Result<()> {
     let pretty_tree = match result {
         ParseResult::Success(s) => s,
         ParseResult::Error(e) => panic!("Expected success, got error: {}", e),
+        ParseResult::Incomplete => panic!("Expected success, got incomplete"),
     };
 
     assert!(!pretty_tree.is_empty());
@@ -0,0 +1,97 @@
+use anyhow::{anyhow, Result};
+use rholang_parser::parser::reparse::ReparseSession;
+use rholang_parser::parser::RholangParser;
+use validated::Validated;
+
+fn point_at_end_of(source: &str) -> tree_sitter::Point {
+    let mut row = 0;
+    let mut column = 0;
+    for ch in source.chars() {
+        if ch == '\n' {
+            row += 1;
+            column = 0;
+        } else {
+            column += 1;
+        }
+    }
+    tree_sitter::Point { row, column }
+}
+
+/// An edit that appends `suffix` to `old_source`, the simplest `InputEdit`
+/// to construct by hand (no bytes before the edit point move).
+fn append_edit(old_source: &str, suffix: &str) -> tree_sitter::InputEdit {
+    let old_end = point_at_end_of(old_source);
+    let mut new_source = String::from(old_source);
+    new_source.push_str(suffix);
+
+    tree_sitter::InputEdit {
+        start_byte: old_source.len(),
+        old_end_byte: old_source.len(),
+        new_end_byte: new_source.len(),
+        start_position: old_end,
+        old_end_position: old_end,
+        new_end_position: point_at_end_of(&new_source),
+    }
+}
+
+#[test]
+fn test_reparse_matches_a_full_parse() -> Result<()> {
+    let session = ReparseSession::new();
+    let parser = RholangParser::new();
+
+    let reparsed = match session.reparse("1 + 2", &[]) {
+        Validated::Good(mut procs) => procs.remove(0),
+        Validated::Fail(err) => return Err(anyhow!("reparse failed: {err:?}")),
+    };
+    let parsed = match parser.parse("1 + 2") {
+        Validated::Good(mut procs) => procs.remove(0),
+        Validated::Fail(errors) => return Err(anyhow!("parse failed: {errors:?}")),
+    };
+
+    assert_eq!(reparsed.proc, parsed.proc);
+    Ok(())
+}
+
+#[test]
+fn test_reparse_with_no_edits_reuses_the_same_tree() -> Result<()> {
+    let session = ReparseSession::new();
+
+    let first = match session.reparse("1 + 2", &[]) {
+        Validated::Good(mut procs) => procs.remove(0),
+        Validated::Fail(err) => return Err(anyhow!("first reparse failed: {err:?}")),
+    };
+    let second = match session.reparse("1 + 2", &[]) {
+        Validated::Good(mut procs) => procs.remove(0),
+        Validated::Fail(err) => return Err(anyhow!("second reparse failed: {err:?}")),
+    };
+
+    assert_eq!(first.proc, second.proc);
+    Ok(())
+}
+
+#[test]
+fn test_reparse_reflects_an_edit() -> Result<()> {
+    let session = ReparseSession::new();
+    let parser = RholangParser::new();
+
+    let old_source = "1 + 2";
+    let new_source = "1 + 2 + 3";
+    let edit = append_edit(old_source, " + 3");
+
+    match session.reparse(old_source, &[]) {
+        Validated::Good(_) => {}
+        Validated::Fail(err) => return Err(anyhow!("initial reparse failed: {err:?}")),
+    }
+
+    let reparsed = match session.reparse(new_source, &[edit]) {
+        Validated::Good(mut procs) => procs.remove(0),
+        Validated::Fail(err) => return Err(anyhow!("edited reparse failed: {err:?}")),
+    };
+    let parsed = match parser.parse(new_source) {
+        Validated::Good(mut procs) => procs.remove(0),
+        Validated::Fail(errors) => return Err(anyhow!("parse failed: {errors:?}")),
+    };
+
+    assert_eq!(reparsed.proc, parsed.proc);
+    Ok(())
+}
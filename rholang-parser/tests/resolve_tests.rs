@@ -0,0 +1,67 @@
+use anyhow::{anyhow, Result};
+use rholang_parser::parser::resolve::Resolution;
+use rholang_parser::parser::RholangParser;
+use validated::Validated;
+
+#[test]
+fn test_resolve_binds_a_use_to_its_new_declaration() -> Result<()> {
+    let parser = RholangParser::new();
+    let code = "new channel in { channel!(\"Hello, world!\") }";
+
+    let resolved = match parser.resolve(code) {
+        Validated::Good(resolved) => resolved,
+        Validated::Fail(errors) => return Err(anyhow!("parse failed: {errors:?}")),
+    };
+
+    let resolutions = &resolved[0].1;
+    assert!(
+        resolutions
+            .iter()
+            .any(|r| matches!(r, Resolution::Bound { id, index: 0, .. } if id.name == "channel")),
+        "expected `channel` to resolve bound at index 0, got {resolutions:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_resolve_reports_a_free_name() -> Result<()> {
+    let parser = RholangParser::new();
+    let code = "free_channel!(\"Hello, world!\")";
+
+    let resolved = match parser.resolve(code) {
+        Validated::Good(resolved) => resolved,
+        Validated::Fail(errors) => return Err(anyhow!("parse failed: {errors:?}")),
+    };
+
+    let resolutions = &resolved[0].1;
+    assert!(
+        resolutions
+            .iter()
+            .any(|r| matches!(r, Resolution::Free { id, .. } if id.name == "free_channel")),
+        "expected `free_channel` to resolve free, got {resolutions:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_resolve_indexes_shadowing_by_depth() -> Result<()> {
+    let parser = RholangParser::new();
+    let code = "new x in { new x in { x!(1) } }";
+
+    let resolved = match parser.resolve(code) {
+        Validated::Good(resolved) => resolved,
+        Validated::Fail(errors) => return Err(anyhow!("parse failed: {errors:?}")),
+    };
+
+    let resolutions = &resolved[0].1;
+    assert!(
+        resolutions
+            .iter()
+            .any(|r| matches!(r, Resolution::Bound { id, index: 0, .. } if id.name == "x")),
+        "expected the innermost `x` to resolve to its own `new`, got {resolutions:?}"
+    );
+
+    Ok(())
+}
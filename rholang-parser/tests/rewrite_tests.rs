@@ -0,0 +1,51 @@
+use anyhow::{anyhow, Result};
+use rholang_parser::ast::Proc;
+use rholang_parser::parser::RholangParser;
+use validated::Validated;
+
+#[test]
+fn test_const_fold_folds_addition() -> Result<()> {
+    let parser = RholangParser::new();
+    let code = "1 + 2";
+
+    let folded = match parser.const_fold(code) {
+        Validated::Good(folded) => folded,
+        Validated::Fail(errors) => return Err(anyhow!("parse failed: {errors:?}")),
+    };
+
+    assert!(matches!(folded[0].proc, Proc::LongLiteral(3)));
+
+    Ok(())
+}
+
+#[test]
+fn test_const_fold_folds_negation() -> Result<()> {
+    let parser = RholangParser::new();
+    let code = "-5";
+
+    let folded = match parser.const_fold(code) {
+        Validated::Good(folded) => folded,
+        Validated::Fail(errors) => return Err(anyhow!("parse failed: {errors:?}")),
+    };
+
+    assert!(matches!(folded[0].proc, Proc::LongLiteral(-5)));
+
+    Ok(())
+}
+
+#[test]
+fn test_const_fold_keeps_a_non_arithmetic_process_unfolded() -> Result<()> {
+    let parser = RholangParser::new();
+    let code = "new x in { x!(1 + 2) }";
+
+    let folded = match parser.const_fold(code) {
+        Validated::Good(folded) => folded,
+        Validated::Fail(errors) => return Err(anyhow!("parse failed: {errors:?}")),
+    };
+
+    // The outer `new` isn't an arithmetic expression, so it's rebuilt as-is
+    // rather than folded -- only the `1 + 2` inside it collapses.
+    assert!(matches!(folded[0].proc, Proc::New { .. }));
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use anyhow::{anyhow, Result};
+use rholang_parser::parser::scope::ScopeDiagnostic;
+use rholang_parser::parser::RholangParser;
+use validated::Validated;
+
+#[test]
+fn test_analyze_reports_no_diagnostics_for_fully_used_binders() -> Result<()> {
+    let parser = RholangParser::new();
+    let code = "new channel in { channel!(\"Hello, world!\") }";
+
+    let analyzed = match parser.analyze(code) {
+        Validated::Good(analyzed) => analyzed,
+        Validated::Fail(errors) => return Err(anyhow!("parse failed: {errors:?}")),
+    };
+
+    assert_eq!(analyzed.len(), 1);
+    assert!(analyzed[0].1.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_analyze_reports_an_unbound_name() -> Result<()> {
+    let parser = RholangParser::new();
+    let code = "free_channel!(\"Hello, world!\")";
+
+    let analyzed = match parser.analyze(code) {
+        Validated::Good(analyzed) => analyzed,
+        Validated::Fail(errors) => return Err(anyhow!("parse failed: {errors:?}")),
+    };
+
+    let diagnostics = &analyzed[0].1;
+    assert!(
+        diagnostics
+            .iter()
+            .any(|d| matches!(d, ScopeDiagnostic::Unbound { id, .. } if id.name == "free_channel")),
+        "expected an Unbound diagnostic for `free_channel`, got {diagnostics:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_analyze_reports_an_unused_binder() -> Result<()> {
+    let parser = RholangParser::new();
+    let code = "new unused_channel in { Nil }";
+
+    let analyzed = match parser.analyze(code) {
+        Validated::Good(analyzed) => analyzed,
+        Validated::Fail(errors) => return Err(anyhow!("parse failed: {errors:?}")),
+    };
+
+    let diagnostics = &analyzed[0].1;
+    assert!(
+        diagnostics
+            .iter()
+            .any(|d| matches!(d, ScopeDiagnostic::Unused { id, .. } if id.name == "unused_channel")),
+        "expected an Unused diagnostic for `unused_channel`, got {diagnostics:?}"
+    );
+
+    Ok(())
+}
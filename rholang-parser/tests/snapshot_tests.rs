@@ -0,0 +1,85 @@
+//! A directory-driven snapshot harness for parse trees, modeled on the WDL/
+//! rust-analyzer `test_data` convention: each `tests/parsing/<name>/` holds a
+//! `source.rho` input alongside its golden `source.tree` (tree-sitter's own
+//! S-expression rendering) and `source.errors` (one rendered diagnostic per
+//! line, empty if `source.rho` parses cleanly). Unlike [`corpus_tests`]'s
+//! pass/fail assertions, this catches structural regressions in the CST shape
+//! itself.
+//!
+//! Run with `BLESS=1 cargo test --test snapshot_tests` to (re)write the
+//! golden files for every case instead of asserting against them -- the usual
+//! way to add a new case or to update one after an intentional grammar
+//! change.
+
+use anyhow::Result;
+use rholang_parser::RholangParser;
+use rstest::rstest;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Render `code`'s parse tree exactly as the grammar produces it, in
+/// tree-sitter's own S-expression form
+fn render_tree(code: &str) -> String {
+    let mut parser = tree_sitter::Parser::new();
+    let language = rholang_tree_sitter::LANGUAGE.into();
+    parser
+        .set_language(&language)
+        .expect("failed to load the Rholang grammar");
+    let tree = parser
+        .parse(code, None)
+        .expect("tree-sitter failed to produce a syntax tree");
+    tree.root_node().to_sexp()
+}
+
+/// Render every `ERROR`/`MISSING` diagnostic the parser reports for `code`,
+/// one per line; empty for inputs with no parse errors
+fn render_errors(parser: &RholangParser, code: &str) -> String {
+    parser
+        .diagnostics(code)
+        .iter()
+        .map(|diagnostic| diagnostic.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Compare `actual` against the contents of `path`, or (when the `BLESS`
+/// environment variable is set to `1`) write `actual` to `path` instead of
+/// failing -- the standard golden-file update workflow.
+fn check_or_bless(path: &Path, actual: &str) {
+    if std::env::var("BLESS").as_deref() == Ok("1") {
+        fs::write(path, actual)
+            .unwrap_or_else(|e| panic!("failed to write {}: {}", path.display(), e));
+        return;
+    }
+
+    let expected = fs::read_to_string(path).unwrap_or_else(|e| {
+        panic!(
+            "failed to read golden file {}: {} (run with BLESS=1 to create it)",
+            path.display(),
+            e
+        )
+    });
+
+    pretty_assertions::assert_eq!(expected.trim_end(), actual.trim_end(), "{}", path.display());
+}
+
+#[rstest]
+fn snapshot_test(
+    #[base_dir = "tests/parsing"]
+    #[files("*/source.rho")]
+    source_path: PathBuf,
+) -> Result<()> {
+    let case_dir = source_path
+        .parent()
+        .expect("source.rho always has a parent directory");
+    let code = fs::read_to_string(&source_path)?;
+
+    let parser = RholangParser::new();
+    let tree = render_tree(&code);
+    let errors = render_errors(&parser, &code);
+
+    check_or_bless(&case_dir.join("source.tree"), &tree);
+    check_or_bless(&case_dir.join("source.errors"), &errors);
+
+    Ok(())
+}
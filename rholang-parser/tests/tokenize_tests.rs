@@ -0,0 +1,35 @@
+use rholang_parser::parser::RholangParser;
+
+#[test]
+fn test_tokenize_splits_a_send_into_its_leaf_tokens() {
+    let parser = RholangParser::new();
+    let code = "x!(1)";
+
+    let (tokens, errors) = parser.tokenize(code);
+
+    assert!(errors.is_empty());
+    assert!(tokens.iter().any(|t| &code[t.range.clone()] == "x"));
+    assert!(tokens.iter().any(|t| &code[t.range.clone()] == "!"));
+    assert!(tokens.iter().any(|t| &code[t.range.clone()] == "1"));
+}
+
+#[test]
+fn test_tokenize_reports_a_lex_error_for_an_unterminated_string() {
+    let parser = RholangParser::new();
+    let code = "\"unterminated";
+
+    let (_tokens, errors) = parser.tokenize(code);
+
+    assert!(!errors.is_empty());
+}
+
+#[test]
+fn test_tokenize_covers_every_byte_of_well_formed_source() {
+    let parser = RholangParser::new();
+    let code = "Nil";
+
+    let (tokens, errors) = parser.tokenize(code);
+
+    assert!(errors.is_empty());
+    assert!(tokens.iter().any(|t| &code[t.range.clone()] == "Nil"));
+}
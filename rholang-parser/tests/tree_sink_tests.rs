@@ -0,0 +1,21 @@
+use rholang_parser::parser::RholangParser;
+
+#[test]
+fn test_node_kind_counts_counts_each_nil_separately() {
+    let parser = RholangParser::new();
+    let code = "Nil | Nil | Nil";
+
+    let counts = parser.node_kind_counts(code);
+
+    assert_eq!(counts.get("nil").copied(), Some(3));
+}
+
+#[test]
+fn test_node_kind_counts_counts_unnamed_tokens_too() {
+    let parser = RholangParser::new();
+    let code = "x!(1)";
+
+    let counts = parser.node_kind_counts(code);
+
+    assert_eq!(counts.get("!").copied(), Some(1));
+}
@@ -0,0 +1,133 @@
+use anyhow::{anyhow, Result};
+use rholang_parser::parser::unparse::unparse;
+use rholang_parser::parser::RholangParser;
+use validated::Validated;
+
+/// Parse `code`'s first top-level process, panicking with a readable message on
+/// any parse failure (none of these fixtures are expected to fail).
+fn parse_one<'a>(parser: &'a RholangParser<'a>, code: &'a str) -> rholang_parser::ast::AnnProc<'a> {
+    match parser.parse(code) {
+        Validated::Good(mut procs) => procs.remove(0),
+        Validated::Fail(errors) => panic!("unexpected parse failure for {code:?}: {errors:?}"),
+    }
+}
+
+/// Assert that unparsing `code` and reparsing the result yields a `Proc`
+/// structurally equal to the one `code` itself parses to.
+fn assert_round_trips(code: &str) -> Result<()> {
+    let parser = RholangParser::new();
+    let ast = parse_one(&parser, code);
+
+    let mut rendered = String::new();
+    unparse(&ast, &mut rendered);
+
+    let reparsed = parse_one(&parser, &rendered);
+    if reparsed.proc != ast.proc {
+        return Err(anyhow!(
+            "round trip mismatch for {code:?}: rendered {rendered:?} reparsed as {:?}, expected {:?}",
+            reparsed.proc, ast.proc
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_round_trip_par() -> Result<()> {
+    assert_round_trips("Nil | Nil")
+}
+
+#[test]
+fn test_round_trip_send_single() -> Result<()> {
+    assert_round_trips("channel!(\"Hello, world!\")")
+}
+
+#[test]
+fn test_round_trip_send_multiple() -> Result<()> {
+    assert_round_trips("channel!!(1, 2, 3)")
+}
+
+#[test]
+fn test_round_trip_new() -> Result<()> {
+    assert_round_trips("new channel in { channel!(\"again\") }")
+}
+
+#[test]
+fn test_round_trip_contract() -> Result<()> {
+    assert_round_trips("contract add(ret, x, y) = { ret!(x) }")
+}
+
+#[test]
+fn test_round_trip_for_comprehension_linear() -> Result<()> {
+    assert_round_trips("for (x <- channel) { Nil }")
+}
+
+#[test]
+fn test_round_trip_for_comprehension_repeated_and_peek() -> Result<()> {
+    assert_round_trips("for (x <= channel ; y <<- other) { Nil }")
+}
+
+#[test]
+fn test_round_trip_match() -> Result<()> {
+    assert_round_trips("match 42 { case 42 => { Nil } case _ => { Nil } }")
+}
+
+#[test]
+fn test_round_trip_let_sequential() -> Result<()> {
+    assert_round_trips("let x = 1 ; y = 2 in { x }")
+}
+
+#[test]
+fn test_round_trip_let_concurrent() -> Result<()> {
+    assert_round_trips("let x = 1 & y = 2 in { x }")
+}
+
+#[test]
+fn test_round_trip_list_with_remainder() -> Result<()> {
+    assert_round_trips("[1, 2, ...rest]")
+}
+
+#[test]
+fn test_round_trip_set() -> Result<()> {
+    assert_round_trips("Set(1, 2, 3)")
+}
+
+#[test]
+fn test_round_trip_tuple_singleton() -> Result<()> {
+    assert_round_trips("(1,)")
+}
+
+#[test]
+fn test_round_trip_map_with_remainder() -> Result<()> {
+    assert_round_trips("{\"a\": 1, ...rest}")
+}
+
+#[test]
+fn test_round_trip_method_call() -> Result<()> {
+    assert_round_trips("x.toString()")
+}
+
+#[test]
+fn test_round_trip_method_call_on_binary_exp() -> Result<()> {
+    assert_round_trips("(1 + 2).toString()")
+}
+
+#[test]
+fn test_round_trip_binary_exp_mixed_precedence() -> Result<()> {
+    assert_round_trips("1 + 2 * 3")
+}
+
+#[test]
+fn test_round_trip_binary_exp_forces_parens() -> Result<()> {
+    assert_round_trips("(1 + 2) * 3")
+}
+
+#[test]
+fn test_round_trip_unary_exp() -> Result<()> {
+    assert_round_trips("not true")
+}
+
+#[test]
+fn test_round_trip_quoted_binary_exp() -> Result<()> {
+    assert_round_trips("@{1 + 2}!(Nil)")
+}
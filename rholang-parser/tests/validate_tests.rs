@@ -0,0 +1,69 @@
+use anyhow::{anyhow, Result};
+use rholang_parser::parser::validate::ValidationDiagnostic;
+use rholang_parser::parser::RholangParser;
+use validated::Validated;
+
+#[test]
+fn test_validate_accepts_a_well_formed_let() -> Result<()> {
+    let parser = RholangParser::new();
+    let code = "let x <- 1 & y <- 2 in { Nil }";
+
+    let validated = match parser.validate(code) {
+        Validated::Good(validated) => validated,
+        Validated::Fail(errors) => return Err(anyhow!("parse failed: {errors:?}")),
+    };
+
+    assert!(matches!(validated[0], Validated::Good(_)));
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_reports_a_duplicate_concurrent_let_binder() -> Result<()> {
+    let parser = RholangParser::new();
+    let code = "let x <- 1 & x <- 2 in { Nil }";
+
+    let validated = match parser.validate(code) {
+        Validated::Good(validated) => validated,
+        Validated::Fail(errors) => return Err(anyhow!("parse failed: {errors:?}")),
+    };
+
+    let Validated::Fail(failure) = &validated[0] else {
+        return Err(anyhow!("expected a validation failure, got {:?}", validated[0]));
+    };
+    assert!(
+        failure
+            .errors
+            .iter()
+            .any(|d| matches!(d, ValidationDiagnostic::DuplicateLetBinder { id, .. } if id.name == "x")),
+        "expected a DuplicateLetBinder diagnostic for `x`, got {:?}",
+        failure.errors
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_reports_an_out_of_range_nth_index() -> Result<()> {
+    let parser = RholangParser::new();
+    let code = "[1, 2, 3].nth(5)";
+
+    let validated = match parser.validate(code) {
+        Validated::Good(validated) => validated,
+        Validated::Fail(errors) => return Err(anyhow!("parse failed: {errors:?}")),
+    };
+
+    let Validated::Fail(failure) = &validated[0] else {
+        return Err(anyhow!("expected a validation failure, got {:?}", validated[0]));
+    };
+    assert!(
+        failure
+            .errors
+            .iter()
+            .any(|d| matches!(d, ValidationDiagnostic::IndexOutOfRange { index: 5, len: 3, .. })),
+        "expected an IndexOutOfRange diagnostic, got {:?}",
+        failure.errors
+    );
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anyhow::{anyhow, Result};
+use rholang_parser::ast::Proc;
+use rholang_parser::parser::RholangParser;
+use validated::Validated;
+
+#[test]
+fn test_nodes_visits_every_child_of_a_par() -> Result<()> {
+    let parser = RholangParser::new();
+    let code = "Nil | Nil";
+
+    let nodes = match parser.nodes(code) {
+        Validated::Good(nodes) => nodes,
+        Validated::Fail(errors) => return Err(anyhow!("parse failed: {errors:?}")),
+    };
+
+    let top_level = &nodes[0];
+    // The `par` itself, plus both `Nil` children.
+    assert_eq!(top_level.len(), 3);
+    assert!(matches!(top_level[0].0.proc, Proc::Par { .. }));
+    assert!(top_level.iter().filter(|(ann, _)| matches!(ann.proc, Proc::Nil)).count() == 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_nodes_visits_in_pre_order() -> Result<()> {
+    let parser = RholangParser::new();
+    let code = "1 + 2";
+
+    let nodes = match parser.nodes(code) {
+        Validated::Good(nodes) => nodes,
+        Validated::Fail(errors) => return Err(anyhow!("parse failed: {errors:?}")),
+    };
+
+    let top_level = &nodes[0];
+    // Pre-order: the `BinaryExp` node comes before either of its operands.
+    assert!(matches!(top_level[0].0.proc, Proc::BinaryExp { .. }));
+    assert!(matches!(top_level[1].0.proc, Proc::LongLiteral(1)));
+    assert!(matches!(top_level[2].0.proc, Proc::LongLiteral(2)));
+
+    Ok(())
+}
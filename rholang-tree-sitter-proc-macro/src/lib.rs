@@ -2,8 +2,8 @@ use proc_macro::TokenStream;
 
 use quote::{quote, quote_spanned};
 use syn::{
-    parse::Parse, parse::ParseStream, parse_macro_input, punctuated::Punctuated, Expr, LitStr,
-    Result, Token,
+    braced, parse::Parse, parse::ParseStream, parse_macro_input, punctuated::Punctuated, Expr,
+    Ident, LitStr, Result, Token,
 };
 use tree_sitter::Language;
 
@@ -15,11 +15,19 @@ use tree_sitter::Language;
 ///
 /// ## Macros
 ///
-/// This crate provides three main macros:
+/// This crate provides five main macros:
 ///
 /// - [`kind!`]: Returns the node kind ID for a given node kind name
 /// - [`kw!`]: Returns the node kind ID for a given keyword
 /// - [`field!`]: Returns the field ID for a given field name
+/// - [`node_tables!`]: Generates the reverse lookups (ID to name) for all three
+/// - [`node_set!`]: Returns a [`NodeSet`] bitset for fast "is this node one of several
+///   kinds" checks, replacing a long `kind!("a") | kind!("b") | ...` guard chain
+///
+/// It also provides a small traversal API built on top of those macros, so a
+/// caller doesn't have to hand-roll its own recursive `TreeCursor` walk for
+/// every kind of node it wants to find: a [`Visitor`] trait plus [`walk`]
+/// driver, and the [`collect_by_kind`]/[`field_text`] query-style helpers.
 ///
 /// ## Usage
 ///
@@ -338,18 +346,227 @@ pub fn field(token_stream: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Generates reverse-lookup functions (ID to name) for node kinds, keywords, and
+/// fields -- the complement to `kind!`/`kw!`/`field!`'s name-to-ID direction, backed by
+/// arrays built at compile time from the same grammar metadata those macros consult.
+///
+/// Expands to five items, meant to be invoked once at module scope:
+///
+/// - `kind_name(id: u16) -> Option<&'static str>`: reverse of `kind!`
+/// - `keyword_name(id: u16) -> Option<&'static str>`: reverse of `kw!`
+/// - `field_name(id: u16) -> Option<&'static str>`: reverse of `field!`
+/// - `all_kinds() -> impl Iterator<Item = (u16, &'static str)>`
+/// - `all_fields() -> impl Iterator<Item = (u16, &'static str)>`
+///
+/// # Examples
+///
+/// ```
+/// use rholang_tree_sitter_proc_macro::{kind, node_tables};
+///
+/// node_tables!();
+///
+/// assert_eq!(kind_name(kind!("new")), Some("new"));
+/// assert!(all_kinds().any(|(id, name)| id == kind!("new") && name == "new"));
+/// ```
+#[proc_macro]
+pub fn node_tables(_token_stream: TokenStream) -> TokenStream {
+    let language: Language = rholang_tree_sitter::LANGUAGE.into();
+
+    let mut kind_ids = Vec::new();
+    let mut kind_names = Vec::new();
+    let mut keyword_ids = Vec::new();
+    let mut keyword_names = Vec::new();
+
+    for id in 0..language.node_kind_count() as u16 {
+        let Some(name) = language.node_kind_for_id(id) else {
+            continue;
+        };
+        if name.is_empty() {
+            continue;
+        }
+
+        if language.node_kind_is_named(id) {
+            kind_ids.push(id);
+            kind_names.push(name.to_string());
+        } else {
+            keyword_ids.push(id);
+            keyword_names.push(name.to_string());
+        }
+    }
+
+    let mut field_ids = Vec::new();
+    let mut field_names = Vec::new();
+    for id in 1..=language.field_count() as u16 {
+        if let Some(name) = language.field_name_for_id(id) {
+            field_ids.push(id);
+            field_names.push(name.to_string());
+        }
+    }
+
+    let expanded = quote! {
+        /// Node-kind ID to grammar name -- the reverse of `kind!`
+        pub fn kind_name(id: u16) -> Option<&'static str> {
+            match id {
+                #(#kind_ids => Some(#kind_names),)*
+                _ => None,
+            }
+        }
+
+        /// Keyword ID to grammar name -- the reverse of `kw!`
+        pub fn keyword_name(id: u16) -> Option<&'static str> {
+            match id {
+                #(#keyword_ids => Some(#keyword_names),)*
+                _ => None,
+            }
+        }
+
+        /// Field ID to grammar name -- the reverse of `field!`
+        pub fn field_name(id: u16) -> Option<&'static str> {
+            match id {
+                #(#field_ids => Some(#field_names),)*
+                _ => None,
+            }
+        }
+
+        /// Every named node kind's ID/name pair in the grammar
+        pub fn all_kinds() -> impl Iterator<Item = (u16, &'static str)> {
+            [#((#kind_ids, #kind_names)),*].into_iter()
+        }
+
+        /// Every field's ID/name pair in the grammar
+        pub fn all_fields() -> impl Iterator<Item = (u16, &'static str)> {
+            [#((#field_ids, #field_names)),*].into_iter()
+        }
+    };
+
+    expanded.into()
+}
+
+/// A fixed-size bitset of node kind IDs, following rust-analyzer's `TokenSet` design:
+/// an "is this node one of several kinds" check against a bitset is one load and one
+/// mask, versus a chain of `==`/`kind!` comparisons. `N` is the number of `u64` words
+/// needed to cover every ID in the grammar, i.e. `language.node_kind_count()` divided
+/// by 64 and rounded up (at least 1); [`node_set!`] picks it for you.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodeSet<const N: usize>(pub [u64; N]);
+
+impl<const N: usize> NodeSet<N> {
+    /// Whether `id` is one of the node kinds this set was built from
+    pub const fn contains(&self, id: u16) -> bool {
+        let word = id as usize / 64;
+        if word >= N {
+            return false;
+        }
+        (self.0[word] >> (id as usize % 64)) & 1 != 0
+    }
+}
+
+/// Returns a [`NodeSet`] bitset of the given node kind names, resolved to their IDs at
+/// compile time the same way [`kind!`] does. Lets traversal code replace a long
+/// `kind!("par") | kind!("new") | ...` match guard with a single
+/// `MY_SET.contains(node.kind_id())`.
+///
+/// # Errors
+///
+/// Generates a compile-time error, pointing at the offending string literal, if any
+/// name is not a valid node kind in the rholang-tree-sitter grammar.
+///
+/// # Examples
+///
+/// ```
+/// use rholang_tree_sitter_proc_macro::{kind, node_set};
+///
+/// let binary_ops = node_set!("add", "sub", "mult", "div");
+///
+/// assert!(binary_ops.contains(kind!("add")));
+/// assert!(!binary_ops.contains(kind!("new")));
+/// ```
+#[proc_macro]
+pub fn node_set(token_stream: TokenStream) -> TokenStream {
+    let names = parse_macro_input!(token_stream with Punctuated::<LitStr, Token![,]>::parse_terminated);
+
+    let language: Language = rholang_tree_sitter::LANGUAGE.into();
+    let word_count = (language.node_kind_count() as usize).div_ceil(64).max(1);
+
+    let mut words = vec![0u64; word_count];
+    let mut compile_errors = Vec::new();
+
+    for name in names.iter() {
+        let requested = name.value();
+        let found_id = language.id_for_node_kind(&requested, true);
+
+        if found_id == 0 {
+            compile_errors.push(quote_spanned!(
+                name.span() =>
+                compile_error!("This is not a valid node kind in the rholang-tree-sitter grammar")
+            ));
+            continue;
+        }
+
+        words[found_id as usize / 64] |= 1u64 << (found_id as usize % 64);
+    }
+
+    if !compile_errors.is_empty() {
+        return quote! { #(#compile_errors)* }.into();
+    }
+
+    quote! {
+        rholang_tree_sitter_proc_macro::NodeSet::<#word_count>([#(#words),*])
+    }
+    .into()
+}
+
 /// A structure to represent a pattern-handler pair in the match_node macro
 struct MatchNodeArm {
     pattern: LitStr,
+    /// An optional `@name` binding the whole matched node to `name`, from a clause
+    /// directly after the pattern
+    binding: Option<Ident>,
+    /// Field names to bind as `Option<Node>` locals, from an optional
+    /// `{ field1, field2 }` clause between the pattern/binding and the guard/handler
+    fields: Vec<Ident>,
+    /// An optional `if <expr>` guard; if it evaluates to `false` this arm falls
+    /// through to the next one instead of matching
+    guard: Option<Expr>,
     handler: Expr,
 }
 
 impl Parse for MatchNodeArm {
     fn parse(input: ParseStream) -> Result<Self> {
         let pattern = input.parse()?;
+
+        let binding = if input.peek(Token![@]) {
+            input.parse::<Token![@]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        let fields = if input.peek(syn::token::Brace) {
+            let content;
+            braced!(content in input);
+            let idents: Punctuated<Ident, Token![,]> = Punctuated::parse_terminated(&content)?;
+            idents.into_iter().collect()
+        } else {
+            Vec::new()
+        };
+
+        let guard = if input.peek(Token![if]) {
+            input.parse::<Token![if]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
         input.parse::<Token![=>]>()?;
         let handler = input.parse()?;
-        Ok(MatchNodeArm { pattern, handler })
+        Ok(MatchNodeArm {
+            pattern,
+            binding,
+            fields,
+            guard,
+            handler,
+        })
     }
 }
 
@@ -368,7 +585,8 @@ impl Parse for MatchNodeInput {
     }
 }
 
-/// Matches a node's kind string against a series of patterns.
+/// Matches a node's kind string against a series of patterns, optionally binding
+/// named fields and/or guarding the match with a predicate.
 ///
 /// This macro is useful for pattern matching on node kinds using string literals.
 /// It generates code that compares the node's kind string with each pattern and
@@ -377,13 +595,24 @@ impl Parse for MatchNodeInput {
 /// # Arguments
 ///
 /// * `node_expr` - An expression that evaluates to a tree-sitter Node.
-/// * `pattern => handler` - A series of pattern-handler pairs, where each pattern is a string literal
-///   and each handler is an expression to be executed if the node's kind matches the pattern.
+/// * `pattern @name { field1, field2 } if guard => handler` - A series of arms, where
+///   each pattern is a string literal and the `@name` binding, `{ .. }` field clause,
+///   and `if` guard are all optional, in that order. Each handler is an expression to
+///   be executed if the node's kind matches the pattern and the guard (if any) holds.
+///
+/// `@name` binds the whole matched node to a local called `name`. Each field named in
+/// a `{ .. }` clause is resolved through [`field!`] at compile time and bound, before
+/// the guard and handler run, to an `Option<Node>` local of the same name via
+/// `node.child_by_field_id(..)` -- so an invalid field name is a compile error, same as
+/// writing `field!("typo")` directly. A guard that evaluates to `false` does not fail
+/// the whole macro -- it falls through to the next arm, exactly as if this pattern
+/// hadn't matched at all.
 ///
 /// # Returns
 ///
-/// The result of the handler expression for the first matching pattern, or the result of the
-/// default handler if no pattern matches.
+/// The result of the handler expression for the first matching arm, or the result of
+/// the default handler if no pattern matches (or every matching pattern's guard
+/// failed).
 ///
 /// # Examples
 ///
@@ -418,33 +647,100 @@ impl Parse for MatchNodeInput {
 ///     )
 /// }
 /// ```
+///
+/// Binding fields and guarding a match:
+///
+/// ```
+/// use rholang_tree_sitter_proc_macro::match_node;
+/// use tree_sitter::Node;
+///
+/// fn describe_send(node: &Node, source: &str) -> String {
+///     match_node!(node,
+///         "send" { channel, inputs } if channel.is_some() => format!(
+///             "send on {} with {} argument(s)",
+///             channel.unwrap().utf8_text(source.as_bytes()).unwrap_or(""),
+///             inputs.map(|n| n.named_child_count()).unwrap_or(0)
+///         ),
+///         "par" if node.child_count() > 2 => "a wide parallel composition".to_string(),
+///         "par" => "a parallel composition".to_string(),
+///         _ => format!("other node type: {}", node.kind())
+///     )
+/// }
+/// ```
+///
+/// Binding the whole node with `@name` alongside its fields:
+///
+/// ```
+/// use rholang_tree_sitter_proc_macro::match_node;
+/// use tree_sitter::Node;
+///
+/// fn new_decl_count(node: &Node) -> usize {
+///     match_node!(node,
+///         "new" @_decl { decls } => decls.map(|d| d.named_child_count()).unwrap_or(0),
+///         _ => 0
+///     )
+/// }
+/// ```
 #[proc_macro]
 pub fn match_node(token_stream: TokenStream) -> TokenStream {
     let input = parse_macro_input!(token_stream as MatchNodeInput);
 
     let node_expr = &input.node_expr;
-    let mut match_arms = Vec::new();
+    let mut arm_blocks = Vec::new();
     let mut has_default_arm = false;
 
     for arm in input.arms.iter() {
         let pattern = &arm.pattern;
         let handler = &arm.handler;
 
+        let binding_stmt = match &arm.binding {
+            Some(name) => quote! { let #name = node; },
+            None => quote! {},
+        };
+
         if pattern.value() == "_" {
             has_default_arm = true;
-            match_arms.push(quote! {
-                _ => #handler
-            });
-        } else {
-            match_arms.push(quote! {
-                kind if kind == #pattern => #handler
+            arm_blocks.push(quote! {
+                #binding_stmt
+                break 'match_node_arm #handler;
             });
+            continue;
         }
+
+        let field_bindings = arm.fields.iter().map(|field| {
+            let field_name = LitStr::new(&field.to_string(), field.span());
+            quote! {
+                let #field = node.child_by_field_id(rholang_tree_sitter_proc_macro::field!(#field_name).get());
+            }
+        });
+
+        let body = quote! { break 'match_node_arm #handler; };
+
+        let body = match &arm.guard {
+            Some(guard) => quote! {
+                if #guard {
+                    #body
+                }
+            },
+            None => body,
+        };
+
+        let matched = quote! {
+            #binding_stmt
+            #(#field_bindings)*
+            #body
+        };
+
+        arm_blocks.push(quote! {
+            if kind == #pattern {
+                #matched
+            }
+        });
     }
 
     if !has_default_arm {
-        match_arms.push(quote! {
-            _ => panic!("Unhandled node kind: {}", kind)
+        arm_blocks.push(quote! {
+            panic!("Unhandled node kind: {}", kind)
         });
     }
 
@@ -452,11 +748,102 @@ pub fn match_node(token_stream: TokenStream) -> TokenStream {
         {
             let node = #node_expr;
             let kind = node.kind();
-            match kind {
-                #(#match_arms),*
+            'match_node_arm: {
+                #(#arm_blocks)*
             }
         }
     };
 
     expanded.into()
 }
+
+/// Per-node callbacks for a [`walk`] over a `tree_sitter::Tree`. Both methods
+/// default to doing nothing, so an implementor only needs to override
+/// whichever of `enter_node`/`leave_node` its traversal actually cares
+/// about. This replaces the copy-pasted recursive-`TreeCursor` walk every
+/// hand-rolled node finder in this crate used to write for itself -- see
+/// `examples/advanced_usage.rs` for the three that motivated it.
+pub trait Visitor {
+    /// Called the first time `walk` reaches `node`, before any of its
+    /// children.
+    fn enter_node(&mut self, _node: tree_sitter::Node, _code: &str) {}
+
+    /// Called after every one of `node`'s children has been fully visited.
+    fn leave_node(&mut self, _node: tree_sitter::Node, _code: &str) {}
+}
+
+/// Drive `visitor` over every node of `tree`, calling `enter_node`/
+/// `leave_node` in a depth-first, left-to-right traversal.
+pub fn walk(tree: &tree_sitter::Tree, code: &str, visitor: &mut impl Visitor) {
+    let mut cursor = tree.walk();
+    walk_from_cursor(&mut cursor, code, visitor);
+}
+
+fn walk_from_cursor(cursor: &mut tree_sitter::TreeCursor, code: &str, visitor: &mut impl Visitor) {
+    let node = cursor.node();
+    visitor.enter_node(node, code);
+
+    if cursor.goto_first_child() {
+        loop {
+            walk_from_cursor(cursor, code, visitor);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+        cursor.goto_parent();
+    }
+
+    visitor.leave_node(node, code);
+}
+
+/// Collect every node in `tree` whose `kind_id()` is `kind_id`, in the order
+/// [`walk`] would visit them -- a query-style alternative to writing a
+/// one-off [`Visitor`] when all a caller wants is "every node of this kind".
+///
+/// # Examples
+///
+/// ```
+/// use rholang_tree_sitter_proc_macro::{collect_by_kind, kind};
+/// use tree_sitter::Parser;
+///
+/// let mut parser = Parser::new();
+/// parser.set_language(&rholang_tree_sitter::LANGUAGE.into()).unwrap();
+/// let tree = parser.parse("new x in { Nil }", None).unwrap();
+///
+/// let news = collect_by_kind(&tree, kind!("new"));
+/// assert_eq!(news.len(), 1);
+/// ```
+pub fn collect_by_kind(tree: &tree_sitter::Tree, kind_id: u16) -> Vec<tree_sitter::Node> {
+    // A direct cursor walk, rather than going through `walk`/`Visitor`: that
+    // trait's callbacks are generic over the node's lifetime per call, so an
+    // impl can't stash the borrowed `Node`s it's handed into a `Vec` tied to
+    // one concrete lifetime the way this function's return type needs to.
+    fn collect<'a>(cursor: &mut tree_sitter::TreeCursor<'a>, kind_id: u16, found: &mut Vec<tree_sitter::Node<'a>>) {
+        let node = cursor.node();
+        if node.kind_id() == kind_id {
+            found.push(node);
+        }
+
+        if cursor.goto_first_child() {
+            loop {
+                collect(cursor, kind_id, found);
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+    }
+
+    let mut found = Vec::new();
+    collect(&mut tree.walk(), kind_id, &mut found);
+    found
+}
+
+/// The source text of `node`'s child in field `field_id`, if that field is
+/// present -- a one-line alternative to
+/// `node.child_by_field_id(id).and_then(|n| n.utf8_text(code.as_bytes()).ok())`.
+pub fn field_text<'a>(node: &tree_sitter::Node, field_id: u16, code: &'a str) -> Option<&'a str> {
+    node.child_by_field_id(field_id)
+        .and_then(|child| child.utf8_text(code.as_bytes()).ok())
+}
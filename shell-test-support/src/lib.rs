@@ -0,0 +1,228 @@
+//! Declarative builder for scripting a shell REPL session in tests.
+//!
+//! `Session` feeds lines through the same `process_line`/`has_double_newline` state
+//! machine the real REPL uses, then asserts on the resulting `InterpretationResult`s
+//! with a `lines_match`-style comparator so volatile substrings (generated names,
+//! hashes, timing) don't make tests brittle.
+//!
+//! `Session` drives the REPL in-process and is cheap to run in bulk; [`pty::PtySession`]
+//! spawns the real compiled binary under a pseudo-terminal for a smaller set of true
+//! end-to-end smoke tests.
+
+pub mod pty;
+
+use shell::multiline_helper::process_line;
+use shell::providers::{ErrorKind, InterpretationResult, InterpreterProvider};
+
+/// One step of a scripted session
+enum Step {
+    /// Feed a line of input through the multiline state machine
+    Input(String),
+    /// Assert that the most recently produced result is a success matching `pattern`
+    ExpectOutput(String),
+    /// Assert that the most recently produced result is an error of the given kind
+    ExpectError(ErrorKind),
+}
+
+/// Builds a scripted REPL session against an `InterpreterProvider` and replays it,
+/// asserting on the transcript as it goes.
+///
+/// ```ignore
+/// Session::new(FakeInterpreterProvider)
+///     .multiline(true)
+///     .input("new x in { ... }")
+///     .input("")
+///     .expect_output("Deployed [..]")
+///     .run()
+///     .await;
+/// ```
+pub struct Session<I: InterpreterProvider> {
+    interpreter: I,
+    multiline: bool,
+    steps: Vec<Step>,
+}
+
+impl<I: InterpreterProvider> Session<I> {
+    /// Start a new session against `interpreter`, in single-line mode by default
+    pub fn new(interpreter: I) -> Self {
+        Session {
+            interpreter,
+            multiline: false,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Start the session in (or out of) multiline mode
+    pub fn multiline(mut self, multiline: bool) -> Self {
+        self.multiline = multiline;
+        self
+    }
+
+    /// Feed a line of input to the session
+    pub fn input(mut self, line: impl Into<String>) -> Self {
+        self.steps.push(Step::Input(line.into()));
+        self
+    }
+
+    /// Assert that the most recently produced result is a success whose output
+    /// matches `pattern`, where `[..]` matches any run of characters
+    pub fn expect_output(mut self, pattern: impl Into<String>) -> Self {
+        self.steps.push(Step::ExpectOutput(pattern.into()));
+        self
+    }
+
+    /// Assert that the most recently produced result is an error of the given kind
+    pub fn expect_error(mut self, kind: ErrorKind) -> Self {
+        self.steps.push(Step::ExpectError(kind));
+        self
+    }
+
+    /// Replay the scripted steps, panicking on the first assertion that fails
+    pub async fn run(self) {
+        let Session {
+            interpreter,
+            multiline,
+            steps,
+        } = self;
+
+        let mut buffer = String::new();
+        let mut in_multiline_mode = multiline;
+        let mut pending: Option<InterpretationResult> = None;
+
+        for step in steps {
+            match step {
+                Step::Input(line) => {
+                    let ready = process_line(&line, &mut buffer, &mut in_multiline_mode);
+                    if ready {
+                        let command = std::mem::take(&mut buffer);
+                        pending = Some(interpreter.interpret(&command).await);
+                    }
+                }
+                Step::ExpectOutput(pattern) => {
+                    let result = pending
+                        .take()
+                        .unwrap_or_else(|| panic!("no result produced yet to match {pattern:?} against"));
+                    match result {
+                        InterpretationResult::Success(output) => {
+                            assert!(
+                                output_matches(&pattern, &output),
+                                "expected output matching {pattern:?}, got {output:?}"
+                            );
+                        }
+                        InterpretationResult::Error(err) => {
+                            panic!("expected output matching {pattern:?}, got error: {err}");
+                        }
+                    }
+                }
+                Step::ExpectError(kind) => {
+                    let result = pending
+                        .take()
+                        .unwrap_or_else(|| panic!("no result produced yet to check error kind {kind:?} against"));
+                    match result {
+                        InterpretationResult::Error(err) => {
+                            assert_eq!(err.kind, kind, "expected error kind {kind:?}, got {:?}", err.kind);
+                        }
+                        InterpretationResult::Success(output) => {
+                            panic!("expected {kind:?} error, got success: {output:?}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Compare multi-line `output` against a multi-line `pattern`, matching each pair of
+/// lines with [`lines_match`]
+fn output_matches(pattern: &str, output: &str) -> bool {
+    let pattern_lines: Vec<&str> = pattern.lines().collect();
+    let output_lines: Vec<&str> = output.lines().collect();
+
+    pattern_lines.len() == output_lines.len()
+        && pattern_lines
+            .iter()
+            .zip(output_lines.iter())
+            .all(|(pattern, line)| lines_match(pattern, line))
+}
+
+/// Compare a single `line` against a `pattern` that may contain `[..]` wildcards,
+/// each matching any run of characters (including none) non-greedily
+pub fn lines_match(pattern: &str, line: &str) -> bool {
+    let mut parts = pattern.split("[..]");
+
+    let first = parts.next().unwrap_or("");
+    if !line.starts_with(first) {
+        return false;
+    }
+    let mut remaining = &line[first.len()..];
+
+    let mut parts: Vec<&str> = parts.collect();
+    let last = parts.pop();
+
+    for part in parts {
+        if part.is_empty() {
+            continue;
+        }
+        match remaining.find(part) {
+            Some(idx) => remaining = &remaining[idx + part.len()..],
+            None => return false,
+        }
+    }
+
+    match last {
+        Some(last) => remaining.ends_with(last),
+        None => remaining.is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shell::providers::FakeInterpreterProvider;
+
+    #[test]
+    fn test_lines_match_exact() {
+        assert!(lines_match("hello", "hello"));
+        assert!(!lines_match("hello", "hello world"));
+    }
+
+    #[test]
+    fn test_lines_match_trailing_wildcard() {
+        assert!(lines_match("Deployed [..]", "Deployed abc123"));
+        assert!(lines_match("Deployed [..]", "Deployed "));
+        assert!(!lines_match("Deployed [..]", "Failed abc123"));
+    }
+
+    #[test]
+    fn test_lines_match_middle_wildcard() {
+        assert!(lines_match("hash [..] accepted", "hash 0xdeadbeef accepted"));
+        assert!(!lines_match("hash [..] accepted", "hash 0xdeadbeef rejected"));
+    }
+
+    #[tokio::test]
+    async fn test_session_expect_output() {
+        // Two trailing blank lines: the first flips on multiline mode, the second
+        // finds the buffer already ending in a blank line and executes it.
+        Session::new(FakeInterpreterProvider)
+            .input("new x in { Nil }")
+            .input("")
+            .input("")
+            .expect_output("new x in [..]")
+            .run()
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_session_multiline_batching() {
+        Session::new(FakeInterpreterProvider)
+            .multiline(true)
+            .input("new x in {")
+            .input("  Nil")
+            .input("}")
+            .input("")
+            .input("")
+            .expect_output("new x in {\n  Nil\n}")
+            .run()
+            .await;
+    }
+}
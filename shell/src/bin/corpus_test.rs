@@ -0,0 +1,132 @@
+//! CLI front-end for running the Rholang corpus as a test suite: discovers
+//! `.rho` files under a root directory, optionally filters and/or shuffles
+//! them, then interprets each one concurrently through a
+//! `RholangParserInterpreterProvider`, printing a pass/fail summary and
+//! exiting nonzero on any failure. `--shuffle` (or passing `--seed`) orders
+//! the run with a seeded `SmallRng`; the seed used is always printed, so an
+//! order-dependent failure can be reproduced with `--seed <n>`.
+
+use anyhow::Result;
+use clap::Parser;
+use futures::stream::{self, StreamExt};
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use shell::providers::{InterpretationResult, InterpreterProvider, RholangParserInterpreterProvider};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Directory to search for `.rho` files
+    #[arg(default_value = "rholang-parser/corpus")]
+    root: PathBuf,
+
+    /// Only run files whose path contains this substring
+    #[arg(short, long)]
+    filter: Option<String>,
+
+    /// Shuffle the run order deterministically using this seed (implies `--shuffle`)
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Shuffle the run order; without `--seed`, a seed is chosen and printed
+    #[arg(long, default_value_t = false)]
+    shuffle: bool,
+
+    /// Number of files interpreted concurrently, defaults to the number of CPUs
+    #[arg(short, long)]
+    concurrency: Option<usize>,
+}
+
+/// Recursively collect every `.rho` file under `root`
+fn discover_files(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        if !dir.is_dir() {
+            continue;
+        }
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "rho") {
+                files.push(path);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// A seed derived from the current time, used when `--shuffle` is passed without
+/// an explicit `--seed` -- still printed, so the run stays reproducible after the fact
+fn time_based_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as u64)
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let mut files = discover_files(&args.root)?;
+    if let Some(filter) = &args.filter {
+        files.retain(|path| path.to_string_lossy().contains(filter.as_str()));
+    }
+
+    if args.shuffle || args.seed.is_some() {
+        let seed = args.seed.unwrap_or_else(time_based_seed);
+        println!("Shuffling with seed {seed} (pass --seed {seed} to reproduce this order)");
+        let mut rng = SmallRng::seed_from_u64(seed);
+        files.shuffle(&mut rng);
+    }
+
+    println!("Running {} file(s)", files.len());
+
+    let concurrency = args
+        .concurrency
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+    let interpreter = Arc::new(RholangParserInterpreterProvider::new()?);
+
+    let results: Vec<Result<(PathBuf, bool, String)>> = stream::iter(files)
+        .map(|path| {
+            let interpreter = Arc::clone(&interpreter);
+            async move {
+                let content = tokio::fs::read_to_string(&path).await?;
+                let (passed, message) = match interpreter.interpret(&content).await {
+                    InterpretationResult::Success(message) => (true, message),
+                    InterpretationResult::Error(err) => (false, err.to_string()),
+                };
+                Ok((path, passed, message))
+            }
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+
+    let mut passed = 0;
+    let mut failed = 0;
+    for result in results {
+        let (path, ok, message) = result?;
+        if ok {
+            passed += 1;
+            println!("PASS {}", path.display());
+        } else {
+            failed += 1;
+            println!("FAIL {}: {}", path.display(), message);
+        }
+    }
+
+    println!("{passed} passed, {failed} failed");
+
+    if failed > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,97 @@
+//! An injectable-clock abstraction so process-management code (and its tests)
+//! don't have to call `tokio::time::sleep` directly: [`RholangParserInterpreterProvider`](crate::providers::RholangParserInterpreterProvider)
+//! sleeps through a [`Clock`] instead, and a test can swap in a [`MockClock`]
+//! whose time only advances when the test explicitly drives it, eliminating
+//! the `sleep(100ms)`-and-hope-it's-enough races real-wall-clock tests have.
+
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// Anything that can report the current time and be slept on
+#[async_trait]
+pub trait Clock: Send + Sync {
+    /// The current instant, per this clock's notion of time
+    fn now(&self) -> Instant;
+
+    /// Suspend the calling task until `duration` has elapsed on this clock
+    async fn sleep(&self, duration: Duration);
+}
+
+/// The default [`Clock`]: the real wall clock, backed by `tokio::time::sleep`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RealClock;
+
+#[async_trait]
+impl Clock for RealClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+/// A [`Clock`] whose time only advances when a test calls [`MockClock::advance`],
+/// so a provider's `sleep` calls resolve deterministically instead of racing the
+/// real wall clock.
+#[derive(Clone)]
+pub struct MockClock {
+    base: Instant,
+    elapsed: Arc<Mutex<Duration>>,
+    notify: Arc<Notify>,
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        MockClock {
+            base: Instant::now(),
+            elapsed: Arc::new(Mutex::new(Duration::ZERO)),
+            notify: Arc::new(Notify::new()),
+        }
+    }
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        MockClock::default()
+    }
+
+    /// Advance this clock by `duration`, waking any task currently blocked in
+    /// [`Clock::sleep`] whose deadline it crosses.
+    pub fn advance(&self, duration: Duration) {
+        let mut elapsed = self.elapsed.lock().unwrap_or_else(|e| e.into_inner());
+        *elapsed += duration;
+        drop(elapsed);
+        self.notify.notify_waiters();
+    }
+
+    fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.base + self.elapsed()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        let deadline = self.elapsed() + duration;
+        loop {
+            if self.elapsed() >= deadline {
+                return;
+            }
+            // Enroll as a waiter before re-checking the deadline, so an `advance`
+            // landing between the check above and this await isn't lost.
+            let notified = self.notify.notified();
+            if self.elapsed() >= deadline {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Tree-sitter-backed replacement for bracket-counting multiline heuristics: parses
+//! the accumulated buffer with the real Rholang grammar and classifies it by what, if
+//! anything, is wrong with it -- rather than hand-counting `{`/`(`/`[` the way
+//! `bracket_parser`/the old `multiline_helper::process_line_continuation` scanner did.
+//!
+//! The key distinction this relies on is tree-sitter's own: a `MISSING` node is
+//! synthesized only when the parser knows exactly what token it expected next and hit
+//! the end of input looking for it (e.g. a closing `}`) -- the "hold the prompt open,
+//! more input will likely fix this" signal. A generic `ERROR` node is tree-sitter's
+//! catch-all for input it couldn't resync with any valid construct at all (e.g. a
+//! stray keyword); appending more lines won't turn that into something valid, so it's
+//! reported as [`Completeness::Invalid`] rather than held open forever.
+
+use tree_sitter::{Language, Node, Parser, Tree};
+
+/// How a parsed buffer relates to being ready to submit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Completeness {
+    /// Parses with no ERROR or MISSING nodes at all
+    Complete,
+    /// Parses with an ERROR node tree-sitter couldn't resync with a valid
+    /// construct -- more input won't fix this
+    Invalid,
+    /// Parses with a MISSING node expected exactly at the end of input --
+    /// hold the prompt open for more lines
+    Incomplete,
+}
+
+fn rholang_language() -> Language {
+    rholang_tree_sitter::LANGUAGE.into()
+}
+
+fn parse(source: &str) -> Tree {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&rholang_language())
+        .expect("Error loading Rholang parser");
+    parser
+        .parse(source, None)
+        .expect("Failed to produce syntax tree")
+}
+
+/// Whether any MISSING node under `node` is expected exactly at `source_len`
+fn has_unterminated_construct(node: Node, source_len: usize) -> bool {
+    if node.is_missing() && node.end_byte() == source_len {
+        return true;
+    }
+
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .any(|child| has_unterminated_construct(child, source_len))
+}
+
+/// Whether any (non-MISSING) ERROR node appears anywhere under `node`
+fn has_error(node: Node) -> bool {
+    if node.is_error() {
+        return true;
+    }
+
+    let mut cursor = node.walk();
+    node.children(&mut cursor).any(has_error)
+}
+
+/// Parse `source` with the Rholang grammar and classify it. Blank/whitespace-only
+/// input is always `Incomplete`, matching the REPL's existing behavior of waiting for
+/// real content before doing anything.
+pub fn is_complete(source: &str) -> Completeness {
+    if source.trim().is_empty() {
+        return Completeness::Incomplete;
+    }
+
+    let tree = parse(source);
+    let root = tree.root_node();
+
+    if has_unterminated_construct(root, source.len()) {
+        Completeness::Incomplete
+    } else if has_error(root) {
+        Completeness::Invalid
+    } else {
+        Completeness::Complete
+    }
+}
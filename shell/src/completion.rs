@@ -0,0 +1,356 @@
+//! Tab-completion sources for the REPL: dot-commands when the line begins
+//! with `.`, Rholang keywords otherwise -- keyed off the same `kw!`-validated
+//! grammar vocabulary this crate already compiles in (see
+//! [`rholang_helper`](crate::rholang_helper) for the other REPL entry
+//! point's name/channel completion).
+//!
+//! `rustyline_async::Readline` (used by [`crate::run_shell`]'s event loop)
+//! has no Tab-keypress hook the way `rustyline::Editor`'s `Helper` trait
+//! does, so for now this is surfaced through the `.complete <prefix>`
+//! special command (see [`crate::process_complete_command`]) rather than a
+//! real key binding -- the [`CompletionProvider`] trait is still the
+//! injectable, testable seam a future Tab handler would plug into.
+
+use rholang_tree_sitter_proc_macro::kw;
+
+/// Special commands recognized by `process_special_command`, offered as
+/// completions when the line being completed starts with `.`
+pub const DOT_COMMANDS: &[&str] = &[
+    ".help", ".mode", ".list", ".delete", ".del", ".reset", ".buffer", ".ps", ".kill", ".quit",
+    ".complete",
+];
+
+/// Rholang keywords offered as completions outside of dot-commands
+pub const RHOLANG_KEYWORDS: &[&str] = &["new", "for", "in", "match", "contract", "select", "bundle"];
+
+// Validated at compile time against the grammar this crate embeds, so a
+// renamed/removed keyword fails the build here instead of silently falling
+// out of sync with `RHOLANG_KEYWORDS`.
+const _: [u16; 7] = [
+    kw!("new"),
+    kw!("for"),
+    kw!("in"),
+    kw!("match"),
+    kw!("contract"),
+    kw!("select"),
+    kw!("bundle"),
+];
+
+/// A source of Tab-completion candidates for a partially-typed line
+pub trait Completer: Send + Sync {
+    /// Given the line typed so far, return the candidates matching its last
+    /// word (or the whole line, for dot-commands) together with the longest
+    /// common prefix among them -- the text a Tab handler would insert
+    /// outright when it's longer than what's already typed.
+    fn complete(&self, line: &str) -> (Vec<String>, String);
+}
+
+/// The default [`Completer`]: dot-commands when `line` starts with `.`,
+/// Rholang keywords otherwise, matching the current word's prefix.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultCompleter;
+
+impl Completer for DefaultCompleter {
+    fn complete(&self, line: &str) -> (Vec<String>, String) {
+        let candidates: Vec<String> = if line.starts_with('.') {
+            DOT_COMMANDS
+                .iter()
+                .filter(|command| command.starts_with(line))
+                .map(|command| command.to_string())
+                .collect()
+        } else {
+            let word = last_word(line);
+            RHOLANG_KEYWORDS
+                .iter()
+                .filter(|keyword| keyword.starts_with(word))
+                .map(|keyword| keyword.to_string())
+                .collect()
+        };
+
+        let common_prefix = common_prefix(&candidates);
+        (candidates, common_prefix)
+    }
+}
+
+/// The identifier-like word ending at the end of `line`
+fn last_word(line: &str) -> &str {
+    let start = line
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    &line[start..]
+}
+
+/// The longest prefix every one of `candidates` starts with, or `""` if there are none
+pub(crate) fn common_prefix(candidates: &[String]) -> String {
+    let mut iter = candidates.iter();
+    let mut prefix = match iter.next() {
+        Some(first) => first.clone(),
+        None => return String::new(),
+    };
+
+    for candidate in iter {
+        let common_len = prefix
+            .chars()
+            .zip(candidate.chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+        prefix.truncate(common_len);
+    }
+
+    prefix
+}
+
+/// Keywords offered as completions while inside an open bracket (a `for`/`match`/
+/// `contract` body, a `select` arm, ...) -- the constructs that can themselves appear
+/// as a process inside another one, as opposed to `bundle`, which only ever wraps a
+/// whole top-level process.
+const IN_BLOCK_KEYWORDS: &[&str] = &["new", "for", "in", "match", "contract", "select"];
+
+/// System channels built into every Rholang program, offered as completions for the
+/// name inside an `@"..."` quote (mirrors the list [`crate::rholang_helper`] offers
+/// for the other REPL entry point).
+const BUILTIN_CHANNELS: &[&str] = &[
+    "stdout",
+    "stdoutAck",
+    "stderr",
+    "stderrAck",
+    "rl",
+    "rs",
+    "deployId",
+    "deployerId",
+    "sysAuthToken",
+];
+
+/// One completion candidate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    /// The text to insert in place of the word being completed
+    pub text: String,
+}
+
+/// A source of cursor- and context-aware Tab-completion candidates, analogous to
+/// [`crate::providers::InterpreterProvider`]: callers that only need *some* completer
+/// (to exercise dispatch logic, say) can depend on this trait instead of a concrete
+/// implementation.
+pub trait CompletionProvider: Send + Sync {
+    /// Complete the word ending at `cursor` (a byte offset into `line`), using
+    /// `buffer` -- the multiline input accumulated so far, if any -- for context: the
+    /// names it has bound with `new`, and whether `line`'s prefix plus `buffer` are
+    /// currently inside an open bracket.
+    fn complete(&self, line: &str, cursor: usize, buffer: &[String]) -> Vec<Completion>;
+}
+
+/// The production [`CompletionProvider`]: dot-commands at top level when the line
+/// starts with `.`, Rholang keywords (narrowed to [`IN_BLOCK_KEYWORDS`] when inside an
+/// open bracket) plus any names already bound with `new` in `buffer`, otherwise.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultCompletionProvider;
+
+impl CompletionProvider for DefaultCompletionProvider {
+    fn complete(&self, line: &str, cursor: usize, buffer: &[String]) -> Vec<Completion> {
+        let typed = &line[..cursor.min(line.len())];
+
+        if buffer.is_empty() && typed.starts_with('.') {
+            return DOT_COMMANDS
+                .iter()
+                .filter(|command| command.starts_with(typed))
+                .map(|command| Completion {
+                    text: command.to_string(),
+                })
+                .collect();
+        }
+
+        let word = last_word(typed);
+
+        // `@"` immediately before the word being completed means we're naming a
+        // channel, e.g. `@"std` -- offer the builtin system channels instead of
+        // keywords/bound names, which can't appear there.
+        if typed.len() >= word.len() + 2 && typed[..typed.len() - word.len()].ends_with("@\"") {
+            let mut candidates: Vec<String> = BUILTIN_CHANNELS
+                .iter()
+                .filter(|channel| channel.starts_with(word))
+                .map(|channel| channel.to_string())
+                .collect();
+            candidates.sort();
+            candidates.dedup();
+            return candidates.into_iter().map(|text| Completion { text }).collect();
+        }
+
+        let joined = buffer.join("\n");
+        let inside_brackets = bracket_depth(&joined) + bracket_depth(typed) > 0;
+
+        let keywords: &[&str] = if inside_brackets {
+            IN_BLOCK_KEYWORDS
+        } else {
+            RHOLANG_KEYWORDS
+        };
+
+        let mut candidates: Vec<String> = keywords
+            .iter()
+            .filter(|keyword| keyword.starts_with(word))
+            .map(|keyword| keyword.to_string())
+            .collect();
+        candidates.extend(
+            new_bound_names(&joined)
+                .into_iter()
+                .filter(|name| name.starts_with(word)),
+        );
+        candidates.sort();
+        candidates.dedup();
+
+        candidates.into_iter().map(|text| Completion { text }).collect()
+    }
+}
+
+/// Net `{`/`(`/`[` open count minus `}`/`)`/`]` close count in `source`, ignoring
+/// delimiters that appear inside `"..."` string literals -- just enough bracket
+/// awareness to tell whether completion is happening inside an open block, without
+/// pulling in a full parse.
+fn bracket_depth(source: &str) -> i32 {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut chars = source.chars();
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    depth
+}
+
+/// Names bound by every `new <names> in` in `source`, found with a plain text scan
+/// rather than a full parse -- consistent with this module's other completions, which
+/// are all text-based rather than tree-sitter-based (see [`crate::rholang_helper`] for
+/// the tree-sitter-backed alternative used by the legacy synchronous REPL entry point).
+fn new_bound_names(source: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = source;
+
+    while let Some(new_at) = rest.find("new ") {
+        let after_new = &rest[new_at + "new ".len()..];
+        let Some(in_at) = after_new.find(" in") else {
+            break;
+        };
+
+        for decl in after_new[..in_at].split(',') {
+            if let Some(name) = decl.split_whitespace().next() {
+                names.push(name.to_string());
+            }
+        }
+
+        rest = &after_new[in_at..];
+    }
+
+    names
+}
+
+/// A [`CompletionProvider`] that always returns the same fixed candidates regardless
+/// of input, for exercising completion-dispatch logic in tests without depending on
+/// the real keyword list.
+pub struct FakeCompletionProvider {
+    candidates: Vec<&'static str>,
+}
+
+impl FakeCompletionProvider {
+    pub fn new(candidates: Vec<&'static str>) -> Self {
+        FakeCompletionProvider { candidates }
+    }
+}
+
+impl CompletionProvider for FakeCompletionProvider {
+    fn complete(&self, _line: &str, _cursor: usize, _buffer: &[String]) -> Vec<Completion> {
+        self.candidates
+            .iter()
+            .map(|candidate| Completion {
+                text: candidate.to_string(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_provider_completes_dot_commands_at_top_level() {
+        let provider = DefaultCompletionProvider;
+        let candidates = provider.complete(".h", 2, &[]);
+
+        assert_eq!(candidates, vec![Completion { text: ".help".to_string() }]);
+    }
+
+    #[test]
+    fn test_default_provider_offers_bundle_only_outside_brackets() {
+        let provider = DefaultCompletionProvider;
+        let candidates = provider.complete("bu", 2, &[]);
+
+        assert_eq!(candidates, vec![Completion { text: "bundle".to_string() }]);
+    }
+
+    #[test]
+    fn test_default_provider_narrows_to_in_block_keywords_inside_brackets() {
+        let provider = DefaultCompletionProvider;
+        let candidates = provider.complete("bu", 2, &["contract foo() = {".to_string()]);
+
+        assert!(candidates.is_empty());
+    }
+
+    #[test]
+    fn test_default_provider_completes_new_bound_names_from_buffer() {
+        let provider = DefaultCompletionProvider;
+        let candidates = provider.complete("st", 2, &["new stdout in {".to_string()]);
+
+        assert_eq!(candidates, vec![Completion { text: "stdout".to_string() }]);
+    }
+
+    #[test]
+    fn test_default_provider_completes_builtin_channels_after_at_quote() {
+        let provider = DefaultCompletionProvider;
+        let candidates = provider.complete("@\"std", 5, &[]);
+
+        assert_eq!(
+            candidates,
+            vec![
+                Completion { text: "stdout".to_string() },
+                Completion { text: "stdoutAck".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bracket_depth_ignores_delimiters_inside_strings() {
+        assert_eq!(bracket_depth(r#"new x in { stdout!("{") }"#), 0);
+    }
+
+    #[test]
+    fn test_fake_completion_provider_returns_fixed_candidates() {
+        let provider = FakeCompletionProvider::new(vec!["foo", "bar"]);
+        let candidates = provider.complete("anything", 0, &[]);
+
+        assert_eq!(
+            candidates,
+            vec![
+                Completion { text: "foo".to_string() },
+                Completion { text: "bar".to_string() },
+            ]
+        );
+    }
+}
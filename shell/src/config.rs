@@ -0,0 +1,135 @@
+//! Persistent shell preferences, loaded from a small versioned TOML file so a
+//! user's `histsize`/default-multiline/prompt choices survive between
+//! sessions instead of being re-specified as CLI flags every time. Mirrors
+//! [`history`](crate::history)'s own append-on-write-only philosophy: this
+//! file is read once at startup and only ever rewritten by an explicit
+//! `save` call, never implicitly.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Current [`ShellConfig`] file format version. Bump this if a future change
+/// needs to distinguish an old on-disk file from a fresh default, e.g. to
+/// migrate a renamed field.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Versioned, persisted shell preferences -- the settings `Args` otherwise
+/// has to take as CLI flags on every run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShellConfig {
+    /// Format version of this file, see [`CONFIG_VERSION`]
+    pub version: u32,
+    /// Maximum number of history entries retained, see [`history::DEFAULT_HISTORY_LIMIT`](crate::history::DEFAULT_HISTORY_LIMIT)
+    pub histsize: usize,
+    /// Whether the REPL starts in multiline mode by default
+    pub multiline: bool,
+    /// Prompt shown while awaiting a new top-level command
+    pub prompt: String,
+    /// Prompt shown while a multiline command is still being entered
+    pub continuation_prompt: String,
+}
+
+impl Default for ShellConfig {
+    fn default() -> Self {
+        ShellConfig {
+            version: CONFIG_VERSION,
+            histsize: crate::history::DEFAULT_HISTORY_LIMIT,
+            multiline: false,
+            prompt: ">>> ".to_string(),
+            continuation_prompt: "... ".to_string(),
+        }
+    }
+}
+
+impl ShellConfig {
+    /// Load the config from `path`, falling back to [`ShellConfig::default`]
+    /// if the file doesn't exist. A file that exists but fails to parse is
+    /// still an error, so a user typo doesn't silently revert to defaults.
+    pub fn load(path: &std::path::Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(ShellConfig::default());
+        }
+
+        let text = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&text)?)
+    }
+
+    /// Write this config to `path` as TOML, creating its parent directory if
+    /// necessary.
+    pub fn save(&self, path: &std::path::Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// The user's config directory, `$XDG_CONFIG_HOME` if set, else `$HOME/.config`
+fn user_config_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("XDG_CONFIG_HOME") {
+        if !dir.is_empty() {
+            return Some(PathBuf::from(dir));
+        }
+    }
+    std::env::var("HOME").ok().map(|home| PathBuf::from(home).join(".config"))
+}
+
+/// Default location for the config file: `rholang-shell/config.toml` under
+/// the user config directory. Returns `None` if neither `$XDG_CONFIG_HOME`
+/// nor `$HOME` is set.
+pub fn default_config_path() -> Option<PathBuf> {
+    user_config_dir().map(|dir| dir.join("rholang-shell").join("config.toml"))
+}
+
+/// Default location for the history file: `rholang-shell/history.txt` under
+/// the user config directory, as a fallback for when neither `--history-file`
+/// nor `$RHOLANG_HISTFILE` picked a path.
+pub fn default_history_path() -> Option<PathBuf> {
+    user_config_dir().map(|dir| dir.join("rholang-shell").join("history.txt"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_missing_file_returns_default() {
+        let dir = std::env::temp_dir().join("rholang_shell_config_test_missing");
+        let path = dir.join("config.toml");
+
+        let config = ShellConfig::load(&path).unwrap();
+        assert_eq!(config, ShellConfig::default());
+    }
+
+    #[test]
+    fn test_save_load_round_trips() {
+        let dir = std::env::temp_dir().join("rholang_shell_config_test_round_trip");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+
+        let config = ShellConfig {
+            version: CONFIG_VERSION,
+            histsize: 42,
+            multiline: true,
+            prompt: "rho> ".to_string(),
+            continuation_prompt: "  > ".to_string(),
+        };
+        config.save(&path).unwrap();
+
+        assert_eq!(ShellConfig::load(&path).unwrap(), config);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_load_rejects_unparseable_file() {
+        let dir = std::env::temp_dir().join("rholang_shell_config_test_invalid");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(&path, "not valid toml {{{").unwrap();
+
+        assert!(ShellConfig::load(&path).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
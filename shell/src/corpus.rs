@@ -0,0 +1,194 @@
+//! Bounded-concurrency corpus processing: a walker task recurses a directory tree
+//! and streams matching paths into a bounded channel rather than collecting them
+//! into a `Vec` up front, while a pool of worker tasks drains that channel and calls
+//! [`InterpreterProvider::interpret`] on each file concurrently. This keeps memory
+//! flat on huge trees and parallelizes parsing across files, unlike a sequential
+//! collect-then-process walk.
+
+use crate::providers::{InterpretationResult, InterpreterError, InterpreterProvider};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+
+/// Configuration for [`process_corpus`]
+#[derive(Debug, Clone)]
+pub struct ProcessConfig {
+    /// Number of worker tasks pulling paths off the walker's channel
+    pub concurrency: usize,
+    /// Whether the walker follows symlinked entries instead of skipping them
+    pub follow_symlinks: bool,
+    /// File extensions (without the leading `.`) the walker matches
+    pub extensions: Vec<String>,
+    /// Capacity of the bounded channel between the walker and the worker pool
+    pub channel_capacity: usize,
+}
+
+impl Default for ProcessConfig {
+    /// `concurrency` defaults to the number of available CPUs (falling back to 1
+    /// if that can't be determined), matching `.rho` files only
+    fn default() -> Self {
+        ProcessConfig {
+            concurrency: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            follow_symlinks: false,
+            extensions: vec!["rho".to_string()],
+            channel_capacity: 64,
+        }
+    }
+}
+
+/// The outcome of interpreting one file
+#[derive(Debug)]
+pub struct FileResult {
+    pub path: PathBuf,
+    pub result: InterpretationResult,
+}
+
+/// Aggregate counts plus the per-file results accumulated by [`process_corpus`]
+#[derive(Debug, Default)]
+pub struct CorpusReport {
+    pub success_count: usize,
+    pub error_count: usize,
+    pub files: Vec<FileResult>,
+}
+
+/// Whether `path`'s extension matches one of `extensions`
+fn matches_extension(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.iter().any(|wanted| wanted == ext))
+}
+
+/// Recurse `root`, sending every file matching `config.extensions` into `tx` as it's
+/// found. Stops early if the receiving end has been dropped (the worker pool exited).
+async fn walk(root: PathBuf, config: Arc<ProcessConfig>, tx: mpsc::Sender<PathBuf>) {
+    let mut stack = vec![root];
+
+    while let Some(dir) = stack.pop() {
+        let Ok(mut entries) = tokio::fs::read_dir(&dir).await else {
+            continue;
+        };
+
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let metadata = if config.follow_symlinks {
+                tokio::fs::metadata(&path).await
+            } else {
+                tokio::fs::symlink_metadata(&path).await
+            };
+            let Ok(metadata) = metadata else {
+                continue;
+            };
+
+            if metadata.is_dir() {
+                stack.push(path);
+            } else if metadata.is_file() && matches_extension(&path, &config.extensions) {
+                if tx.send(path).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Process every file matching `config.extensions` under `root` with `interpreter`: a
+/// walker task streams matching paths into a bounded channel (capacity
+/// `config.channel_capacity`) while `config.concurrency` worker tasks pull from it and
+/// call `interpreter.interpret` concurrently.
+pub async fn process_corpus<I>(root: &Path, interpreter: Arc<I>, config: ProcessConfig) -> CorpusReport
+where
+    I: InterpreterProvider + Send + Sync + 'static,
+{
+    let config = Arc::new(config);
+    let (tx, rx) = mpsc::channel(config.channel_capacity);
+    let rx = Arc::new(Mutex::new(rx));
+
+    let walker = tokio::spawn(walk(root.to_path_buf(), Arc::clone(&config), tx));
+
+    let mut workers = Vec::with_capacity(config.concurrency);
+    for _ in 0..config.concurrency {
+        let interpreter = Arc::clone(&interpreter);
+        let rx = Arc::clone(&rx);
+        workers.push(tokio::spawn(async move {
+            let mut results = Vec::new();
+            loop {
+                let path = rx.lock().await.recv().await;
+                let Some(path) = path else { break };
+
+                let result = match tokio::fs::read_to_string(&path).await {
+                    Ok(content) => interpreter.interpret(&content).await,
+                    Err(e) => InterpretationResult::Error(InterpreterError::other_error(format!(
+                        "Failed to read file {}: {}",
+                        path.display(),
+                        e
+                    ))),
+                };
+                results.push(FileResult { path, result });
+            }
+            results
+        }));
+    }
+
+    let _ = walker.await;
+
+    let mut report = CorpusReport::default();
+    for worker in workers {
+        let Ok(results) = worker.await else {
+            continue;
+        };
+        for file_result in results {
+            match &file_result.result {
+                InterpretationResult::Success(_) => report.success_count += 1,
+                InterpretationResult::Error(_) => report.error_count += 1,
+            }
+            report.files.push(file_result);
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::RholangParserInterpreterProvider;
+
+    #[tokio::test]
+    async fn test_process_corpus_counts_success_and_error_files() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join("rholang_shell_corpus_test");
+        tokio::fs::create_dir_all(&dir).await?;
+        tokio::fs::write(dir.join("good.rho"), "new channel in { @\"stdout\"!(\"Hello\") }").await?;
+        tokio::fs::write(dir.join("bad.rho"), "new channel in { @\"stdout\"!(\"Hello\")").await?;
+        tokio::fs::write(dir.join("ignored.txt"), "not rholang").await?;
+
+        let interpreter = Arc::new(RholangParserInterpreterProvider::new()?);
+        let report = process_corpus(&dir, interpreter, ProcessConfig::default()).await;
+
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(report.success_count, 1);
+        assert_eq!(report.error_count, 1);
+
+        tokio::fs::remove_dir_all(&dir).await.ok();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_process_corpus_respects_configured_extensions() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join("rholang_shell_corpus_test_ext");
+        tokio::fs::create_dir_all(&dir).await?;
+        tokio::fs::write(dir.join("a.rho"), "Nil").await?;
+        tokio::fs::write(dir.join("b.rholang"), "Nil").await?;
+
+        let interpreter = Arc::new(RholangParserInterpreterProvider::new()?);
+        let config = ProcessConfig {
+            extensions: vec!["rholang".to_string()],
+            ..ProcessConfig::default()
+        };
+        let report = process_corpus(&dir, interpreter, config).await;
+
+        assert_eq!(report.files.len(), 1);
+        assert_eq!(report.files[0].path.file_name().unwrap(), "b.rholang");
+
+        tokio::fs::remove_dir_all(&dir).await.ok();
+        Ok(())
+    }
+}
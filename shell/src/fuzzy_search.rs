@@ -0,0 +1,127 @@
+//! A fuzzy subsequence matcher for searching REPL command history.
+//!
+//! `rustyline_async::Readline` (used by [`crate::run_shell`]'s event loop) has
+//! no raw-keypress hook the way `rustyline::Editor`'s `Helper` trait does, so
+//! there's no way to intercept Ctrl+R mid-keystroke and swap the prompt into
+//! a live incremental-search mode the way a `rustyline`-backed shell could --
+//! the same limitation [`crate::completion`] documents for Tab-completion.
+//! For now this is surfaced through the `.history fuzzy <query>` special
+//! command (see [`crate::process_history_command`]) instead of a real key
+//! binding; [`fuzzy_rank`] is the injectable, testable seam a future
+//! Ctrl+R handler would plug into.
+
+/// Rank `candidates` against `query` by fuzzy subsequence match: every
+/// character of `query` must appear in a candidate, in order, but not
+/// necessarily contiguously. Candidates that don't contain `query` as a
+/// subsequence are dropped. Among those that match, candidates score higher
+/// when their matched characters are closer together and when they land on
+/// a word boundary (the start of the candidate, or just after `.` or
+/// whitespace) -- so `".ps"` ranks `".pipe stop"` above `"nap stew"` even
+/// though both contain the subsequence. Ties keep `candidates`' relative
+/// order. Matching is case-insensitive.
+pub fn fuzzy_rank<'a>(query: &str, candidates: &[&'a str]) -> Vec<(i64, &'a str)> {
+    let mut ranked: Vec<(i64, &str)> = candidates
+        .iter()
+        .filter_map(|candidate| score(query, candidate).map(|score| (score, *candidate)))
+        .collect();
+    ranked.sort_by(|a, b| b.0.cmp(&a.0));
+    ranked
+}
+
+/// Score `candidate` against `query`, or `None` if `query` isn't a
+/// subsequence of `candidate`.
+fn score(query: &str, candidate: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let candidate_chars: Vec<char> = candidate.chars().collect();
+    let mut query_chars = query.chars();
+    let mut query_char = query_chars.next();
+
+    let mut total = 0i64;
+    let mut last_matched_index: Option<usize> = None;
+
+    for (i, &c) in candidate_chars.iter().enumerate() {
+        let Some(q) = query_char else { break };
+        if !c.eq_ignore_ascii_case(&q) {
+            continue;
+        }
+
+        total += 10;
+
+        if let Some(last) = last_matched_index {
+            let gap = i - last - 1;
+            total += 5 - (gap as i64).min(5);
+        }
+
+        let at_word_boundary =
+            i == 0 || candidate_chars[i - 1] == '.' || candidate_chars[i - 1].is_whitespace();
+        if at_word_boundary {
+            total += 15;
+        }
+
+        last_matched_index = Some(i);
+        query_char = query_chars.next();
+    }
+
+    if query_char.is_some() {
+        None
+    } else {
+        Some(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_rank_requires_an_in_order_subsequence() {
+        let candidates = ["new x in { Nil }", "1 + 2", "for (y <- x) { Nil }"];
+        let ranked = fuzzy_rank("nwx", &candidates);
+
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].1, "new x in { Nil }");
+    }
+
+    #[test]
+    fn test_fuzzy_rank_excludes_non_matching_candidates() {
+        let candidates = ["1 + 2", "3 + 4"];
+        assert!(fuzzy_rank("xyz", &candidates).is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_rank_scores_contiguous_matches_higher_than_scattered() {
+        let candidates = ["nap stew", "new"];
+        let ranked = fuzzy_rank("new", &candidates);
+
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].1, "new");
+        assert_eq!(ranked[1].1, "nap stew");
+    }
+
+    #[test]
+    fn test_fuzzy_rank_scores_word_boundary_matches_higher() {
+        let candidates = [".pipe stop", "a.ps"];
+        let ranked = fuzzy_rank("ps", &candidates);
+
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].1, ".pipe stop");
+        assert_eq!(ranked[1].1, "a.ps");
+    }
+
+    #[test]
+    fn test_fuzzy_rank_is_case_insensitive() {
+        let candidates = ["New x in { Nil }"];
+        assert_eq!(fuzzy_rank("new", &candidates).len(), 1);
+    }
+
+    #[test]
+    fn test_fuzzy_rank_empty_query_matches_every_candidate_with_zero_score() {
+        let candidates = ["a", "b"];
+        let ranked = fuzzy_rank("", &candidates);
+
+        assert_eq!(ranked, vec![(0, "a"), (0, "b")]);
+    }
+}
@@ -0,0 +1,197 @@
+//! Persistent, searchable command history for the REPL, modeled on oursh's
+//! `repl/history` module: an in-memory, capacity-bounded [`VecDeque`] backed
+//! by an append-only file, flushed on every submitted command rather than
+//! only at exit. Multiline commands are stored on one line with their
+//! newlines escaped, so the file stays one-entry-per-line.
+
+use anyhow::Result;
+use std::collections::VecDeque;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+/// Default cap on the number of entries [`History`] retains, used by `Args`
+/// unless overridden with `--history-limit`
+pub const DEFAULT_HISTORY_LIMIT: usize = 1000;
+
+/// Escape embedded newlines (and the escape character itself) so a multiline
+/// command round-trips as a single line on disk
+fn encode(command: &str) -> String {
+    command.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+/// Reverse of [`encode`]
+fn decode(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('\\') => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// An append-only, capacity-bounded, deduplicated command history
+pub struct History {
+    entries: VecDeque<String>,
+    limit: usize,
+    path: Option<PathBuf>,
+}
+
+impl History {
+    /// An empty, in-memory-only history with no backing file, for tests
+    pub fn in_memory(limit: usize) -> Self {
+        History {
+            entries: VecDeque::new(),
+            limit,
+            path: None,
+        }
+    }
+
+    /// Load history from `path` if it exists (capped to `limit` entries, oldest
+    /// dropped first), and use `path` as the file subsequent `record` calls
+    /// append to.
+    pub fn load(path: impl Into<PathBuf>, limit: usize) -> Result<Self> {
+        let path = path.into();
+        let mut entries = VecDeque::new();
+
+        if path.exists() {
+            let file = std::fs::File::open(&path)?;
+            for line in std::io::BufReader::new(file).lines() {
+                entries.push_back(decode(&line?));
+            }
+            while entries.len() > limit {
+                entries.pop_front();
+            }
+        }
+
+        Ok(History {
+            entries,
+            limit,
+            path: Some(path),
+        })
+    }
+
+    /// Record `command`, skipping it if it's identical to the immediately
+    /// preceding entry, then append it to the backing file (if any) and drop
+    /// the oldest entry past `limit`.
+    pub fn record(&mut self, command: &str) -> Result<()> {
+        if self.entries.back().map(String::as_str) == Some(command) {
+            return Ok(());
+        }
+
+        self.entries.push_back(command.to_string());
+        while self.entries.len() > self.limit {
+            self.entries.pop_front();
+        }
+
+        if let Some(path) = &self.path {
+            let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", encode(command))?;
+        }
+
+        Ok(())
+    }
+
+    /// All entries, oldest first, numbered from 1 -- the same numbering `get`
+    /// and the `.history` special command use
+    pub fn entries(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.entries.iter().enumerate().map(|(i, entry)| (i + 1, entry.as_str()))
+    }
+
+    /// The entry at 1-based index `n`, as addressed by `!N`
+    pub fn get(&self, n: usize) -> Option<&str> {
+        n.checked_sub(1).and_then(|i| self.entries.get(i)).map(String::as_str)
+    }
+
+    /// The most recent entry containing `needle` as a substring, for the
+    /// Tab-completion and reverse-incremental-search (Ctrl-R) paths
+    pub fn search(&self, needle: &str) -> Option<&str> {
+        self.entries.iter().rev().find(|entry| entry.contains(needle)).map(String::as_str)
+    }
+
+    /// Every entry containing `needle` as a substring, newest first, numbered the same
+    /// way as `entries` -- backs the `.history search <substr>` special command
+    pub fn search_all(&self, needle: &str) -> Vec<(usize, &str)> {
+        self.entries()
+            .filter(|(_, entry)| entry.contains(needle))
+            .rev()
+            .collect()
+    }
+
+    /// Clear every entry, in memory and (if there's a backing file) on disk
+    pub fn clear(&mut self) -> Result<()> {
+        self.entries.clear();
+
+        if let Some(path) = &self.path {
+            std::fs::File::create(path)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_dedupes_consecutive_identical_entries() {
+        let mut history = History::in_memory(10);
+        history.record("Nil").unwrap();
+        history.record("Nil").unwrap();
+
+        assert_eq!(history.entries().collect::<Vec<_>>(), vec![(1, "Nil")]);
+    }
+
+    #[test]
+    fn test_record_caps_at_limit() {
+        let mut history = History::in_memory(2);
+        history.record("one").unwrap();
+        history.record("two").unwrap();
+        history.record("three").unwrap();
+
+        assert_eq!(
+            history.entries().collect::<Vec<_>>(),
+            vec![(1, "two"), (2, "three")]
+        );
+    }
+
+    #[test]
+    fn test_search_all_returns_matches_newest_first() {
+        let mut history = History::in_memory(10);
+        history.record("new x in { Nil }").unwrap();
+        history.record("1 + 2").unwrap();
+        history.record("new y in { Nil }").unwrap();
+
+        assert_eq!(
+            history.search_all("new"),
+            vec![(3, "new y in { Nil }"), (1, "new x in { Nil }")]
+        );
+    }
+
+    #[test]
+    fn test_clear_empties_entries() {
+        let mut history = History::in_memory(10);
+        history.record("Nil").unwrap();
+        history.clear().unwrap();
+
+        assert!(history.entries().next().is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_round_trips_multiline_commands() {
+        let command = "new x in {\n  Nil\n}";
+        assert_eq!(decode(&encode(command)), command);
+    }
+}
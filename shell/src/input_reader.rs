@@ -0,0 +1,182 @@
+//! A UTF-8-safe streaming line reader, for async byte sources (files, pipes, sockets)
+//! where a single read can land anywhere -- including in the middle of a multibyte
+//! character like `é` or `λ` -- unlike [`rustyline_async::Readline`]'s terminal input,
+//! which already decodes a full line before handing it to us.
+//!
+//! Splitting on a raw `\n` byte is always safe regardless of where reads land: every
+//! byte of a UTF-8 continuation or multibyte lead sequence is `>= 0x80`, so `\n`
+//! (`0x0A`) can never appear as part of one. [`Utf8LineReader`] takes advantage of
+//! this to buffer bytes across reads and only attempt to decode once it has found a
+//! complete line, rather than decoding each raw chunk as it arrives.
+
+use anyhow::{Context, Result};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Reads complete UTF-8 lines from an [`AsyncRead`] source, buffering any bytes read
+/// past the last newline seen so far instead of dropping or mis-decoding them.
+pub struct Utf8LineReader<R> {
+    reader: R,
+    pending: Vec<u8>,
+    eof: bool,
+}
+
+impl<R: AsyncRead + Unpin> Utf8LineReader<R> {
+    /// Wrap `reader`, with an empty pending buffer
+    pub fn new(reader: R) -> Self {
+        Utf8LineReader {
+            reader,
+            pending: Vec::new(),
+            eof: false,
+        }
+    }
+
+    /// The next complete line, with its trailing `\n` (and `\r`, if present) stripped,
+    /// or `None` once the source is exhausted and no partial line remains. A final
+    /// line with no trailing newline is still returned, flushed out on EOF rather than
+    /// dropped.
+    pub async fn read_line(&mut self) -> Result<Option<String>> {
+        loop {
+            if let Some(line) = self.take_buffered_line()? {
+                return Ok(Some(line));
+            }
+
+            if self.eof {
+                return if self.pending.is_empty() {
+                    Ok(None)
+                } else {
+                    let line = std::mem::take(&mut self.pending);
+                    decode(line).map(Some)
+                };
+            }
+
+            let mut chunk = [0u8; 4096];
+            let n = self.reader.read(&mut chunk).await?;
+            if n == 0 {
+                self.eof = true;
+                continue;
+            }
+            self.pending.extend_from_slice(&chunk[..n]);
+        }
+    }
+
+    /// Pull a complete line out of `pending` if one is buffered, leaving any bytes
+    /// past it (the start of the next line) in place
+    fn take_buffered_line(&mut self) -> Result<Option<String>> {
+        let Some(newline_at) = self.pending.iter().position(|&b| b == b'\n') else {
+            return Ok(None);
+        };
+
+        let mut line: Vec<u8> = self.pending.drain(..=newline_at).collect();
+        line.pop(); // the '\n' itself
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+
+        decode(line).map(Some)
+    }
+}
+
+/// Decode `bytes` as UTF-8, reporting (rather than panicking on) an invalid sequence
+fn decode(bytes: Vec<u8>) -> Result<String> {
+    String::from_utf8(bytes).context("input was not valid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use tokio::io::ReadBuf;
+
+    /// An `AsyncRead` that hands back exactly the chunks it was given, one per
+    /// `poll_read` call, so tests can control precisely where a read lands --
+    /// including mid-multibyte-character -- regardless of buffer size
+    struct ChunkedReader {
+        chunks: VecDeque<Vec<u8>>,
+    }
+
+    impl AsyncRead for ChunkedReader {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            if let Some(chunk) = self.chunks.pop_front() {
+                buf.put_slice(&chunk);
+            }
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    /// Feed `chunks` through a `Utf8LineReader` one `read()` at a time and collect
+    /// every line it yields, to check that splitting the same byte stream at
+    /// different points never changes the result
+    async fn collect_lines(chunks: &[&[u8]]) -> Result<Vec<String>> {
+        let stream = ChunkedReader {
+            chunks: chunks.iter().map(|chunk| chunk.to_vec()).collect(),
+        };
+        let mut reader = Utf8LineReader::new(stream);
+
+        let mut lines = Vec::new();
+        while let Some(line) = reader.read_line().await? {
+            lines.push(line);
+        }
+        Ok(lines)
+    }
+
+    #[tokio::test]
+    async fn test_read_line_reassembles_a_line_split_across_reads() -> Result<()> {
+        let lines = collect_lines(&[b"new x", b" in { Nil }\n"]).await?;
+        assert_eq!(lines, vec!["new x in { Nil }".to_string()]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_line_reassembles_a_multibyte_character_split_across_reads() -> Result<()> {
+        // 'é' is the two-byte UTF-8 sequence 0xC3 0xA9; split between the two bytes
+        let mut line = "caf".as_bytes().to_vec();
+        line.push(0xC3);
+        let mut rest = vec![0xA9];
+        rest.extend_from_slice(b"\n");
+
+        let lines = collect_lines(&[&line, &rest]).await?;
+        assert_eq!(lines, vec!["café".to_string()]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_line_reassembles_lambda_split_one_byte_at_a_time() -> Result<()> {
+        // 'λ' is the two-byte UTF-8 sequence 0xCE 0xBB
+        let mut bytes: Vec<&[u8]> = Vec::new();
+        let encoded = "λ\n".as_bytes().to_vec();
+        for byte in &encoded {
+            bytes.push(std::slice::from_ref(byte));
+        }
+
+        let lines = collect_lines(&bytes).await?;
+        assert_eq!(lines, vec!["λ".to_string()]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_line_flushes_a_final_line_with_no_trailing_newline_at_eof() -> Result<()> {
+        let lines = collect_lines(&[b"1 + 2\n", b"3 + 4"]).await?;
+        assert_eq!(lines, vec!["1 + 2".to_string(), "3 + 4".to_string()]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_line_returns_none_at_a_clean_eof() -> Result<()> {
+        let lines = collect_lines(&[b""]).await?;
+        assert!(lines.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_line_strips_carriage_returns() -> Result<()> {
+        let lines = collect_lines(&[b"Nil\r\n"]).await?;
+        assert_eq!(lines, vec!["Nil".to_string()]);
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+//! A non-interactive line protocol for driving the interpreter from another
+//! process, selected by `--json` instead of the interactive REPL -- the
+//! shell's embeddable counterpart to editor/CI integrations that want to
+//! exchange structured requests and responses over a stream rather than
+//! scrape prose output.
+//!
+//! Each input line is one JSON request object: `{"id": n, "command": "..."}`
+//! to evaluate `command`, or a control request -- `{"op": "list"}` to list
+//! running processes, `{"id": n, "op": "kill", "pid": k}` to kill one. `.`-commands
+//! have no meaning here; `.ps`/`.kill` are reached through `op` instead. Every
+//! request gets exactly one newline-delimited JSON response in reply:
+//! `{"id": n, "ok": bool, "output": string|null, "error": string|null}`. Unlike the
+//! REPL's completeness-driven buffering (see [`crate::process_single_line_input`]),
+//! each line is already a self-contained request, so there is no multiline
+//! buffering to bypass.
+
+use std::io::Write;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncRead;
+
+use crate::input_reader::Utf8LineReader;
+use crate::providers::{InterpretationResult, InterpreterProvider};
+
+/// One line of JSON input: either `command` (an evaluation request) or `op`
+/// (a control request), never both.
+#[derive(Debug, Deserialize)]
+struct JsonRequest {
+    id: Option<u64>,
+    command: Option<String>,
+    op: Option<String>,
+    pid: Option<usize>,
+}
+
+/// One line of JSON output, echoing the request's `id` (if any) alongside
+/// either `output` on success or `error` on failure.
+#[derive(Debug, Serialize)]
+struct JsonResponse {
+    id: Option<u64>,
+    ok: bool,
+    output: Option<String>,
+    error: Option<String>,
+}
+
+impl JsonResponse {
+    fn ok(id: Option<u64>, output: String) -> Self {
+        JsonResponse {
+            id,
+            ok: true,
+            output: Some(output),
+            error: None,
+        }
+    }
+
+    fn err(id: Option<u64>, error: impl Into<String>) -> Self {
+        JsonResponse {
+            id,
+            ok: false,
+            output: None,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// Handle one already-parsed `request`, producing the response to write back.
+async fn handle_request<I: InterpreterProvider>(
+    request: JsonRequest,
+    interpreter: &I,
+    timeout: Duration,
+) -> JsonResponse {
+    let JsonRequest { id, command, op, pid } = request;
+
+    if let Some(op) = op.as_deref() {
+        return match op {
+            "list" => match interpreter.list_processes() {
+                Ok(processes) => {
+                    let listed: Vec<_> = processes
+                        .into_iter()
+                        .map(|(pid, code)| serde_json::json!({ "pid": pid, "command": code }))
+                        .collect();
+                    match serde_json::to_string(&listed) {
+                        Ok(output) => JsonResponse::ok(id, output),
+                        Err(e) => JsonResponse::err(id, e.to_string()),
+                    }
+                }
+                Err(e) => JsonResponse::err(id, e.to_string()),
+            },
+            "kill" => match pid {
+                None => JsonResponse::err(id, "\"kill\" requires a \"pid\" field"),
+                Some(pid) => match interpreter.kill_process(pid) {
+                    Ok(true) => JsonResponse::ok(id, format!("process {pid} killed")),
+                    Ok(false) => JsonResponse::err(id, format!("process {pid} not found")),
+                    Err(e) => JsonResponse::err(id, e.to_string()),
+                },
+            },
+            other => JsonResponse::err(id, format!("unknown op: {other:?}")),
+        };
+    }
+
+    match command {
+        Some(command) => match interpreter.interpret_with_deadline(&command, timeout).await {
+            InterpretationResult::Success(output) => JsonResponse::ok(id, output),
+            InterpretationResult::Error(err) => JsonResponse::err(id, err.message),
+        },
+        None => JsonResponse::err(id, "request has neither \"command\" nor \"op\""),
+    }
+}
+
+/// Drive the JSON line protocol to completion: read requests from `reader` one
+/// line at a time, dispatch each through `interpreter` (under `timeout`), and
+/// write one response line to `stdout` per request. Returns once `reader`
+/// reaches EOF.
+pub async fn run_json_mode<R, W, I>(reader: R, stdout: &mut W, interpreter: &I, timeout: Duration) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: Write,
+    I: InterpreterProvider,
+{
+    let mut lines = Utf8LineReader::new(reader);
+
+    while let Some(line) = lines.read_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<JsonRequest>(&line) {
+            Ok(request) => handle_request(request, interpreter, timeout).await,
+            Err(e) => JsonResponse::err(None, format!("invalid JSON request: {e}")),
+        };
+
+        writeln!(stdout, "{}", serde_json::to_string(&response)?)?;
+    }
+
+    Ok(())
+}
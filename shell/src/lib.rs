@@ -1,11 +1,33 @@
+pub mod clock;
+pub mod completeness;
+pub mod completion;
+pub mod config;
+pub mod corpus;
+pub mod fuzzy_search;
+pub mod history;
+pub mod input_reader;
+pub mod json_mode;
+pub mod multiline_helper;
+pub mod permissions;
+pub mod pipe;
+pub mod plugin;
 pub mod providers;
+pub mod resolver;
+pub mod rho_golden;
+pub mod rho_test;
+pub mod rholang_helper;
+pub mod supervisor;
 
 use anyhow::Result;
-use bracket_parser::{BracketParser, BracketState};
 use clap::Parser;
-use providers::InterpreterProvider;
+use completion::CompletionProvider;
+use futures::stream::{FuturesUnordered, StreamExt};
+use providers::{InterpretationResult, InterpreterError, InterpreterProvider, JobId};
 use rustyline_async::{Readline, ReadlineEvent};
 use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -13,6 +35,205 @@ pub struct Args {
     /// Enable multiline mode
     #[arg(short, long, default_value_t = false)]
     pub multiline: bool,
+
+    /// Watch a Rholang file and re-evaluate it whenever it changes on disk
+    #[arg(short, long)]
+    pub watch: Option<PathBuf>,
+
+    /// Maximum time, in seconds, a single evaluation is allowed to run before it is
+    /// treated as timed out
+    #[arg(short, long, default_value_t = 30)]
+    pub timeout: u64,
+
+    /// Run every `.rho` file matched by this glob as a `//=`-annotated golden test
+    /// (see [`rho_golden`]) and exit with a nonzero status if any failed, instead
+    /// of starting the REPL
+    #[arg(long)]
+    pub test: Option<String>,
+
+    /// Parse this file and report every recovery-point diagnostic (see
+    /// [`rholang_parser::RholangParser::parse_with_recovery`]) instead of starting
+    /// the REPL, exiting with a nonzero status if any were found
+    #[arg(long)]
+    pub check: Option<PathBuf>,
+
+    /// Output format for evaluation results: human-readable text, or one JSON object
+    /// per evaluation (newline-delimited) for editor/tooling integration
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// Run the non-interactive JSON line protocol on stdin/stdout instead of the REPL --
+    /// see [`json_mode`] for the request/response shapes
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+
+    /// Allow sends on `rho:io:*` system channels
+    #[arg(long, default_value_t = false, conflicts_with = "deny_io")]
+    pub allow_io: bool,
+
+    /// Deny sends on `rho:io:*` system channels
+    #[arg(long, default_value_t = false)]
+    pub deny_io: bool,
+
+    /// Allow sends on `rho:registry:*` system channels
+    #[arg(long, default_value_t = false, conflicts_with = "deny_registry")]
+    pub allow_registry: bool,
+
+    /// Deny sends on `rho:registry:*` system channels
+    #[arg(long, default_value_t = false)]
+    pub deny_registry: bool,
+
+    /// Allow sends on `rho:rchain:deploy*` system channels
+    #[arg(long, default_value_t = false, conflicts_with = "deny_deploy")]
+    pub allow_deploy: bool,
+
+    /// Deny sends on `rho:rchain:deploy*` system channels
+    #[arg(long, default_value_t = false)]
+    pub deny_deploy: bool,
+
+    /// Allow every system channel capability (`rho:io`, `rho:registry`, `rho:rchain:deploy`)
+    #[arg(long, default_value_t = false)]
+    pub allow_all: bool,
+
+    /// Path to the persistent command-history file. If left at its default, falls
+    /// back to `$RHOLANG_HISTFILE`, then to a path under the user config directory
+    /// (see [`config::default_history_path`])
+    #[arg(long, default_value = DEFAULT_HISTORY_FILE)]
+    pub history_file: PathBuf,
+
+    /// Maximum number of history entries retained. If left at its default, falls
+    /// back to `histsize` in the persisted [`config::ShellConfig`]
+    #[arg(long, default_value_t = history::DEFAULT_HISTORY_LIMIT)]
+    pub history_limit: usize,
+
+    /// Path to the persisted shell-preferences file (`histsize`, default multiline,
+    /// prompt strings). Defaults to a path under the user config directory
+    /// (see [`config::default_config_path`])
+    #[arg(long)]
+    pub config_file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// `Args::history_file`'s clap default -- used to detect whether the user passed
+/// `--history-file` explicitly, so it can take priority over `$RHOLANG_HISTFILE`
+/// and the config-dir default
+const DEFAULT_HISTORY_FILE: &str = "history.txt";
+
+/// Non-interactive alternatives to the REPL, selected as a subcommand
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Evaluate a single `.rho` file once and exit, instead of starting the REPL
+    Run {
+        /// Path to the `.rho` file to evaluate
+        path: PathBuf,
+    },
+}
+
+impl Args {
+    /// Build the [`permissions::Permissions`] these flags describe. `run_shell`
+    /// calls this itself to wrap its `InterpreterProvider` in
+    /// [`permissions::PermissionedInterpreterProvider`]; exposed separately so
+    /// other callers can build the same value without constructing a provider.
+    pub fn permissions(&self) -> permissions::Permissions {
+        let mut builder = permissions::Permissions::builder();
+        if self.allow_all {
+            builder = builder.allow_all();
+        }
+        if self.allow_io {
+            builder = builder.allow_io();
+        }
+        if self.deny_io {
+            builder = builder.deny_io();
+        }
+        if self.allow_registry {
+            builder = builder.allow_registry();
+        }
+        if self.deny_registry {
+            builder = builder.deny_registry();
+        }
+        if self.allow_deploy {
+            builder = builder.allow_deploy();
+        }
+        if self.deny_deploy {
+            builder = builder.deny_deploy();
+        }
+        builder.build()
+    }
+}
+
+/// How an `InterpretationResult` is rendered to `stdout`
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The existing human-readable `Display`-based rendering
+    Text,
+    /// One JSON object per evaluation, newline-delimited
+    Json,
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputFormat::Text => write!(f, "text"),
+            OutputFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Which input loop [`run_shell`] drives, selected by `--json`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LoopConfig {
+    /// The interactive REPL: readline, history, multiline buffering, `.`-commands
+    Interactive,
+    /// The non-interactive JSON line protocol over stdin/stdout -- see [`json_mode`]
+    Json,
+}
+
+impl From<&Args> for LoopConfig {
+    fn from(args: &Args) -> Self {
+        if args.json {
+            LoopConfig::Json
+        } else {
+            LoopConfig::Interactive
+        }
+    }
+}
+
+/// A position in the multiline input buffer: the line `.goto`/`.edit`/`.insert`/
+/// `.delete` act on when called without an explicit line number. `col` is reserved for
+/// within-line editing a future key-bound cursor could use; nothing reads it yet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Point {
+    /// 0-based index into the buffer
+    pub line: usize,
+    /// 0-based byte offset into that line
+    pub col: usize,
+}
+
+/// A background job's outcome, once its [`providers::JobHandle`] resolves
+#[derive(Debug, Clone)]
+enum JobState {
+    Running,
+    Finished(InterpretationResult),
+}
+
+/// One entry in `run_shell`'s job table: the code a `&`-suffixed line launched,
+/// and its state as of the last time its `JobHandle` was polled
+#[derive(Debug, Clone)]
+struct Job {
+    code: String,
+    state: JobState,
+}
+
+impl Job {
+    fn state_label(&self) -> &'static str {
+        match &self.state {
+            JobState::Running => "running",
+            JobState::Finished(InterpretationResult::Success(_)) => "done",
+            JobState::Finished(InterpretationResult::Error(_)) => "failed",
+        }
+    }
 }
 
 pub fn help_message() -> String {
@@ -20,20 +241,280 @@ pub fn help_message() -> String {
         + "\n  .help, - Show this help message"
         + "\n  .mode - Toggle between multiline and single line modes"
         + "\n  .list - List all edited lines"
+        + "\n  .list -n - List all edited lines, numbered, with the cursor marked"
+        + "\n  .goto N - Move the cursor to line N"
+        + "\n  .edit N <text> - Replace line N with <text>"
+        + "\n  .insert N <text> - Insert <text> as a new line N"
+        + "\n  .delete N - Remove a specific line"
         + "\n  .delete or .del - Remove the last edited line"
         + "\n  .reset or Ctrl+C - Interrupt current input (in multiline mode: clear buffer)"
         + "\n  .ps - List all running processes"
         + "\n  .kill <index> - Kill a running process by index"
+        + "\n  .complete <prefix> - Show Tab-completion candidates for a dot-command or Rholang keyword"
+        + "\n  .load <path> - Replay a Rholang script, one top-level command at a time"
+        + "\n  .load -c <path> - Same, but continue past evaluation failures instead of stopping"
+        + "\n  .pipe <cmd> - Pipe the next evaluation's output through an external command"
+        + "\n  .plugin load <path> - Spawn an external plugin and register the commands it declares"
+        + "\n  .plugin list - List loaded plugins"
+        + "\n  .plugin kill <pid> - Kill a loaded plugin by pid"
+        + "\n  .history - List numbered command history"
+        + "\n  .history clear - Clear command history"
+        + "\n  .history search <substr> - Search command history, newest first"
+        + "\n  .history fuzzy <query> - Fuzzy subsequence search over command history, best match first"
+        + "\n  .test <file.rho> - Run a //=-annotated golden test file and report pass/fail"
+        + "\n  <code> & - Run <code> as a background job instead of waiting for it"
+        + "\n  .jobs - List background jobs and their state (running/done/failed)"
+        + "\n  .fg <n> - Block until background job n completes, then print its output"
+        + "\n  .wait [<n>] - Wait for job n (or every running job) to finish"
+        + "\n  !N - Re-run history entry N"
+        + "\n  .!N - Load history entry N into the edit buffer for re-execution or editing"
         + "\n  .quit - Exit the shell"
 }
 
+/// Handle `.history`, `.history clear`, `.history search <substr>`, and
+/// `.history fuzzy <query>`: list every entry (numbered from 1, the same numbering
+/// `!N` indexes into), clear the history, list entries matching a substring
+/// newest-first, or rank entries by fuzzy subsequence match (see
+/// [`fuzzy_search::fuzzy_rank`]) best-first. Returns `true` if `command` was one of
+/// these, so the caller knows not to also dispatch it to `process_special_command`.
+pub fn process_history_command<W: Write>(
+    command: &str,
+    stdout: &mut W,
+    history: &mut history::History,
+) -> Result<bool> {
+    if command == ".history" {
+        let mut entries = history.entries().peekable();
+        if entries.peek().is_none() {
+            writeln!(stdout, "No history")?;
+        } else {
+            for (n, entry) in entries {
+                writeln!(stdout, "{:4}  {}", n, entry)?;
+            }
+        }
+        return Ok(true);
+    }
+
+    if command == ".history clear" {
+        history.clear()?;
+        writeln!(stdout, "History cleared")?;
+        return Ok(true);
+    }
+
+    if let Some(needle) = command.strip_prefix(".history search ") {
+        let matches = history.search_all(needle);
+        if matches.is_empty() {
+            writeln!(stdout, "No history entries matching {:?}", needle)?;
+        } else {
+            for (n, entry) in matches {
+                writeln!(stdout, "{:4}  {}", n, entry)?;
+            }
+        }
+        return Ok(true);
+    }
+
+    if let Some(query) = command.strip_prefix(".history fuzzy ") {
+        let entries: Vec<&str> = history.entries().map(|(_, entry)| entry).collect();
+        let ranked = fuzzy_search::fuzzy_rank(query, &entries);
+        if ranked.is_empty() {
+            writeln!(stdout, "No history entries fuzzy-matching {:?}", query)?;
+        } else {
+            for (score, entry) in ranked {
+                writeln!(stdout, "{:5}  {}", score, entry)?;
+            }
+        }
+        return Ok(true);
+    }
+
+    Ok(false)
+}
+
+/// Handle the `.test <file.rho>` command: run `path` through [`rho_golden::run_golden_file`]
+/// and report whether it passed. Returns `true` if `command` was a `.test` command,
+/// so the caller knows not to also dispatch it to `process_special_command`.
+pub async fn process_test_command<W: Write, I: InterpreterProvider>(
+    command: &str,
+    stdout: &mut W,
+    interpreter: &I,
+) -> Result<bool> {
+    let path = match command.strip_prefix(".test ") {
+        Some(path) => path.trim(),
+        None => return Ok(false),
+    };
+
+    match rho_golden::run_golden_file(std::path::Path::new(path), interpreter).await {
+        Ok(result) if result.passed => writeln!(stdout, "ok   {path}")?,
+        Ok(result) => writeln!(stdout, "FAIL {path} -- {}", result.failure.unwrap_or_default())?,
+        Err(e) => writeln!(stdout, "Error running {path}: {e}")?,
+    }
+
+    Ok(true)
+}
+
+/// Handle the `.complete <prefix>` command: look up `prefix`'s completions via
+/// `completer` -- using `buffer` for context, so a name already bound by a
+/// `new ... in` entered earlier in the current multiline command completes too --
+/// and print the candidates plus their common prefix. Returns `true` if `command`
+/// was a `.complete` command, so the caller knows not to also dispatch it to
+/// `process_special_command`.
+pub fn process_complete_command<W: Write, P: CompletionProvider>(
+    command: &str,
+    stdout: &mut W,
+    completer: &P,
+    buffer: &[String],
+) -> Result<bool> {
+    let prefix = match command.strip_prefix(".complete ") {
+        Some(prefix) => prefix,
+        None => return Ok(false),
+    };
+
+    let candidates = completer.complete(prefix, prefix.len(), buffer);
+    if candidates.is_empty() {
+        writeln!(stdout, "No completions for {:?}", prefix)?;
+    } else {
+        let texts: Vec<String> = candidates.into_iter().map(|c| c.text).collect();
+        writeln!(stdout, "Completions: {}", texts.join(", "))?;
+        writeln!(stdout, "Common prefix: {}", completion::common_prefix(&texts))?;
+    }
+    Ok(true)
+}
+
+/// Handle `.load <path>` (and `.load -c <path>`): read `path` and replay it one
+/// top-level command at a time through `interpreter`, via [`load_commands`], printing
+/// each result as it comes in. On the first evaluation failure, reports
+/// `<path>:<line>: ...` and stops there -- unless the `-c` flag was given, in which case
+/// it reports the failure and continues with the rest of the file. Returns `true` if
+/// `command` was a `.load` command, so the caller knows not to also dispatch it to
+/// `process_special_command`.
+pub async fn process_load_command<W: Write, I: InterpreterProvider>(
+    command: &str,
+    stdout: &mut W,
+    interpreter: &I,
+    format: OutputFormat,
+) -> Result<bool> {
+    let (continue_on_error, path) = if let Some(rest) = command.strip_prefix(".load -c ") {
+        (true, rest.trim())
+    } else if let Some(rest) = command.strip_prefix(".load ") {
+        (false, rest.trim())
+    } else {
+        return Ok(false);
+    };
+
+    let file = match tokio::fs::File::open(path).await {
+        Ok(file) => file,
+        Err(e) => {
+            writeln!(stdout, "Error reading {}: {}", path, e)?;
+            return Ok(true);
+        }
+    };
+
+    let mut commands = Vec::new();
+    load_commands(file, |line_no, command| {
+        commands.push((line_no, command.to_string()));
+        Ok(())
+    })
+    .await?;
+
+    for (line_no, command) in commands {
+        let result = interpreter.interpret(&command).await;
+        print_result(stdout, &result, format)?;
+
+        if result.is_error() {
+            writeln!(stdout, "{}:{}: evaluation failed", path, line_no)?;
+            if !continue_on_error {
+                writeln!(stdout, "Aborting .load")?;
+                break;
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+/// Handle `.plugin load <path>`, `.plugin list`, and `.plugin kill <pid>`: spawn (or
+/// list, or kill) an external plugin process talking JSON-RPC over its stdin/stdout --
+/// see [`plugin`] for the handshake and call protocol. Returns `true` if `command` was
+/// a `.plugin` command, so the caller knows not to also dispatch it to
+/// `process_special_command`.
+pub async fn process_plugin_command<W: Write>(
+    command: &str,
+    stdout: &mut W,
+    registry: &plugin::PluginRegistry,
+) -> Result<bool> {
+    if let Some(path) = command.strip_prefix(".plugin load ") {
+        let path = path.trim();
+        match registry.load(path).await {
+            Ok((pid, commands)) => writeln!(
+                stdout,
+                "Loaded plugin {path} (pid {pid}), providing: {}",
+                commands.join(", ")
+            )?,
+            Err(e) => writeln!(stdout, "Error loading plugin {path}: {e}")?,
+        }
+        return Ok(true);
+    }
+
+    if command == ".plugin list" {
+        let plugins = registry.list();
+        if plugins.is_empty() {
+            writeln!(stdout, "No plugins loaded")?;
+        } else {
+            writeln!(stdout, "Loaded plugins:")?;
+            for (pid, description) in plugins {
+                writeln!(stdout, "  {pid}: {description}")?;
+            }
+        }
+        return Ok(true);
+    }
+
+    if let Some(rest) = command.strip_prefix(".plugin kill ") {
+        match rest.trim().parse::<usize>() {
+            Ok(pid) => match registry.kill(pid) {
+                Ok(true) => writeln!(stdout, "Plugin {pid} killed")?,
+                Ok(false) => writeln!(stdout, "Plugin {pid} not found")?,
+                Err(e) => writeln!(stdout, "Error killing plugin {pid}: {e}")?,
+            },
+            Err(_) => writeln!(stdout, "Invalid plugin pid: {}", rest.trim())?,
+        }
+        return Ok(true);
+    }
+
+    Ok(false)
+}
+
+/// If `line`'s leading whitespace-separated token names a command a loaded plugin
+/// registered, forward the rest of the line to it (see [`plugin::PluginRegistry::dispatch`])
+/// and print its result, returning `true` so the caller skips the normal Rholang
+/// evaluation path for this line. Returns `false`, printing nothing, for any line that
+/// doesn't start with a registered plugin command.
+pub async fn dispatch_plugin_command<W: Write>(
+    line: &str,
+    stdout: &mut W,
+    registry: &plugin::PluginRegistry,
+) -> Result<bool> {
+    let command = match line.split_whitespace().next() {
+        Some(command) if registry.has_command(command) => command,
+        _ => return Ok(false),
+    };
+    let args = line[command.len()..].trim_start();
+
+    match registry.dispatch(command, args).await {
+        Ok(Some(result)) => writeln!(stdout, "{result}")?,
+        Ok(None) => writeln!(stdout, "Unknown plugin command: {command}")?,
+        Err(e) => writeln!(stdout, "Error calling plugin command {command}: {e}")?,
+    }
+    Ok(true)
+}
+
 /// Process a special command (starting with '.')
 /// Returns true if the command was processed, false otherwise
 pub fn process_special_command<W: Write, I: InterpreterProvider>(
     command: &str,
     buffer: &mut Vec<String>,
+    cursor: &mut Point,
     multiline: &mut bool,
+    pending_pipe: &mut Option<String>,
     stdout: &mut W,
+    prompt: &str,
     update_prompt: impl FnOnce(&str) -> Result<()>,
     interpreter: &I,
 ) -> Result<bool> {
@@ -41,6 +522,17 @@ pub fn process_special_command<W: Write, I: InterpreterProvider>(
         return Ok(false);
     }
 
+    if let Some(rest) = command.strip_prefix(".pipe ") {
+        let target = rest.trim();
+        if target.is_empty() {
+            writeln!(stdout, "Usage: .pipe <cmd>")?;
+        } else {
+            *pending_pipe = Some(target.to_string());
+            writeln!(stdout, "Next evaluation's output will be piped through: {target}")?;
+        }
+        return Ok(false);
+    }
+
     // Check for .kill command with an index
     if command.starts_with(".kill ") {
         let parts: Vec<&str> = command.splitn(2, ' ').collect();
@@ -59,6 +551,58 @@ pub fn process_special_command<W: Write, I: InterpreterProvider>(
         }
     }
 
+    if let Some(rest) = command.strip_prefix(".goto ") {
+        match rest.trim().parse::<usize>() {
+            Ok(n) if n >= 1 && n <= buffer.len() => {
+                cursor.line = n - 1;
+                cursor.col = 0;
+                writeln!(stdout, "Moved cursor to line {n}")?;
+            }
+            _ => writeln!(stdout, "No such line: {}", rest.trim())?,
+        }
+        return Ok(false);
+    }
+
+    if let Some(rest) = command.strip_prefix(".edit ") {
+        let (n, text) = rest.split_once(' ').unwrap_or((rest, ""));
+        match n.parse::<usize>() {
+            Ok(n) if n >= 1 && n <= buffer.len() => {
+                buffer[n - 1] = text.to_string();
+                cursor.line = n - 1;
+                cursor.col = 0;
+                writeln!(stdout, "Replaced line {n}")?;
+            }
+            _ => writeln!(stdout, "No such line: {n}")?,
+        }
+        return Ok(false);
+    }
+
+    if let Some(rest) = command.strip_prefix(".insert ") {
+        let (n, text) = rest.split_once(' ').unwrap_or((rest, ""));
+        match n.parse::<usize>() {
+            Ok(n) if n >= 1 && n <= buffer.len() + 1 => {
+                buffer.insert(n - 1, text.to_string());
+                cursor.line = n - 1;
+                cursor.col = 0;
+                writeln!(stdout, "Inserted line {n}")?;
+            }
+            _ => writeln!(stdout, "No such line: {n}")?,
+        }
+        return Ok(false);
+    }
+
+    if let Some(rest) = command.strip_prefix(".delete ") {
+        match rest.trim().parse::<usize>() {
+            Ok(n) if n >= 1 && n <= buffer.len() => {
+                let removed = buffer.remove(n - 1);
+                cursor.line = cursor.line.min(buffer.len().saturating_sub(1));
+                writeln!(stdout, "Removed line {n}: {removed}")?;
+            }
+            _ => writeln!(stdout, "No such line: {}", rest.trim())?,
+        }
+        return Ok(false);
+    }
+
     match command {
         ".help" => {
             writeln!(stdout, "{}", help_message())?;
@@ -70,7 +614,7 @@ pub fn process_special_command<W: Write, I: InterpreterProvider>(
                 "Switched to multiline mode (enter twice to execute)"
             } else {
                 buffer.clear();
-                update_prompt(">>> ")?;
+                update_prompt(prompt)?;
                 "Switched to single line mode"
             };
             writeln!(stdout, "{mode_message}")?;
@@ -85,9 +629,17 @@ pub fn process_special_command<W: Write, I: InterpreterProvider>(
                 writeln!(stdout, "{line}")?;
             }
         }
+        ".list -n" => {
+            writeln!(stdout, "Edited lines:")?;
+            for (i, line) in buffer.iter().enumerate() {
+                let marker = if i == cursor.line { ">" } else { " " };
+                writeln!(stdout, "{marker}{:4}  {line}", i + 1)?;
+            }
+        }
         ".delete" | ".del" => {
             if !buffer.is_empty() {
                 let removed = buffer.pop().unwrap();
+                cursor.line = cursor.line.min(buffer.len().saturating_sub(1));
                 writeln!(stdout, "Removed last line: {removed}")?;
             } else {
                 writeln!(stdout, "Buffer is empty, nothing to delete")?;
@@ -95,7 +647,8 @@ pub fn process_special_command<W: Write, I: InterpreterProvider>(
         }
         ".reset" => {
             buffer.clear();
-            update_prompt(">>> ")?;
+            *cursor = Point::default();
+            update_prompt(prompt)?;
             writeln!(stdout, "Buffer reset")?;
         }
         ".buffer" => {
@@ -121,11 +674,16 @@ pub fn process_special_command<W: Write, I: InterpreterProvider>(
     Ok(false) // Don't exit
 }
 
-/// Process a line of input in multiline mode
-/// Returns Some(command) if a command is ready to be executed, None otherwise
+/// Process a line of input in multiline mode.
+/// Returns Some(command) if a command is ready to be executed, None otherwise.
+/// Auto-executes as soon as the accumulated buffer parses as a complete Rholang
+/// program (see [`completeness::is_complete`]); an empty line remains a forced-submit
+/// escape hatch for input tree-sitter never considers complete.
 pub fn process_multiline_input(
     line: String,
     buffer: &mut Vec<String>,
+    prompt: &str,
+    continuation_prompt: &str,
     update_prompt: impl FnOnce(&str) -> Result<()>,
 ) -> Result<Option<String>> {
     if buffer.is_empty() {
@@ -133,70 +691,108 @@ pub fn process_multiline_input(
             return Ok(None);
         }
         *buffer = vec![line];
-        update_prompt("... ")?;
-        return Ok(None);
+    } else if !line.is_empty() {
+        buffer.push(line);
+    } else {
+        let command = buffer.join("\n");
+        buffer.clear();
+        update_prompt(prompt)?;
+        return Ok(Some(command));
     }
 
-    if !line.is_empty() {
-        buffer.push(line);
-        return Ok(None);
+    let joined = buffer.join("\n");
+    if completeness::is_complete(&joined) == completeness::Completeness::Complete {
+        buffer.clear();
+        update_prompt(prompt)?;
+        return Ok(Some(joined));
     }
 
-    let command = buffer.join("\n");
-    buffer.clear();
-    update_prompt(">>> ")?;
-    Ok(Some(command))
+    update_prompt(continuation_prompt)?;
+    Ok(None)
 }
 
-/// Process a line of input in single line mode
-/// Returns Some(command) if a command is ready to be executed, None otherwise
-/// If the line ends inside brackets, switches to multiline mode and returns None
+/// Process a line of input in single line mode.
+/// Returns Some(command) if a command is ready to be executed, None otherwise.
+/// If the line parses with an unterminated construct (e.g. an unclosed bracket),
+/// switches to multiline mode and returns None; otherwise -- complete or simply
+/// invalid -- executes it immediately and lets `interpret` surface the real error.
 pub fn process_single_line_input(
     line: String,
     buffer: &mut Vec<String>,
     multiline: &mut bool,
+    continuation_prompt: &str,
     update_prompt: impl FnOnce(&str) -> Result<()>,
 ) -> Result<Option<String>> {
     if line.is_empty() {
         return Ok(None);
     }
 
-    // Check if the line ends inside brackets
-    let mut bracket_parser = match BracketParser::new() {
-        Ok(parser) => parser,
-        Err(_e) => {
-            // If we can't create the parser, just execute the line normally
-            // This is a fallback in case of an error
-            return Ok(Some(line));
-        }
-    };
-
-    let state = bracket_parser.get_final_state(&line);
-
-    if state == BracketState::Inside {
-        // Line ends inside brackets, switch to multiline mode
+    if completeness::is_complete(&line) == completeness::Completeness::Incomplete {
         *multiline = true;
         buffer.push(line);
-        update_prompt("... ")?;
+        update_prompt(continuation_prompt)?;
         return Ok(None);
     }
 
-    // Line doesn't end inside brackets, execute it immediately
     Ok(Some(line))
 }
 
+/// Read `reader` line by line through a [`Utf8LineReader`](input_reader::Utf8LineReader)
+/// and replay it through the same completeness-driven buffering
+/// `process_single_line_input`/`process_multiline_input` use for the REPL, calling
+/// `dispatch` with each fully-formed command (and the 1-based line it completed on) as
+/// soon as it's ready -- this is the shared implementation behind `.load <path>`. Reading
+/// through `Utf8LineReader` rather than splitting raw chunks by hand means a slow or
+/// chunked source (a large file, a pipe) can never land a read in the middle of a
+/// multibyte character. A final line left sitting in an unterminated buffer at EOF is
+/// still dispatched, so the interpreter gets a chance to surface the real parse error
+/// instead of it being silently dropped. `dispatch` returning `Err` aborts the read at
+/// that line.
+pub async fn load_commands<R: tokio::io::AsyncRead + Unpin>(
+    reader: R,
+    mut dispatch: impl FnMut(usize, &str) -> Result<()>,
+) -> Result<()> {
+    let mut lines = input_reader::Utf8LineReader::new(reader);
+    let mut buffer = Vec::new();
+    let mut multiline = false;
+    let mut last_line = 0;
+
+    let mut line_no = 0;
+    while let Some(line) = lines.read_line().await? {
+        line_no += 1;
+        last_line = line_no;
+
+        let command = if multiline {
+            process_multiline_input(line, &mut buffer, ">>> ", "... ", |_| Ok(()))?
+        } else {
+            process_single_line_input(line, &mut buffer, &mut multiline, "... ", |_| Ok(()))?
+        };
+
+        if let Some(command) = command {
+            dispatch(line_no, &command)?;
+        }
+    }
+
+    if !buffer.is_empty() {
+        dispatch(last_line, &buffer.join("\n"))?;
+    }
+
+    Ok(())
+}
+
 /// Handle an interrupt event (Ctrl+C)
 pub fn handle_interrupt<W: Write, I: InterpreterProvider>(
     buffer: &mut Vec<String>,
     multiline: bool,
     stdout: &mut W,
+    prompt: &str,
     update_prompt: impl FnOnce(&str) -> Result<()>,
     interpreter: &I,
 ) -> Result<()> {
     // Clear buffer in multiline mode
     if multiline {
         buffer.clear();
-        update_prompt(">>> ")?;
+        update_prompt(prompt)?;
     }
 
     // Kill all running processes
@@ -213,18 +809,327 @@ pub fn handle_interrupt<W: Write, I: InterpreterProvider>(
     Ok(())
 }
 
-/// Run the shell with the provided interpreter provider
-pub async fn run_shell<I: InterpreterProvider>(
+/// Interval used to poll the watched file for changes, and to coalesce bursts
+/// of writes (e.g. an editor's save-then-rewrite) into a single re-run.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Clear the terminal screen so a watch re-run reads like a live dashboard
+fn clear_screen<W: Write>(stdout: &mut W) -> Result<()> {
+    write!(stdout, "\x1B[2J\x1B[1;1H")?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Write an `InterpretationResult` to `stdout` in the requested `format`
+fn print_result<W: Write>(
+    stdout: &mut W,
+    result: &InterpretationResult,
+    format: OutputFormat,
+) -> Result<()> {
+    match format {
+        OutputFormat::Json => writeln!(stdout, "{}", serde_json::to_string(result)?)?,
+        OutputFormat::Text => match result {
+            InterpretationResult::Success(output) => writeln!(stdout, "Output: {output}")?,
+            InterpretationResult::Error(err) => {
+                write!(stdout, "Error: {}", err.message)?;
+                if let Some(position) = &err.position {
+                    write!(stdout, " at {}", position)?;
+                }
+                writeln!(stdout)?;
+            }
+        },
+    }
+    Ok(())
+}
+
+/// Send `result`'s rendered text into `process` (the success output, or the error
+/// message on failure) and print back whatever lines it writes to its own output --
+/// the shared implementation behind `.pipe <cmd>`, generic over [`pipe::LineWriter`]/
+/// [`pipe::LineReader`] so it can be exercised with [`pipe::FakePipe`] in tests instead
+/// of a real subprocess. Write/read failures are reported the same way an interpreter
+/// error is, rather than propagated up to abort the shell.
+pub async fn pipe_result_through<P, W>(result: &InterpretationResult, process: &mut P, stdout: &mut W) -> Result<()>
+where
+    P: pipe::LineWriter + pipe::LineReader,
+    W: Write,
+{
+    let text = match result {
+        InterpretationResult::Success(output) => output.clone(),
+        InterpretationResult::Error(err) => err.message.clone(),
+    };
+
+    if let Err(e) = process.write_line(&text).await {
+        writeln!(stdout, "Error piping output: {}", e)?;
+        return Ok(());
+    }
+    if let Err(e) = process.close().await {
+        writeln!(stdout, "Error piping output: {}", e)?;
+        return Ok(());
+    }
+
+    loop {
+        match process.read_line().await {
+            Ok(Some(line)) => writeln!(stdout, "{line}")?,
+            Ok(None) => break,
+            Err(e) => {
+                writeln!(stdout, "Error piping output: {}", e)?;
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Load the watched file and run it through the interpreter, printing the result
+async fn run_watched_file<W: Write, I: InterpreterProvider>(
+    path: &std::path::Path,
+    interpreter: &I,
+    stdout: &mut W,
+    format: OutputFormat,
+) -> Result<()> {
+    clear_screen(stdout)?;
+    writeln!(stdout, "Watching {}", path.display())?;
+
+    let code = match std::fs::read_to_string(path) {
+        Ok(code) => code,
+        Err(e) => {
+            writeln!(stdout, "Error reading {}: {}", path.display(), e)?;
+            return Ok(());
+        }
+    };
+
+    let result = interpreter.interpret(&code).await;
+    print_result(stdout, &result, format)
+}
+
+/// Watch `path`'s parent directory for filesystem events via `notify`, forwarding a
+/// unit signal on an unbounded channel whenever `path` itself is touched. `notify`'s
+/// watcher callback runs on its own thread, so this bridges it into the async world
+/// the same way the rest of this crate bridges blocking work -- by handing events to
+/// a `tokio::sync::mpsc` channel. The `RecommendedWatcher` must be kept alive for as
+/// long as the receiver is read from, so it's returned alongside it rather than dropped.
+fn spawn_file_watcher(
+    path: &std::path::Path,
+) -> Result<(notify::RecommendedWatcher, tokio::sync::mpsc::UnboundedReceiver<()>)> {
+    use notify::Watcher;
+
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let watched = path.to_path_buf();
+
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+            if event.paths.iter().any(|changed| changed == &watched) {
+                let _ = tx.send(());
+            }
+        }
+    })?;
+
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| std::path::Path::new("."));
+    watcher.watch(parent, notify::RecursiveMode::NonRecursive)?;
+
+    Ok((watcher, rx))
+}
+
+/// Watch `path` for filesystem modifications, re-evaluating it through `interpreter`
+/// on every change. Debounces bursts of events arriving within `WATCH_DEBOUNCE` into a
+/// single re-run, and keeps watching (rather than exiting) on parse/runtime failure.
+/// Between runs, tells `interpreter` to clean up every process from the previous run
+/// (`kill_all_processes`) before re-evaluating, so a long-running or runaway process
+/// from the last version of the file doesn't keep executing alongside the new one.
+pub async fn run_watch<I: InterpreterProvider>(
+    path: PathBuf,
+    interpreter: I,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut stdout = std::io::stdout();
+    run_watched_file(&path, &interpreter, &mut stdout, format).await?;
+
+    let (_watcher, mut changes) = spawn_file_watcher(&path)?;
+
+    while changes.recv().await.is_some() {
+        // Coalesce any further events that arrive while we were noticing this one
+        loop {
+            tokio::select! {
+                event = changes.recv() => if event.is_none() { return Ok(()) },
+                _ = tokio::time::sleep(WATCH_DEBOUNCE) => break,
+            }
+        }
+
+        interpreter.kill_all_processes()?;
+        run_watched_file(&path, &interpreter, &mut stdout, format).await?;
+    }
+
+    Ok(())
+}
+
+/// Evaluate `path` once through `interpreter` and print its result in `format` -- the
+/// non-interactive counterpart to `--watch` and the REPL, for `shell run path.rho` and
+/// CI use. Returns `Err` if the interpretation itself produced an error, so a failing
+/// `.rho` file makes the process exit non-zero.
+pub async fn run_once<I: InterpreterProvider>(
+    path: &std::path::Path,
+    interpreter: &I,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut stdout = std::io::stdout();
+    let code = std::fs::read_to_string(path)?;
+    let result = interpreter.interpret(&code).await;
+    print_result(&mut stdout, &result, format)?;
+
+    if result.is_error() {
+        anyhow::bail!("{}: interpretation failed", path.display());
+    }
+
+    Ok(())
+}
+
+/// Parse `path` with [`rholang_parser::RholangParser::parse_with_recovery`] and print
+/// every recovery-point diagnostic it found, one per line. Unlike [`run_once`], this
+/// never runs the interpreter -- it only reports what the parser could and couldn't
+/// make sense of, so `--check` stays useful even against code the interpreter backend
+/// would reject for unrelated reasons (missing capabilities, a down external process).
+pub fn run_check(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    let code = std::fs::read_to_string(path)?;
+    let parser = rholang_parser::RholangParser::new();
+    let (_procs, errors) = parser.parse_with_recovery(&code);
+
+    for error in &errors {
+        println!("{}", error);
+    }
+
+    if !errors.is_empty() {
+        anyhow::bail!("{}: {} recovery error(s)", path.display(), errors.len());
+    }
+
+    Ok(())
+}
+
+/// Drive a single evaluation under a `timeout_secs` deadline, also cancelling it early if
+/// Ctrl+C is pressed while it is in flight. Either case surfaces as an `InterpretationResult::Error`
+/// rather than aborting the shell, so the REPL drops back to the prompt with history intact.
+async fn run_with_timeout_and_ctrl_c<I: InterpreterProvider>(
+    interpreter: &I,
+    command: &str,
+    timeout_secs: u64,
+) -> InterpretationResult {
+    let token = CancellationToken::new();
+    let eval = interpreter.interpret_cancellable(command, token.clone());
+
+    tokio::select! {
+        outcome = tokio::time::timeout(Duration::from_secs(timeout_secs), eval) => {
+            outcome.unwrap_or_else(|_| {
+                token.cancel();
+                InterpretationResult::Error(InterpreterError::timeout_error(format!(
+                    "Evaluation timed out after {timeout_secs} seconds"
+                )))
+            })
+        }
+        _ = tokio::signal::ctrl_c() => {
+            token.cancel();
+            InterpretationResult::Error(InterpreterError::cancellation_error(
+                "Evaluation was cancelled with Ctrl+C",
+            ))
+        }
+    }
+}
+
+/// Drive `jobs_in_flight` until the job identified by `id` finishes, recording
+/// its result in `jobs` along the way (other jobs that happen to finish first
+/// are recorded too, so their next `.jobs`/`.fg`/`.wait` sees current state).
+async fn wait_for_job(
+    id: JobId,
+    jobs: &mut std::collections::HashMap<JobId, Job>,
+    jobs_in_flight: &mut FuturesUnordered<providers::JobHandle<'_>>,
+) {
+    while !matches!(jobs.get(&id).map(|job| &job.state), Some(JobState::Finished(_))) {
+        match jobs_in_flight.next().await {
+            Some((finished_id, result)) => {
+                if let Some(job) = jobs.get_mut(&finished_id) {
+                    job.state = JobState::Finished(result);
+                }
+            }
+            None => break,
+        }
+    }
+}
+
+/// Run the shell with the provided interpreter provider, wrapped in
+/// [`permissions::PermissionedInterpreterProvider`] so every `--allow-*`/`--deny-*`
+/// flag in `args` is actually enforced against it, not just parsed.
+pub async fn run_shell<I: InterpreterProvider + Sync>(
     args: Args,
     interpreter: I,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let interpreter = permissions::PermissionedInterpreterProvider::new(interpreter, args.permissions());
+
+    if let Some(Command::Run { path }) = args.command.clone() {
+        return run_once(&path, &interpreter, args.format).await;
+    }
+
+    if let Some(path) = args.watch.clone() {
+        return run_watch(path, interpreter, args.format).await;
+    }
+
+    if let Some(pattern) = args.test.clone() {
+        let mut stdout = std::io::stdout();
+        rho_golden::run_batch(&pattern, &interpreter, &mut stdout).await?;
+        return Ok(());
+    }
+
+    if let Some(path) = args.check.clone() {
+        return run_check(&path);
+    }
+
+    if let LoopConfig::Json = LoopConfig::from(&args) {
+        let mut stdout = std::io::stdout();
+        json_mode::run_json_mode(tokio::io::stdin(), &mut stdout, &interpreter, Duration::from_secs(args.timeout))
+            .await?;
+        return Ok(());
+    }
+
     writeln!(std::io::stdout(), "{}", help_message())?;
 
-    let prompt = ">>> ".to_string();
+    let config_path = args.config_file.clone().or_else(config::default_config_path);
+    let shell_config = match &config_path {
+        Some(path) => config::ShellConfig::load(path)?,
+        None => config::ShellConfig::default(),
+    };
+
+    let prompt = shell_config.prompt.clone();
+    let continuation_prompt = shell_config.continuation_prompt.clone();
+
+    let history_file = if args.history_file != PathBuf::from(DEFAULT_HISTORY_FILE) {
+        args.history_file.clone()
+    } else if let Some(path) = std::env::var("RHOLANG_HISTFILE").ok().filter(|path| !path.is_empty()) {
+        PathBuf::from(path)
+    } else {
+        config::default_history_path().unwrap_or_else(|| PathBuf::from(DEFAULT_HISTORY_FILE))
+    };
+    let history_limit = if args.history_limit != history::DEFAULT_HISTORY_LIMIT {
+        args.history_limit
+    } else {
+        shell_config.histsize
+    };
 
     let (mut rl, mut stdout) = Readline::new(prompt.clone())?;
     let mut buffer: Vec<String> = Vec::new();
-    let mut multiline = args.multiline;
+    let mut cursor = Point::default();
+    let mut pending_pipe: Option<String> = None;
+    let mut multiline = args.multiline || shell_config.multiline;
+    let completer = completion::DefaultCompletionProvider;
+    let mut history = history::History::load(&history_file, history_limit)?;
+    for (_, entry) in history.entries() {
+        rl.add_history_entry(entry.to_string());
+    }
+    let plugins = plugin::PluginRegistry::new();
+
+    let mut jobs: std::collections::HashMap<JobId, Job> = std::collections::HashMap::new();
+    let mut jobs_in_flight = FuturesUnordered::new();
+    let mut next_job_id = 1usize;
 
     rl.should_print_line_on(true, false);
 
@@ -234,12 +1139,128 @@ pub async fn run_shell<I: InterpreterProvider>(
                 Ok(ReadlineEvent::Line(line)) => {
                     let line = line.trim().to_string();
 
+                    // Expand `!N` into history entry N before anything else sees the line
+                    let line = match line.strip_prefix('!').and_then(|n| n.parse::<usize>().ok()) {
+                        Some(n) => match history.get(n) {
+                            Some(entry) => entry.to_string(),
+                            None => {
+                                writeln!(stdout, "No such history entry: {}", n)?;
+                                continue;
+                            }
+                        },
+                        None => line,
+                    };
+
+                    if process_complete_command(&line, &mut stdout, &completer, &buffer)? {
+                        continue;
+                    }
+
+                    if process_history_command(&line, &mut stdout, &mut history)? {
+                        continue;
+                    }
+
+                    if process_load_command(&line, &mut stdout, &interpreter, args.format).await? {
+                        continue;
+                    }
+
+                    if process_test_command(&line, &mut stdout, &interpreter).await? {
+                        continue;
+                    }
+
+                    if process_plugin_command(&line, &mut stdout, &plugins).await? {
+                        continue;
+                    }
+
+                    if dispatch_plugin_command(&line, &mut stdout, &plugins).await? {
+                        continue;
+                    }
+
+                    if let Some(rest) = line.strip_prefix(".!") {
+                        match rest.trim().parse::<usize>() {
+                            Ok(n) => match history.get(n) {
+                                Some(entry) => {
+                                    buffer = entry.split('\n').map(String::from).collect();
+                                    cursor = Point::default();
+                                    multiline = true;
+                                    rl.update_prompt(&continuation_prompt)?;
+                                    writeln!(stdout, "Loaded history entry {n} into the buffer for editing")?;
+                                }
+                                None => writeln!(stdout, "No such history entry: {n}")?,
+                            },
+                            Err(_) => writeln!(stdout, "Usage: .!<n>")?,
+                        }
+                        continue;
+                    }
+
+                    if line == ".jobs" {
+                        if jobs.is_empty() {
+                            writeln!(stdout, "No background jobs")?;
+                        } else {
+                            let mut ids: Vec<JobId> = jobs.keys().copied().collect();
+                            ids.sort();
+                            for id in ids {
+                                let job = &jobs[&id];
+                                writeln!(stdout, "[{id}] {}  {}", job.state_label(), job.code)?;
+                            }
+                        }
+                        continue;
+                    }
+
+                    if let Some(rest) = line.strip_prefix(".fg ") {
+                        match rest.trim().parse::<usize>() {
+                            Ok(n) => {
+                                let id = JobId(n);
+                                if jobs.contains_key(&id) {
+                                    wait_for_job(id, &mut jobs, &mut jobs_in_flight).await;
+                                    if let Some(JobState::Finished(result)) = jobs.get(&id).map(|job| &job.state) {
+                                        print_result(&mut stdout, result, args.format)?;
+                                    }
+                                } else {
+                                    writeln!(stdout, "No such job: {n}")?;
+                                }
+                            }
+                            Err(_) => writeln!(stdout, "Usage: .fg <job id>")?,
+                        }
+                        continue;
+                    }
+
+                    if let Some(rest) = line.strip_prefix(".wait") {
+                        let rest = rest.trim();
+                        if rest.is_empty() {
+                            while !jobs_in_flight.is_empty() {
+                                if let Some((id, result)) = jobs_in_flight.next().await {
+                                    if let Some(job) = jobs.get_mut(&id) {
+                                        job.state = JobState::Finished(result);
+                                    }
+                                }
+                            }
+                            writeln!(stdout, "All jobs finished")?;
+                        } else {
+                            match rest.parse::<usize>() {
+                                Ok(n) => {
+                                    let id = JobId(n);
+                                    if jobs.contains_key(&id) {
+                                        wait_for_job(id, &mut jobs, &mut jobs_in_flight).await;
+                                        writeln!(stdout, "Job {n} finished")?;
+                                    } else {
+                                        writeln!(stdout, "No such job: {n}")?;
+                                    }
+                                }
+                                Err(_) => writeln!(stdout, "Usage: .wait [<job id>]")?,
+                            }
+                        }
+                        continue;
+                    }
+
                     // Process special commands
                     let should_exit = process_special_command(
                         &line,
                         &mut buffer,
+                        &mut cursor,
                         &mut multiline,
+                        &mut pending_pipe,
                         &mut stdout,
+                        &prompt,
                         |prompt| Ok(rl.update_prompt(prompt)?),
                         &interpreter,
                     )?;
@@ -253,12 +1274,15 @@ pub async fn run_shell<I: InterpreterProvider>(
                     }
 
                     rl.add_history_entry(line.clone());
+                    history.record(&line)?;
 
                     // Process input based on mode
                     let command_option = if multiline {
                         process_multiline_input(
                             line,
                             &mut buffer,
+                            &prompt,
+                            &continuation_prompt,
                             |prompt| Ok(rl.update_prompt(prompt)?),
                         )?
                     } else {
@@ -266,17 +1290,47 @@ pub async fn run_shell<I: InterpreterProvider>(
                             line,
                             &mut buffer,
                             &mut multiline,
+                            &continuation_prompt,
                             |prompt| Ok(rl.update_prompt(prompt)?),
                         )?
                     };
 
                     // Execute command if one is ready
                     if let Some(command) = command_option {
-                        writeln!(stdout, "Executing code: {command}")?;
-                        let result = interpreter.interpret(&command).await;
-                        match result {
-                            Ok(output) => writeln!(stdout, "Output: {output}")?,
-                            Err(e) => writeln!(stdout, "Error interpreting line: {e}")?,
+                        if let Some(code) = command.trim_end().strip_suffix('&') {
+                            let code = code.trim_end().to_string();
+                            let id = JobId(next_job_id);
+                            next_job_id += 1;
+                            writeln!(stdout, "[job {id}] pid {id}")?;
+                            jobs.insert(
+                                id,
+                                Job {
+                                    code: code.clone(),
+                                    state: JobState::Running,
+                                },
+                            );
+                            jobs_in_flight.push(interpreter.spawn(id, &code));
+                        } else {
+                            writeln!(stdout, "Executing code: {command}")?;
+                            let result = run_with_timeout_and_ctrl_c(&interpreter, &command, args.timeout).await;
+
+                            if let Some(pipe_command) = pending_pipe.take() {
+                                let mut parts = pipe_command.split_whitespace();
+                                match parts.next() {
+                                    Some(program) => {
+                                        let args: Vec<String> = parts.map(str::to_string).collect();
+                                        match pipe::PipedProcess::spawn(program, &args) {
+                                            Ok(mut process) => {
+                                                pipe_result_through(&result, &mut process, &mut stdout).await?
+                                            }
+                                            Err(e) => writeln!(stdout, "Error spawning {}: {}", pipe_command, e)?,
+                                        }
+                                    }
+                                    None => writeln!(stdout, "Usage: .pipe <cmd>")?,
+                                }
+                            } else {
+                                print_result(&mut stdout, &result, args.format)?;
+                            }
                         }
                     }
                 }
@@ -288,6 +1342,7 @@ pub async fn run_shell<I: InterpreterProvider>(
                         &mut buffer,
                         multiline,
                         &mut stdout,
+                        &prompt,
                         |prompt| Ok(rl.update_prompt(prompt)?),
                         &interpreter,
                     )?;
@@ -297,9 +1352,34 @@ pub async fn run_shell<I: InterpreterProvider>(
                     writeln!(stdout, "Error: {e:?}")?;
                     break;
                 }
+            },
+            (id, result) = async {
+                if jobs_in_flight.is_empty() {
+                    std::future::pending().await
+                } else {
+                    jobs_in_flight.next().await.expect("jobs_in_flight is non-empty")
+                }
+            } => {
+                writeln!(
+                    stdout,
+                    "[job {id}] {}",
+                    if result.is_success() { "Done" } else { "Failed" }
+                )?;
+                if let Some(job) = jobs.get_mut(&id) {
+                    job.state = JobState::Finished(result);
+                }
             }
         }
     }
+
+    // Drain every in-flight process before exiting, however the loop above was
+    // broken out of (Eof, Ctrl+D, `.quit`, or a readline error), so a process
+    // started just before exit is never orphaned.
+    let mut supervisor = supervisor::Supervisor::new(Duration::from_secs(5));
+    supervisor.register(supervisor::ProviderComponent::new("interpreter", &interpreter));
+    supervisor.register(plugins);
+    supervisor.shutdown().await?;
+
     rl.flush()?;
     Ok(())
 }
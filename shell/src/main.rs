@@ -1,11 +1,23 @@
 use anyhow::Result;
 use clap::Parser;
 
-use shell::{providers::RholangParserInterpreterProvider, run_shell, Args};
+use shell::{
+    providers::{FakeInterpreterProvider, RholangParserInterpreterProvider},
+    run_shell, Args,
+};
+
+/// Set by `shell-test-support`'s `pty::AppBuilder` so pty-driven integration tests can
+/// drive this binary over a real tty without needing a live Rholang interpreter backend
+const FAKE_INTERPRETER_ENV_VAR: &str = "SHELL_FAKE_INTERPRETER";
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
+
+    if std::env::var_os(FAKE_INTERPRETER_ENV_VAR).is_some() {
+        return run_shell(args, FakeInterpreterProvider).await;
+    }
+
     let interpreter = RholangParserInterpreterProvider::new()?;
     run_shell(args, interpreter).await
 }
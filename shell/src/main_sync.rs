@@ -1,5 +1,7 @@
 use rustyline::error::ReadlineError;
-use rustyline::{DefaultEditor, Result};
+use rustyline::history::DefaultHistory;
+use rustyline::{Editor, Result};
+use shell::rholang_helper::RholangHelper;
 
 #[allow(dead_code)]
 fn fake_interpreter(line: String) -> Result<String> {
@@ -10,8 +12,8 @@ fn fake_interpreter(line: String) -> Result<String> {
 
 #[allow(dead_code)]
 fn main() -> Result<()> {
-    // `()` can be used when no completer is required
-    let mut rl = DefaultEditor::new()?;
+    let mut rl: Editor<RholangHelper, DefaultHistory> = Editor::new()?;
+    rl.set_helper(Some(RholangHelper));
     #[cfg(feature = "with-file-history")]
     if rl.load_history("history.txt").is_err() {
         println!("No previous history.");
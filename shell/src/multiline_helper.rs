@@ -29,6 +29,114 @@ pub fn process_line(line: &str, buffer: &mut String, in_multiline_mode: &mut boo
     }
 }
 
+/// Delimiter/quote/comment state carried between successive [`process_line_continuation`]
+/// calls, so a single malformed paste (an unterminated string, an unbalanced bracket)
+/// doesn't leave the prompt waiting forever -- the blank-line escape hatch always forces
+/// a submit regardless of what this state says.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContinuationState {
+    /// Net `{`/`(`/`[` open count minus `}`/`)`/`]` close count seen so far
+    depth: i32,
+    /// Whether the scan is currently inside a `"..."` string literal
+    in_string: bool,
+    /// Whether the scan is currently inside a `/* ... */` block comment
+    in_block_comment: bool,
+    /// Whether the previous line fed in was blank, for detecting the two-blank-line
+    /// forced-submit escape hatch
+    last_blank: bool,
+}
+
+/// Outcome of feeding one line into [`process_line_continuation`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineOutcome {
+    /// The buffer isn't a complete, balanced statement yet; keep reading and render
+    /// a continuation prompt
+    NeedMore,
+    /// The buffer is balanced and ready to execute
+    Execute,
+    /// Two consecutive blank lines were entered; execute whatever accumulated even
+    /// though it may still be unbalanced
+    ForceExecute,
+}
+
+/// Fold `line`'s effect on delimiter depth into `state`, skipping delimiters that
+/// appear inside string literals or comments
+fn scan_line(line: &str, state: &mut ContinuationState) {
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if state.in_block_comment {
+            if c == '*' && chars.peek() == Some(&'/') {
+                chars.next();
+                state.in_block_comment = false;
+            }
+            continue;
+        }
+
+        if state.in_string {
+            match c {
+                '\\' => {
+                    chars.next(); // skip the escaped character
+                }
+                '"' => state.in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => state.in_string = true,
+            '/' if chars.peek() == Some(&'/') => break, // line comment: rest of line is ignored
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                state.in_block_comment = true;
+            }
+            '{' | '(' | '[' => state.depth += 1,
+            '}' | ')' | ']' => state.depth -= 1,
+            _ => {}
+        }
+    }
+}
+
+/// Feed one line of input into a bracket-aware multiline continuation detector.
+///
+/// Tracks delimiter depth, string literals, and block comments across the accumulated
+/// `buffer` (via `state`), auto-executing as soon as the buffer is balanced rather than
+/// waiting for a blank line. Two consecutive blank lines remain a forced-submit escape
+/// hatch for input that never balances.
+pub fn process_line_continuation(
+    line: &str,
+    buffer: &mut String,
+    state: &mut ContinuationState,
+) -> LineOutcome {
+    let blank = line.is_empty();
+
+    if blank && state.last_blank {
+        *buffer = buffer.trim_end().to_string();
+        *state = ContinuationState::default();
+        return LineOutcome::ForceExecute;
+    }
+
+    if !buffer.is_empty() {
+        buffer.push('\n');
+    }
+    buffer.push_str(line);
+    state.last_blank = blank;
+
+    scan_line(line, state);
+
+    if state.depth <= 0
+        && !state.in_string
+        && !state.in_block_comment
+        && !buffer.trim().is_empty()
+    {
+        *state = ContinuationState::default();
+        return LineOutcome::Execute;
+    }
+
+    LineOutcome::NeedMore
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,4 +192,69 @@ mod tests {
         assert_eq!(multiline, false);
         assert_eq!(execute, true);
     }
+
+    #[test]
+    fn test_continuation_executes_single_line_without_brackets() {
+        let mut buffer = String::new();
+        let mut state = ContinuationState::default();
+
+        let outcome = process_line_continuation("1 + 2", &mut buffer, &mut state);
+
+        assert_eq!(outcome, LineOutcome::Execute);
+        assert_eq!(buffer, "1 + 2");
+    }
+
+    #[test]
+    fn test_continuation_waits_for_balanced_brackets() {
+        let mut buffer = String::new();
+        let mut state = ContinuationState::default();
+
+        assert_eq!(
+            process_line_continuation("new x in {", &mut buffer, &mut state),
+            LineOutcome::NeedMore
+        );
+        assert_eq!(
+            process_line_continuation("  Nil", &mut buffer, &mut state),
+            LineOutcome::NeedMore
+        );
+        assert_eq!(
+            process_line_continuation("}", &mut buffer, &mut state),
+            LineOutcome::Execute
+        );
+        assert_eq!(buffer, "new x in {\n  Nil\n}");
+    }
+
+    #[test]
+    fn test_continuation_ignores_brackets_in_strings_and_comments() {
+        let mut buffer = String::new();
+        let mut state = ContinuationState::default();
+
+        let outcome = process_line_continuation(
+            "@\"stdout\"!(\"{ not a bracket }\") // trailing { comment",
+            &mut buffer,
+            &mut state,
+        );
+
+        assert_eq!(outcome, LineOutcome::Execute);
+    }
+
+    #[test]
+    fn test_continuation_force_executes_on_two_blank_lines() {
+        let mut buffer = String::new();
+        let mut state = ContinuationState::default();
+
+        assert_eq!(
+            process_line_continuation("new x in {", &mut buffer, &mut state),
+            LineOutcome::NeedMore
+        );
+        assert_eq!(
+            process_line_continuation("", &mut buffer, &mut state),
+            LineOutcome::NeedMore
+        );
+        assert_eq!(
+            process_line_continuation("", &mut buffer, &mut state),
+            LineOutcome::ForceExecute
+        );
+        assert_eq!(buffer, "new x in {");
+    }
 }
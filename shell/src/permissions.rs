@@ -0,0 +1,180 @@
+//! A Deno-`Permissions`-style capability system gating which `rho:` system
+//! channels an [`InterpreterProvider`](crate::providers::InterpreterProvider)
+//! will honor a send on. [`Permissions`] tracks one [`PermissionState`] per
+//! [`Capability`]; [`requested_capabilities`] statically scans a program's
+//! `uri_literal` nodes up front, so a caller can decide whether to run it (or
+//! prompt) before ever invoking the interpreter.
+
+use rholang_tree_sitter_proc_macro::kind;
+use tree_sitter::{Language, Node, Parser};
+
+/// A category of `rho:`-scheme system channel a program might touch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// `rho:io:*` -- stdout/stderr and similar
+    Io,
+    /// `rho:registry:*` -- the name registry
+    Registry,
+    /// `rho:rchain:deploy*` -- deploy-time parameters (deployer ID, timestamp, ...)
+    Deploy,
+}
+
+impl Capability {
+    /// Classify a `uri_literal`'s text (backticks included) by its `rho:`
+    /// scheme segment, or `None` for a URI outside any gated category.
+    fn from_uri(uri: &str) -> Option<Self> {
+        let uri = uri.trim_matches('`');
+        if uri.starts_with("rho:io:") {
+            Some(Capability::Io)
+        } else if uri.starts_with("rho:registry") {
+            Some(Capability::Registry)
+        } else if uri.starts_with("rho:rchain:deploy") {
+            Some(Capability::Deploy)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether a [`Capability`] is allowed, denied, or should prompt before use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    Prompt,
+}
+
+/// Per-capability permission grants, consulted by
+/// [`PermissionedInterpreterProvider`] before honoring a send on a gated
+/// `rho:` channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permissions {
+    io: PermissionState,
+    registry: PermissionState,
+    deploy: PermissionState,
+}
+
+impl Default for Permissions {
+    /// Every capability prompts by default, matching Deno's default posture.
+    fn default() -> Self {
+        Permissions {
+            io: PermissionState::Prompt,
+            registry: PermissionState::Prompt,
+            deploy: PermissionState::Prompt,
+        }
+    }
+}
+
+impl Permissions {
+    pub fn builder() -> PermissionsBuilder {
+        PermissionsBuilder::default()
+    }
+
+    pub fn state(&self, capability: Capability) -> PermissionState {
+        match capability {
+            Capability::Io => self.io,
+            Capability::Registry => self.registry,
+            Capability::Deploy => self.deploy,
+        }
+    }
+
+    pub fn is_granted(&self, capability: Capability) -> bool {
+        self.state(capability) == PermissionState::Granted
+    }
+}
+
+/// Builder for [`Permissions`], mirroring the shell's `--allow-io`/`--deny-io`/
+/// `--allow-all`-style CLI flags: each capability left untouched defaults to
+/// [`PermissionState::Prompt`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PermissionsBuilder {
+    io: Option<PermissionState>,
+    registry: Option<PermissionState>,
+    deploy: Option<PermissionState>,
+}
+
+impl PermissionsBuilder {
+    pub fn allow_io(mut self) -> Self {
+        self.io = Some(PermissionState::Granted);
+        self
+    }
+
+    pub fn deny_io(mut self) -> Self {
+        self.io = Some(PermissionState::Denied);
+        self
+    }
+
+    pub fn allow_registry(mut self) -> Self {
+        self.registry = Some(PermissionState::Granted);
+        self
+    }
+
+    pub fn deny_registry(mut self) -> Self {
+        self.registry = Some(PermissionState::Denied);
+        self
+    }
+
+    pub fn allow_deploy(mut self) -> Self {
+        self.deploy = Some(PermissionState::Granted);
+        self
+    }
+
+    pub fn deny_deploy(mut self) -> Self {
+        self.deploy = Some(PermissionState::Denied);
+        self
+    }
+
+    /// Grant every capability, matching `--allow-all`.
+    pub fn allow_all(mut self) -> Self {
+        self.io = Some(PermissionState::Granted);
+        self.registry = Some(PermissionState::Granted);
+        self.deploy = Some(PermissionState::Granted);
+        self
+    }
+
+    pub fn build(self) -> Permissions {
+        Permissions {
+            io: self.io.unwrap_or(PermissionState::Prompt),
+            registry: self.registry.unwrap_or(PermissionState::Prompt),
+            deploy: self.deploy.unwrap_or(PermissionState::Prompt),
+        }
+    }
+}
+
+fn rholang_language() -> Language {
+    rholang_tree_sitter::LANGUAGE.into()
+}
+
+/// Statically scan `code` for every gated `rho:` URI literal it references,
+/// without running it, pairing each one with the literal URI text it was
+/// written with (backticks stripped) so a denial can name the exact channel.
+pub fn requested_capabilities(code: &str) -> Vec<(Capability, String)> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&rholang_language())
+        .expect("Error loading Rholang parser");
+
+    let tree = match parser.parse(code, None) {
+        Some(tree) => tree,
+        None => return Vec::new(),
+    };
+
+    let mut capabilities = Vec::new();
+    collect_capabilities(tree.root_node(), code, &mut capabilities);
+    capabilities
+}
+
+fn collect_capabilities(node: Node, source: &str, into: &mut Vec<(Capability, String)>) {
+    if node.kind_id() == kind!("uri_literal") {
+        if let Ok(text) = node.utf8_text(source.as_bytes()) {
+            if let Some(capability) = Capability::from_uri(text) {
+                into.push((capability, text.trim_matches('`').to_string()));
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_capabilities(child, source, into);
+    }
+}
@@ -0,0 +1,139 @@
+//! Pluggable output sinks so an evaluation's result can be routed somewhere other than
+//! the terminal -- e.g. the `.pipe <cmd>` special command, which spawns an external
+//! process and streams the next evaluated command's output into its stdin, printing
+//! back whatever it writes to its stdout. [`LineWriter`]/[`LineReader`] are the
+//! injectable seam, with [`PipedProcess`] as the real, subprocess-backed implementation
+//! (mirroring [`crate::providers::SubprocessInterpreterProvider`]'s use of
+//! `tokio::process::Command`) and [`FakePipe`] as the repo's usual canned-behavior test
+//! double (see [`crate::providers::FakeInterpreterProvider`]).
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+
+/// A line-oriented destination output can be streamed to, analogous to `std::io::Write`
+/// but async, so it can be backed by a spawned process's stdin as well as an in-memory
+/// buffer in tests.
+#[async_trait]
+pub trait LineWriter: Send {
+    /// Write `line` followed by a newline
+    async fn write_line(&mut self, line: &str) -> Result<()>;
+
+    /// Signal that no more lines are coming, so a process reading from the other end of
+    /// a pipe sees EOF. A no-op by default for sinks that don't need it.
+    async fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A line-oriented source output can be read back from, the other half of piping
+/// through an external process -- its stdout.
+#[async_trait]
+pub trait LineReader: Send {
+    /// The next line, with its trailing newline stripped, or `None` at EOF
+    async fn read_line(&mut self) -> Result<Option<String>>;
+}
+
+/// A spawned external process, wired up as a [`LineWriter`]/[`LineReader`] pair over its
+/// stdin/stdout, for the `.pipe <cmd>` special command.
+pub struct PipedProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl PipedProcess {
+    /// Spawn `command args...` with its stdin and stdout piped, killing it on drop so a
+    /// pipe target that's never fully drained doesn't outlive the shell.
+    pub fn spawn(command: &str, args: &[String]) -> Result<Self> {
+        let mut child = Command::new(command)
+            .args(args)
+            .kill_on_drop(true)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("{} has no stdin", command))?;
+        let stdout = BufReader::new(
+            child
+                .stdout
+                .take()
+                .ok_or_else(|| anyhow::anyhow!("{} has no stdout", command))?,
+        );
+
+        Ok(PipedProcess { child, stdin, stdout })
+    }
+}
+
+#[async_trait]
+impl LineWriter for PipedProcess {
+    async fn write_line(&mut self, line: &str) -> Result<()> {
+        self.stdin.write_all(line.as_bytes()).await?;
+        self.stdin.write_all(b"\n").await?;
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.stdin.shutdown().await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LineReader for PipedProcess {
+    async fn read_line(&mut self) -> Result<Option<String>> {
+        let mut line = String::new();
+        let n = self.stdout.read_line(&mut line).await?;
+        if n == 0 {
+            let _ = self.child.wait().await;
+            return Ok(None);
+        }
+
+        if line.ends_with('\n') {
+            line.pop();
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+        Ok(Some(line))
+    }
+}
+
+/// A [`LineWriter`]/[`LineReader`] double that records every line written to it and
+/// plays back a fixed, canned sequence of lines, for exercising `.pipe`'s dispatch logic
+/// without spawning a real process.
+#[derive(Debug, Default)]
+pub struct FakePipe {
+    pub written: Vec<String>,
+    responses: VecDeque<String>,
+}
+
+impl FakePipe {
+    pub fn new(responses: Vec<&str>) -> Self {
+        FakePipe {
+            written: Vec::new(),
+            responses: responses.into_iter().map(str::to_string).collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl LineWriter for FakePipe {
+    async fn write_line(&mut self, line: &str) -> Result<()> {
+        self.written.push(line.to_string());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LineReader for FakePipe {
+    async fn read_line(&mut self) -> Result<Option<String>> {
+        Ok(self.responses.pop_front())
+    }
+}
@@ -0,0 +1,249 @@
+//! External interpreter plugins, spawned as child processes and driven over a
+//! JSON-RPC protocol on their stdin/stdout -- lets a user add Rholang tooling
+//! (formatters, linters, custom evaluators) to the shell without recompiling
+//! this crate. `.plugin load <path>` spawns `path`, sends it a `config`
+//! handshake, and registers whatever command names/arities it declares in
+//! its response; thereafter, a REPL line whose leading token names one of
+//! those commands is forwarded to the plugin as a `run` call instead of being
+//! evaluated as Rholang.
+//!
+//! The handshake/call shapes, once decoded from each JSON-RPC envelope:
+//!   - handshake:  `--> {"jsonrpc":"2.0","method":"config","id":0}`
+//!                 `<-- {"jsonrpc":"2.0","id":0,"result":{"commands":[{"name":"fmt","arity":1}]}}`
+//!   - a call:     `--> {"jsonrpc":"2.0","method":"run","params":{"command":"fmt","args":["x.rho"]},"id":1}`
+//!                 `<-- {"jsonrpc":"2.0","id":1,"result":"...formatted output..."}`
+
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+
+use crate::supervisor::Component;
+
+/// One command a plugin declared in its `config` handshake response: the name
+/// a user types to invoke it, and how many whitespace-separated arguments it
+/// expects. Arity is informational only -- `dispatch` doesn't enforce it, a
+/// plugin is free to reject a bad call itself.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginCommandSpec {
+    pub name: String,
+    pub arity: usize,
+}
+
+/// The `result` body of a `config` handshake response.
+#[derive(Debug, Clone, Deserialize)]
+struct PluginSignature {
+    commands: Vec<PluginCommandSpec>,
+}
+
+/// A live plugin child process: its piped stdin/stdout, the command names it
+/// registered, and the JSON-RPC request id to use for its next call.
+struct LoadedPlugin {
+    path: String,
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    commands: Vec<String>,
+    next_request_id: u64,
+}
+
+/// Live plugin child processes, keyed by a `.plugin list`/`.plugin kill`-style
+/// pid -- the same pid-registry/kill shape `InterpreterProvider` implementations
+/// keep for in-flight evaluations (see `ControllableInterpreterProvider`'s
+/// `next_pid`/`processes` in `providers.rs`), but for plugin processes instead
+/// of Rholang ones.
+#[derive(Clone, Default)]
+pub struct PluginRegistry {
+    next_pid: Arc<Mutex<usize>>,
+    plugins: Arc<Mutex<HashMap<usize, LoadedPlugin>>>,
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawn `path`, perform the `config` handshake, and register whatever
+    /// commands it declared. Returns the pid it was registered under and the
+    /// command names now routed to it.
+    pub async fn load(&self, path: &str) -> Result<(usize, Vec<String>)> {
+        let mut child = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow!("failed to spawn plugin {path}: {e}"))?;
+
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("plugin {path} has no stdin"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("plugin {path} has no stdout"))?;
+        let mut stdout = BufReader::new(stdout);
+
+        let handshake = json!({"jsonrpc": "2.0", "method": "config", "id": 0});
+        stdin.write_all(format!("{handshake}\n").as_bytes()).await?;
+        stdin.flush().await?;
+
+        let mut line = String::new();
+        stdout.read_line(&mut line).await?;
+        let response: Value = serde_json::from_str(line.trim())
+            .map_err(|e| anyhow!("plugin {path} sent an invalid config response: {e}"))?;
+        let result = response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| anyhow!("plugin {path}'s config response has no \"result\""))?;
+        let signature: PluginSignature = serde_json::from_value(result)
+            .map_err(|e| anyhow!("plugin {path}'s config result doesn't match the expected shape: {e}"))?;
+
+        let commands: Vec<String> = signature.commands.iter().map(|c| c.name.clone()).collect();
+
+        let pid = {
+            let mut next_pid = self.next_pid.lock().unwrap();
+            *next_pid += 1;
+            *next_pid
+        };
+
+        self.plugins.lock().unwrap().insert(
+            pid,
+            LoadedPlugin {
+                path: path.to_string(),
+                child,
+                stdin,
+                stdout,
+                commands: commands.clone(),
+                next_request_id: 1,
+            },
+        );
+
+        Ok((pid, commands))
+    }
+
+    /// Whether any loaded plugin has registered `command`.
+    pub fn has_command(&self, command: &str) -> bool {
+        self.pid_for_command(command).is_some()
+    }
+
+    fn pid_for_command(&self, command: &str) -> Option<usize> {
+        self.plugins
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(_, plugin)| plugin.commands.iter().any(|name| name == command))
+            .map(|(pid, _)| *pid)
+    }
+
+    /// Forward `args` (the rest of the input line, split on whitespace) as a
+    /// `run` JSON-RPC call to whichever plugin registered `command`, and
+    /// return its rendered `result`. Returns `Ok(None)` if no plugin has
+    /// registered `command`, rather than an error -- that's the caller's cue
+    /// to fall back to treating the line as ordinary Rholang.
+    pub async fn dispatch(&self, command: &str, args: &str) -> Result<Option<String>> {
+        let Some(pid) = self.pid_for_command(command) else {
+            return Ok(None);
+        };
+
+        // Pull the plugin out of the map for the call's duration: talking to
+        // its pipes needs `&mut` access the `Mutex` can't lend out across an
+        // `.await`, and no other caller should be mid-call with it anyway.
+        let mut plugin = self
+            .plugins
+            .lock()
+            .unwrap()
+            .remove(&pid)
+            .expect("pid_for_command just found this pid");
+
+        let result = Self::call(&mut plugin, command, args).await;
+        self.plugins.lock().unwrap().insert(pid, plugin);
+
+        result.map(Some)
+    }
+
+    async fn call(plugin: &mut LoadedPlugin, command: &str, args: &str) -> Result<String> {
+        let id = plugin.next_request_id;
+        plugin.next_request_id += 1;
+
+        let params: Vec<&str> = if args.is_empty() {
+            Vec::new()
+        } else {
+            args.split_whitespace().collect()
+        };
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": "run",
+            "params": {"command": command, "args": params},
+            "id": id,
+        });
+
+        plugin.stdin.write_all(format!("{request}\n").as_bytes()).await?;
+        plugin.stdin.flush().await?;
+
+        let mut line = String::new();
+        plugin.stdout.read_line(&mut line).await?;
+        let response: Value = serde_json::from_str(line.trim())
+            .map_err(|e| anyhow!("plugin {} sent an invalid response: {e}", plugin.path))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(anyhow!("plugin {} reported an error: {error}", plugin.path));
+        }
+        Ok(response.get("result").cloned().unwrap_or(Value::Null).to_string())
+    }
+
+    /// List every live plugin as a `(pid, description)` pair, in the same
+    /// shape `.ps` lists Rholang processes.
+    pub fn list(&self) -> Vec<(usize, String)> {
+        self.plugins
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(pid, plugin)| (*pid, format!("{} [{}]", plugin.path, plugin.commands.join(", "))))
+            .collect()
+    }
+
+    /// Kill the plugin registered under `pid`. Returns `true` if it was found.
+    pub fn kill(&self, pid: usize) -> Result<bool> {
+        let Some(mut plugin) = self.plugins.lock().unwrap().remove(&pid) else {
+            return Ok(false);
+        };
+        plugin.child.start_kill()?;
+        Ok(true)
+    }
+
+    /// Kill every live plugin -- called on shell shutdown so a loaded plugin
+    /// is never left running after the REPL exits.
+    pub fn kill_all(&self) -> Result<usize> {
+        let mut plugins = self.plugins.lock().unwrap();
+        let count = plugins.len();
+        for (_, mut plugin) in plugins.drain() {
+            let _ = plugin.child.start_kill();
+        }
+        Ok(count)
+    }
+}
+
+/// Adapts [`PluginRegistry`] into a [`Component`], the same way
+/// [`crate::supervisor::ProviderComponent`] adapts an [`crate::providers::InterpreterProvider`],
+/// so `run_shell`'s [`crate::supervisor::Supervisor`] kills every loaded plugin alongside
+/// every in-flight Rholang process on exit.
+#[async_trait]
+impl Component for PluginRegistry {
+    fn name(&self) -> &str {
+        "plugins"
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        let killed = self.kill_all()?;
+        if killed > 0 {
+            eprintln!("plugins: killed {killed} plugin process(es)");
+        }
+        Ok(())
+    }
+}
@@ -1,63 +1,140 @@
+use crate::clock::{Clock, RealClock};
+use crate::permissions::{requested_capabilities, Permissions, PermissionState};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use nix::sys::signal::{self, Signal};
+use nix::unistd::Pid;
 use rholang_parser::{errors::ParseResult, RholangParser};
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::fmt;
+use std::process::Stdio;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
-use tokio::sync::oneshot;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::{mpsc, oneshot};
 use tokio::task;
 use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
+
+/// Represents the type of error that occurred during interpretation
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum ErrorKind {
+    /// Error that occurs during parsing of Rholang code
+    ParsingError,
+    /// Error that occurs during runtime execution of Rholang code
+    RuntimeError,
+    /// Error that occurs when a timeout is reached
+    TimeoutError,
+    /// Error that occurs when an operation is cancelled
+    CancellationError,
+    /// Other unspecified errors
+    OtherError,
+}
+
+/// Represents a position in the source code
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SourcePosition {
+    /// Line number (1-based)
+    pub line: usize,
+    /// Column number (1-based)
+    pub column: usize,
+}
+
+impl fmt::Display for SourcePosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// A range of source positions an error spans, for editors that want to underline
+/// more than a single point. Reserved for richer diagnostics; not yet populated by
+/// any provider.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Span {
+    pub start: SourcePosition,
+    pub end: SourcePosition,
+}
 
 /// Represents an error that occurred during interpretation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct InterpreterError {
+    /// The kind of error that occurred
+    pub kind: ErrorKind,
     /// A human-readable error message
     pub message: String,
     /// The position in the source code where the error occurred (if available)
-    pub position: Option<String>,
+    pub position: Option<SourcePosition>,
     /// The source code that caused the error (if available)
     pub source: Option<String>,
+    /// The range of source positions the error spans (if available)
+    pub span: Option<Span>,
 }
 
 impl InterpreterError {
     /// Create a new parsing error
     pub fn parsing_error(
         message: impl Into<String>,
-        position: Option<String>,
+        position: Option<SourcePosition>,
+        source: Option<String>,
+    ) -> Self {
+        InterpreterError {
+            kind: ErrorKind::ParsingError,
+            message: message.into(),
+            position,
+            source,
+            span: None,
+        }
+    }
+
+    /// Create a new runtime error
+    pub fn runtime_error(
+        message: impl Into<String>,
+        position: Option<SourcePosition>,
         source: Option<String>,
     ) -> Self {
         InterpreterError {
+            kind: ErrorKind::RuntimeError,
             message: message.into(),
             position,
             source,
+            span: None,
         }
     }
 
     /// Create a new timeout error
     pub fn timeout_error(message: impl Into<String>) -> Self {
         InterpreterError {
+            kind: ErrorKind::TimeoutError,
             message: message.into(),
             position: None,
             source: None,
+            span: None,
         }
     }
 
     /// Create a new cancellation error
     pub fn cancellation_error(message: impl Into<String>) -> Self {
         InterpreterError {
+            kind: ErrorKind::CancellationError,
             message: message.into(),
             position: None,
             source: None,
+            span: None,
         }
     }
 
     /// Create a new other error
     pub fn other_error(message: impl Into<String>) -> Self {
         InterpreterError {
+            kind: ErrorKind::OtherError,
             message: message.into(),
             position: None,
             source: None,
+            span: None,
         }
     }
 }
@@ -78,6 +155,8 @@ impl fmt::Display for InterpreterError {
     }
 }
 
+impl std::error::Error for InterpreterError {}
+
 /// Represents the result of an interpretation operation
 #[derive(Debug, Clone)]
 pub enum InterpretationResult {
@@ -87,6 +166,25 @@ pub enum InterpretationResult {
     Error(InterpreterError),
 }
 
+impl Serialize for InterpretationResult {
+    /// Serializes a success as `{"value": ...}` and an error as its own flat
+    /// `{kind, message, position, source, span}` record, so a line of JSON output
+    /// is self-describing without an extra tag wrapping it.
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            InterpretationResult::Success(value) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("value", value)?;
+                map.end()
+            }
+            InterpretationResult::Error(err) => err.serialize(serializer),
+        }
+    }
+}
+
 impl InterpretationResult {
     /// Returns true if the result is a success
     pub fn is_success(&self) -> bool {
@@ -117,6 +215,51 @@ impl InterpretationResult {
     }
 }
 
+/// One chunk of a streamed [`RholangParserInterpreterProvider::interpret_stream`] call
+#[derive(Debug, Clone)]
+pub enum InterpretationChunk {
+    /// A partial slice of pretty-tree output, in the order it was produced
+    Partial(String),
+    /// The call finished; carries the same terminal result `interpret` would have returned
+    Done(InterpretationResult),
+    /// The call was cancelled (via `kill_process`) before it finished
+    Cancelled,
+}
+
+/// How many unread chunks `interpret_stream`'s channel buffers before the
+/// producer task blocks on a slow reader
+const STREAM_CHANNEL_CAPACITY: usize = 16;
+
+/// How many lines of pretty-tree output `interpret_stream` batches into a
+/// single `InterpretationChunk::Partial`
+const STREAM_CHUNK_LINES: usize = 10;
+
+/// A handle onto a streamed [`RholangParserInterpreterProvider::interpret_stream`]
+/// call: reads [`InterpretationChunk`]s off a bounded channel the spawned
+/// producer task writes into. The channel's own waker drives readiness, so a
+/// `kill_process` on this stream's `pid` (which fires the same `cancel_sender`
+/// oneshot `interpret` uses) unblocks a pending `next()` promptly with a final
+/// `InterpretationChunk::Cancelled`, rather than the reader waiting for the
+/// whole parse to finish.
+pub struct InterpretationStream {
+    pid: usize,
+    receiver: mpsc::Receiver<InterpretationChunk>,
+}
+
+impl InterpretationStream {
+    /// The process ID this stream is registered under; pass it to `kill_process`
+    /// to cancel the call early.
+    pub fn pid(&self) -> usize {
+        self.pid
+    }
+
+    /// Read the next chunk, or `None` once the stream is exhausted (a
+    /// `Done`/`Cancelled` terminal chunk has already been yielded)
+    pub async fn next(&mut self) -> Option<InterpretationChunk> {
+        self.receiver.recv().await
+    }
+}
+
 /// Trait for interpreter providers
 /// This trait defines the interface for interpreters that can be used with the shell
 #[async_trait]
@@ -124,6 +267,38 @@ pub trait InterpreterProvider {
     /// Interpret a string of code and return the result
     async fn interpret(&self, code: &str) -> InterpretationResult;
 
+    /// Interpret a string of code, honoring a cancellation token.
+    ///
+    /// Implementations that run in discrete steps should check `token.is_cancelled()`
+    /// between steps so a cancellation surfaces as `InterpreterError::cancellation_error`
+    /// instead of running to completion. The default implementation simply ignores the
+    /// token and delegates to `interpret`.
+    async fn interpret_cancellable(
+        &self,
+        code: &str,
+        token: CancellationToken,
+    ) -> InterpretationResult {
+        let _ = token;
+        self.interpret(code).await
+    }
+
+    /// Interpret a string of code under a one-off `deadline`, instead of whatever
+    /// timeout the provider itself is configured with.
+    ///
+    /// The default implementation races `interpret` against `deadline`, surfacing
+    /// `InterpreterError::timeout_error` (naming the configured duration) if it
+    /// elapses first.
+    async fn interpret_with_deadline(&self, code: &str, deadline: Duration) -> InterpretationResult {
+        timeout(deadline, self.interpret(code))
+            .await
+            .unwrap_or_else(|_| {
+                InterpretationResult::Error(InterpreterError::timeout_error(format!(
+                    "Evaluation timed out after {:?}",
+                    deadline
+                )))
+            })
+    }
+
     /// List all running processes
     /// Returns a vector of tuples containing the process ID and the code being executed
     fn list_processes(&self) -> Result<Vec<(usize, String)>>;
@@ -135,6 +310,60 @@ pub trait InterpreterProvider {
     /// Kill all running processes
     /// Returns the number of processes that were killed
     fn kill_all_processes(&self) -> Result<usize>;
+
+    /// Launch `code` as a background job identified by `id`, returning a
+    /// [`JobHandle`] immediately rather than awaiting completion -- used by
+    /// `run_shell`'s job-control commands (`.jobs`, `.fg`, `.wait`) for input
+    /// lines ending in `&`, so the prompt is free again as soon as the job is
+    /// launched instead of blocking on its result.
+    ///
+    /// The default implementation just defers the same work `interpret` would
+    /// do, as a boxed future borrowing `self` -- `run_shell` drives every live
+    /// job's `JobHandle` concurrently in a `FuturesUnordered` alongside the
+    /// readline prompt, which gives genuine concurrent progress without
+    /// requiring a `tokio::spawn`ed, `Send + 'static` task (and the `Arc`
+    /// plumbing that would force on every provider).
+    fn spawn<'a>(&'a self, id: JobId, code: &str) -> JobHandle<'a>
+    where
+        Self: Sync,
+    {
+        JobHandle {
+            id,
+            future: Box::pin(self.interpret(code)),
+        }
+    }
+}
+
+/// Identifies one backgrounded job in `run_shell`'s job table -- distinct
+/// from the pid [`InterpreterProvider::list_processes`]/`kill_process` track,
+/// since a single job's evaluation may itself register (and outlive) several
+/// of those.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JobId(pub usize);
+
+impl fmt::Display for JobId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A backgrounded evaluation in flight, returned by [`InterpreterProvider::spawn`].
+/// Implements [`Future`](std::future::Future), resolving to its [`JobId`] paired
+/// with the finished [`InterpretationResult`], so a pool of these can be driven
+/// together in a `FuturesUnordered` and still report which job just finished.
+pub struct JobHandle<'a> {
+    pub id: JobId,
+    future: std::pin::Pin<Box<dyn std::future::Future<Output = InterpretationResult> + Send + 'a>>,
+}
+
+impl std::future::Future for JobHandle<'_> {
+    type Output = (JobId, InterpretationResult);
+
+    fn poll(self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Self::Output> {
+        let this = self.get_mut();
+        let id = this.id;
+        this.future.as_mut().poll(cx).map(|result| (id, result))
+    }
 }
 
 /// A fake interpreter provider that simply returns the input code
@@ -148,6 +377,28 @@ impl InterpreterProvider for FakeInterpreterProvider {
         InterpretationResult::Success(code.to_string())
     }
 
+    /// Fake implementation that simulates a few interpretation steps, checking the
+    /// cancellation token between each one so tests can exercise cancellation without
+    /// a real interpreter.
+    async fn interpret_cancellable(
+        &self,
+        code: &str,
+        token: CancellationToken,
+    ) -> InterpretationResult {
+        const SIMULATED_STEPS: usize = 4;
+
+        for _ in 0..SIMULATED_STEPS {
+            if token.is_cancelled() {
+                return InterpretationResult::Error(InterpreterError::cancellation_error(
+                    "Evaluation was cancelled",
+                ));
+            }
+            tokio::task::yield_now().await;
+        }
+
+        InterpretationResult::Success(code.to_string())
+    }
+
     /// List all running processes
     /// This is a fake implementation that always returns an empty list
     /// since FakeInterpreterProvider doesn't actually manage processes
@@ -181,6 +432,67 @@ struct ProcessInfo {
     cancel_sender: Option<oneshot::Sender<()>>,
 }
 
+/// Policy applied when a new `interpret` call arrives while `max_concurrency`
+/// running processes are already in flight, mirroring watchexec's
+/// on-busy-update model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnBusy {
+    /// Wait for a slot to free up, then run as normal
+    Queue,
+    /// Cancel the oldest running process (reusing its `cancel_sender`), then run
+    /// the new one immediately
+    Restart,
+    /// Refuse the new submission, returning an error, without touching any
+    /// currently running process
+    DoNothing,
+    /// Refuse the new submission, returning an error, without touching any
+    /// currently running process
+    Reject,
+}
+
+/// How often `OnBusy::Queue` re-checks whether a process slot has freed up
+const BUSY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Policy controlling whether/how a failed `interpret` attempt is retried,
+/// adapting the PVF subsystem's preparation-retry approach so a flaky
+/// execution environment doesn't fail a whole REPL command on the first
+/// transient hiccup.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first; `1` disables retrying
+    pub max_attempts: usize,
+    /// Delay between attempts
+    pub backoff: Duration,
+    /// Whether a given error is worth retrying. Genuine `parsing_error`s are
+    /// deterministic -- the same code fails the same way every time -- and
+    /// should return `false` here.
+    pub retry_on: fn(&InterpreterError) -> bool,
+}
+
+impl Default for RetryPolicy {
+    /// No retrying: a single attempt, whatever the outcome
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            backoff: Duration::ZERO,
+            retry_on: |_| false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Retry `TimeoutError`s and `OtherError`s (e.g. a lock-contention hiccup or a
+    /// task join failure) up to `max_attempts` times, waiting `backoff` between
+    /// attempts. `ParsingError`s and `CancellationError`s are never retried.
+    pub fn transient(max_attempts: usize, backoff: Duration) -> Self {
+        RetryPolicy {
+            max_attempts,
+            backoff,
+            retry_on: |err| matches!(err.kind, ErrorKind::TimeoutError | ErrorKind::OtherError),
+        }
+    }
+}
+
 /// Provider for the Rholang parser
 /// This implements the InterpreterProvider trait
 #[derive(Clone)]
@@ -191,18 +503,61 @@ pub struct RholangParserInterpreterProvider {
     next_pid: Arc<Mutex<usize>>,
     /// Delay for async interpretation (in milliseconds)
     delay_ms: Arc<Mutex<u64>>,
+    /// Maximum time a single `interpret` call is allowed to run before it is timed out
+    timeout_duration: Arc<Mutex<Duration>>,
+    /// Policy applied when `max_concurrency` processes are already running
+    on_busy: Arc<Mutex<OnBusy>>,
+    /// Maximum number of processes allowed to run concurrently, or `None` for unbounded
+    max_concurrency: Arc<Mutex<Option<usize>>>,
+    /// Policy controlling whether a failed attempt is retried
+    retry_policy: Arc<Mutex<RetryPolicy>>,
+    /// Clock used for the simulated-processing-time delay, so tests can swap in a
+    /// `MockClock` instead of racing the real wall clock
+    clock: Arc<dyn Clock>,
 }
 
 impl RholangParserInterpreterProvider {
-    /// Create a new instance of the Rholang parser interpreter provider
+    /// Create a new instance of the Rholang parser interpreter provider, using the
+    /// real wall clock
     pub fn new() -> Result<Self> {
+        Self::with_clock(Arc::new(RealClock))
+    }
+
+    /// Create a new instance backed by the given `clock` instead of the real wall
+    /// clock, so tests can drive the simulated-processing-time delay deterministically
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Result<Self> {
         Ok(RholangParserInterpreterProvider {
             processes: Arc::new(Mutex::new(HashMap::new())),
             next_pid: Arc::new(Mutex::new(1)),
             delay_ms: Arc::new(Mutex::new(2000)), // Default delay: 2 seconds
+            timeout_duration: Arc::new(Mutex::new(Duration::from_secs(30))),
+            on_busy: Arc::new(Mutex::new(OnBusy::Queue)),
+            max_concurrency: Arc::new(Mutex::new(None)),
+            retry_policy: Arc::new(Mutex::new(RetryPolicy::default())),
+            clock,
         })
     }
 
+    /// Set the policy applied when `max_concurrency` processes are already running
+    pub fn set_on_busy(&self, on_busy: OnBusy) -> Result<()> {
+        let mut guard = self
+            .on_busy
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock on_busy: {}", e))?;
+        *guard = on_busy;
+        Ok(())
+    }
+
+    /// Set the maximum number of processes allowed to run concurrently, or `None` for unbounded
+    pub fn set_max_concurrency(&self, max_concurrency: Option<usize>) -> Result<()> {
+        let mut guard = self
+            .max_concurrency
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock max_concurrency: {}", e))?;
+        *guard = max_concurrency;
+        Ok(())
+    }
+
     /// Set the delay for async interpretation
     pub fn set_delay(&self, delay_ms: u64) -> Result<()> {
         let mut delay = self
@@ -212,12 +567,158 @@ impl RholangParserInterpreterProvider {
         *delay = delay_ms;
         Ok(())
     }
-}
 
-/// Implementation of the InterpreterProvider trait for the Rholang parser
-#[async_trait]
-impl InterpreterProvider for RholangParserInterpreterProvider {
-    async fn interpret(&self, code: &str) -> InterpretationResult {
+    /// Set the timeout applied to every subsequent `interpret` call
+    pub fn set_timeout(&self, timeout: Duration) -> Result<()> {
+        let mut timeout_duration = self
+            .timeout_duration
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock timeout_duration: {}", e))?;
+        *timeout_duration = timeout;
+        Ok(())
+    }
+
+    /// Set the policy controlling whether a failed `interpret` attempt is retried
+    pub fn set_retry_policy(&self, retry_policy: RetryPolicy) -> Result<()> {
+        let mut guard = self
+            .retry_policy
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock retry_policy: {}", e))?;
+        *guard = retry_policy;
+        Ok(())
+    }
+
+    /// Like `interpret`, but streams the pretty-tree output through a bounded
+    /// channel in batches of [`STREAM_CHUNK_LINES`] lines instead of blocking
+    /// until the whole thing is ready. The current parser API only produces a
+    /// pretty tree all at once, so this doesn't parallelize the parse itself --
+    /// it lets a slow consumer (e.g. a REPL rendering a large tree) start
+    /// displaying output before it has read every chunk.
+    ///
+    /// The call is tracked in the same process map `interpret` uses, so
+    /// `list_processes`/`kill_process`/`kill_all_processes` apply to it too:
+    /// killing this stream's `pid` (see [`InterpretationStream::pid`]) fires the
+    /// same cancellation oneshot, unblocking a pending
+    /// [`InterpretationStream::next`] with a final `InterpretationChunk::Cancelled`
+    /// instead of waiting for the whole result.
+    pub fn interpret_stream(&self, code: &str) -> InterpretationStream {
+        let code_clone = code.to_string();
+        let code_for_task = code.to_string();
+
+        let processes = Arc::clone(&self.processes);
+        let next_pid = Arc::clone(&self.next_pid);
+
+        let (cancel_sender, mut cancel_receiver) = oneshot::channel();
+        let (chunk_sender, chunk_receiver) = mpsc::channel(STREAM_CHANNEL_CAPACITY);
+
+        // Locking `next_pid`/`processes` can only fail if another thread panicked
+        // while holding the lock; there's no `Result` to report that through here
+        // (unlike `interpret`), so fall back to a stream carrying a single error chunk.
+        let pid = match next_pid.lock() {
+            Ok(mut guard) => {
+                let pid = *guard;
+                *guard += 1;
+                pid
+            }
+            Err(e) => {
+                let _ = chunk_sender.try_send(InterpretationChunk::Done(InterpretationResult::Error(
+                    InterpreterError::other_error(format!("Failed to lock next_pid: {}", e)),
+                )));
+                return InterpretationStream {
+                    pid: 0,
+                    receiver: chunk_receiver,
+                };
+            }
+        };
+
+        match processes.lock() {
+            Ok(mut guard) => {
+                guard.insert(
+                    pid,
+                    ProcessInfo {
+                        code: code_clone,
+                        cancel_sender: Some(cancel_sender),
+                    },
+                );
+            }
+            Err(e) => {
+                let _ = chunk_sender.try_send(InterpretationChunk::Done(InterpretationResult::Error(
+                    InterpreterError::other_error(format!("Failed to lock processes: {}", e)),
+                )));
+                return InterpretationStream { pid, receiver: chunk_receiver };
+            }
+        }
+
+        task::spawn(async move {
+            let finish = |processes: &Arc<Mutex<HashMap<usize, ProcessInfo>>>| {
+                if let Ok(mut guard) = processes.lock() {
+                    guard.remove(&pid);
+                }
+            };
+
+            let mut parser = match RholangParser::new() {
+                Ok(parser) => parser,
+                Err(e) => {
+                    let _ = chunk_sender
+                        .send(InterpretationChunk::Done(InterpretationResult::Error(
+                            InterpreterError::other_error(format!("Failed to create parser: {}", e)),
+                        )))
+                        .await;
+                    finish(&processes);
+                    return;
+                }
+            };
+
+            let result = match parser.get_pretty_tree(&code_for_task) {
+                ParseResult::Success(tree_string) => {
+                    let lines: Vec<&str> = tree_string.lines().collect();
+                    for chunk in lines.chunks(STREAM_CHUNK_LINES).map(|lines| lines.join("\n")) {
+                        tokio::select! {
+                            send_result = chunk_sender.send(InterpretationChunk::Partial(chunk)) => {
+                                if send_result.is_err() {
+                                    finish(&processes);
+                                    return;
+                                }
+                            }
+                            _ = &mut cancel_receiver => {
+                                let _ = chunk_sender.send(InterpretationChunk::Cancelled).await;
+                                finish(&processes);
+                                return;
+                            }
+                        }
+                    }
+                    InterpretationResult::Success(tree_string)
+                }
+                ParseResult::Error(err) => {
+                    let position = err.position.map(|pos| SourcePosition {
+                        line: pos.line,
+                        column: pos.column,
+                    });
+                    InterpretationResult::Error(InterpreterError::parsing_error(
+                        err.message,
+                        position,
+                        err.source,
+                    ))
+                }
+                ParseResult::Incomplete => InterpretationResult::Error(InterpreterError::parsing_error(
+                    "Incomplete input",
+                    None,
+                    Some(code_for_task.clone()),
+                )),
+            };
+
+            let _ = chunk_sender.send(InterpretationChunk::Done(result)).await;
+            finish(&processes);
+        });
+
+        InterpretationStream { pid, receiver: chunk_receiver }
+    }
+
+    /// Run a single `interpret` attempt: allocate a fresh PID, register it in the
+    /// process map, and parse `code` under the configured delay/timeout/on-busy
+    /// policy. Used directly by `interpret`'s retry loop, so each retry shows up
+    /// in `list_processes` as its own attempt.
+    async fn interpret_once(&self, code: &str) -> InterpretationResult {
         // Create a new parser for each call to avoid mutability issues
         let mut parser = match RholangParser::new() {
             Ok(parser) => parser,
@@ -236,6 +737,7 @@ impl InterpreterProvider for RholangParserInterpreterProvider {
         // Clone the Arc<Mutex<>> for the task
         let processes = Arc::clone(&self.processes);
         let next_pid = Arc::clone(&self.next_pid);
+        let clock = Arc::clone(&self.clock);
 
         // Create a oneshot channel for cancellation
         let (cancel_sender, cancel_receiver) = oneshot::channel();
@@ -256,6 +758,77 @@ impl InterpreterProvider for RholangParserInterpreterProvider {
             pid
         };
 
+        // Apply the configured on-busy policy if we're already at the concurrency cap
+        let on_busy = match self.on_busy.lock() {
+            Ok(guard) => *guard,
+            Err(e) => {
+                return InterpretationResult::Error(InterpreterError::other_error(format!(
+                    "Failed to lock on_busy: {}",
+                    e
+                )))
+            }
+        };
+        let max_concurrency = match self.max_concurrency.lock() {
+            Ok(guard) => *guard,
+            Err(e) => {
+                return InterpretationResult::Error(InterpreterError::other_error(format!(
+                    "Failed to lock max_concurrency: {}",
+                    e
+                )))
+            }
+        };
+
+        if let Some(max) = max_concurrency {
+            loop {
+                let at_capacity = match processes.lock() {
+                    Ok(guard) => guard.len() >= max,
+                    Err(e) => {
+                        return InterpretationResult::Error(InterpreterError::other_error(format!(
+                            "Failed to lock processes: {}",
+                            e
+                        )))
+                    }
+                };
+                if !at_capacity {
+                    break;
+                }
+
+                match on_busy {
+                    OnBusy::Queue => {
+                        tokio::time::sleep(BUSY_POLL_INTERVAL).await;
+                    }
+                    OnBusy::Restart => {
+                        let mut guard = match processes.lock() {
+                            Ok(guard) => guard,
+                            Err(e) => {
+                                return InterpretationResult::Error(InterpreterError::other_error(
+                                    format!("Failed to lock processes: {}", e),
+                                ))
+                            }
+                        };
+                        if let Some(&oldest_pid) = guard.keys().min() {
+                            if let Some(mut oldest) = guard.remove(&oldest_pid) {
+                                if let Some(sender) = oldest.cancel_sender.take() {
+                                    let _ = sender.send(());
+                                }
+                            }
+                        }
+                        break;
+                    }
+                    OnBusy::DoNothing => {
+                        return InterpretationResult::Error(InterpreterError::other_error(
+                            "Interpreter is busy; submission was dropped (on-busy policy: do-nothing)",
+                        ));
+                    }
+                    OnBusy::Reject => {
+                        return InterpretationResult::Error(InterpreterError::other_error(
+                            "Interpreter is busy; submission was rejected (on-busy policy: reject)",
+                        ));
+                    }
+                }
+            }
+        }
+
         // Store the process info
         {
             let mut processes = match processes.lock() {
@@ -287,6 +860,17 @@ impl InterpreterProvider for RholangParserInterpreterProvider {
             }
         };
 
+        // Get the configured timeout
+        let timeout_duration = match self.timeout_duration.lock() {
+            Ok(guard) => *guard,
+            Err(e) => {
+                return InterpretationResult::Error(InterpreterError::other_error(format!(
+                    "Failed to lock timeout_duration: {}",
+                    e
+                )))
+            }
+        };
+
         // Spawn a task to run the parser asynchronously
         let handle = task::spawn(async move {
             // Create a future that completes when the cancel signal is received
@@ -296,30 +880,40 @@ impl InterpreterProvider for RholangParserInterpreterProvider {
             let interpret_future = async {
                 // Add a delay to simulate processing time
                 if delay > 0 {
-                    tokio::time::sleep(Duration::from_millis(delay)).await;
+                    clock.sleep(Duration::from_millis(delay)).await;
                 }
 
                 // Parse the code and return the result
                 match parser.get_pretty_tree(&code_for_task) {
                     ParseResult::Success(tree_string) => InterpretationResult::Success(tree_string),
                     ParseResult::Error(err) => {
-                        let position = err.position.map(|pos| format!("{}", pos));
+                        let position = err.position.map(|pos| SourcePosition {
+                            line: pos.line,
+                            column: pos.column,
+                        });
                         InterpretationResult::Error(InterpreterError::parsing_error(
                             err.message,
                             position,
                             err.source,
                         ))
                     }
+                    ParseResult::Incomplete => {
+                        InterpretationResult::Error(InterpreterError::parsing_error(
+                            "Incomplete input",
+                            None,
+                            Some(code_for_task.clone()),
+                        ))
+                    }
                 }
             };
 
             // Run the parser with a timeout
-            let timeout_future = timeout(Duration::from_secs(30), interpret_future);
+            let timeout_future = timeout(timeout_duration, interpret_future);
 
             // Wait for either the parser to finish, the timeout to expire, or the cancel signal to be received
             tokio::select! {
                 result = timeout_future => {
-                    result.unwrap_or_else(|_| InterpretationResult::Error(InterpreterError::timeout_error("Parser timed out after 30 seconds")))
+                    result.unwrap_or_else(|_| InterpretationResult::Error(InterpreterError::timeout_error(format!("Parser timed out after {:?}", timeout_duration))))
                 }
                 _ = cancel_future => {
                     InterpretationResult::Error(InterpreterError::cancellation_error("Parser was cancelled"))
@@ -347,6 +941,68 @@ impl InterpreterProvider for RholangParserInterpreterProvider {
 
         result
     }
+}
+
+/// Implementation of the InterpreterProvider trait for the Rholang parser
+#[async_trait]
+impl InterpreterProvider for RholangParserInterpreterProvider {
+    /// Run `interpret_once`, retrying per the configured `RetryPolicy` on transient
+    /// failures. Each attempt allocates its own PID (via `interpret_once`), so a
+    /// retry shows up in `list_processes` like any other running process. Once
+    /// retries are exhausted, the final error's message records how many attempts
+    /// were made.
+    async fn interpret(&self, code: &str) -> InterpretationResult {
+        let retry_policy = match self.retry_policy.lock() {
+            Ok(guard) => guard.clone(),
+            Err(e) => {
+                return InterpretationResult::Error(InterpreterError::other_error(format!(
+                    "Failed to lock retry_policy: {}",
+                    e
+                )))
+            }
+        };
+
+        let mut attempt = 1;
+        loop {
+            let result = self.interpret_once(code).await;
+
+            let err = match &result {
+                InterpretationResult::Error(err) => err,
+                InterpretationResult::Success(_) => return result,
+            };
+
+            if attempt < retry_policy.max_attempts && (retry_policy.retry_on)(err) {
+                if retry_policy.backoff > Duration::ZERO {
+                    self.clock.sleep(retry_policy.backoff).await;
+                }
+                attempt += 1;
+                continue;
+            }
+
+            if attempt > 1 {
+                let mut err = err.clone();
+                err.message = format!("{} (after {} attempts)", err.message, attempt);
+                return InterpretationResult::Error(err);
+            }
+
+            return result;
+        }
+    }
+
+    /// Interpret `code`, additionally honoring an externally-supplied cancellation token
+    /// (e.g. from a Ctrl+C handler) alongside the provider's own 30-second timeout.
+    async fn interpret_cancellable(
+        &self,
+        code: &str,
+        token: CancellationToken,
+    ) -> InterpretationResult {
+        tokio::select! {
+            result = self.interpret(code) => result,
+            _ = token.cancelled() => {
+                InterpretationResult::Error(InterpreterError::cancellation_error("Evaluation was cancelled"))
+            }
+        }
+    }
 
     /// List all running processes
     /// Returns a vector of tuples containing the process ID and the code being executed
@@ -400,3 +1056,840 @@ impl InterpreterProvider for RholangParserInterpreterProvider {
         Ok(count)
     }
 }
+
+/// Information about an in-flight [`ControllableInterpreterProvider`] call
+struct ControllableProcess {
+    /// The code being interpreted
+    code: String,
+    /// The cancel sender to abort the process
+    cancel_sender: Option<oneshot::Sender<()>>,
+    /// The sender a test uses to resolve the call via [`ControllableInterpreterProvider::release`]
+    release_sender: Option<oneshot::Sender<InterpretationResult>>,
+}
+
+/// An interpreter provider for deterministic process-management tests: `interpret`
+/// blocks with no real delay or sleep involved until the test either calls
+/// [`ControllableInterpreterProvider::release`] (resolving it with a chosen result)
+/// or cancels it via `kill_process`/`kill_all_processes` (resolving it as cancelled),
+/// so tests no longer need to race a real `sleep(100ms)` against process-management
+/// operations.
+#[derive(Clone, Default)]
+pub struct ControllableInterpreterProvider {
+    /// Map of process ID to process information
+    processes: Arc<Mutex<HashMap<usize, ControllableProcess>>>,
+    /// Next process ID to assign
+    next_pid: Arc<Mutex<usize>>,
+}
+
+impl ControllableInterpreterProvider {
+    /// Create a new instance of the controllable interpreter provider
+    pub fn new() -> Self {
+        ControllableInterpreterProvider::default()
+    }
+
+    /// Resolve the pending `interpret` call tracked under `pid` with `result`.
+    /// Returns `false` if there's no such process (it already finished or was
+    /// never started).
+    pub fn release(&self, pid: usize, result: InterpretationResult) -> Result<bool> {
+        let mut processes = self
+            .processes
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock processes: {}", e))?;
+        if let Some(mut process) = processes.remove(&pid) {
+            if let Some(sender) = process.release_sender.take() {
+                let _ = sender.send(result);
+            }
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+#[async_trait]
+impl InterpreterProvider for ControllableInterpreterProvider {
+    async fn interpret(&self, code: &str) -> InterpretationResult {
+        let (cancel_sender, cancel_receiver) = oneshot::channel();
+        let (release_sender, release_receiver) = oneshot::channel();
+
+        let pid = {
+            let mut next_pid = match self.next_pid.lock() {
+                Ok(guard) => guard,
+                Err(e) => {
+                    return InterpretationResult::Error(InterpreterError::other_error(format!(
+                        "Failed to lock next_pid: {}",
+                        e
+                    )))
+                }
+            };
+            let pid = *next_pid;
+            *next_pid += 1;
+            pid
+        };
+
+        {
+            let mut processes = match self.processes.lock() {
+                Ok(guard) => guard,
+                Err(e) => {
+                    return InterpretationResult::Error(InterpreterError::other_error(format!(
+                        "Failed to lock processes: {}",
+                        e
+                    )))
+                }
+            };
+            processes.insert(
+                pid,
+                ControllableProcess {
+                    code: code.to_string(),
+                    cancel_sender: Some(cancel_sender),
+                    release_sender: Some(release_sender),
+                },
+            );
+        }
+
+        let result = tokio::select! {
+            result = release_receiver => {
+                result.unwrap_or_else(|_| InterpretationResult::Error(InterpreterError::other_error("Process was dropped without being released")))
+            }
+            _ = cancel_receiver => {
+                InterpretationResult::Error(InterpreterError::cancellation_error("Process was cancelled"))
+            }
+        };
+
+        if let Ok(mut processes) = self.processes.lock() {
+            processes.remove(&pid);
+        }
+
+        result
+    }
+
+    fn list_processes(&self) -> Result<Vec<(usize, String)>> {
+        let processes = self
+            .processes
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock processes: {}", e))?;
+        Ok(processes
+            .iter()
+            .map(|(pid, process)| (*pid, process.code.clone()))
+            .collect())
+    }
+
+    fn kill_process(&self, pid: usize) -> Result<bool> {
+        let mut processes = self
+            .processes
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock processes: {}", e))?;
+        if let Some(mut process) = processes.remove(&pid) {
+            if let Some(sender) = process.cancel_sender.take() {
+                let _ = sender.send(());
+            }
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn kill_all_processes(&self) -> Result<usize> {
+        let mut processes = self
+            .processes
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock processes: {}", e))?;
+        let count = processes.len();
+        for (_, mut process) in processes.drain() {
+            if let Some(sender) = process.cancel_sender.take() {
+                let _ = sender.send(());
+            }
+        }
+        Ok(count)
+    }
+}
+
+/// Send one JSON-RPC 2.0 `interpret` request to `command args...`'s stdin and
+/// read the matching response from its stdout: `{"jsonrpc":"2.0","id":id,
+/// "method":"interpret","params":{"code":code}}` answered by either
+/// `{"result":"..."}` or `{"error":{"message":"..."}}` on the next line.
+/// A fresh subprocess is spawned per request, with `kill_on_drop` set so that
+/// dropping this future (e.g. because the caller's `select!` picked a timeout
+/// or cancellation branch instead) also terminates the child.
+async fn run_subprocess_request(command: &str, args: &[String], id: u64, code: &str) -> InterpretationResult {
+    let mut child = match Command::new(command)
+        .args(args)
+        .kill_on_drop(true)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            return InterpretationResult::Error(InterpreterError::other_error(format!(
+                "Failed to spawn interpreter subprocess {}: {}",
+                command, e
+            )))
+        }
+    };
+
+    let mut stdin = match child.stdin.take() {
+        Some(stdin) => stdin,
+        None => {
+            return InterpretationResult::Error(InterpreterError::other_error(
+                "Interpreter subprocess has no stdin",
+            ))
+        }
+    };
+
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "interpret",
+        "params": { "code": code },
+    });
+
+    if let Err(e) = stdin.write_all(format!("{}\n", request).as_bytes()).await {
+        return InterpretationResult::Error(InterpreterError::other_error(format!(
+            "Failed to write to interpreter subprocess: {}",
+            e
+        )));
+    }
+    drop(stdin);
+
+    let stdout = match child.stdout.take() {
+        Some(stdout) => stdout,
+        None => {
+            return InterpretationResult::Error(InterpreterError::other_error(
+                "Interpreter subprocess has no stdout",
+            ))
+        }
+    };
+
+    let response_line = match BufReader::new(stdout).lines().next_line().await {
+        Ok(Some(line)) => line,
+        Ok(None) => {
+            return InterpretationResult::Error(InterpreterError::other_error(
+                "Interpreter subprocess closed stdout without a response",
+            ))
+        }
+        Err(e) => {
+            return InterpretationResult::Error(InterpreterError::other_error(format!(
+                "Failed to read from interpreter subprocess: {}",
+                e
+            )))
+        }
+    };
+
+    let _ = child.kill().await;
+
+    parse_jsonrpc_response(&response_line)
+}
+
+/// Parse a single JSON-RPC 2.0 response line into an `InterpretationResult`.
+fn parse_jsonrpc_response(line: &str) -> InterpretationResult {
+    let response: Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        Err(e) => {
+            return InterpretationResult::Error(InterpreterError::other_error(format!(
+                "Malformed JSON-RPC response: {}",
+                e
+            )))
+        }
+    };
+
+    if let Some(error) = response.get("error") {
+        let message = error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("Unknown interpreter error")
+            .to_string();
+        return InterpretationResult::Error(InterpreterError {
+            kind: ErrorKind::RuntimeError,
+            message,
+            position: None,
+            source: None,
+            span: None,
+        });
+    }
+
+    match response.get("result").and_then(Value::as_str) {
+        Some(result) => InterpretationResult::Success(result.to_string()),
+        None => InterpretationResult::Error(InterpreterError::other_error(
+            "JSON-RPC response missing both result and error",
+        )),
+    }
+}
+
+/// Provider that delegates interpretation to an external interpreter binary,
+/// speaking newline-delimited JSON-RPC 2.0 over its stdio (see
+/// [`run_subprocess_request`]). A fresh subprocess is spawned per `interpret`
+/// call and tracked the same way [`RholangParserInterpreterProvider`] tracks
+/// its parsing tasks, so `list_processes`/`kill_process`/`kill_all_processes`
+/// and the 30-second timeout work identically: killing a process cancels its
+/// oneshot, which drops the `run_subprocess_request` future and, via
+/// `kill_on_drop`, the child process along with it.
+#[derive(Clone)]
+pub struct SubprocessInterpreterProvider {
+    /// The interpreter binary to launch
+    command: Arc<String>,
+    /// Arguments passed to the interpreter binary on every invocation
+    args: Arc<Vec<String>>,
+    /// Map of process ID to process information
+    processes: Arc<Mutex<HashMap<usize, ProcessInfo>>>,
+    /// Next process ID to assign
+    next_pid: Arc<Mutex<usize>>,
+    /// Next JSON-RPC request ID to assign
+    next_request_id: Arc<Mutex<u64>>,
+}
+
+impl SubprocessInterpreterProvider {
+    /// Create a provider that launches `command args...` for every `interpret` call
+    pub fn new(command: impl Into<String>, args: Vec<String>) -> Self {
+        SubprocessInterpreterProvider {
+            command: Arc::new(command.into()),
+            args: Arc::new(args),
+            processes: Arc::new(Mutex::new(HashMap::new())),
+            next_pid: Arc::new(Mutex::new(1)),
+            next_request_id: Arc::new(Mutex::new(1)),
+        }
+    }
+}
+
+/// Implementation of the InterpreterProvider trait for an out-of-process interpreter
+#[async_trait]
+impl InterpreterProvider for SubprocessInterpreterProvider {
+    async fn interpret(&self, code: &str) -> InterpretationResult {
+        let code_clone = code.to_string();
+        let code_for_task = code.to_string();
+
+        let command = Arc::clone(&self.command);
+        let args = Arc::clone(&self.args);
+        let processes = Arc::clone(&self.processes);
+
+        let (cancel_sender, cancel_receiver) = oneshot::channel();
+
+        let pid = {
+            let mut next_pid = match self.next_pid.lock() {
+                Ok(guard) => guard,
+                Err(e) => {
+                    return InterpretationResult::Error(InterpreterError::other_error(format!(
+                        "Failed to lock next_pid: {}",
+                        e
+                    )))
+                }
+            };
+            let pid = *next_pid;
+            *next_pid += 1;
+            pid
+        };
+
+        let request_id = {
+            let mut next_request_id = match self.next_request_id.lock() {
+                Ok(guard) => guard,
+                Err(e) => {
+                    return InterpretationResult::Error(InterpreterError::other_error(format!(
+                        "Failed to lock next_request_id: {}",
+                        e
+                    )))
+                }
+            };
+            let request_id = *next_request_id;
+            *next_request_id += 1;
+            request_id
+        };
+
+        {
+            let mut processes = match processes.lock() {
+                Ok(guard) => guard,
+                Err(e) => {
+                    return InterpretationResult::Error(InterpreterError::other_error(format!(
+                        "Failed to lock processes: {}",
+                        e
+                    )))
+                }
+            };
+            processes.insert(
+                pid,
+                ProcessInfo {
+                    code: code_clone,
+                    cancel_sender: Some(cancel_sender),
+                },
+            );
+        }
+
+        let handle = task::spawn(async move {
+            let interpret_future = run_subprocess_request(&command, &args, request_id, &code_for_task);
+            let timeout_future = timeout(Duration::from_secs(30), interpret_future);
+
+            tokio::select! {
+                result = timeout_future => {
+                    result.unwrap_or_else(|_| InterpretationResult::Error(InterpreterError::timeout_error("Interpreter subprocess timed out after 30 seconds")))
+                }
+                _ = cancel_receiver => {
+                    InterpretationResult::Error(InterpreterError::cancellation_error("Interpreter subprocess was cancelled"))
+                }
+            }
+        });
+
+        let result = handle.await.unwrap_or_else(|e| {
+            InterpretationResult::Error(InterpreterError::other_error(format!("Task error: {}", e)))
+        });
+
+        let mut processes = match self.processes.lock() {
+            Ok(guard) => guard,
+            Err(e) => {
+                return InterpretationResult::Error(InterpreterError::other_error(format!(
+                    "Failed to lock processes: {}",
+                    e
+                )))
+            }
+        };
+        processes.remove(&pid);
+
+        result
+    }
+
+    /// Interpret `code`, additionally honoring an externally-supplied cancellation token
+    /// alongside the provider's own 30-second timeout.
+    async fn interpret_cancellable(&self, code: &str, token: CancellationToken) -> InterpretationResult {
+        tokio::select! {
+            result = self.interpret(code) => result,
+            _ = token.cancelled() => {
+                InterpretationResult::Error(InterpreterError::cancellation_error("Evaluation was cancelled"))
+            }
+        }
+    }
+
+    /// List all running processes
+    fn list_processes(&self) -> Result<Vec<(usize, String)>> {
+        let processes = self
+            .processes
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock processes: {}", e))?;
+        let mut result = Vec::new();
+        for (pid, info) in processes.iter() {
+            result.push((*pid, info.code.clone()));
+        }
+        Ok(result)
+    }
+
+    /// Kill a process by ID
+    fn kill_process(&self, pid: usize) -> Result<bool> {
+        let mut processes = self
+            .processes
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock processes: {}", e))?;
+        if let Some(mut info) = processes.remove(&pid) {
+            if let Some(sender) = info.cancel_sender.take() {
+                let _ = sender.send(());
+            }
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Kill all running processes
+    fn kill_all_processes(&self) -> Result<usize> {
+        let mut processes = self
+            .processes
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock processes: {}", e))?;
+        let count = processes.len();
+        for (_, mut info) in processes.drain() {
+            if let Some(sender) = info.cancel_sender.take() {
+                let _ = sender.send(());
+            }
+        }
+        Ok(count)
+    }
+}
+
+/// Signal `kill_process`/`kill_all_processes` send to a running
+/// [`ExternalInterpreterProvider`] child, mirroring watchexec's stop-signal
+/// option. Defaults to `Terminate`, escalating to `Kill` if the child is
+/// still alive after the configured stop-timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopSignal {
+    Terminate,
+    Interrupt,
+    Hangup,
+    Kill,
+}
+
+impl StopSignal {
+    fn to_nix_signal(self) -> Signal {
+        match self {
+            StopSignal::Terminate => Signal::SIGTERM,
+            StopSignal::Interrupt => Signal::SIGINT,
+            StopSignal::Hangup => Signal::SIGHUP,
+            StopSignal::Kill => Signal::SIGKILL,
+        }
+    }
+}
+
+/// How often `escalate_kill` polls (via the zero-signal liveness probe) for
+/// the child to have exited on its own before the `stop_timeout` deadline
+const ESCALATION_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Send `stop_signal` to the OS process `os_pid`, then poll for up to
+/// `stop_timeout` for it to exit on its own before escalating to `SIGKILL`.
+///
+/// Liveness is probed with the POSIX "signal 0" idiom (`kill(pid, None)`):
+/// it sends no actual signal, it just reports whether `pid` still exists
+/// (an `ESRCH` error means it's gone). This lets `kill_process` escalate
+/// without needing to share the `tokio::process::Child` handle owned by
+/// `interpret`'s spawned task.
+///
+/// This function blocks the calling thread for up to `stop_timeout` via
+/// `std::thread::sleep`, because `InterpreterProvider::kill_process` is a
+/// synchronous method with no `.await` point to wait on; callers on an
+/// async executor should keep `stop_timeout` short.
+fn escalate_kill(os_pid: u32, stop_signal: StopSignal, stop_timeout: Duration) {
+    let pid = Pid::from_raw(os_pid as i32);
+
+    if signal::kill(pid, stop_signal.to_nix_signal()).is_err() {
+        // Already gone.
+        return;
+    }
+
+    let deadline = Instant::now() + stop_timeout;
+    while Instant::now() < deadline {
+        if signal::kill(pid, None).is_err() {
+            return;
+        }
+        std::thread::sleep(ESCALATION_POLL_INTERVAL);
+    }
+
+    let _ = signal::kill(pid, Signal::SIGKILL);
+}
+
+/// Information about a running [`ExternalInterpreterProvider`] child process
+struct ExternalProcessInfo {
+    /// The code being interpreted
+    code: String,
+    /// The real OS process ID of the spawned child, targeted by
+    /// `kill_process`'s stop-signal escalation
+    os_pid: u32,
+}
+
+/// Provider that interprets code by launching an actual `rnode`/evaluator
+/// subprocess per call via `tokio::process::Command`, rather than parsing
+/// in-process like [`RholangParserInterpreterProvider`] or round-tripping a
+/// single JSON-RPC request like [`SubprocessInterpreterProvider`]. This gives
+/// the shell a real execution path: `code` is written to the child's stdin,
+/// and its stdout/exit status become the `InterpretationResult`.
+///
+/// Unlike the other providers, `kill_process`/`kill_all_processes` don't
+/// cancel an in-flight future via a oneshot -- they send a real OS signal
+/// (`stop_signal`, default `SIGTERM`) to the tracked `os_pid`, escalating to
+/// `SIGKILL` if the child hasn't exited after `stop_timeout` (see
+/// [`escalate_kill`]). `interpret`'s own spawned task observes the child
+/// exiting as a non-zero exit status, surfaced as `InterpreterError`.
+#[derive(Clone)]
+pub struct ExternalInterpreterProvider {
+    /// The interpreter binary to launch
+    command: Arc<String>,
+    /// Arguments passed to the interpreter binary on every invocation
+    args: Arc<Vec<String>>,
+    /// Map of process ID to process information
+    processes: Arc<Mutex<HashMap<usize, ExternalProcessInfo>>>,
+    /// Next process ID to assign
+    next_pid: Arc<Mutex<usize>>,
+    /// Signal sent to ask a child to stop before escalating to `SIGKILL`
+    stop_signal: Arc<Mutex<StopSignal>>,
+    /// How long to wait for `stop_signal` to take effect before escalating
+    stop_timeout: Arc<Mutex<Duration>>,
+}
+
+impl ExternalInterpreterProvider {
+    /// Create a provider that launches `command args...` for every `interpret` call
+    pub fn new(command: impl Into<String>, args: Vec<String>) -> Self {
+        ExternalInterpreterProvider {
+            command: Arc::new(command.into()),
+            args: Arc::new(args),
+            processes: Arc::new(Mutex::new(HashMap::new())),
+            next_pid: Arc::new(Mutex::new(1)),
+            stop_signal: Arc::new(Mutex::new(StopSignal::Terminate)),
+            stop_timeout: Arc::new(Mutex::new(Duration::from_secs(2))),
+        }
+    }
+
+    /// Set the signal `kill_process`/`kill_all_processes` send before escalating to `SIGKILL`
+    pub fn set_stop_signal(&self, stop_signal: StopSignal) -> Result<()> {
+        let mut guard = self
+            .stop_signal
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock stop_signal: {}", e))?;
+        *guard = stop_signal;
+        Ok(())
+    }
+
+    /// Set how long `kill_process`/`kill_all_processes` wait for `stop_signal` to take
+    /// effect before escalating to `SIGKILL`
+    pub fn set_stop_timeout(&self, stop_timeout: Duration) -> Result<()> {
+        let mut guard = self
+            .stop_timeout
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock stop_timeout: {}", e))?;
+        *guard = stop_timeout;
+        Ok(())
+    }
+}
+
+/// Implementation of the InterpreterProvider trait for a real OS-process execution backend
+#[async_trait]
+impl InterpreterProvider for ExternalInterpreterProvider {
+    async fn interpret(&self, code: &str) -> InterpretationResult {
+        let mut child = match Command::new(self.command.as_str())
+            .args(self.args.as_slice())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                return InterpretationResult::Error(InterpreterError::other_error(format!(
+                    "Failed to spawn external interpreter {}: {}",
+                    self.command, e
+                )))
+            }
+        };
+
+        let os_pid = match child.id() {
+            Some(os_pid) => os_pid,
+            None => {
+                return InterpretationResult::Error(InterpreterError::other_error(
+                    "External interpreter exited immediately after spawn",
+                ))
+            }
+        };
+
+        let pid = {
+            let mut next_pid = match self.next_pid.lock() {
+                Ok(guard) => guard,
+                Err(e) => {
+                    return InterpretationResult::Error(InterpreterError::other_error(format!(
+                        "Failed to lock next_pid: {}",
+                        e
+                    )))
+                }
+            };
+            let pid = *next_pid;
+            *next_pid += 1;
+            pid
+        };
+
+        {
+            let mut processes = match self.processes.lock() {
+                Ok(guard) => guard,
+                Err(e) => {
+                    return InterpretationResult::Error(InterpreterError::other_error(format!(
+                        "Failed to lock processes: {}",
+                        e
+                    )))
+                }
+            };
+            processes.insert(
+                pid,
+                ExternalProcessInfo {
+                    code: code.to_string(),
+                    os_pid,
+                },
+            );
+        }
+
+        let finish = || {
+            if let Ok(mut processes) = self.processes.lock() {
+                processes.remove(&pid);
+            }
+        };
+
+        if let Some(mut stdin) = child.stdin.take() {
+            if let Err(e) = stdin.write_all(code.as_bytes()).await {
+                finish();
+                return InterpretationResult::Error(InterpreterError::other_error(format!(
+                    "Failed to write to external interpreter: {}",
+                    e
+                )));
+            }
+        }
+
+        let output = match child.wait_with_output().await {
+            Ok(output) => output,
+            Err(e) => {
+                finish();
+                return InterpretationResult::Error(InterpreterError::other_error(format!(
+                    "Failed to wait on external interpreter: {}",
+                    e
+                )));
+            }
+        };
+
+        finish();
+
+        if output.status.success() {
+            InterpretationResult::Success(String::from_utf8_lossy(&output.stdout).into_owned())
+        } else {
+            InterpretationResult::Error(InterpreterError {
+                kind: ErrorKind::RuntimeError,
+                message: format!(
+                    "External interpreter exited with {}: {}",
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr)
+                ),
+                position: None,
+                source: None,
+                span: None,
+            })
+        }
+    }
+
+    /// Interpret `code`, additionally honoring an externally-supplied cancellation token by
+    /// killing the spawned child the same way `kill_process` would.
+    async fn interpret_cancellable(&self, code: &str, token: CancellationToken) -> InterpretationResult {
+        tokio::select! {
+            result = self.interpret(code) => result,
+            _ = token.cancelled() => {
+                if let Ok(processes) = self.list_processes() {
+                    for (pid, process_code) in processes {
+                        if process_code == code {
+                            let _ = self.kill_process(pid);
+                        }
+                    }
+                }
+                InterpretationResult::Error(InterpreterError::cancellation_error("Evaluation was cancelled"))
+            }
+        }
+    }
+
+    /// List all running processes
+    fn list_processes(&self) -> Result<Vec<(usize, String)>> {
+        let processes = self
+            .processes
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock processes: {}", e))?;
+        Ok(processes
+            .iter()
+            .map(|(pid, info)| (*pid, info.code.clone()))
+            .collect())
+    }
+
+    /// Send the configured stop signal to the process's OS pid, escalating to `SIGKILL` if it's
+    /// still alive after `stop_timeout`. `interpret`'s own task observes the child exiting and
+    /// removes it from the process map, so this doesn't remove it itself.
+    fn kill_process(&self, pid: usize) -> Result<bool> {
+        let os_pid = {
+            let processes = self
+                .processes
+                .lock()
+                .map_err(|e| anyhow!("Failed to lock processes: {}", e))?;
+            match processes.get(&pid) {
+                Some(info) => info.os_pid,
+                None => return Ok(false),
+            }
+        };
+
+        let stop_signal = *self
+            .stop_signal
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock stop_signal: {}", e))?;
+        let stop_timeout = *self
+            .stop_timeout
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock stop_timeout: {}", e))?;
+
+        escalate_kill(os_pid, stop_signal, stop_timeout);
+        Ok(true)
+    }
+
+    /// Apply the same stop-signal escalation as `kill_process` to every running process
+    fn kill_all_processes(&self) -> Result<usize> {
+        let os_pids: Vec<u32> = {
+            let processes = self
+                .processes
+                .lock()
+                .map_err(|e| anyhow!("Failed to lock processes: {}", e))?;
+            processes.values().map(|info| info.os_pid).collect()
+        };
+
+        let stop_signal = *self
+            .stop_signal
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock stop_signal: {}", e))?;
+        let stop_timeout = *self
+            .stop_timeout
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock stop_timeout: {}", e))?;
+
+        for &os_pid in &os_pids {
+            escalate_kill(os_pid, stop_signal, stop_timeout);
+        }
+
+        Ok(os_pids.len())
+    }
+}
+
+/// Wraps any `InterpreterProvider` with a [`Permissions`] check: before
+/// delegating, statically scans `code` for [`requested_capabilities`], and
+/// refuses to run it (without ever invoking the wrapped provider) if any of
+/// those capabilities isn't `Granted`.
+///
+/// `PermissionState::Prompt` is treated the same as `Denied` here: this
+/// provider sits behind `InterpreterProvider::interpret`'s synchronous,
+/// non-interactive interface, with no terminal to prompt on. A caller that
+/// wants real prompting should resolve `Prompt` capabilities to a concrete
+/// `Granted`/`Denied` (e.g. by asking the user) before constructing the
+/// `Permissions` this provider is built with.
+#[derive(Clone)]
+pub struct PermissionedInterpreterProvider<P> {
+    inner: P,
+    permissions: Permissions,
+}
+
+impl<P: InterpreterProvider + Sync> PermissionedInterpreterProvider<P> {
+    /// Wrap `inner`, gating every `interpret`/`interpret_cancellable` call on `permissions`
+    pub fn new(inner: P, permissions: Permissions) -> Self {
+        PermissionedInterpreterProvider { inner, permissions }
+    }
+
+    /// Check `code`'s statically-requested capabilities against `self.permissions`,
+    /// returning the denial to surface as an `InterpretationResult::Error` if any
+    /// of them isn't granted.
+    fn check(&self, code: &str) -> Option<InterpretationResult> {
+        for (capability, uri) in requested_capabilities(code) {
+            if self.permissions.state(capability) != PermissionState::Granted {
+                return Some(InterpretationResult::Error(InterpreterError::other_error(
+                    format!("permission denied for {}", uri),
+                )));
+            }
+        }
+        None
+    }
+}
+
+#[async_trait]
+impl<P: InterpreterProvider + Sync> InterpreterProvider for PermissionedInterpreterProvider<P> {
+    async fn interpret(&self, code: &str) -> InterpretationResult {
+        match self.check(code) {
+            Some(denied) => denied,
+            None => self.inner.interpret(code).await,
+        }
+    }
+
+    async fn interpret_cancellable(&self, code: &str, token: CancellationToken) -> InterpretationResult {
+        match self.check(code) {
+            Some(denied) => denied,
+            None => self.inner.interpret_cancellable(code, token).await,
+        }
+    }
+
+    fn list_processes(&self) -> Result<Vec<(usize, String)>> {
+        self.inner.list_processes()
+    }
+
+    fn kill_process(&self, pid: usize) -> Result<bool> {
+        self.inner.kill_process(pid)
+    }
+
+    fn kill_all_processes(&self) -> Result<usize> {
+        self.inner.kill_all_processes()
+    }
+}
@@ -0,0 +1,189 @@
+//! Static name resolution over a parsed Rholang tree-sitter tree, intended to
+//! run before a reducer hands source off to evaluation -- the same separate
+//! resolution pass a tree-walking interpreter runs before evaluation.
+//! [`resolve`] walks the tree once, tracking a scope stack of bound names,
+//! and resolves every channel/variable reference against it to a De Bruijn
+//! [`DebruijnIndex`] (`depth`, `slot`) pair. A reference with no enclosing
+//! binding is a free name, reported as an [`InterpreterError::runtime_error`]
+//! with its exact [`SourcePosition`] instead of only surfacing once
+//! evaluation actually reaches it.
+//!
+//! This covers the two binding forms the request for it named -- `new`'s
+//! `decls` and `for`'s receive patterns -- which is also where the reducer's
+//! own De Bruijn-indexed environment actually needs slots. `contract`
+//! formals, `let`, and `match` patterns bind names too, but by structural
+//! pattern match rather than positional lookup, so they're left for a
+//! later pass rather than forced into this same index scheme.
+//!
+//! [`resolve`]'s only intended caller is `rh_interpreter::RhInterpreter::interpret`,
+//! but that file depends on an external `rholang` crate that isn't part of
+//! this workspace and isn't declared as a module anywhere in this crate, so
+//! that wiring doesn't exist in any buildable form yet -- `resolve` is
+//! reachable only from its own tests below until that dependency lands.
+use crate::providers::{InterpreterError, SourcePosition};
+use rholang_tree_sitter_proc_macro::{field, kind};
+use std::collections::HashMap;
+
+/// Where one reference resolved to: `depth` scopes out from where it's
+/// used, and `slot` within that scope's declaration order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DebruijnIndex {
+    pub depth: usize,
+    pub slot: usize,
+}
+
+/// Every resolved reference in a tree, keyed by `tree_sitter::Node::id()`.
+#[derive(Debug, Default)]
+pub struct ResolutionMap {
+    indices: HashMap<usize, DebruijnIndex>,
+}
+
+impl ResolutionMap {
+    /// The `(depth, slot)` `node` resolved to, if it's a reference [`resolve`]
+    /// was able to bind.
+    pub fn get(&self, node: &tree_sitter::Node) -> Option<DebruijnIndex> {
+        self.indices.get(&node.id()).copied()
+    }
+}
+
+/// One `new`/`for`-introduced scope: the names it binds, in declaration
+/// order (so a name's index in this `Vec` is its De Bruijn slot).
+struct Scope {
+    names: Vec<String>,
+}
+
+/// Walk `tree`, resolving every `var`/`var_ref` reference against the
+/// scopes opened by enclosing `new`/`for` nodes. Returns the resolution map
+/// alongside one [`InterpreterError::runtime_error`] per reference with no
+/// enclosing binding.
+pub fn resolve(tree: &tree_sitter::Tree, code: &str) -> (ResolutionMap, Vec<InterpreterError>) {
+    let mut map = ResolutionMap::default();
+    let mut errors = Vec::new();
+    let mut scopes: Vec<Scope> = Vec::new();
+
+    walk_node(tree.root_node(), code, &mut scopes, &mut map, &mut errors);
+
+    (map, errors)
+}
+
+fn walk_node(
+    node: tree_sitter::Node,
+    code: &str,
+    scopes: &mut Vec<Scope>,
+    map: &mut ResolutionMap,
+    errors: &mut Vec<InterpreterError>,
+) {
+    if node.kind_id() == kind!("new") {
+        let names = node
+            .child_by_field_id(field!("decls"))
+            .map(|decls| decl_names(decls, code))
+            .unwrap_or_default();
+
+        scopes.push(Scope { names });
+        if let Some(proc_node) = node.child_by_field_id(field!("proc")) {
+            walk_node(proc_node, code, scopes, map, errors);
+        }
+        scopes.pop();
+        return;
+    }
+
+    if node.kind_id() == kind!("input") {
+        let mut names = Vec::new();
+        if let Some(receipts) = node.child_by_field_id(field!("receipts")) {
+            let mut receipts_cursor = receipts.walk();
+            for receipt in receipts.named_children(&mut receipts_cursor) {
+                let mut binds_cursor = receipt.walk();
+                for bind in receipt.named_children(&mut binds_cursor) {
+                    // Resolve the bind's source/channel expression against the
+                    // *outer* scopes -- it's the rhs of `<-`, evaluated before
+                    // any of this `for`'s own pattern names come into scope.
+                    let mut bind_children = bind.named_children(&mut bind.walk());
+                    let (pattern, source) = match (bind_children.next(), bind_children.next()) {
+                        (Some(pattern), Some(source)) => (Some(pattern), source),
+                        (Some(source), None) => (None, source),
+                        (None, None) => continue,
+                    };
+                    walk_node(source, code, scopes, map, errors);
+                    if let Some(pattern) = pattern {
+                        collect_vars(pattern, code, &mut names);
+                    }
+                }
+            }
+        }
+
+        scopes.push(Scope { names });
+        if let Some(proc_node) = node.child_by_field_id(field!("proc")) {
+            walk_node(proc_node, code, scopes, map, errors);
+        }
+        scopes.pop();
+        return;
+    }
+
+    if node.kind_id() == kind!("var") || node.kind_id() == kind!("var_ref") {
+        resolve_reference(node, code, scopes, map, errors);
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        walk_node(child, code, scopes, map, errors);
+    }
+}
+
+/// The declared name of each child of a `new` node's `decls` field, in
+/// order -- each declaration's own first child is the bound `var`.
+fn decl_names(decls: tree_sitter::Node, code: &str) -> Vec<String> {
+    let mut cursor = decls.walk();
+    decls
+        .named_children(&mut cursor)
+        .filter_map(|decl| decl.named_child(0))
+        .map(|var| node_text(var, code).to_string())
+        .collect()
+}
+
+/// Every `var` leaf under `node`, left-to-right -- used to pull the names a
+/// `for` bind's pattern introduces, whether it's a bare channel name or a
+/// quoted process pattern like `@x`.
+fn collect_vars(node: tree_sitter::Node, code: &str, out: &mut Vec<String>) {
+    if node.kind_id() == kind!("var") {
+        out.push(node_text(node, code).to_string());
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_vars(child, code, out);
+    }
+}
+
+fn resolve_reference(
+    node: tree_sitter::Node,
+    code: &str,
+    scopes: &[Scope],
+    map: &mut ResolutionMap,
+    errors: &mut Vec<InterpreterError>,
+) {
+    let name = node_text(node, code);
+
+    for (depth, scope) in scopes.iter().rev().enumerate() {
+        if let Some(slot) = scope.names.iter().position(|bound| bound == name) {
+            map.indices.insert(node.id(), DebruijnIndex { depth, slot });
+            return;
+        }
+    }
+
+    let point = node.start_position();
+    let position = SourcePosition {
+        line: point.row + 1,
+        column: point.column + 1,
+    };
+    errors.push(InterpreterError::runtime_error(
+        format!("unbound name `{name}`"),
+        Some(position),
+        Some(name.to_string()),
+    ));
+}
+
+fn node_text<'a>(node: tree_sitter::Node, code: &'a str) -> &'a str {
+    node.utf8_text(code.as_bytes()).unwrap_or("")
+}
@@ -1,7 +1,19 @@
+//! Bridges [`crate::resolver`] and the reducer's own error type onto this
+//! crate's [`Interpreter`]/[`crate::providers::InterpreterError`] shapes.
+//!
+//! **Blocked:** this file is not declared as a module anywhere in the crate
+//! (no `mod rh_interpreter;` in `lib.rs` or `main.rs`) because it depends on
+//! `rholang::rust::interpreter::*`, an external crate that isn't part of
+//! this workspace. It doesn't compile and isn't exercised by any test --
+//! treat the code below as written against that crate's API as understood
+//! at the time, not as a confirmed match, until the dependency is added and
+//! this module is wired in.
+
 use crate::interpreter::Interpreter;
+use crate::resolver;
 
 use rholang::rust::interpreter::interpreter;
-use rholang::rust::interpreter::errors::InterpreterError;
+use rholang::rust::interpreter::errors::{ErrorKind as RholangErrorKind, InterpreterError as RholangInterpreterError};
 use rholang::rust::interpreter::reduce::DebruijnInterpreter;
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex, RwLock};
@@ -13,10 +25,48 @@ use rholang::rust::interpreter::dispatch::RholangAndScalaDispatcher;
 
 pub struct RhInterpreter;
 
+/// Map the reducer's own error type onto [`crate::providers::InterpreterError`],
+/// preserving `ErrorKind` and `SourcePosition` instead of the `{:?}`-debug blob
+/// `interpret` used to collapse every failure into -- so a caller can
+/// `downcast_ref::<crate::providers::InterpreterError>()` on the `anyhow::Error`
+/// `interpret` returns and branch on `ParsingError` vs `RuntimeError` vs
+/// `TimeoutError`.
+impl From<RholangInterpreterError> for crate::providers::InterpreterError {
+    fn from(error: RholangInterpreterError) -> Self {
+        use crate::providers::{ErrorKind, SourcePosition};
+
+        let kind = match error.kind {
+            RholangErrorKind::ParsingError => ErrorKind::ParsingError,
+            RholangErrorKind::RuntimeError => ErrorKind::RuntimeError,
+            RholangErrorKind::TimeoutError => ErrorKind::TimeoutError,
+            RholangErrorKind::CancellationError => ErrorKind::CancellationError,
+            _ => ErrorKind::OtherError,
+        };
+        let position = error
+            .position
+            .map(|position| SourcePosition { line: position.line, column: position.column });
+
+        crate::providers::InterpreterError {
+            kind,
+            message: error.message,
+            position,
+            source: None,
+            span: None,
+        }
+    }
+}
+
 #[async_trait]
 impl Interpreter for RhInterpreter {
     
-        /// Creates a default DebruijnInterpreter instance configured and ready to use
+        /// Creates a default DebruijnInterpreter instance configured and ready to use.
+        ///
+        /// `environment_map`/`free_map` start out empty here because this constructor
+        /// has no program to resolve yet; once one is available, [`resolver::resolve`]
+        /// is the principled source for them -- its `ResolutionMap` gives each bound
+        /// name's De Bruijn slot (for `environment_map`) and its `Vec<InterpreterError>`
+        /// names every reference that resolved to nothing (for `free_map`), rather than
+        /// these collections discovering either only as `execute` runs.
     fn create_default_interpreter() -> DebruijnInterpreter {
         // Create shared resources
         let environment_map = Arc::new(RwLock::new(HashMap::new()));
@@ -39,6 +89,18 @@ impl Interpreter for RhInterpreter {
     }
 
     async fn interpret(&self, code: String) -> Result<String> {
+        // Resolve names statically before handing the program to the reducer, so a
+        // free channel/variable is reported with its exact position instead of
+        // surfacing later as an opaque runtime failure.
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&rholang_tree_sitter::LANGUAGE.into())?;
+        if let Some(tree) = parser.parse(&code, None) {
+            let (_resolution, errors) = resolver::resolve(&tree, &code);
+            if let Some(error) = errors.into_iter().next() {
+                return Err(anyhow!("{error}"));
+            }
+        }
+
         // Create a new DebruijnInterpreter
         let map = Arc::new(RwLock::new(HashMap::new()));
         let set = Arc::new(RwLock::new(HashSet::new()));
@@ -48,7 +110,7 @@ impl Interpreter for RhInterpreter {
         // Execute the Rholang code
         match db_interpreter.execute(code.as_str()) {
             Ok(result) => Ok(result.pretty_print()),
-            Err(error) => Err(anyhow!("Interpreter error: {:?}", error))
+            Err(error) => Err(anyhow::Error::new(crate::providers::InterpreterError::from(error))),
         }
     }
 }
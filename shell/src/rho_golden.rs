@@ -0,0 +1,292 @@
+//! A second, stricter annotation-driven `.rho` test runner, for suites that
+//! want structured, regex-based expectations instead of [`rho_test`]'s
+//! single `// EXPECT: <substring>` / `//~ ERROR <substring>` directives.
+//!
+//! Every line beginning with `//=` at the very start of the file is
+//! concatenated (with the `//=` prefix stripped) and parsed as one JSON
+//! object, the file's header; the remaining lines are the program:
+//!
+//! ```text
+//! //= {"output": {"stdout": "Hello, .*!", "stderr": "^$"}, "exit": "success"}
+//! new stdout(`rho:io:stdout`) in { stdout!("Hello, world!") }
+//! ```
+//!
+//! `stdout`/`stderr` are each a newline-delimited list of regex patterns,
+//! one per expected output line; the captured stream is checked against
+//! them as a full-line, order-independent multiset match (every produced
+//! line matches some pattern and every pattern matches some line), so
+//! output whose lines come out in a different order each run still passes.
+//!
+//! [`InterpreterProvider::interpret`] surfaces only a single result string
+//! rather than genuinely separate stdout/stderr handles, so this runner maps
+//! a [`InterpretationResult::Success`] output to `stdout` and a
+//! [`InterpretationResult::Error`] message to `stderr` -- the same
+//! success/failure split [`InterpretationResult`] itself already draws.
+
+use crate::providers::{InterpretationResult, InterpreterProvider};
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// The `//=`-header JSON object
+#[derive(Debug, Clone, Deserialize)]
+struct GoldenHeader {
+    output: GoldenOutput,
+    exit: ExitExpectation,
+}
+
+/// Per-stream expected patterns, newline-delimited, one pattern per expected
+/// line. Either stream may be omitted, in which case it isn't checked.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct GoldenOutput {
+    stdout: Option<String>,
+    stderr: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ExitExpectation {
+    Success,
+    Failure,
+}
+
+/// One `.rho` golden file's outcome
+#[derive(Debug, Clone)]
+pub struct GoldenResult {
+    pub path: PathBuf,
+    pub passed: bool,
+    /// Why it failed, one line per violated expectation, or `None` if it passed
+    pub failure: Option<String>,
+}
+
+/// Split `source` into its `//=` header (prefix-stripped, joined by whitespace)
+/// and the program that follows it
+fn split_header(source: &str) -> (String, String) {
+    let mut header = String::new();
+    let mut header_lines = 0;
+
+    for line in source.lines() {
+        match line.strip_prefix("//=") {
+            Some(rest) => {
+                header.push_str(rest.trim());
+                header_lines += 1;
+            }
+            None => break,
+        }
+    }
+
+    let body = source.lines().skip(header_lines).collect::<Vec<_>>().join("\n");
+    (header, body)
+}
+
+/// `text` split into lines for multiset matching -- unlike `str::lines`, an
+/// empty stream is one empty line rather than zero lines, so a `"^$"`
+/// pattern can match "no output" the way a test author would expect.
+fn output_lines(text: &str) -> Vec<&str> {
+    if text.is_empty() {
+        vec![""]
+    } else {
+        text.lines().collect()
+    }
+}
+
+/// Whether `patterns` and `lines` admit a perfect bipartite matching --
+/// every pattern matched by a distinct line and vice versa -- via Kuhn's
+/// augmenting-path algorithm. Suites are small enough that this simple
+/// O(V*E) approach is plenty fast.
+fn full_multiset_match(patterns: &[Regex], lines: &[&str]) -> bool {
+    if patterns.len() != lines.len() {
+        return false;
+    }
+
+    let mut line_assignment: Vec<Option<usize>> = vec![None; lines.len()];
+
+    fn augment(
+        pattern_index: usize,
+        patterns: &[Regex],
+        lines: &[&str],
+        line_assignment: &mut [Option<usize>],
+        visited: &mut [bool],
+    ) -> bool {
+        for (line_index, line) in lines.iter().enumerate() {
+            if visited[line_index] || !patterns[pattern_index].is_match(line) {
+                continue;
+            }
+            visited[line_index] = true;
+
+            let available = match line_assignment[line_index] {
+                None => true,
+                Some(other) => augment(other, patterns, lines, line_assignment, visited),
+            };
+            if available {
+                line_assignment[line_index] = Some(pattern_index);
+                return true;
+            }
+        }
+        false
+    }
+
+    for pattern_index in 0..patterns.len() {
+        let mut visited = vec![false; lines.len()];
+        if !augment(pattern_index, patterns, lines, &mut line_assignment, &mut visited) {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Check `actual`'s lines against `expected`'s newline-delimited patterns (if
+/// any), returning a failure description on mismatch
+fn check_stream(name: &str, expected: Option<&str>, actual: &str) -> Result<Option<String>> {
+    let Some(expected) = expected else {
+        return Ok(None);
+    };
+
+    let patterns: Vec<Regex> = expected
+        .lines()
+        .map(Regex::new)
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|e| anyhow!("invalid {name} pattern in //= header: {e}"))?;
+    let lines = output_lines(actual);
+
+    if full_multiset_match(&patterns, &lines) {
+        Ok(None)
+    } else {
+        Ok(Some(format!(
+            "{name}: expected lines matching {:?}, got {:?}",
+            expected.lines().collect::<Vec<_>>(),
+            lines
+        )))
+    }
+}
+
+/// Run one golden `.rho` file through `provider` and check it against its
+/// `//=` header
+pub async fn run_golden_file<I: InterpreterProvider>(path: &Path, provider: &I) -> Result<GoldenResult> {
+    let source = std::fs::read_to_string(path)?;
+    let (header, body) = split_header(&source);
+    let header: GoldenHeader =
+        serde_json::from_str(&header).map_err(|e| anyhow!("{}: invalid //= header: {e}", path.display()))?;
+
+    let result = provider.interpret(&body).await;
+
+    let mut failures = Vec::new();
+
+    let expected_success = header.exit == ExitExpectation::Success;
+    if result.is_success() != expected_success {
+        failures.push(format!(
+            "expected exit {:?}, got {}",
+            header.exit,
+            if result.is_success() { "success" } else { "failure" }
+        ));
+    }
+
+    let (stdout_text, stderr_text) = match &result {
+        InterpretationResult::Success(output) => (output.as_str(), ""),
+        InterpretationResult::Error(err) => ("", err.message.as_str()),
+    };
+
+    if let Some(failure) = check_stream("stdout", header.output.stdout.as_deref(), stdout_text)? {
+        failures.push(failure);
+    }
+    if let Some(failure) = check_stream("stderr", header.output.stderr.as_deref(), stderr_text)? {
+        failures.push(failure);
+    }
+
+    Ok(GoldenResult {
+        path: path.to_path_buf(),
+        passed: failures.is_empty(),
+        failure: if failures.is_empty() {
+            None
+        } else {
+            Some(failures.join("; "))
+        },
+    })
+}
+
+/// Run every `.rho` file matched by `pattern` (a glob, e.g. `tests/golden/*.rho`)
+/// through [`run_golden_file`], printing a per-file pass/fail summary. Returns
+/// `Ok(())` if every file passed, or an error summarizing how many didn't --
+/// the caller surfaces that as a nonzero process exit code for CI.
+pub async fn run_batch<I: InterpreterProvider, W: std::io::Write>(
+    pattern: &str,
+    provider: &I,
+    stdout: &mut W,
+) -> Result<()> {
+    let paths = glob::glob(pattern).map_err(|e| anyhow!("invalid glob {pattern:?}: {e}"))?;
+
+    let mut passed = 0;
+    let mut failed = 0;
+
+    for entry in paths {
+        let path = entry?;
+        let result = run_golden_file(&path, provider).await?;
+
+        if result.passed {
+            passed += 1;
+            writeln!(stdout, "ok   {}", result.path.display())?;
+        } else {
+            failed += 1;
+            writeln!(
+                stdout,
+                "FAIL {} -- {}",
+                result.path.display(),
+                result.failure.unwrap_or_default()
+            )?;
+        }
+    }
+
+    writeln!(stdout, "{passed} passed, {failed} failed")?;
+
+    if failed > 0 {
+        anyhow::bail!("{failed} golden test(s) failed");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_header_strips_the_prefix_and_keeps_the_rest_as_the_body() {
+        let source = "//= {\"a\": 1}\nnew x in { Nil }";
+        let (header, body) = split_header(source);
+        assert_eq!(header, "{\"a\": 1}");
+        assert_eq!(body, "new x in { Nil }");
+    }
+
+    #[test]
+    fn test_split_header_joins_multiple_header_lines() {
+        let source = "//= {\"a\":\n//= 1}\nbody";
+        let (header, body) = split_header(source);
+        assert_eq!(header, "{\"a\":1}");
+        assert_eq!(body, "body");
+    }
+
+    #[test]
+    fn test_full_multiset_match_ignores_order() {
+        let patterns = vec![Regex::new("^a$").unwrap(), Regex::new("^b$").unwrap()];
+        assert!(full_multiset_match(&patterns, &["b", "a"]));
+    }
+
+    #[test]
+    fn test_full_multiset_match_fails_on_count_mismatch() {
+        let patterns = vec![Regex::new("^a$").unwrap()];
+        assert!(!full_multiset_match(&patterns, &["a", "a"]));
+    }
+
+    #[test]
+    fn test_check_stream_matches_empty_output_against_anchored_empty_pattern() {
+        let result = check_stream("stderr", Some("^$"), "").unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_check_stream_reports_a_mismatch() {
+        let result = check_stream("stdout", Some("^Hello.*$"), "Goodbye").unwrap();
+        assert!(result.is_some());
+    }
+}
@@ -0,0 +1,178 @@
+//! Annotation-driven `.rho` test runner, adapted from lang_tester/ui_test:
+//! scan a source file for inline expectation comments, feed the whole file
+//! to an [`InterpreterProvider`], and compare the resulting
+//! [`InterpretationResult`] against them.
+//!
+//! Two directive forms are recognized:
+//! - `// EXPECT: <substring>` -- the file must interpret as
+//!   [`InterpretationResult::Success`] whose output contains `<substring>`
+//! - `//~ ERROR <substring>` -- the file must interpret as
+//!   [`InterpretationResult::Error`] whose message contains `<substring>`
+//!
+//! [`run_test_file`] is the entry point a `.rho` golden-file suite's CI loop
+//! would call per file; its `bless` flag rewrites a file's directives from
+//! the actual output instead of checking them, so baselines can be
+//! regenerated in bulk rather than hand-edited one substring at a time.
+
+use crate::providers::{InterpretationResult, InterpreterProvider};
+use anyhow::Result;
+use std::path::Path;
+
+/// What a single scanned directive asserts about the file's interpretation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationKind {
+    /// `// EXPECT: <substring>`
+    Expect,
+    /// `//~ ERROR <substring>`
+    Error,
+}
+
+/// One directive scanned out of a `.rho` source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    /// 1-based source line the directive appears on
+    pub line: usize,
+    pub kind: AnnotationKind,
+    pub pattern: String,
+}
+
+/// Scan `source` for `// EXPECT: <substring>` and `//~ ERROR <substring>` directives
+pub fn scan_annotations(source: &str) -> Vec<Annotation> {
+    let mut annotations = Vec::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+
+        if let Some(pattern) = trimmed.strip_prefix("// EXPECT:") {
+            annotations.push(Annotation {
+                line: index + 1,
+                kind: AnnotationKind::Expect,
+                pattern: pattern.trim().to_string(),
+            });
+        } else if let Some(pattern) = trimmed.strip_prefix("//~ ERROR") {
+            annotations.push(Annotation {
+                line: index + 1,
+                kind: AnnotationKind::Error,
+                pattern: pattern.trim().to_string(),
+            });
+        }
+    }
+
+    annotations
+}
+
+/// Trim trailing whitespace from every line, so differences in line-ending
+/// whitespace don't fail a match
+fn normalize(text: &str) -> String {
+    text.lines().map(|line| line.trim_end()).collect::<Vec<_>>().join("\n")
+}
+
+/// One annotation's pass/fail outcome against an already-computed result
+#[derive(Debug, Clone)]
+pub struct AnnotationResult {
+    pub annotation: Annotation,
+    pub passed: bool,
+}
+
+/// Check every annotation scanned out of `source` against `result`
+pub fn check_annotations(source: &str, result: &InterpretationResult) -> Vec<AnnotationResult> {
+    let normalized = match result {
+        InterpretationResult::Success(output) => normalize(output),
+        InterpretationResult::Error(err) => normalize(&err.message),
+    };
+
+    scan_annotations(source)
+        .into_iter()
+        .map(|annotation| {
+            let passed = match annotation.kind {
+                AnnotationKind::Expect => result.is_success() && normalized.contains(&annotation.pattern),
+                AnnotationKind::Error => result.is_error() && normalized.contains(&annotation.pattern),
+            };
+            AnnotationResult { annotation, passed }
+        })
+        .collect()
+}
+
+/// Render a unified-diff-style report for one failed annotation: what was
+/// expected versus what the interpreter actually returned.
+pub fn diff_report(annotation: &Annotation, actual: &InterpretationResult) -> String {
+    let expected_kind = match annotation.kind {
+        AnnotationKind::Expect => "Success",
+        AnnotationKind::Error => "Error",
+    };
+    let (actual_kind, actual_text) = match actual {
+        InterpretationResult::Success(output) => ("Success", output.as_str()),
+        InterpretationResult::Error(err) => ("Error", err.message.as_str()),
+    };
+
+    format!(
+        "line {}:\n- expected {} containing {:?}\n+ actual {}: {:?}",
+        annotation.line, expected_kind, annotation.pattern, actual_kind, actual_text
+    )
+}
+
+/// The directive that would replace every `// EXPECT:`/`//~ ERROR` line in a
+/// `--bless`ed file, derived from `result`'s first line of output/message
+fn blessed_directive(result: &InterpretationResult) -> String {
+    match result {
+        InterpretationResult::Success(output) => {
+            format!("// EXPECT: {}", normalize(output).lines().next().unwrap_or(""))
+        }
+        InterpretationResult::Error(err) => {
+            format!("//~ ERROR {}", normalize(&err.message).lines().next().unwrap_or(""))
+        }
+    }
+}
+
+/// Rewrite every `// EXPECT:`/`//~ ERROR` line in `path` to match `result`'s
+/// actual output, so a golden file's baseline can be regenerated in bulk
+/// instead of hand-edited substring by substring. Every directive line in
+/// the file becomes the same single-line replacement, since a single
+/// `interpret` call only produces one overall result to bless against.
+fn bless_file(path: &Path, source: &str, result: &InterpretationResult) -> Result<()> {
+    let replacement = blessed_directive(result);
+
+    let blessed: Vec<&str> = source
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("// EXPECT:") || trimmed.starts_with("//~ ERROR") {
+                replacement.as_str()
+            } else {
+                line
+            }
+        })
+        .collect();
+
+    std::fs::write(path, blessed.join("\n"))?;
+    Ok(())
+}
+
+/// Run `path` through `provider`: in `--bless` mode, rewrite its directives
+/// from the actual result and return `Ok(())`; otherwise, check every
+/// directive and return `Err` summarizing every failure as a diff.
+pub async fn run_test_file<I: InterpreterProvider>(path: &Path, provider: &I, bless: bool) -> Result<()> {
+    let source = std::fs::read_to_string(path)?;
+    let result = provider.interpret(&source).await;
+
+    if bless {
+        return bless_file(path, &source, &result);
+    }
+
+    let failures: Vec<String> = check_annotations(&source, &result)
+        .iter()
+        .filter(|r| !r.passed)
+        .map(|r| diff_report(&r.annotation, &result))
+        .collect();
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        anyhow::bail!(
+            "{}: {} annotation(s) failed:\n{}",
+            path.display(),
+            failures.len(),
+            failures.join("\n\n")
+        )
+    }
+}
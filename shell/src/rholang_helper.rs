@@ -0,0 +1,188 @@
+//! A tree-sitter-backed `rustyline` [`Helper`] for the Rholang REPL: completes
+//! builtin system channels and in-scope `new`-bound names, colorizes keywords/
+//! strings/URIs, and holds the prompt open across newlines until the buffer
+//! parses as complete (see [`crate::completeness`]).
+
+use crate::completeness::{is_complete, Completeness};
+use rholang_tree_sitter_proc_macro::{field, kind};
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+use rustyline::{Context, Helper};
+use std::borrow::Cow;
+use tree_sitter::{Language, Node, Parser, Tree};
+
+/// System channels built into every Rholang program, offered as completions
+/// alongside whatever names the program itself has bound with `new`.
+const BUILTIN_CHANNELS: &[&str] = &[
+    "stdout",
+    "stdoutAck",
+    "stderr",
+    "stderrAck",
+    "rl",
+    "rs",
+    "deployId",
+    "deployerId",
+    "sysAuthToken",
+];
+
+fn rholang_language() -> Language {
+    rholang_tree_sitter::LANGUAGE.into()
+}
+
+fn parse(source: &str) -> Tree {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&rholang_language())
+        .expect("Error loading Rholang parser");
+    parser
+        .parse(source, None)
+        .expect("Failed to produce syntax tree")
+}
+
+/// Collect the names bound by every `new ... in` in `source`.
+fn new_bound_names(tree: &Tree, source: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    collect_new_bound_names(tree.root_node(), source, &mut names);
+    names
+}
+
+fn collect_new_bound_names(node: Node, source: &str, names: &mut Vec<String>) {
+    if node.kind_id() == kind!("new") {
+        if let Some(decls_node) = node.child_by_field_id(field!("decls")) {
+            let mut decls_cursor = decls_node.walk();
+            for decl in decls_node.named_children(&mut decls_cursor) {
+                if let Some(var_node) = decl.named_child(0) {
+                    if let Ok(text) = var_node.utf8_text(source.as_bytes()) {
+                        names.push(text.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_new_bound_names(child, source, names);
+    }
+}
+
+/// Find the start of the identifier-like word ending at `pos`.
+fn word_start(line: &str, pos: usize) -> usize {
+    line[..pos]
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .map(|i| i + 1)
+        .unwrap_or(0)
+}
+
+/// Append `text`, wrapped in ANSI color `code`, to `out`.
+fn push_colored(out: &mut String, code: &str, text: &str) {
+    out.push_str(code);
+    out.push_str(text);
+    out.push_str("\x1b[0m");
+}
+
+/// Walk `node`'s leaves in source order, appending each one to `out` -- a
+/// keyword token (an unnamed, all-alphabetic terminal) in magenta, a string
+/// or URI literal in green, and everything else (including the whitespace
+/// between tokens) unchanged.
+fn highlight_node(node: Node, source: &str, out: &mut String, last: &mut usize) {
+    if node.child_count() == 0 {
+        let start = node.start_byte();
+        let end = node.end_byte();
+        out.push_str(&source[*last..start]);
+
+        let text = &source[start..end];
+        if node.kind_id() == kind!("string_literal") || node.kind_id() == kind!("uri_literal") {
+            push_colored(out, "\x1b[32m", text);
+        } else if !node.is_named() && !text.is_empty() && text.chars().all(|c| c.is_ascii_alphabetic()) {
+            push_colored(out, "\x1b[35m", text);
+        } else {
+            out.push_str(text);
+        }
+
+        *last = end;
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        highlight_node(child, source, out, last);
+    }
+}
+
+/// `rustyline` helper providing Rholang-aware completion, highlighting, and
+/// multi-line validation, backed directly by the tree-sitter grammar.
+#[derive(Default)]
+pub struct RholangHelper;
+
+impl Completer for RholangHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let start = word_start(line, pos);
+        let word = &line[start..pos];
+
+        if word.is_empty() {
+            return Ok((start, Vec::new()));
+        }
+
+        let tree = parse(line);
+        let mut candidates = new_bound_names(&tree, line);
+        candidates.extend(BUILTIN_CHANNELS.iter().map(|name| name.to_string()));
+        candidates.sort();
+        candidates.dedup();
+
+        let matches = candidates
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(word))
+            .map(|candidate| Pair {
+                display: candidate.clone(),
+                replacement: candidate,
+            })
+            .collect();
+
+        Ok((start, matches))
+    }
+}
+
+impl Hinter for RholangHelper {
+    type Hint = String;
+
+    fn hint(&self, _line: &str, _pos: usize, _ctx: &Context<'_>) -> Option<String> {
+        None
+    }
+}
+
+impl Highlighter for RholangHelper {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        let tree = parse(line);
+        let mut out = String::with_capacity(line.len());
+        let mut last = 0usize;
+        highlight_node(tree.root_node(), line, &mut out, &mut last);
+        out.push_str(&line[last..]);
+        Cow::Owned(out)
+    }
+}
+
+impl Validator for RholangHelper {
+    fn validate(&self, ctx: &mut ValidationContext) -> rustyline::Result<ValidationResult> {
+        let input = ctx.input();
+        if input.trim().is_empty() {
+            return Ok(ValidationResult::Incomplete);
+        }
+
+        match is_complete(input) {
+            Completeness::Incomplete => Ok(ValidationResult::Incomplete),
+            Completeness::Complete | Completeness::Invalid => Ok(ValidationResult::Valid(None)),
+        }
+    }
+}
+
+impl Helper for RholangHelper {}
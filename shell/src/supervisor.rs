@@ -0,0 +1,120 @@
+//! A small lifecycle-management subsystem: a [`Supervisor`] owns a set of named
+//! [`Component`]s (most commonly an [`InterpreterProvider`] wrapped in
+//! [`ProviderComponent`]) and drains them on shutdown, so in-flight Rholang
+//! processes are killed rather than orphaned when the shell exits.
+
+use crate::providers::InterpreterProvider;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// A named, independently-shutdownable unit the [`Supervisor`] manages.
+///
+/// `run()` defaults to an immediate no-op, which is all an [`InterpreterProvider`]
+/// needs (it has no background task of its own, only in-flight `interpret` calls);
+/// override it for a component that does own a long-running task.
+#[async_trait]
+pub trait Component {
+    /// A human-readable name, used in shutdown log lines
+    fn name(&self) -> &str;
+
+    /// Run the component's background work, if any. The default implementation
+    /// does nothing and returns immediately.
+    async fn run(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Gracefully stop the component, e.g. killing its in-flight processes
+    async fn shutdown(&self) -> Result<()>;
+}
+
+/// Adapts any [`InterpreterProvider`] into a [`Component`]: `shutdown` kills
+/// every process the provider still has running.
+pub struct ProviderComponent<'p, P> {
+    name: String,
+    provider: &'p P,
+}
+
+impl<'p, P: InterpreterProvider> ProviderComponent<'p, P> {
+    pub fn new(name: impl Into<String>, provider: &'p P) -> Self {
+        ProviderComponent {
+            name: name.into(),
+            provider,
+        }
+    }
+}
+
+#[async_trait]
+impl<'p, P: InterpreterProvider + Sync> Component for ProviderComponent<'p, P> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        let killed = self.provider.kill_all_processes()?;
+        if killed > 0 {
+            eprintln!("{}: killed {killed} in-flight process(es)", self.name);
+        }
+        Ok(())
+    }
+}
+
+/// Owns a set of [`Component`]s and coordinates their shutdown: calling
+/// `shutdown` on every registered component and bounding the whole drain by
+/// `drain_timeout`, so a slow or stuck component can't hang process exit
+/// forever.
+///
+/// [`Self::run`] additionally waits for Ctrl-C/SIGINT before draining, for a
+/// caller with no interactive input loop of its own to coordinate with (e.g.
+/// `--watch` mode). An interactive REPL that already reacts to Ctrl-C itself
+/// (as `run_shell` does, via `rustyline_async`'s `ReadlineEvent::Interrupted`)
+/// should call [`Self::shutdown`] directly from its own exit paths instead, to
+/// avoid two independent consumers of the same signal.
+pub struct Supervisor<'a> {
+    components: Vec<Box<dyn Component + 'a>>,
+    drain_timeout: Duration,
+}
+
+impl<'a> Supervisor<'a> {
+    /// Create a supervisor that allows `drain_timeout` for all registered
+    /// components to shut down before giving up and returning anyway.
+    pub fn new(drain_timeout: Duration) -> Self {
+        Supervisor {
+            components: Vec::new(),
+            drain_timeout,
+        }
+    }
+
+    /// Register a component to be shut down by [`Self::shutdown`]/[`Self::run`]
+    pub fn register(&mut self, component: impl Component + 'a) {
+        self.components.push(Box::new(component));
+    }
+
+    /// Wait for Ctrl-C/SIGINT, then shut every registered component down
+    pub async fn run(&self) -> Result<()> {
+        tokio::signal::ctrl_c().await?;
+        self.shutdown().await
+    }
+
+    /// Shut every registered component down, bounding the whole drain by
+    /// `drain_timeout`. Always returns `Ok`, even if the timeout elapses --
+    /// shutdown is best-effort, not something callers should fail exit over.
+    pub async fn shutdown(&self) -> Result<()> {
+        let drain = async {
+            for component in &self.components {
+                if let Err(e) = component.shutdown().await {
+                    eprintln!("{}: error during shutdown: {e}", component.name());
+                }
+            }
+        };
+
+        if tokio::time::timeout(self.drain_timeout, drain).await.is_err() {
+            eprintln!(
+                "Supervisor: drain timeout of {:?} elapsed; exiting anyway",
+                self.drain_timeout
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -34,3 +34,15 @@ fn test_args_no_multiline_flag() {
     let args = Args::parse_from(["rhosh"]);
     assert!(!args.multiline, "Default multiline value should be false");
 }
+
+#[test]
+fn test_args_check_flag_defaults_to_none() {
+    let args = Args::parse_from(["rhosh"]);
+    assert!(args.check.is_none());
+}
+
+#[test]
+fn test_args_check_flag_takes_a_path() {
+    let args = Args::parse_from(["rhosh", "--check", "program.rho"]);
+    assert_eq!(args.check, Some(std::path::PathBuf::from("program.rho")));
+}
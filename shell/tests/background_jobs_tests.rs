@@ -0,0 +1,87 @@
+//! End-to-end coverage for the `&`/`.jobs`/`.fg`/`.wait` background job subsystem in
+//! `run_shell`, driven through the real compiled binary over a pty (see
+//! `shell_test_support::pty`) since the job table and its `FuturesUnordered` select arm
+//! live inline in `run_shell`'s REPL loop rather than in a unit-testable function.
+
+use shell_test_support::pty::AppBuilder;
+
+#[test]
+fn test_jobs_lists_a_backgrounded_job() {
+    let mut session = AppBuilder::new().spawn().expect("failed to spawn shell");
+
+    session.send_line("Nil &").expect("failed to send line");
+    session.expect("[job 1] pid 1");
+
+    session.send_line(".jobs").expect("failed to send line");
+    let output = session.expect("Nil");
+    assert!(output.contains("[1]"), "expected job 1 in .jobs output:\n{output}");
+
+    session.send_line(".quit").expect("failed to send line");
+}
+
+#[test]
+fn test_jobs_reports_no_background_jobs_when_empty() {
+    let mut session = AppBuilder::new().spawn().expect("failed to spawn shell");
+
+    session.send_line(".jobs").expect("failed to send line");
+    session.expect("No background jobs");
+
+    session.send_line(".quit").expect("failed to send line");
+}
+
+#[test]
+fn test_fg_blocks_until_job_finishes_and_prints_its_output() {
+    let mut session = AppBuilder::new().spawn().expect("failed to spawn shell");
+
+    session.send_line("Nil &").expect("failed to send line");
+    session.expect("[job 1] pid 1");
+
+    session.send_line(".fg 1").expect("failed to send line");
+    // `FakeInterpreterProvider` echoes its input back as the successful output.
+    session.expect("Output: Nil");
+
+    session.send_line(".quit").expect("failed to send line");
+}
+
+#[test]
+fn test_fg_reports_unknown_job_id() {
+    let mut session = AppBuilder::new().spawn().expect("failed to spawn shell");
+
+    session.send_line(".fg 7").expect("failed to send line");
+    session.expect("No such job: 7");
+
+    session.send_line(".quit").expect("failed to send line");
+}
+
+#[test]
+fn test_wait_drains_every_running_job() {
+    let mut session = AppBuilder::new().spawn().expect("failed to spawn shell");
+
+    session.send_line("Nil &").expect("failed to send line");
+    session.expect("[job 1] pid 1");
+    session.send_line("Nil &").expect("failed to send line");
+    session.expect("[job 2] pid 2");
+
+    session.send_line(".wait").expect("failed to send line");
+    session.expect("All jobs finished");
+
+    // Once drained, both jobs should show as done rather than running.
+    session.send_line(".jobs").expect("failed to send line");
+    let output = session.expect("[2] done");
+    assert!(output.contains("[1] done"), "expected job 1 done in .jobs output:\n{output}");
+
+    session.send_line(".quit").expect("failed to send line");
+}
+
+#[test]
+fn test_wait_on_a_single_job_reports_its_id() {
+    let mut session = AppBuilder::new().spawn().expect("failed to spawn shell");
+
+    session.send_line("Nil &").expect("failed to send line");
+    session.expect("[job 1] pid 1");
+
+    session.send_line(".wait 1").expect("failed to send line");
+    session.expect("Job 1 finished");
+
+    session.send_line(".quit").expect("failed to send line");
+}
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use shell::providers::{ControllableInterpreterProvider, InterpretationResult, InterpreterProvider};
+
+#[tokio::test]
+async fn test_controllable_interpreter_provider_release() -> Result<()> {
+    let provider = ControllableInterpreterProvider::new();
+
+    let handle = tokio::spawn({
+        let provider = provider.clone();
+        async move { provider.interpret("doesn't matter").await }
+    });
+
+    let pid = loop {
+        let processes = provider.list_processes()?;
+        if let Some((pid, _)) = processes.first() {
+            break *pid;
+        }
+        tokio::task::yield_now().await;
+    };
+
+    let released = provider.release(pid, InterpretationResult::Success("done".to_string()))?;
+    assert!(released);
+
+    let result = handle.await?;
+    assert_eq!(result.unwrap(), "done");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_controllable_interpreter_provider_kill() -> Result<()> {
+    let provider = ControllableInterpreterProvider::new();
+
+    let handle = tokio::spawn({
+        let provider = provider.clone();
+        async move { provider.interpret("doesn't matter").await }
+    });
+
+    let pid = loop {
+        let processes = provider.list_processes()?;
+        if let Some((pid, _)) = processes.first() {
+            break *pid;
+        }
+        tokio::task::yield_now().await;
+    };
+
+    let killed = provider.kill_process(pid)?;
+    assert!(killed);
+
+    let result = handle.await?;
+    assert!(result.is_error());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_controllable_interpreter_provider_release_of_unknown_pid() -> Result<()> {
+    let provider = ControllableInterpreterProvider::new();
+    let released = provider.release(999, InterpretationResult::Success("unused".to_string()))?;
+    assert!(!released);
+    Ok(())
+}
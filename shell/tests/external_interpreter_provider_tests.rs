@@ -0,0 +1,107 @@
+use anyhow::Result;
+use std::time::Duration;
+
+use shell::providers::{ExternalInterpreterProvider, InterpreterProvider, StopSignal};
+
+/// Wait for `provider.list_processes()` to report at least one process.
+async fn wait_for_processes(provider: &ExternalInterpreterProvider) -> Result<Vec<(usize, String)>> {
+    loop {
+        let processes = provider.list_processes()?;
+        if !processes.is_empty() {
+            return Ok(processes);
+        }
+        tokio::task::yield_now().await;
+    }
+}
+
+#[tokio::test]
+async fn test_external_interpreter_provider_echoes_stdin_on_success() -> Result<()> {
+    let provider = ExternalInterpreterProvider::new("cat", vec![]);
+
+    let result = provider.interpret("Nil").await;
+
+    assert!(result.is_success(), "{:?}", result.unwrap_err());
+    assert_eq!(result.unwrap(), "Nil");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_external_interpreter_provider_surfaces_nonzero_exit() -> Result<()> {
+    let provider = ExternalInterpreterProvider::new("sh", vec!["-c".to_string(), "exit 7".to_string()]);
+
+    let result = provider.interpret("ignored").await;
+
+    assert!(result.is_error(), "expected a nonzero exit to surface as an error");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_external_interpreter_provider_escalates_to_sigkill_after_stop_timeout() -> Result<()> {
+    // Ignores SIGTERM, so `kill_process` can only succeed by escalating to SIGKILL
+    // once `stop_timeout` elapses.
+    let provider = ExternalInterpreterProvider::new(
+        "sh",
+        vec!["-c".to_string(), "trap '' TERM; sleep 5".to_string()],
+    );
+    provider.set_stop_timeout(Duration::from_millis(100))?;
+
+    let handle = tokio::spawn({
+        let provider = provider.clone();
+        async move { provider.interpret("ignored").await }
+    });
+
+    let processes = wait_for_processes(&provider).await?;
+    let pid = processes[0].0;
+
+    let killed = provider.kill_process(pid)?;
+    assert!(killed, "expected kill_process to find the tracked pid");
+
+    let result = tokio::time::timeout(Duration::from_secs(5), handle).await??;
+    assert!(
+        result.is_error(),
+        "expected the SIGKILL-escalated child to exit as a nonzero status"
+    );
+
+    let processes = provider.list_processes()?;
+    assert!(processes.is_empty(), "expected the process to be removed once interpret's task observed the exit");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_external_interpreter_provider_kill_process_honors_configured_stop_signal() -> Result<()> {
+    // SIGTERM (the default) kills a plain `sleep` immediately, without ever needing
+    // to escalate -- exercising `set_stop_signal` explicitly rather than relying on
+    // the default.
+    let provider = ExternalInterpreterProvider::new("sleep", vec!["5".to_string()]);
+    provider.set_stop_signal(StopSignal::Terminate)?;
+    provider.set_stop_timeout(Duration::from_secs(2))?;
+
+    let handle = tokio::spawn({
+        let provider = provider.clone();
+        async move { provider.interpret("ignored").await }
+    });
+
+    let processes = wait_for_processes(&provider).await?;
+    let pid = processes[0].0;
+
+    let killed = provider.kill_process(pid)?;
+    assert!(killed);
+
+    let result = tokio::time::timeout(Duration::from_secs(2), handle).await??;
+    assert!(result.is_error(), "expected the terminated child to exit as a nonzero status");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_external_interpreter_provider_kill_process_reports_unknown_pid() -> Result<()> {
+    let provider = ExternalInterpreterProvider::new("cat", vec![]);
+
+    let killed = provider.kill_process(12345)?;
+    assert!(!killed, "killing an untracked pid should report false, not an error");
+
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use std::io::Cursor;
+use std::time::Duration;
+
+use anyhow::Result;
+use shell::json_mode::run_json_mode;
+use shell::providers::{ControllableInterpreterProvider, FakeInterpreterProvider, InterpretationResult};
+
+/// Reads the JSON responses `run_json_mode` wrote, one per line, decoded back
+/// into `serde_json::Value`s so assertions don't have to hardcode key order.
+fn responses(raw: &[u8]) -> Vec<serde_json::Value> {
+    String::from_utf8(raw.to_vec())
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_eval_request_echoes_id_and_output() -> Result<()> {
+    let input = Cursor::new(b"{\"id\": 1, \"command\": \"1 + 1\"}\n" as &[u8]);
+    let mut output = Vec::new();
+
+    run_json_mode(input, &mut output, &FakeInterpreterProvider, Duration::from_secs(5)).await?;
+
+    let responses = responses(&output);
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[0]["ok"], true);
+    assert_eq!(responses[0]["output"], "1 + 1");
+    assert_eq!(responses[0]["error"], serde_json::Value::Null);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_malformed_json_reports_an_error_response_instead_of_aborting() -> Result<()> {
+    let input = Cursor::new(b"not json\n{\"id\": 2, \"command\": \"ok\"}\n" as &[u8]);
+    let mut output = Vec::new();
+
+    run_json_mode(input, &mut output, &FakeInterpreterProvider, Duration::from_secs(5)).await?;
+
+    let responses = responses(&output);
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["ok"], false);
+    assert!(responses[0]["error"].as_str().unwrap().contains("invalid JSON request"));
+    assert_eq!(responses[1]["ok"], true);
+    assert_eq!(responses[1]["output"], "ok");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_request_with_neither_command_nor_op_is_an_error() -> Result<()> {
+    let input = Cursor::new(b"{\"id\": 3}\n" as &[u8]);
+    let mut output = Vec::new();
+
+    run_json_mode(input, &mut output, &FakeInterpreterProvider, Duration::from_secs(5)).await?;
+
+    let responses = responses(&output);
+    assert_eq!(responses[0]["ok"], false);
+    assert!(responses[0]["error"]
+        .as_str()
+        .unwrap()
+        .contains("neither \"command\" nor \"op\""));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_op_reports_running_processes() -> Result<()> {
+    let provider = ControllableInterpreterProvider::new();
+    let handle = tokio::spawn({
+        let provider = provider.clone();
+        async move { provider.interpret("new x in { Nil }").await }
+    });
+
+    let pid = loop {
+        if let Some((pid, _)) = provider.list_processes()?.first() {
+            break *pid;
+        }
+        tokio::task::yield_now().await;
+    };
+
+    let input = Cursor::new(b"{\"op\": \"list\"}\n" as &[u8]);
+    let mut output = Vec::new();
+    run_json_mode(input, &mut output, &provider, Duration::from_secs(5)).await?;
+
+    let responses = responses(&output);
+    assert_eq!(responses[0]["ok"], true);
+    let listed: serde_json::Value = serde_json::from_str(responses[0]["output"].as_str().unwrap())?;
+    assert_eq!(listed[0]["pid"], pid);
+
+    provider.release(pid, InterpretationResult::Success("done".to_string()))?;
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_kill_op_kills_the_named_process() -> Result<()> {
+    let provider = ControllableInterpreterProvider::new();
+    let handle = tokio::spawn({
+        let provider = provider.clone();
+        async move { provider.interpret("new x in { Nil }").await }
+    });
+
+    let pid = loop {
+        if let Some((pid, _)) = provider.list_processes()?.first() {
+            break *pid;
+        }
+        tokio::task::yield_now().await;
+    };
+
+    let input = Cursor::new(format!("{{\"id\": 9, \"op\": \"kill\", \"pid\": {pid}}}\n").into_bytes());
+    let mut output = Vec::new();
+    run_json_mode(input, &mut output, &provider, Duration::from_secs(5)).await?;
+
+    let responses = responses(&output);
+    assert_eq!(responses[0]["ok"], true);
+
+    let result = handle.await?;
+    assert!(result.is_error());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_kill_op_without_pid_field_is_an_error() -> Result<()> {
+    let input = Cursor::new(b"{\"op\": \"kill\"}\n" as &[u8]);
+    let mut output = Vec::new();
+
+    run_json_mode(input, &mut output, &FakeInterpreterProvider, Duration::from_secs(5)).await?;
+
+    let responses = responses(&output);
+    assert_eq!(responses[0]["ok"], false);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unknown_op_is_an_error() -> Result<()> {
+    let input = Cursor::new(b"{\"op\": \"frobnicate\"}\n" as &[u8]);
+    let mut output = Vec::new();
+
+    run_json_mode(input, &mut output, &FakeInterpreterProvider, Duration::from_secs(5)).await?;
+
+    let responses = responses(&output);
+    assert_eq!(responses[0]["ok"], false);
+    assert!(responses[0]["error"].as_str().unwrap().contains("unknown op"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_blank_lines_are_skipped_without_producing_a_response() -> Result<()> {
+    let input = Cursor::new(b"\n{\"id\": 1, \"command\": \"x\"}\n\n" as &[u8]);
+    let mut output = Vec::new();
+
+    run_json_mode(input, &mut output, &FakeInterpreterProvider, Duration::from_secs(5)).await?;
+
+    assert_eq!(responses(&output).len(), 1);
+
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use anyhow::Result;
 use shell::{
-    handle_interrupt, help_message, process_multiline_input, process_single_line_input,
-    process_special_command, providers::InterpretationResult, Args,
+    handle_interrupt, help_message, load_commands, pipe::FakePipe, pipe_result_through,
+    process_multiline_input, process_single_line_input, process_special_command,
+    providers::InterpretationResult, Args, Point,
 };
 use std::io::Cursor;
 
@@ -64,8 +65,11 @@ fn test_process_special_command_help() -> Result<()> {
     let should_exit = process_special_command(
         ".help",
         &mut buffer,
+        &mut Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -88,8 +92,11 @@ fn test_process_special_command_mode() -> Result<()> {
     let should_exit = process_special_command(
         ".mode",
         &mut buffer,
+        &mut Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -112,8 +119,11 @@ fn test_process_special_command_quit() -> Result<()> {
     let should_exit = process_special_command(
         ".quit",
         &mut buffer,
+        &mut Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -135,8 +145,11 @@ fn test_process_special_command_list() -> Result<()> {
     let should_exit = process_special_command(
         ".list",
         &mut buffer,
+        &mut Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -160,8 +173,11 @@ fn test_process_special_command_delete() -> Result<()> {
     let should_exit = process_special_command(
         ".delete",
         &mut buffer,
+        &mut Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -184,8 +200,11 @@ fn test_process_special_command_delete_empty() -> Result<()> {
     let should_exit = process_special_command(
         ".delete",
         &mut buffer,
+        &mut Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -197,6 +216,238 @@ fn test_process_special_command_delete_empty() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_process_special_command_goto() -> Result<()> {
+    let mut buffer = vec!["line1".to_string(), "line2".to_string()];
+    let mut cursor = Point::default();
+    let mut multiline = true;
+    let mut stdout = Cursor::new(Vec::new());
+    let interpreter = MockInterpreterProvider::new();
+
+    let should_exit = process_special_command(
+        ".goto 2",
+        &mut buffer,
+        &mut cursor,
+        &mut multiline,
+        &mut None,
+        &mut stdout,
+        ">>> ",
+        |_| Ok(()),
+        &interpreter,
+    )?;
+
+    assert!(!should_exit);
+    assert_eq!(cursor, Point { line: 1, col: 0 });
+    let output = String::from_utf8(stdout.into_inner())?;
+    assert!(output.contains("Moved cursor to line 2"));
+
+    Ok(())
+}
+
+#[test]
+fn test_process_special_command_goto_out_of_range() -> Result<()> {
+    let mut buffer = vec!["line1".to_string()];
+    let mut cursor = Point::default();
+    let mut multiline = true;
+    let mut stdout = Cursor::new(Vec::new());
+    let interpreter = MockInterpreterProvider::new();
+
+    let should_exit = process_special_command(
+        ".goto 5",
+        &mut buffer,
+        &mut cursor,
+        &mut multiline,
+        &mut None,
+        &mut stdout,
+        ">>> ",
+        |_| Ok(()),
+        &interpreter,
+    )?;
+
+    assert!(!should_exit);
+    assert_eq!(cursor, Point::default());
+    let output = String::from_utf8(stdout.into_inner())?;
+    assert!(output.contains("No such line: 5"));
+
+    Ok(())
+}
+
+#[test]
+fn test_process_special_command_edit() -> Result<()> {
+    let mut buffer = vec!["line1".to_string(), "line2".to_string()];
+    let mut multiline = true;
+    let mut stdout = Cursor::new(Vec::new());
+    let interpreter = MockInterpreterProvider::new();
+
+    let should_exit = process_special_command(
+        ".edit 1 replaced",
+        &mut buffer,
+        &mut Point::default(),
+        &mut multiline,
+        &mut None,
+        &mut stdout,
+        ">>> ",
+        |_| Ok(()),
+        &interpreter,
+    )?;
+
+    assert!(!should_exit);
+    assert_eq!(buffer, vec!["replaced".to_string(), "line2".to_string()]);
+    let output = String::from_utf8(stdout.into_inner())?;
+    assert!(output.contains("Replaced line 1"));
+
+    Ok(())
+}
+
+#[test]
+fn test_process_special_command_insert() -> Result<()> {
+    let mut buffer = vec!["line1".to_string(), "line2".to_string()];
+    let mut multiline = true;
+    let mut stdout = Cursor::new(Vec::new());
+    let interpreter = MockInterpreterProvider::new();
+
+    let should_exit = process_special_command(
+        ".insert 2 inserted",
+        &mut buffer,
+        &mut Point::default(),
+        &mut multiline,
+        &mut None,
+        &mut stdout,
+        ">>> ",
+        |_| Ok(()),
+        &interpreter,
+    )?;
+
+    assert!(!should_exit);
+    assert_eq!(
+        buffer,
+        vec![
+            "line1".to_string(),
+            "inserted".to_string(),
+            "line2".to_string()
+        ]
+    );
+    let output = String::from_utf8(stdout.into_inner())?;
+    assert!(output.contains("Inserted line 2"));
+
+    Ok(())
+}
+
+#[test]
+fn test_process_special_command_delete_line_number() -> Result<()> {
+    let mut buffer = vec![
+        "line1".to_string(),
+        "line2".to_string(),
+        "line3".to_string(),
+    ];
+    let mut multiline = true;
+    let mut stdout = Cursor::new(Vec::new());
+    let interpreter = MockInterpreterProvider::new();
+
+    let should_exit = process_special_command(
+        ".delete 2",
+        &mut buffer,
+        &mut Point::default(),
+        &mut multiline,
+        &mut None,
+        &mut stdout,
+        ">>> ",
+        |_| Ok(()),
+        &interpreter,
+    )?;
+
+    assert!(!should_exit);
+    assert_eq!(buffer, vec!["line1".to_string(), "line3".to_string()]);
+    let output = String::from_utf8(stdout.into_inner())?;
+    assert!(output.contains("Removed line 2: line2"));
+
+    Ok(())
+}
+
+#[test]
+fn test_process_special_command_list_numbered_marks_cursor() -> Result<()> {
+    let mut buffer = vec!["line1".to_string(), "line2".to_string()];
+    let mut cursor = Point { line: 1, col: 0 };
+    let mut multiline = true;
+    let mut stdout = Cursor::new(Vec::new());
+    let interpreter = MockInterpreterProvider::new();
+
+    let should_exit = process_special_command(
+        ".list -n",
+        &mut buffer,
+        &mut cursor,
+        &mut multiline,
+        &mut None,
+        &mut stdout,
+        ">>> ",
+        |_| Ok(()),
+        &interpreter,
+    )?;
+
+    assert!(!should_exit);
+    let output = String::from_utf8(stdout.into_inner())?;
+    assert!(output.contains("   1  line1"));
+    assert!(output.contains(">   2  line2"));
+
+    Ok(())
+}
+
+#[test]
+fn test_process_special_command_pipe_arms_pending_pipe() -> Result<()> {
+    let mut buffer = Vec::new();
+    let mut multiline = false;
+    let mut pending_pipe = None;
+    let mut stdout = Cursor::new(Vec::new());
+    let interpreter = MockInterpreterProvider::new();
+
+    let should_exit = process_special_command(
+        ".pipe jq .",
+        &mut buffer,
+        &mut Point::default(),
+        &mut multiline,
+        &mut pending_pipe,
+        &mut stdout,
+        ">>> ",
+        |_| Ok(()),
+        &interpreter,
+    )?;
+
+    assert!(!should_exit);
+    assert_eq!(pending_pipe, Some("jq .".to_string()));
+    let output = String::from_utf8(stdout.into_inner())?;
+    assert!(output.contains("jq ."));
+
+    Ok(())
+}
+
+#[test]
+fn test_process_special_command_pipe_empty_usage() -> Result<()> {
+    let mut buffer = Vec::new();
+    let mut multiline = false;
+    let mut pending_pipe = None;
+    let mut stdout = Cursor::new(Vec::new());
+    let interpreter = MockInterpreterProvider::new();
+
+    let should_exit = process_special_command(
+        ".pipe ",
+        &mut buffer,
+        &mut Point::default(),
+        &mut multiline,
+        &mut pending_pipe,
+        &mut stdout,
+        ">>> ",
+        |_| Ok(()),
+        &interpreter,
+    )?;
+
+    assert!(!should_exit);
+    assert_eq!(pending_pipe, None);
+    let output = String::from_utf8(stdout.into_inner())?;
+    assert!(output.contains("Usage: .pipe <cmd>"));
+
+    Ok(())
+}
+
 #[test]
 fn test_process_special_command_reset() -> Result<()> {
     let mut buffer = vec!["line1".to_string(), "line2".to_string()];
@@ -207,8 +458,11 @@ fn test_process_special_command_reset() -> Result<()> {
     let should_exit = process_special_command(
         ".reset",
         &mut buffer,
+        &mut Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -234,8 +488,11 @@ fn test_process_special_command_ps() -> Result<()> {
     let should_exit = process_special_command(
         ".ps",
         &mut buffer,
+        &mut Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -259,8 +516,11 @@ fn test_process_special_command_ps_empty() -> Result<()> {
     let should_exit = process_special_command(
         ".ps",
         &mut buffer,
+        &mut Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -285,8 +545,11 @@ fn test_process_special_command_kill() -> Result<()> {
     let should_exit = process_special_command(
         ".kill 1",
         &mut buffer,
+        &mut Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -308,8 +571,11 @@ fn test_process_special_command_kill_nonexistent() -> Result<()> {
     let should_exit = process_special_command(
         ".kill 999",
         &mut buffer,
+        &mut Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -331,8 +597,11 @@ fn test_process_special_command_kill_invalid() -> Result<()> {
     let should_exit = process_special_command(
         ".kill abc",
         &mut buffer,
+        &mut Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -354,8 +623,11 @@ fn test_process_special_command_unknown() -> Result<()> {
     let should_exit = process_special_command(
         ".unknown",
         &mut buffer,
+        &mut Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -377,8 +649,11 @@ fn test_process_special_command_not_special() -> Result<()> {
     let should_exit = process_special_command(
         "not a special command",
         &mut buffer,
+        &mut Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -393,7 +668,7 @@ fn test_process_special_command_not_special() -> Result<()> {
 #[test]
 fn test_process_multiline_input_empty_buffer_empty_line() -> Result<()> {
     let mut buffer = Vec::new();
-    let command = process_multiline_input("".to_string(), &mut buffer, |_| Ok(()))?;
+    let command = process_multiline_input("".to_string(), &mut buffer, ">>> ", "... ", |_| Ok(()))?;
     assert!(command.is_none());
     assert!(buffer.is_empty());
     Ok(())
@@ -402,7 +677,8 @@ fn test_process_multiline_input_empty_buffer_empty_line() -> Result<()> {
 #[test]
 fn test_process_multiline_input_empty_buffer_nonempty_line() -> Result<()> {
     let mut buffer = Vec::new();
-    let command = process_multiline_input("line1".to_string(), &mut buffer, |_| Ok(()))?;
+    let command =
+        process_multiline_input("line1".to_string(), &mut buffer, ">>> ", "... ", |_| Ok(()))?;
     assert!(command.is_none());
     assert_eq!(buffer, vec!["line1".to_string()]);
     Ok(())
@@ -411,7 +687,8 @@ fn test_process_multiline_input_empty_buffer_nonempty_line() -> Result<()> {
 #[test]
 fn test_process_multiline_input_nonempty_buffer_nonempty_line() -> Result<()> {
     let mut buffer = vec!["line1".to_string()];
-    let command = process_multiline_input("line2".to_string(), &mut buffer, |_| Ok(()))?;
+    let command =
+        process_multiline_input("line2".to_string(), &mut buffer, ">>> ", "... ", |_| Ok(()))?;
     assert!(command.is_none());
     assert_eq!(buffer, vec!["line1".to_string(), "line2".to_string()]);
     Ok(())
@@ -420,7 +697,7 @@ fn test_process_multiline_input_nonempty_buffer_nonempty_line() -> Result<()> {
 #[test]
 fn test_process_multiline_input_nonempty_buffer_empty_line() -> Result<()> {
     let mut buffer = vec!["line1".to_string(), "line2".to_string()];
-    let command = process_multiline_input("".to_string(), &mut buffer, |_| Ok(()))?;
+    let command = process_multiline_input("".to_string(), &mut buffer, ">>> ", "... ", |_| Ok(()))?;
     assert_eq!(command, Some("line1\nline2".to_string()));
     assert!(buffer.is_empty());
     Ok(())
@@ -431,7 +708,9 @@ fn test_process_single_line_input_empty_line() -> Result<()> {
     let mut buffer = Vec::new();
     let mut multiline = false;
     let command =
-        process_single_line_input("".to_string(), &mut buffer, &mut multiline, |_| Ok(()))?;
+        process_single_line_input("".to_string(), &mut buffer, &mut multiline, "... ", |_| {
+            Ok(())
+        })?;
     assert!(command.is_none());
     assert!(buffer.is_empty());
     assert!(!multiline);
@@ -442,8 +721,13 @@ fn test_process_single_line_input_empty_line() -> Result<()> {
 fn test_process_single_line_input_complete_line() -> Result<()> {
     let mut buffer = Vec::new();
     let mut multiline = false;
-    let command =
-        process_single_line_input("1 + 2".to_string(), &mut buffer, &mut multiline, |_| Ok(()))?;
+    let command = process_single_line_input(
+        "1 + 2".to_string(),
+        &mut buffer,
+        &mut multiline,
+        "... ",
+        |_| Ok(()),
+    )?;
     assert_eq!(command, Some("1 + 2".to_string()));
     assert!(buffer.is_empty());
     assert!(!multiline);
@@ -458,6 +742,7 @@ fn test_process_single_line_input_incomplete_line() -> Result<()> {
         "new x in {".to_string(),
         &mut buffer,
         &mut multiline,
+        "... ",
         |_| Ok(()),
     )?;
     assert!(command.is_none());
@@ -480,6 +765,7 @@ fn test_handle_interrupt() -> Result<()> {
         &mut buffer,
         multiline,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -506,6 +792,7 @@ fn test_handle_interrupt_single_line() -> Result<()> {
         &mut buffer,
         multiline,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -518,6 +805,88 @@ fn test_handle_interrupt_single_line() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_load_commands_emits_one_command_per_top_level_statement() -> Result<()> {
+    let source = Cursor::new(b"new x in {\n  x!(1)\n}\n1 + 2\n" as &[u8]);
+    let mut commands = Vec::new();
+
+    load_commands(source, |line_no, command| {
+        commands.push((line_no, command.to_string()));
+        Ok(())
+    })
+    .await?;
+
+    assert_eq!(
+        commands,
+        vec![
+            (3, "new x in {\n  x!(1)\n}".to_string()),
+            (4, "1 + 2".to_string()),
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_load_commands_dispatches_trailing_unterminated_buffer_at_eof() -> Result<()> {
+    let source = Cursor::new(b"new x in {\n  x!(1)\n" as &[u8]);
+    let mut commands = Vec::new();
+
+    load_commands(source, |line_no, command| {
+        commands.push((line_no, command.to_string()));
+        Ok(())
+    })
+    .await?;
+
+    assert_eq!(commands, vec![(2, "new x in {\n  x!(1)".to_string())]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_load_commands_stops_when_dispatch_errs() {
+    let source = Cursor::new(b"1 + 2\n3 + 4\n" as &[u8]);
+    let mut commands = Vec::new();
+
+    let result = load_commands(source, |line_no, command| {
+        commands.push((line_no, command.to_string()));
+        anyhow::bail!("dispatch failed")
+    })
+    .await;
+
+    assert!(result.is_err());
+    assert_eq!(commands, vec![(1, "1 + 2".to_string())]);
+}
+
+#[tokio::test]
+async fn test_pipe_result_through_writes_success_output_and_prints_response() -> Result<()> {
+    let result = InterpretationResult::Success("42".to_string());
+    let mut process = FakePipe::new(vec!["42 piped"]);
+    let mut stdout = Cursor::new(Vec::new());
+
+    pipe_result_through(&result, &mut process, &mut stdout).await?;
+
+    assert_eq!(process.written, vec!["42".to_string()]);
+    let output = String::from_utf8(stdout.into_inner())?;
+    assert!(output.contains("42 piped"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pipe_result_through_writes_error_message() -> Result<()> {
+    let result =
+        InterpretationResult::Error(shell::providers::InterpreterError::other_error("boom"));
+    let mut process = FakePipe::new(vec![]);
+    let mut stdout = Cursor::new(Vec::new());
+
+    pipe_result_through(&result, &mut process, &mut stdout).await?;
+
+    assert_eq!(process.written, vec!["boom".to_string()]);
+
+    Ok(())
+}
+
 #[test]
 fn test_args() {
     let args = Args { multiline: true };
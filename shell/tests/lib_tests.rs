@@ -21,8 +21,11 @@ async fn test_process_special_command_help() -> Result<()> {
     let should_exit = process_special_command(
         ".help",
         &mut buffer,
+        &mut shell::Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -51,8 +54,11 @@ async fn test_process_special_command_mode() -> Result<()> {
     let should_exit = process_special_command(
         ".mode",
         &mut buffer,
+        &mut shell::Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -82,8 +88,11 @@ async fn test_process_special_command_mode_to_multiline() -> Result<()> {
     let should_exit = process_special_command(
         ".mode",
         &mut buffer,
+        &mut shell::Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -113,8 +122,11 @@ async fn test_process_special_command_quit() -> Result<()> {
     let should_exit = process_special_command(
         ".quit",
         &mut buffer,
+        &mut shell::Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -143,8 +155,11 @@ async fn test_process_special_command_list() -> Result<()> {
     let should_exit = process_special_command(
         ".list",
         &mut buffer,
+        &mut shell::Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -175,8 +190,11 @@ async fn test_process_special_command_delete() -> Result<()> {
     let should_exit = process_special_command(
         ".delete",
         &mut buffer,
+        &mut shell::Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -207,8 +225,11 @@ async fn test_process_special_command_delete_empty() -> Result<()> {
     let should_exit = process_special_command(
         ".delete",
         &mut buffer,
+        &mut shell::Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -237,8 +258,11 @@ async fn test_process_special_command_reset() -> Result<()> {
     let should_exit = process_special_command(
         ".reset",
         &mut buffer,
+        &mut shell::Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -268,8 +292,11 @@ async fn test_process_special_command_buffer() -> Result<()> {
     let should_exit = process_special_command(
         ".buffer",
         &mut buffer,
+        &mut shell::Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -300,8 +327,11 @@ async fn test_process_special_command_unknown() -> Result<()> {
     let should_exit = process_special_command(
         ".unknown",
         &mut buffer,
+        &mut shell::Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -330,8 +360,11 @@ async fn test_process_special_command_not_special() -> Result<()> {
     let should_exit = process_special_command(
         "not_special",
         &mut buffer,
+        &mut shell::Point::default(),
         &mut multiline,
+        &mut None,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -350,7 +383,7 @@ async fn test_process_special_command_not_special() -> Result<()> {
 async fn test_process_multiline_input_empty_buffer_empty_line() -> Result<()> {
     let mut buffer = Vec::new();
 
-    let command = process_multiline_input("".to_string(), &mut buffer, |_| Ok(()))?;
+    let command = process_multiline_input("".to_string(), &mut buffer, ">>> ", "... ", |_| Ok(()))?;
 
     assert!(command.is_none(), "Empty line should not produce a command");
     assert!(buffer.is_empty(), "Buffer should remain empty");
@@ -362,7 +395,8 @@ async fn test_process_multiline_input_empty_buffer_empty_line() -> Result<()> {
 async fn test_process_multiline_input_empty_buffer_with_line() -> Result<()> {
     let mut buffer = Vec::new();
 
-    let command = process_multiline_input("line1".to_string(), &mut buffer, |_| Ok(()))?;
+    let command =
+        process_multiline_input("line1".to_string(), &mut buffer, ">>> ", "... ", |_| Ok(()))?;
 
     assert!(command.is_none(), "First line should not produce a command");
     assert_eq!(buffer.len(), 1, "Buffer should have one item");
@@ -375,7 +409,8 @@ async fn test_process_multiline_input_empty_buffer_with_line() -> Result<()> {
 async fn test_process_multiline_input_add_line() -> Result<()> {
     let mut buffer = vec!["line1".to_string()];
 
-    let command = process_multiline_input("line2".to_string(), &mut buffer, |_| Ok(()))?;
+    let command =
+        process_multiline_input("line2".to_string(), &mut buffer, ">>> ", "... ", |_| Ok(()))?;
 
     assert!(
         command.is_none(),
@@ -392,7 +427,7 @@ async fn test_process_multiline_input_add_line() -> Result<()> {
 async fn test_process_multiline_input_execute() -> Result<()> {
     let mut buffer = vec!["line1".to_string(), "line2".to_string()];
 
-    let command = process_multiline_input("".to_string(), &mut buffer, |_| Ok(()))?;
+    let command = process_multiline_input("".to_string(), &mut buffer, ">>> ", "... ", |_| Ok(()))?;
 
     assert!(command.is_some(), "Empty line should produce a command");
     assert_eq!(
@@ -411,7 +446,9 @@ async fn test_process_single_line_input_empty() -> Result<()> {
     let mut multiline = false;
 
     let command =
-        process_single_line_input("".to_string(), &mut buffer, &mut multiline, |_| Ok(()))?;
+        process_single_line_input("".to_string(), &mut buffer, &mut multiline, "... ", |_| {
+            Ok(())
+        })?;
 
     assert!(command.is_none(), "Empty line should not produce a command");
     assert!(buffer.is_empty(), "Buffer should remain empty");
@@ -429,6 +466,7 @@ async fn test_process_single_line_input_with_content() -> Result<()> {
         "let x = 10;".to_string(),
         &mut buffer,
         &mut multiline,
+        "... ",
         |_| Ok(()),
     )?;
 
@@ -453,6 +491,7 @@ async fn test_process_single_line_input_with_brackets() -> Result<()> {
         "for (x <- y) {".to_string(),
         &mut buffer,
         &mut multiline,
+        "... ",
         |_| Ok(()),
     )?;
 
@@ -481,6 +520,7 @@ async fn test_handle_interrupt_multiline() -> Result<()> {
         &mut buffer,
         multiline,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -513,6 +553,7 @@ async fn test_handle_interrupt_single_line() -> Result<()> {
         &mut buffer,
         multiline,
         &mut stdout,
+        ">>> ",
         |_| Ok(()),
         &interpreter,
     )?;
@@ -0,0 +1,97 @@
+use anyhow::Result;
+use clap::Parser;
+use shell::permissions::{Capability, Permissions, PermissionState};
+use shell::providers::{FakeInterpreterProvider, InterpreterProvider, PermissionedInterpreterProvider};
+use shell::{run_shell, Args};
+use std::fs;
+use std::path::PathBuf;
+
+const STDOUT_SEND: &str = "new stdout(`rho:io:stdout`) in { stdout!(\"Hello, world!\") }";
+
+/// Write a `.rho` fixture at a unique path under the OS temp directory.
+fn write_rho_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{name}-{}.rho", std::process::id()));
+    fs::write(&path, contents).expect("failed to write fixture");
+    path
+}
+
+#[tokio::test]
+async fn test_permissioned_provider_denies_ungranted_capability() -> Result<()> {
+    let permissions = Permissions::builder().deny_io().build();
+    let provider = PermissionedInterpreterProvider::new(FakeInterpreterProvider, permissions);
+
+    let result = provider.interpret(STDOUT_SEND).await;
+    assert!(result.is_error(), "a denied rho:io send should not reach the inner provider");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_permissioned_provider_treats_prompt_as_denied() -> Result<()> {
+    // Default permissions leave every capability at `Prompt`, and this provider
+    // has no terminal to prompt on.
+    let provider = PermissionedInterpreterProvider::new(FakeInterpreterProvider, Permissions::default());
+
+    let result = provider.interpret(STDOUT_SEND).await;
+    assert!(result.is_error(), "Prompt should be denied outside an interactive session");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_permissioned_provider_delegates_when_granted() -> Result<()> {
+    let permissions = Permissions::builder().allow_io().build();
+    let provider = PermissionedInterpreterProvider::new(FakeInterpreterProvider, permissions);
+
+    let result = provider.interpret(STDOUT_SEND).await;
+    assert!(!result.is_error(), "a granted capability should reach the inner provider");
+    assert_eq!(result.unwrap(), STDOUT_SEND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_permissioned_provider_ignores_ungated_code() -> Result<()> {
+    let provider =
+        PermissionedInterpreterProvider::new(FakeInterpreterProvider, Permissions::default());
+
+    let result = provider.interpret("1 + 1").await;
+    assert!(!result.is_error(), "code with no gated capability should never be denied");
+
+    Ok(())
+}
+
+#[test]
+fn test_args_permissions_reflects_deny_io_flag() {
+    let args = Args::parse_from(["rhosh", "--deny-io"]);
+    let permissions = args.permissions();
+    assert_eq!(permissions.state(Capability::Io), PermissionState::Denied);
+    assert_eq!(permissions.state(Capability::Registry), PermissionState::Prompt);
+}
+
+#[test]
+fn test_args_permissions_reflects_allow_all_flag() {
+    let args = Args::parse_from(["rhosh", "--allow-all"]);
+    let permissions = args.permissions();
+    assert!(permissions.is_granted(Capability::Io));
+    assert!(permissions.is_granted(Capability::Registry));
+    assert!(permissions.is_granted(Capability::Deploy));
+}
+
+#[tokio::test]
+async fn test_run_shell_denies_io_send_end_to_end() -> Result<()> {
+    let path = write_rho_file("permissions-deny-io", STDOUT_SEND);
+
+    let args = Args::parse_from(["rhosh", "--deny-io", "run", path.to_str().unwrap()]);
+    let interpreter = FakeInterpreterProvider;
+
+    let result = run_shell(args, interpreter).await;
+    let _ = fs::remove_file(&path);
+
+    assert!(
+        result.is_err(),
+        "`--deny-io` should make a run of a rho:io-using program fail instead of silently executing it"
+    );
+
+    Ok(())
+}
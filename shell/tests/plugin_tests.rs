@@ -0,0 +1,167 @@
+use std::fs;
+use std::io::Cursor;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use shell::plugin::PluginRegistry;
+use shell::{dispatch_plugin_command, process_plugin_command};
+
+/// Write a `#!/bin/sh` fixture plugin at a unique path under the OS temp
+/// directory, marked executable, so `PluginRegistry::load` can spawn it like
+/// any other plugin binary.
+fn write_plugin_script(name: &str, body: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{name}-{}.sh", std::process::id()));
+    fs::write(&path, format!("#!/bin/sh\n{body}\n")).expect("failed to write plugin fixture");
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).expect("failed to chmod plugin fixture");
+    path
+}
+
+#[tokio::test]
+async fn test_load_registers_the_commands_a_plugin_declares() -> Result<()> {
+    let script = write_plugin_script(
+        "echo-plugin",
+        r#"
+read -r handshake
+echo '{"jsonrpc":"2.0","id":0,"result":{"commands":[{"name":"echo","arity":1}]}}'
+read -r call
+echo '{"jsonrpc":"2.0","id":1,"result":"echoed"}'
+"#,
+    );
+
+    let registry = PluginRegistry::new();
+    let (pid, commands) = registry.load(script.to_str().unwrap()).await?;
+
+    assert!(pid > 0);
+    assert_eq!(commands, vec!["echo".to_string()]);
+    assert!(registry.has_command("echo"));
+
+    let result = registry.dispatch("echo", "hello").await?;
+    assert_eq!(result, Some("\"echoed\"".to_string()));
+
+    registry.kill(pid)?;
+    let _ = fs::remove_file(&script);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dispatch_returns_none_for_an_unregistered_command() -> Result<()> {
+    let registry = PluginRegistry::new();
+    assert_eq!(registry.dispatch("nonexistent", "").await?, None);
+    assert!(!registry.has_command("nonexistent"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_kill_removes_the_plugin_from_list_and_reports_false_twice() -> Result<()> {
+    let script = write_plugin_script(
+        "noop-plugin",
+        r#"
+read -r handshake
+echo '{"jsonrpc":"2.0","id":0,"result":{"commands":[{"name":"noop","arity":0}]}}'
+sleep 30
+"#,
+    );
+
+    let registry = PluginRegistry::new();
+    let (pid, _) = registry.load(script.to_str().unwrap()).await?;
+
+    assert_eq!(registry.list().len(), 1);
+    assert!(registry.kill(pid)?);
+    assert!(registry.list().is_empty());
+    assert!(!registry.kill(pid)?);
+
+    let _ = fs::remove_file(&script);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_load_reports_an_error_for_a_nonexistent_path() {
+    let registry = PluginRegistry::new();
+    let result = registry.load("/no/such/plugin/binary").await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_process_plugin_command_load_list_and_kill() -> Result<()> {
+    let script = write_plugin_script(
+        "lib-echo-plugin",
+        r#"
+read -r handshake
+echo '{"jsonrpc":"2.0","id":0,"result":{"commands":[{"name":"echo","arity":1}]}}'
+sleep 30
+"#,
+    );
+
+    let registry = PluginRegistry::new();
+    let mut stdout = Cursor::new(Vec::new());
+
+    let handled = process_plugin_command(&format!(".plugin load {}", script.to_str().unwrap()), &mut stdout, &registry).await?;
+    assert!(handled);
+    let output = String::from_utf8(stdout.into_inner())?;
+    assert!(output.starts_with("Loaded plugin"));
+    assert!(output.contains("echo"));
+
+    let mut stdout = Cursor::new(Vec::new());
+    let handled = process_plugin_command(".plugin list", &mut stdout, &registry).await?;
+    assert!(handled);
+    let output = String::from_utf8(stdout.into_inner())?;
+    assert!(output.contains("Loaded plugins:"));
+
+    let pid = registry.list()[0].0;
+    let mut stdout = Cursor::new(Vec::new());
+    let handled = process_plugin_command(&format!(".plugin kill {pid}"), &mut stdout, &registry).await?;
+    assert!(handled);
+    let output = String::from_utf8(stdout.into_inner())?;
+    assert!(output.contains("killed"));
+    assert!(registry.list().is_empty());
+
+    let _ = fs::remove_file(&script);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_process_plugin_command_ignores_non_plugin_input() -> Result<()> {
+    let registry = PluginRegistry::new();
+    let mut stdout = Cursor::new(Vec::new());
+    let handled = process_plugin_command("new x in { Nil }", &mut stdout, &registry).await?;
+    assert!(!handled);
+    assert!(stdout.into_inner().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dispatch_plugin_command_forwards_to_a_registered_plugin() -> Result<()> {
+    let script = write_plugin_script(
+        "lib-dispatch-plugin",
+        r#"
+read -r handshake
+echo '{"jsonrpc":"2.0","id":0,"result":{"commands":[{"name":"echo","arity":1}]}}'
+read -r call
+echo '{"jsonrpc":"2.0","id":1,"result":"echoed"}'
+"#,
+    );
+
+    let registry = PluginRegistry::new();
+    let (pid, _) = registry.load(script.to_str().unwrap()).await?;
+
+    let mut stdout = Cursor::new(Vec::new());
+    let handled = dispatch_plugin_command("echo hello", &mut stdout, &registry).await?;
+    assert!(handled);
+    let output = String::from_utf8(stdout.into_inner())?;
+    assert_eq!(output.trim(), "\"echoed\"");
+
+    registry.kill(pid)?;
+    let _ = fs::remove_file(&script);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dispatch_plugin_command_returns_false_for_unregistered_commands() -> Result<()> {
+    let registry = PluginRegistry::new();
+    let mut stdout = Cursor::new(Vec::new());
+    let handled = dispatch_plugin_command("new x in { Nil }", &mut stdout, &registry).await?;
+    assert!(!handled);
+    assert!(stdout.into_inner().is_empty());
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use anyhow::Result;
+use std::sync::Arc;
+use std::time::Duration;
+
+use shell::clock::MockClock;
+use shell::providers::{
+    ErrorKind, InterpretationResult, InterpreterError, InterpreterProvider, RetryPolicy,
+    RholangParserInterpreterProvider,
+};
+
+#[test]
+fn test_default_retry_policy_disables_retrying() {
+    let policy = RetryPolicy::default();
+    assert_eq!(policy.max_attempts, 1);
+    assert_eq!(policy.backoff, Duration::ZERO);
+}
+
+#[test]
+fn test_transient_retry_policy_retries_timeouts_and_other_errors() {
+    let policy = RetryPolicy::transient(3, Duration::from_millis(50));
+    assert_eq!(policy.max_attempts, 3);
+    assert_eq!(policy.backoff, Duration::from_millis(50));
+
+    let timeout = InterpreterError::timeout_error("timed out");
+    let other = InterpreterError::other_error("lock contention");
+    assert!((policy.retry_on)(&timeout));
+    assert!((policy.retry_on)(&other));
+}
+
+#[test]
+fn test_transient_retry_policy_never_retries_parsing_or_cancellation_errors() {
+    let policy = RetryPolicy::transient(3, Duration::from_millis(50));
+
+    let parsing = InterpreterError::parsing_error("unexpected token", None, None);
+    let cancellation = InterpreterError::cancellation_error("cancelled");
+    assert!(!(policy.retry_on)(&parsing));
+    assert!(!(policy.retry_on)(&cancellation));
+}
+
+/// A `MockClock` that never advances means the simulated-processing-time delay
+/// never elapses on its own, while a tiny real `timeout` still fires -- giving a
+/// deterministic, fast way to force every attempt to time out.
+#[tokio::test]
+async fn test_retry_policy_retries_timeouts_then_gives_up_with_attempt_count() -> Result<()> {
+    let interpreter = RholangParserInterpreterProvider::with_clock(Arc::new(MockClock::new()))?;
+    interpreter.set_delay(1000)?;
+    interpreter.set_timeout(Duration::from_millis(1))?;
+    interpreter.set_retry_policy(RetryPolicy::transient(3, Duration::ZERO))?;
+
+    let result = interpreter.interpret("new x in { x!(5) }").await;
+
+    match result {
+        InterpretationResult::Error(err) => {
+            assert_eq!(err.kind, ErrorKind::TimeoutError);
+            assert!(
+                err.message.contains("(after 3 attempts)"),
+                "expected the attempt count in the final error message, got: {}",
+                err.message
+            );
+        }
+        InterpretationResult::Success(_) => panic!("expected every attempt to time out"),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_retry_policy_does_not_retry_a_parsing_error() -> Result<()> {
+    let interpreter = RholangParserInterpreterProvider::new()?;
+    interpreter.set_delay(0)?;
+    interpreter.set_retry_policy(RetryPolicy::transient(5, Duration::from_millis(1)))?;
+
+    // Extra closing braces make this a deterministic parse failure, not a timeout.
+    let result = interpreter.interpret("new x in { x!(5) }}}").await;
+
+    match result {
+        InterpretationResult::Error(err) => {
+            assert_eq!(err.kind, ErrorKind::ParsingError);
+            assert!(
+                !err.message.contains("attempts"),
+                "a parsing error should never be retried, got: {}",
+                err.message
+            );
+        }
+        InterpretationResult::Success(_) => panic!("expected a parsing error"),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_retry_policy_max_attempts_one_never_retries() -> Result<()> {
+    let interpreter = RholangParserInterpreterProvider::with_clock(Arc::new(MockClock::new()))?;
+    interpreter.set_delay(1000)?;
+    interpreter.set_timeout(Duration::from_millis(1))?;
+    // Default policy: max_attempts = 1, so even a retryable timeout isn't retried.
+    interpreter.set_retry_policy(RetryPolicy::default())?;
+
+    let result = interpreter.interpret("new x in { x!(5) }").await;
+
+    match result {
+        InterpretationResult::Error(err) => {
+            assert_eq!(err.kind, ErrorKind::TimeoutError);
+            assert!(
+                !err.message.contains("attempts"),
+                "a single-attempt policy shouldn't record an attempt count, got: {}",
+                err.message
+            );
+        }
+        InterpretationResult::Success(_) => panic!("expected the single attempt to time out"),
+    }
+
+    Ok(())
+}
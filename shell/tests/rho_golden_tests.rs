@@ -0,0 +1,82 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use shell::providers::FakeInterpreterProvider;
+use shell::rho_golden::run_golden_file;
+
+/// Write a golden `.rho` fixture at a unique path under the OS temp directory.
+fn write_golden_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{name}-{}.rho", std::process::id()));
+    fs::write(&path, contents).expect("failed to write golden fixture");
+    path
+}
+
+#[tokio::test]
+async fn test_golden_file_passes_when_echoed_output_matches_the_stdout_pattern() -> Result<()> {
+    let path = write_golden_file(
+        "passing",
+        "//= {\"output\": {\"stdout\": \"Hello, .*!\"}, \"exit\": \"success\"}\nHello, world!",
+    );
+
+    let result = run_golden_file(&path, &FakeInterpreterProvider).await?;
+    assert!(result.passed, "{:?}", result.failure);
+
+    let _ = fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_golden_file_fails_when_output_does_not_match() -> Result<()> {
+    let path = write_golden_file(
+        "failing",
+        "//= {\"output\": {\"stdout\": \"Goodbye.*\"}, \"exit\": \"success\"}\nHello, world!",
+    );
+
+    let result = run_golden_file(&path, &FakeInterpreterProvider).await?;
+    assert!(!result.passed);
+    assert!(result.failure.unwrap().contains("stdout"));
+
+    let _ = fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_golden_file_fails_when_exit_expectation_does_not_match() -> Result<()> {
+    let path = write_golden_file(
+        "wrong-exit",
+        "//= {\"output\": {\"stdout\": \"Hello, .*!\"}, \"exit\": \"failure\"}\nHello, world!",
+    );
+
+    let result = run_golden_file(&path, &FakeInterpreterProvider).await?;
+    assert!(!result.passed);
+    assert!(result.failure.unwrap().contains("exit"));
+
+    let _ = fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_golden_file_matches_multiline_stdout_as_an_order_independent_multiset() -> Result<()> {
+    let path = write_golden_file(
+        "multiline",
+        "//= {\"output\": {\"stdout\": \"^b$\\n^a$\"}, \"exit\": \"success\"}\na\nb",
+    );
+
+    let result = run_golden_file(&path, &FakeInterpreterProvider).await?;
+    assert!(result.passed, "{:?}", result.failure);
+
+    let _ = fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_golden_file_reports_an_error_for_an_invalid_header() -> Result<()> {
+    let path = write_golden_file("bad-header", "//= not json\nHello, world!");
+
+    let result = run_golden_file(&path, &FakeInterpreterProvider).await;
+    assert!(result.is_err());
+
+    let _ = fs::remove_file(&path);
+    Ok(())
+}
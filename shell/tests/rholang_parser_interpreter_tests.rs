@@ -1,7 +1,7 @@
 use anyhow::Result;
-use std::time::Duration;
-use tokio::time::sleep;
+use std::sync::Arc;
 
+use shell::clock::MockClock;
 use shell::providers::{InterpreterProvider, RholangParserInterpreterProvider};
 
 #[tokio::test]
@@ -41,12 +41,28 @@ async fn test_rholang_parser_interpreter_with_invalid_code() -> Result<()> {
     Ok(())
 }
 
+/// Wait for `interpreter.list_processes()` to report at least one process, without
+/// advancing any real time. Process registration happens synchronously before
+/// `interpret` ever reaches its simulated delay, so this only has to give the
+/// spawned task a chance to run -- unlike a fixed `sleep(100ms)`, it can't be too
+/// short (flaky) or too long (slow) for the machine it runs on.
+async fn wait_for_processes(interpreter: &RholangParserInterpreterProvider) -> Result<Vec<(usize, String)>> {
+    loop {
+        let processes = interpreter.list_processes()?;
+        if !processes.is_empty() {
+            return Ok(processes);
+        }
+        tokio::task::yield_now().await;
+    }
+}
+
 #[tokio::test]
 async fn test_rholang_parser_interpreter_process_management() -> Result<()> {
-    let interpreter = RholangParserInterpreterProvider::new()?;
-
-    // Set a delay to ensure the process stays running long enough for us to check
-    interpreter.set_delay(500)?;
+    // A MockClock whose time never advances means the simulated-processing-time
+    // delay never elapses on its own, so this test can kill the process
+    // deterministically instead of racing a real sleep against it.
+    let interpreter = RholangParserInterpreterProvider::with_clock(Arc::new(MockClock::new()))?;
+    interpreter.set_delay(1)?;
 
     // Start a process
     let handle = tokio::spawn({
@@ -57,15 +73,8 @@ async fn test_rholang_parser_interpreter_process_management() -> Result<()> {
         }
     });
 
-    // Give it a moment to start
-    sleep(Duration::from_millis(100)).await;
-
     // List processes
-    let processes = interpreter.list_processes()?;
-    assert!(
-        !processes.is_empty(),
-        "Expected at least one running process"
-    );
+    let processes = wait_for_processes(&interpreter).await?;
 
     // Get the process ID
     let pid = processes[0].0;
@@ -87,10 +96,8 @@ async fn test_rholang_parser_interpreter_process_management() -> Result<()> {
 
 #[tokio::test]
 async fn test_rholang_parser_interpreter_kill_all_processes() -> Result<()> {
-    let interpreter = RholangParserInterpreterProvider::new()?;
-
-    // Set a delay to ensure processes stay running
-    interpreter.set_delay(1000)?;
+    let interpreter = RholangParserInterpreterProvider::with_clock(Arc::new(MockClock::new()))?;
+    interpreter.set_delay(1)?;
 
     // Start multiple processes
     let handle1 = tokio::spawn({
@@ -109,11 +116,12 @@ async fn test_rholang_parser_interpreter_kill_all_processes() -> Result<()> {
         }
     });
 
-    // Give them a moment to start
-    sleep(Duration::from_millis(100)).await;
-
     // List processes to verify they're running
-    let processes = interpreter.list_processes()?;
+    let mut processes = wait_for_processes(&interpreter).await?;
+    while processes.len() < 2 {
+        tokio::task::yield_now().await;
+        processes = interpreter.list_processes()?;
+    }
     assert_eq!(processes.len(), 2, "Expected two running processes");
 
     // Kill all processes
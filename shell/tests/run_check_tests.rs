@@ -0,0 +1,31 @@
+use std::fs;
+use std::path::PathBuf;
+
+use shell::run_check;
+
+/// Write a `.rho` fixture at a unique path under the OS temp directory.
+fn write_rho_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{name}-{}.rho", std::process::id()));
+    fs::write(&path, contents).expect("failed to write fixture");
+    path
+}
+
+#[test]
+fn test_run_check_succeeds_for_well_formed_source() {
+    let path = write_rho_file("check-ok", "new channel in { channel!(\"Hello, world!\") }");
+
+    let result = run_check(&path);
+    assert!(result.is_ok(), "{:?}", result.err());
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_run_check_fails_for_source_with_recovery_errors() {
+    let path = write_rho_file("check-bad", "new x in { x!(1) } new y in { @@@ }");
+
+    let result = run_check(&path);
+    assert!(result.is_err());
+
+    let _ = fs::remove_file(&path);
+}
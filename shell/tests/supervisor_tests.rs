@@ -0,0 +1,98 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use shell::providers::FakeInterpreterProvider;
+use shell::supervisor::{Component, ProviderComponent, Supervisor};
+
+/// A `Component` whose `shutdown` records that it ran and never fails or hangs --
+/// for asserting every registered component actually gets drained.
+struct RecordingComponent {
+    name: String,
+    shut_down: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl Component for RecordingComponent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        self.shut_down.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// A `Component` whose `shutdown` never returns, for proving the `Supervisor`
+/// doesn't hang process exit on a stuck component.
+struct StuckComponent;
+
+#[async_trait]
+impl Component for StuckComponent {
+    fn name(&self) -> &str {
+        "stuck"
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        std::future::pending().await
+    }
+}
+
+#[tokio::test]
+async fn test_supervisor_shutdown_drains_every_registered_component() -> Result<()> {
+    let mut supervisor = Supervisor::new(Duration::from_secs(1));
+
+    let first_shut_down = Arc::new(AtomicBool::new(false));
+    let second_shut_down = Arc::new(AtomicBool::new(false));
+
+    supervisor.register(RecordingComponent {
+        name: "first".to_string(),
+        shut_down: first_shut_down.clone(),
+    });
+    supervisor.register(RecordingComponent {
+        name: "second".to_string(),
+        shut_down: second_shut_down.clone(),
+    });
+
+    supervisor.shutdown().await?;
+
+    assert!(first_shut_down.load(Ordering::SeqCst), "expected the first component to be drained");
+    assert!(second_shut_down.load(Ordering::SeqCst), "expected the second component to be drained");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_supervisor_shutdown_bounds_a_stuck_component_by_drain_timeout() -> Result<()> {
+    let mut supervisor = Supervisor::new(Duration::from_millis(50));
+    supervisor.register(StuckComponent);
+
+    let started = Instant::now();
+    tokio::time::timeout(Duration::from_secs(1), supervisor.shutdown())
+        .await
+        .expect("supervisor.shutdown() should itself return well within the outer test timeout")?;
+
+    assert!(
+        started.elapsed() < Duration::from_secs(1),
+        "a stuck component should be bounded by drain_timeout, not hang shutdown() forever"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_provider_component_shutdown_kills_the_provider_s_processes() -> Result<()> {
+    let provider = FakeInterpreterProvider;
+    let component = ProviderComponent::new("interpreter", &provider);
+
+    // `FakeInterpreterProvider::kill_all_processes` has nothing to kill, so this is
+    // mainly asserting the adapter delegates without erroring.
+    component.shutdown().await?;
+    assert_eq!(component.name(), "interpreter");
+
+    Ok(())
+}